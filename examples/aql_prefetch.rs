@@ -0,0 +1,72 @@
+//! Compare fetching a large AQL result with and without batch prefetching.
+//!
+//! With prefetching enabled, the request for the next batch is in flight while
+//! the current batch is being deserialized. The saving grows with the number
+//! of batches and the network latency to the server. The
+//! `prefetch_overlaps_decoding` unit test shows it without a server, with an
+//! injected latency.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use arangors::{client::ClientExt, AqlQuery, Connection, Database};
+
+const URL: &str = "http://localhost:8529";
+const ROUNDS: u32 = 5;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Row {
+    index: u64,
+    name: String,
+    tags: Vec<String>,
+}
+
+fn query() -> AqlQuery<'static> {
+    AqlQuery::builder()
+        .query(
+            "FOR i IN 1..200000 RETURN { index: i, name: CONCAT('row-', i), tags: ['a', 'b', \
+             'c'] }",
+        )
+        .batch_size(1000)
+        .build()
+}
+
+#[maybe_async::maybe_async]
+async fn bench<C: ClientExt>(database: &Database<C>) -> Duration {
+    let mut total = Duration::default();
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        let rows: Vec<Row> = database.aql_query(query()).await.unwrap();
+        total += start.elapsed();
+        assert_eq!(rows.len(), 200000);
+    }
+    total / ROUNDS
+}
+
+#[cfg_attr(feature = "reqwest_async", tokio::main)]
+#[cfg_attr(feature = "surf_async", async_std::main)]
+#[cfg_attr(feature = "reqwest_blocking", maybe_async::must_be_sync)]
+async fn main() {
+    env_logger::init();
+
+    let conn = Connection::establish_jwt(URL, "username", "password")
+        .await
+        .unwrap();
+    let mut database = conn.db("test_db").await.unwrap();
+
+    database.set_batch_prefetch(false);
+    let sequential = bench(&database).await;
+    println!("sequential: {:?}", sequential);
+
+    database.set_batch_prefetch(true);
+    let prefetch = bench(&database).await;
+    println!("prefetch:   {:?}", prefetch);
+}
+
+#[cfg(not(any(
+    feature = "reqwest_blocking",
+    feature = "reqwest_async",
+    feature = "surf_async"
+)))]
+fn main() {}
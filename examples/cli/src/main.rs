@@ -0,0 +1,172 @@
+//! A command line client for ArangoDB built on arangors.
+//!
+//! ```text
+//! arangors-cli [OPTIONS] <COMMAND>
+//!
+//! Commands:
+//!   query <AQL>                      run an AQL query, print one JSON result per line
+//!   import <COLLECTION> <FILE>       insert the JSON documents of FILE, one per line
+//!   export <COLLECTION>              print the documents of COLLECTION, one per line
+//!   describe <COLLECTION>            print the properties, count and indexes
+//!
+//! Options:
+//!   --config <FILE> --profile <NAME> connect with a profile, see
+//!                                    `arangors::connection::profile`
+//!   --url <URL>                      server url [default: http://localhost:8529]
+//!   --user <USER>                    username, with the password in
+//!                                    the ARANGO_PASSWORD environment variable
+//!   --db <NAME>                      database [default: the one of the profile,
+//!                                    or _system]
+//! ```
+//!
+//! For example, to copy a collection between two servers:
+//! ```text
+//! arangors-cli --config arango.toml --profile prod export users > users.jsonl
+//! arangors-cli --config arango.toml --profile staging import users users.jsonl
+//! ```
+use std::{
+    env,
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use anyhow::{anyhow, bail, Error};
+use arangors::{
+    client::{reqwest::ReqwestClient, ClientExt},
+    connection::{profile::Profile, Auth},
+    document::options::InsertOptions,
+    AqlQuery, Connection, Database,
+};
+use serde_json::Value;
+
+type Db = Database<ReqwestClient>;
+
+/// Number of documents inserted per request by `import`
+const IMPORT_BATCH_SIZE: usize = 1000;
+/// Number of documents fetched per request by `export`
+const EXPORT_BATCH_SIZE: u32 = 1000;
+
+#[derive(Default)]
+struct Options {
+    config: Option<String>,
+    profile: Option<String>,
+    url: Option<String>,
+    user: Option<String>,
+    db: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let mut options = Options::default();
+    let mut args = env::args().skip(1);
+    let mut command = Vec::new();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow!("missing value of {}", arg))
+        };
+        match arg.as_str() {
+            "--config" => options.config = Some(value()?),
+            "--profile" => options.profile = Some(value()?),
+            "--url" => options.url = Some(value()?),
+            "--user" => options.user = Some(value()?),
+            "--db" => options.db = Some(value()?),
+            _ => command.push(arg),
+        }
+    }
+
+    let db = connect(&options).await?;
+    match command.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["query", aql] => query(&db, aql).await,
+        ["import", collection, file] => import(&db, collection, file).await,
+        ["export", collection] => export(&db, collection).await,
+        ["describe", collection] => describe(&db, collection).await,
+        _ => bail!("usage: arangors-cli [OPTIONS] query|import|export|describe ..."),
+    }
+}
+
+async fn connect(options: &Options) -> Result<Db, Error> {
+    let (conn, default_db) = match (&options.config, &options.profile) {
+        (Some(config), Some(name)) => {
+            let profile = Profile::load(config, name)?;
+            (profile.connect().await?, profile.database().to_owned())
+        }
+        (None, None) => {
+            let url = options.url.as_deref().unwrap_or("http://localhost:8529");
+            let password = env::var("ARANGO_PASSWORD").unwrap_or_default();
+            let auth = match &options.user {
+                Some(user) => Auth::jwt(user, &password),
+                None => Auth::None,
+            };
+            let client = ReqwestClient::new(None)?;
+            let conn = Connection::establish_with_client(client, url, auth).await?;
+            (conn, "_system".to_owned())
+        }
+        _ => bail!("--config and --profile go together"),
+    };
+    let name = options.db.clone().unwrap_or(default_db);
+    Ok(conn.db(&name).await?)
+}
+
+async fn query(db: &Db, aql: &str) -> Result<(), Error> {
+    let results: Vec<Value> = db.aql_str(aql).await?;
+    for result in results {
+        println!("{}", result);
+    }
+    Ok(())
+}
+
+async fn import(db: &Db, collection: &str, file: &str) -> Result<(), Error> {
+    let collection = db.collection(collection).await?;
+    let mut lines = BufReader::new(File::open(file)?).lines();
+    let (mut created, mut failed) = (0, 0);
+    loop {
+        let batch = lines
+            .by_ref()
+            .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+            .take(IMPORT_BATCH_SIZE)
+            .map(|line| Ok(serde_json::from_str::<Value>(&line?)?))
+            .collect::<Result<Vec<Value>, Error>>()?;
+        if batch.is_empty() {
+            break;
+        }
+        for result in collection
+            .create_documents(batch, InsertOptions::default())
+            .await?
+        {
+            match result {
+                Ok(_) => created += 1,
+                Err(err) => {
+                    failed += 1;
+                    eprintln!("{}", err);
+                }
+            }
+        }
+    }
+    eprintln!("{} created, {} failed", created, failed);
+    Ok(())
+}
+
+async fn export(db: &Db, collection: &str) -> Result<(), Error> {
+    let aql = AqlQuery::builder()
+        .query("FOR doc IN @@collection RETURN doc")
+        .bind_var("@collection", collection)
+        .batch_size(EXPORT_BATCH_SIZE)
+        .build();
+    let mut documents = db.aql_query_stream::<Value>(aql).await?;
+    while let Some(document) = documents.next_result().await? {
+        println!("{}", document);
+    }
+    Ok(())
+}
+
+async fn describe(db: &Db, collection: &str) -> Result<(), Error> {
+    let collection = db.collection(collection).await?;
+    println!("{:#?}", collection.properties().await?);
+    let count = collection.document_count().await?.info.count;
+    println!("count: {}", count.unwrap_or_default());
+    for index in db.indexes(collection.name()).await?.indexes {
+        println!("{:#?}", index);
+    }
+    Ok(())
+}
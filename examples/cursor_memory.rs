@@ -0,0 +1,123 @@
+//! Compare the peak memory of decoding a large cursor batch as a whole and
+//! one result at a time.
+//!
+//! A `Cursor<T>` holds the body of the response and the `Vec` of all its
+//! results at once, a `RawBatch<T>` only the body and the result being read.
+//! No server is needed, the batch is generated. Set its size in MB with
+//! `CURSOR_MEMORY_MB`, 256 by default:
+//!
+//! ```text
+//! CURSOR_MEMORY_MB=512 cargo run --release --example cursor_memory
+//! ```
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use serde::Deserialize;
+
+use arangors::aql::{Cursor, RawBatch};
+
+/// The system allocator, keeping track of the memory in use and its peak.
+struct Counting;
+
+static IN_USE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let in_use = IN_USE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(in_use, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        IN_USE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+#[derive(Deserialize, Debug)]
+struct Row {
+    index: u64,
+    name: String,
+    tags: Vec<String>,
+}
+
+const MB: usize = 1024 * 1024;
+
+/// The body of a cursor response of about `size` bytes.
+fn batch_body(size: usize) -> String {
+    let mut body = String::with_capacity(size + 1024);
+    body.push_str(r#"{"result":["#);
+    let mut index = 0;
+    while body.len() < size {
+        if index > 0 {
+            body.push(',');
+        }
+        body.push_str(&format!(
+            r#"{{"index":{0},"name":"row-{0}","tags":["a","b","c"]}}"#,
+            index
+        ));
+        index += 1;
+    }
+    body.push_str(r#"],"hasMore":false,"cached":false,"error":false,"code":201}"#);
+    body
+}
+
+/// Run `decode` on a fresh body and report the memory it used on top of it.
+fn measure(label: &str, size: usize, decode: impl FnOnce(String) -> u64) {
+    let body = batch_body(size);
+    let body_len = body.len();
+    let base = IN_USE.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+
+    let start = Instant::now();
+    let sum = decode(body);
+    let elapsed = start.elapsed();
+
+    let peak = PEAK.load(Ordering::Relaxed) - (base - body_len);
+    println!(
+        "{:<10} peak {:>6} MB for a {} MB body in {:?} (checksum {})",
+        label,
+        peak / MB,
+        body_len / MB,
+        elapsed,
+        sum
+    );
+}
+
+fn main() {
+    let size = std::env::var("CURSOR_MEMORY_MB")
+        .ok()
+        .and_then(|mb| mb.parse::<usize>().ok())
+        .unwrap_or(256)
+        * MB;
+
+    measure("Cursor", size, |body| {
+        let cursor: Cursor<Row> = serde_json::from_str(&body).unwrap();
+        cursor
+            .result
+            .iter()
+            .map(|row| row.index + (row.name.len() + row.tags.len()) as u64)
+            .sum()
+    });
+
+    measure("RawBatch", size, |body| {
+        RawBatch::<Row>::parse(body)
+            .unwrap()
+            .map(|row| {
+                let row = row.unwrap();
+                row.index + (row.name.len() + row.tags.len()) as u64
+            })
+            .sum()
+    });
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use typed_builder::TypedBuilder;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -150,7 +151,7 @@ pub struct StopwordsAnalyzerProperties {
     pub stopwords: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum AnalyzerInfo {
     /// The `identity` Analyzer does not take additional properties.
@@ -231,6 +232,87 @@ pub enum AnalyzerInfo {
     },
 }
 
+impl AnalyzerInfo {
+    /// Name of the Analyzer, prefixed with the name of its database and `::`
+    /// in the definitions returned by the server, except for built-in ones.
+    pub fn name(&self) -> &str {
+        match self {
+            AnalyzerInfo::Identity { name, .. }
+            | AnalyzerInfo::Delimiter { name, .. }
+            | AnalyzerInfo::Stem { name, .. }
+            | AnalyzerInfo::Norm { name, .. }
+            | AnalyzerInfo::Ngram { name, .. }
+            | AnalyzerInfo::Text { name, .. }
+            | AnalyzerInfo::Geojson { name, .. }
+            | AnalyzerInfo::Stopwords { name, .. }
+            | AnalyzerInfo::Pipeline { name, .. } => name,
+        }
+    }
+}
+
+/// Options of `Database::sync_analyzers`.
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[builder(doc)]
+pub struct AnalyzerSyncOptions {
+    /// Drop the Analyzers of the database which are not in the desired
+    /// definitions. Built-in Analyzers are never dropped.
+    #[builder(default)]
+    pub remove_extra: bool,
+
+    /// Drop Analyzers to update or remove even when Views or inverted indexes
+    /// use them. Without it, dropping such an Analyzer fails.
+    #[builder(default)]
+    pub force: bool,
+}
+
+/// Names of the Analyzers handled by `Database::sync_analyzers`, without the
+/// database prefix.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnalyzerSyncReport {
+    pub created: Vec<String>,
+    /// Dropped and created again with the desired definition
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged: Vec<String>,
+}
+
+/// Whether the definition of an existing Analyzer satisfies the desired one.
+///
+/// The server fills in the properties left out at creation with their
+/// defaults, so only the properties set in `desired` are compared. Arrays
+/// must have the same length and each desired element must match an
+/// existing one, as the server may reorder e.g. the features.
+pub(crate) fn analyzer_matches(desired: &AnalyzerInfo, current: &AnalyzerInfo) -> bool {
+    let desired = serde_json::to_value(desired).unwrap_or(Value::Null);
+    let current = serde_json::to_value(current).unwrap_or(Value::Null);
+    match (desired, current) {
+        (Value::Object(mut desired), Value::Object(mut current)) => {
+            desired.remove("name");
+            current.remove("name");
+            value_matches(&Value::Object(desired), &Value::Object(current))
+        }
+        _ => false,
+    }
+}
+
+fn value_matches(desired: &Value, current: &Value) -> bool {
+    match (desired, current) {
+        (Value::Object(desired), Value::Object(current)) => desired.iter().all(|(key, value)| {
+            current
+                .get(key)
+                .is_some_and(|current| value_matches(value, current))
+        }),
+        (Value::Array(desired), Value::Array(current)) => {
+            desired.len() == current.len()
+                && desired
+                    .iter()
+                    .all(|value| current.iter().any(|current| value_matches(value, current)))
+        }
+        (Value::Number(desired), Value::Number(current)) => desired.as_f64() == current.as_f64(),
+        (desired, current) => desired == current,
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AnalyzerDescription {
     pub name: String,
@@ -300,3 +382,46 @@ pub enum PipelineAnalyzers {
         features: Option<Vec<AnalyzerFeature>>,
     },
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn analyzer_sync_comparison() {
+        let desired = AnalyzerInfo::Norm {
+            name: "norm_en".to_owned(),
+            features: Some(vec![AnalyzerFeature::Frequency, AnalyzerFeature::Norm]),
+            properties: Some(
+                NormAnalyzerProperties::builder()
+                    .locale("en".to_owned())
+                    .case(AnalyzerCase::Lower)
+                    .build(),
+            ),
+        };
+        let current: AnalyzerInfo = serde_json::from_value(serde_json::json!({
+            "name": "test_db::norm_en",
+            "type": "norm",
+            "properties": { "locale": "en", "case": "lower", "accent": true },
+            "features": ["norm", "frequency"]
+        }))
+        .unwrap();
+        assert_eq!(current.name(), "test_db::norm_en");
+        assert!(analyzer_matches(&desired, &current));
+
+        let changed: AnalyzerInfo = serde_json::from_value(serde_json::json!({
+            "name": "test_db::norm_en",
+            "type": "norm",
+            "properties": { "locale": "en", "case": "upper", "accent": true },
+            "features": ["norm", "frequency"]
+        }))
+        .unwrap();
+        assert!(!analyzer_matches(&desired, &changed));
+
+        let identity = AnalyzerInfo::Identity {
+            name: "norm_en".to_owned(),
+            features: None,
+        };
+        assert!(!analyzer_matches(&identity, &current));
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
 /// Types related to AQL query in arangoDB.
 ///
 /// While aql queries are performed on database, it would be ponderous to
@@ -7,13 +9,33 @@
 /// 1. (optional) construct a AqlQuery object.
 ///     - (optional) construct AqlOption.
 /// 1. perform AQL query via `database.aql_query`.
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use serde::{Deserialize, Serialize};
-use serde_json::value::Value;
+use http::{header::HeaderMap, HeaderValue, Method, Request};
+use log::{trace, warn};
+use maybe_async::maybe_async;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::value::{RawValue, Value};
 use typed_builder::TypedBuilder;
+use url::Url;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
 
-#[derive(Debug, Serialize, TypedBuilder)]
+use crate::{
+    client::{ClientExt, RequestExt},
+    response::deserialize_response,
+    transaction::TRANSACTION_HEADER,
+    ClientError, Database,
+};
+
+#[derive(Debug, Clone, Serialize, TypedBuilder)]
 #[builder(
     doc,
     builder_method(doc = r#"Create a builder for building `AqlQuery`.
@@ -34,9 +56,18 @@ pub struct AqlQuery<'a> {
     query: &'a str,
 
     /// bind parameters to substitute in query string
+    ///
+    /// Keys can be borrowed `&str` or owned `String`, so that a
+    /// `HashMap<&str, Value>` as well as dynamically generated keys are
+    /// accepted.
     #[serde(skip_serializing_if = "HashMap::is_empty")]
-    #[builder(default)]
-    bind_vars: HashMap<&'a str, Value>,
+    #[builder(
+        default,
+        setter(transform = |bind_vars: impl IntoIterator<Item = (impl Into<Cow<'a, str>>, Value)>| {
+            bind_vars.into_iter().map(|(k, v)| (k.into(), v)).collect()
+        })
+    )]
+    bind_vars: HashMap<Cow<'a, str>, Value>,
 
     /// Indicates whether the number of documents in the result set should be
     /// returned in the "count" attribute of the result.
@@ -97,6 +128,54 @@ pub struct AqlQuery<'a> {
     options: Option<AqlOptions>,
 }
 
+impl<'a> AqlQuery<'a> {
+    /// The warning policy set in the options of this query.
+    pub(crate) fn warning_policy(&self) -> Option<WarningPolicy> {
+        self.options.as_ref().and_then(|o| o.warning_policy)
+    }
+
+    /// The request context set in the options of this query, or the default
+    /// one.
+    pub(crate) fn context(&self) -> QueryContext {
+        self.options
+            .as_ref()
+            .and_then(|o| o.context.clone())
+            .unwrap_or_default()
+    }
+
+    /// The body of an explain request for this query, leaving out the cursor
+    /// attributes.
+    pub(crate) fn explain_body(&self) -> Result<String, ClientError> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Explain<'q, 'a> {
+            query: &'a str,
+            #[serde(skip_serializing_if = "HashMap::is_empty")]
+            bind_vars: &'q HashMap<Cow<'a, str>, Value>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            options: Option<&'q AqlOptions>,
+        }
+        Ok(serde_json::to_string(&Explain {
+            query: self.query,
+            bind_vars: &self.bind_vars,
+            options: self.options.as_ref(),
+        })?)
+    }
+
+    /// Rename the collections bound to the collection bind parameters, the
+    /// ones whose key starts with `@`.
+    pub(crate) fn map_collections(&mut self, rename: impl Fn(&str) -> String) {
+        for (key, value) in self.bind_vars.iter_mut() {
+            if !key.starts_with('@') {
+                continue;
+            }
+            if let Value::String(name) = value {
+                *name = rename(name);
+            }
+        }
+    }
+}
+
 // when binding the first query variable
 #[allow(non_camel_case_types, missing_docs)]
 impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __options>
@@ -123,7 +202,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
         'a,
         (
             __query,
-            (HashMap<&'a str, Value>,),
+            (HashMap<Cow<'a, str>, Value>,),
             __count,
             __batch_size,
             __cache,
@@ -133,7 +212,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
         ),
     >
     where
-        K: Into<&'a str>,
+        K: Into<Cow<'a, str>>,
         V: Into<Value>,
     {
         let mut bind_vars = HashMap::new();
@@ -164,7 +243,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
             'a,
             (
                 __query,
-                (HashMap<&'a str, Value>,),
+                (HashMap<Cow<'a, str>, Value>,),
                 __count,
                 __batch_size,
                 __cache,
@@ -176,7 +255,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
         serde_json::Error,
     >
     where
-        K: Into<&'a str>,
+        K: Into<Cow<'a, str>>,
         V: serde::Serialize,
     {
         Ok(self.bind_var(key, serde_json::to_value(value)?))
@@ -190,7 +269,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
         'a,
         (
             __query,
-            (HashMap<&'a str, Value>,),
+            (HashMap<Cow<'a, str>, Value>,),
             __count,
             __batch_size,
             __cache,
@@ -209,7 +288,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
         'a,
         (
             __query,
-            (HashMap<&'a str, Value>,),
+            (HashMap<Cow<'a, str>, Value>,),
             __count,
             __batch_size,
             __cache,
@@ -219,7 +298,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
         ),
     >
     where
-        K: Into<&'a str>,
+        K: Into<Cow<'a, str>>,
         V: Into<Value>,
     {
         (self.fields.1).0.insert(key.into(), value.into());
@@ -236,7 +315,7 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
             'a,
             (
                 __query,
-                (HashMap<&'a str, Value>,),
+                (HashMap<Cow<'a, str>, Value>,),
                 __count,
                 __batch_size,
                 __cache,
@@ -248,14 +327,59 @@ impl<'a, __query, __count, __batch_size, __cache, __memory_limit, __ttl, __optio
         serde_json::Error,
     >
     where
-        K: Into<&'a str>,
+        K: Into<Cow<'a, str>>,
         V: serde::Serialize,
     {
         Ok(self.bind_var(key, serde_json::to_value(value)?))
     }
 }
 
-#[derive(Debug, Serialize, TypedBuilder, PartialEq)]
+/// How much profiling information is returned with a query result, set with
+/// `AqlOptions::builder().profile()` from a `bool` or a level.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(into = "u8")]
+pub enum ProfileLevel {
+    /// No profiling, level 0
+    Disabled,
+    /// Duration of the query phases in `extra.profile`, level 1
+    Phases,
+    /// Also the plan with the statistics of each execution node, in
+    /// `extra.plan` and `extra.stats.nodes`, level 2
+    Nodes,
+}
+
+impl From<bool> for ProfileLevel {
+    fn from(enabled: bool) -> Self {
+        if enabled {
+            ProfileLevel::Phases
+        } else {
+            ProfileLevel::Disabled
+        }
+    }
+}
+
+impl From<u8> for ProfileLevel {
+    /// Levels above 2 are the same as 2.
+    fn from(level: u8) -> Self {
+        match level {
+            0 => ProfileLevel::Disabled,
+            1 => ProfileLevel::Phases,
+            _ => ProfileLevel::Nodes,
+        }
+    }
+}
+
+impl From<ProfileLevel> for u8 {
+    fn from(level: ProfileLevel) -> Self {
+        match level {
+            ProfileLevel::Disabled => 0,
+            ProfileLevel::Phases => 1,
+            ProfileLevel::Nodes => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TypedBuilder, PartialEq)]
 #[builder(doc)]
 #[serde(rename_all = "camelCase")]
 pub struct AqlOptions {
@@ -274,12 +398,15 @@ pub struct AqlOptions {
     #[builder(default, setter(strip_option))]
     fail_on_warning: Option<bool>,
 
-    /// If set to true, then the additional query profiling information will
-    /// be returned in the sub-attribute profile of the extra return attribute
-    /// if the query result is not served from the query cache.
+    /// If set to true or 1, then the additional query profiling information
+    /// will be returned in the sub-attribute profile of the extra return
+    /// attribute if the query result is not served from the query cache.
+    ///
+    /// If set to 2, the query plan with the runtime statistics of each
+    /// execution node is returned as well, see [`ProfileLevel`].
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[builder(default, setter(strip_option))]
-    profile: Option<bool>,
+    #[builder(default, setter(into, strip_option))]
+    profile: Option<ProfileLevel>,
 
     /// Limits the maximum number of warnings a query will return.
     ///
@@ -289,6 +416,18 @@ pub struct AqlOptions {
     #[builder(default, setter(strip_option))]
     max_warning_count: Option<u32>,
 
+    /// What to do with the warnings returned with the query result, instead
+    /// of the policy of the database or transaction. Not sent to the server.
+    #[serde(skip)]
+    #[builder(default, setter(strip_option))]
+    warning_policy: Option<WarningPolicy>,
+
+    /// Transaction, dirty read flag and extra headers of the requests of the
+    /// query. Not sent to the server as an option.
+    #[serde(skip)]
+    #[builder(default, setter(strip_option))]
+    context: Option<QueryContext>,
+
     /// If set to true and the query contains a LIMIT clause, then the result
     /// will have an extra attribute with the sub-attributes stats and
     /// fullCount, `{ ... , "extra": { "stats": { "fullCount": 123 } } }`.
@@ -323,15 +462,28 @@ pub struct AqlOptions {
     ///
     /// There is also a pseudo-rule `"all"`, which will match all optimizer
     /// rules.
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ///
+    /// Sent as `optimizer.rules`.
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "optimizer_rules"
+    )]
     #[builder(default)]
     optimizer: Vec<String>,
 
+    /// Whether the query plan cache is used for this query, if the query is
+    /// eligible.
+    ///
+    /// Repeated queries that only differ in bind values can then skip query
+    /// planning. Supported since ArangoDB 3.12.4.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    use_plan_cache: Option<bool>,
+
     /// Maximum number of operations after which an intermediate commit is
     /// performed automatically.
     ///
     /// Honored by the RocksDB storage engine only.
-    #[cfg(feature = "rocksdb")]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     intermediate_commit_count: Option<u32>,
@@ -340,7 +492,6 @@ pub struct AqlOptions {
     /// performed automatically.
     ///
     /// Honored by the RocksDB storage engine only.
-    #[cfg(feature = "rocksdb")]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     intermediate_commit_size: Option<u32>,
@@ -348,7 +499,6 @@ pub struct AqlOptions {
     /// Transaction size limit in bytes.
     ///
     /// Honored by the RocksDB storage engine only.
-    #[cfg(feature = "rocksdb")]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     max_transaction_size: Option<u32>,
@@ -365,6 +515,16 @@ pub struct AqlOptions {
     satellite_sync_wait: Option<bool>,
 }
 
+/// The server reads the rules from an `optimizer` object, a plain array is
+/// ignored.
+fn optimizer_rules<S: serde::Serializer>(rules: &[String], ser: S) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Optimizer<'a> {
+        rules: &'a [String],
+    }
+    Optimizer { rules }.serialize(ser)
+}
+
 impl Default for AqlOptions {
     fn default() -> AqlOptions {
         Self::builder().build()
@@ -377,6 +537,387 @@ impl AqlOptions {
     }
 }
 
+/// An AQL query run repeatedly with different bind values, e.g. by a request
+/// handler, keeping the query string and its options in one place.
+///
+/// The prepared query holds a template [`AqlQuery`], each execution is the
+/// template with its own bind values added to the ones of the template. Run
+/// it with `Database::aql_prepared`, or once per set of bind values with
+/// `Database::aql_prepared_many`. With
+/// [`use_plan_cache`](AqlOptionsBuilder::use_plan_cache) set in the options
+/// of the template, the executions after the first one skip query planning
+/// on servers supporting the plan cache.
+///
+/// # Example
+/// ```rust, ignore
+/// let by_email = PreparedQuery::new(
+///     AqlQuery::builder()
+///         .query("FOR u IN users FILTER u.email == @email RETURN u")
+///         .options(AqlOptions::builder().use_plan_cache(true).build())
+///         .build(),
+/// );
+/// let users: Vec<User> = db.aql_prepared(&by_email, [("email", email)]).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct PreparedQuery<'a> {
+    template: AqlQuery<'a>,
+}
+
+impl<'a> PreparedQuery<'a> {
+    pub fn new(template: AqlQuery<'a>) -> Self {
+        Self { template }
+    }
+
+    /// The query string
+    pub fn query(&self) -> &'a str {
+        self.template.query
+    }
+
+    /// An execution of this query with `bind_vars`.
+    pub fn bind<K>(&self, bind_vars: impl IntoIterator<Item = (K, Value)>) -> AqlQuery<'a>
+    where
+        K: Into<Cow<'a, str>>,
+    {
+        let mut aql = self.template.clone();
+        aql.bind_vars
+            .extend(bind_vars.into_iter().map(|(k, v)| (k.into(), v)));
+        aql
+    }
+}
+
+impl<'a> From<AqlQuery<'a>> for PreparedQuery<'a> {
+    fn from(template: AqlQuery<'a>) -> Self {
+        Self::new(template)
+    }
+}
+
+/// Request context of an AQL query.
+///
+/// Combines the stream transaction to run the query in, whether the query
+/// may read from followers, and extra HTTP headers. They are sent with the
+/// query and with the requests fetching its further batches. Set it for a
+/// single query with [`AqlOptions`]; the default context sends nothing extra.
+///
+/// # Example
+/// ```rust, ignore
+/// let context = QueryContext::builder()
+///     .transaction_id(transaction.id())
+///     .allow_dirty_read(true)
+///     .build();
+/// let aql = AqlQuery::builder()
+///     .query("FOR u IN users RETURN u")
+///     .options(AqlOptions::builder().context(context).build())
+///     .build();
+/// let users: Vec<Value> = db.aql_query(aql).await?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, TypedBuilder)]
+#[builder(doc)]
+pub struct QueryContext {
+    /// Id of a running stream transaction to run the query in.
+    #[builder(default, setter(into, strip_option))]
+    transaction_id: Option<String>,
+
+    /// Whether the query may be answered by a follower, possibly with
+    /// outdated data, in an active failover or cluster deployment.
+    #[builder(default)]
+    allow_dirty_read: bool,
+
+    /// Extra headers, overridden by the transaction and dirty read settings.
+    #[builder(default)]
+    headers: HeaderMap,
+}
+
+/// Header allowing reads from followers
+pub(crate) const DIRTY_READ_HEADER: &str = "x-arango-allow-dirty-read";
+
+impl QueryContext {
+    pub fn transaction_id(&self) -> Option<&str> {
+        self.transaction_id.as_deref()
+    }
+
+    pub fn allow_dirty_read(&self) -> bool {
+        self.allow_dirty_read
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Build a request carrying the headers of this context.
+    pub(crate) fn request(
+        &self,
+        method: Method,
+        url: Url,
+        body: String,
+    ) -> Result<Request<String>, ClientError> {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(url.as_str())
+            .body(body)
+            .unwrap();
+        let headers = request.headers_mut();
+        headers.extend(self.headers.clone());
+        if let Some(id) = &self.transaction_id {
+            let id = HeaderValue::from_str(id).map_err(|_| {
+                ClientError::InvalidArgument(format!("invalid transaction id: {}", id))
+            })?;
+            headers.insert(TRANSACTION_HEADER, id);
+        }
+        if self.allow_dirty_read {
+            headers.insert(DIRTY_READ_HEADER, HeaderValue::from_static("true"));
+        }
+        Ok(request)
+    }
+}
+
+/// An entry of the query plan cache, as listed by
+/// `Database::plan_cache_entries`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanCacheEntry {
+    /// The plan cache key
+    pub hash: String,
+    /// The query string
+    pub query: String,
+    /// Hash of the query string
+    pub query_hash: Option<u64>,
+    /// Bind values the cached plan depends on, e.g. collection names
+    #[serde(default)]
+    pub bind_vars: HashMap<String, Value>,
+    /// Collections and views used by the query
+    #[serde(default)]
+    pub data_sources: Vec<String>,
+    /// When the plan was added to the cache
+    pub created: Option<String>,
+    /// Number of times the cached plan was used
+    pub hits: u64,
+    /// Memory used by the entry in bytes
+    pub memory_usage: Option<u64>,
+}
+
+/// How a query would be executed, as reported by `Database::analyze_query`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryAnalysis {
+    /// Indexes used by the plan, in the order of the execution nodes
+    pub indexes_used: Vec<IndexUsage>,
+    /// Collections read in full, without an index
+    pub full_scans: Vec<String>,
+    /// Optimizer rules applied to the plan
+    pub rules: Vec<String>,
+    /// Estimated cost of the plan
+    pub estimated_cost: f64,
+    /// Estimated number of results
+    pub estimated_nr_items: u64,
+    /// Warnings raised when planning the query
+    pub warnings: Vec<Warning>,
+}
+
+impl QueryAnalysis {
+    /// Whether the plan reads a collection without an index.
+    pub fn has_full_scan(&self) -> bool {
+        !self.full_scans.is_empty()
+    }
+
+    /// Fail with `ClientError::FullCollectionScan` when the plan reads a
+    /// collection without an index, to keep queries from regressing to full
+    /// scans.
+    pub fn deny_full_scans(self) -> Result<Self, ClientError> {
+        if self.has_full_scan() {
+            return Err(ClientError::FullCollectionScan(self.full_scans));
+        }
+        Ok(self)
+    }
+
+    fn visit(&mut self, nodes: &[ExecutionNode]) -> Result<(), serde_json::Error> {
+        for node in nodes {
+            let collection = node.collection().unwrap_or_default().to_owned();
+            if node.node_type == "EnumerateCollectionNode" && !self.full_scans.contains(&collection)
+            {
+                self.full_scans.push(collection.clone());
+            }
+            if let Some(indexes) = node.details.get("indexes") {
+                let indexes = Vec::<PlanIndex>::deserialize(indexes)?;
+                self.indexes_used
+                    .extend(indexes.into_iter().map(|index| IndexUsage {
+                        collection: collection.clone(),
+                        id: index.id,
+                        name: index.name,
+                        index_type: index.index_type,
+                        fields: index.fields,
+                    }));
+            }
+            // subqueries are nested in the plan of servers before 3.8
+            if let Some(subquery) = node.details.get("subquery") {
+                self.visit(&SubqueryPlan::deserialize(subquery)?.nodes)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The analysis of an explained query, failing when a node of its plan
+/// has malformed indexes or subquery.
+impl TryFrom<ExplainedQuery> for QueryAnalysis {
+    type Error = serde_json::Error;
+
+    fn try_from(explained: ExplainedQuery) -> Result<Self, Self::Error> {
+        let mut analysis = QueryAnalysis {
+            indexes_used: Vec::new(),
+            full_scans: Vec::new(),
+            rules: explained.plan.rules,
+            estimated_cost: explained.plan.estimated_cost,
+            estimated_nr_items: explained.plan.estimated_nr_items,
+            warnings: explained.warnings,
+        };
+        analysis.visit(&explained.plan.nodes)?;
+        Ok(analysis)
+    }
+}
+
+/// An index used by a query plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexUsage {
+    /// Collection of the index
+    pub collection: String,
+    /// Index id
+    pub id: String,
+    /// Index name
+    pub name: String,
+    /// Index type, like `persistent` or `primary`
+    pub index_type: String,
+    /// Indexed attributes
+    pub fields: Vec<String>,
+}
+
+/// The execution plan of a query, as returned by
+/// `Database::explain_query`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainedQuery {
+    /// The plan chosen by the optimizer
+    pub plan: ExecutionPlan,
+    /// Warnings raised when planning the query
+    #[serde(default)]
+    pub warnings: Vec<Warning>,
+    /// Whether the results of the query could be stored in the query results
+    /// cache
+    #[serde(default)]
+    pub cacheable: bool,
+    /// Statistics of the optimizer
+    pub stats: Option<ExplainStats>,
+}
+
+/// An execution plan of a query.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPlan {
+    /// Execution nodes, each depending on the results of the nodes listed in
+    /// its [`dependencies`](ExecutionNode::dependencies)
+    #[serde(default)]
+    pub nodes: Vec<ExecutionNode>,
+    /// Optimizer rules applied to the plan
+    #[serde(default)]
+    pub rules: Vec<String>,
+    /// Collections used by the query
+    #[serde(default)]
+    pub collections: Vec<PlanCollection>,
+    /// Variables of the query, including the ones created by the optimizer
+    #[serde(default)]
+    pub variables: Vec<PlanVariable>,
+    /// Estimated cost of the plan
+    #[serde(default)]
+    pub estimated_cost: f64,
+    /// Estimated number of results
+    #[serde(default)]
+    pub estimated_nr_items: u64,
+    /// Whether the query modifies documents
+    #[serde(default)]
+    pub is_modification_query: bool,
+}
+
+/// A node of an [`ExecutionPlan`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionNode {
+    /// Node type, like `EnumerateCollectionNode` or `IndexNode`
+    #[serde(rename = "type")]
+    pub node_type: String,
+    /// Node id, unique in the plan
+    pub id: u64,
+    /// Ids of the nodes this node takes its input from
+    #[serde(default)]
+    pub dependencies: Vec<u64>,
+    /// Estimated cost of the plan up to this node
+    #[serde(default)]
+    pub estimated_cost: f64,
+    /// Estimated number of items returned by this node
+    #[serde(default)]
+    pub estimated_nr_items: u64,
+    /// Attributes specific to the node type, like `collection`, `indexes` or
+    /// `expression`
+    #[serde(flatten)]
+    pub details: serde_json::Map<String, Value>,
+}
+
+impl ExecutionNode {
+    /// The collection read or written by the node, if any.
+    pub fn collection(&self) -> Option<&str> {
+        self.details.get("collection").and_then(Value::as_str)
+    }
+}
+
+/// A collection used by an [`ExecutionPlan`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PlanCollection {
+    /// Collection name
+    pub name: String,
+    /// Access type, `read`, `write` or `exclusive`
+    #[serde(rename = "type")]
+    pub access_type: String,
+}
+
+/// A variable of an [`ExecutionPlan`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PlanVariable {
+    /// Variable id
+    pub id: u64,
+    /// Variable name, generated names are numbers
+    pub name: String,
+}
+
+/// Statistics of the optimizer for an [`ExplainedQuery`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExplainStats {
+    /// Number of optimizer rules executed
+    pub rules_executed: u32,
+    /// Number of optimizer rules skipped
+    pub rules_skipped: u32,
+    /// Number of plans created by the optimizer
+    pub plans_created: u32,
+    /// Peak memory usage of the query planning in bytes
+    pub peak_memory_usage: Option<u64>,
+    /// Duration of the query planning in seconds
+    pub execution_time: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubqueryPlan {
+    #[serde(default)]
+    nodes: Vec<ExecutionNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlanIndex {
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type")]
+    index_type: String,
+    #[serde(default)]
+    fields: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryStats {
@@ -419,6 +960,29 @@ pub struct QueryStats {
     pub full_count: Option<usize>,
     pub http_requests: usize,
     pub execution_time: f64,
+
+    /// Memory used by the query in bytes when the stats were taken, sent by
+    /// ArangoDB 3.12 and later.
+    pub memory_usage: Option<u64>,
+
+    /// Maximum memory used by the query in bytes.
+    pub peak_memory_usage: Option<u64>,
+
+    /// Runtime statistics of each execution node, with profile level 2.
+    pub nodes: Option<Vec<NodeStats>>,
+}
+
+/// Runtime statistics of an execution node of the query plan.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct NodeStats {
+    /// Id of the node in `QueryExtra::plan`
+    pub id: u64,
+    /// Number of calls to the node
+    pub calls: u64,
+    /// Number of items returned by the node
+    pub items: u64,
+    /// Execution time of the node in seconds
+    pub runtime: f64,
 }
 
 #[derive(Deserialize, Debug)]
@@ -456,96 +1020,1884 @@ pub struct Cursor<T> {
     pub extra: Option<QueryExtra>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct QueryExtra {
-    // TODO
-    pub stats: Option<QueryStats>,
-    // TODO
-    pub warnings: Option<Vec<Value>>,
+/// Results of an AQL query fetched within a time budget, see
+/// `Database::aql_query_within`.
+#[derive(Debug)]
+pub struct PartialResults<T> {
+    /// Results fetched before the budget was exhausted
+    pub result: Vec<T>,
+    /// Id of the server cursor holding the remaining results, `None` when all
+    /// results were fetched.
+    ///
+    /// Continue with `aql_next_batches_within` or `aql_next_batch_with`,
+    /// passing the [`context`](PartialResults::context). The cursor is not
+    /// deleted by the client, it expires after its TTL on the server when
+    /// abandoned.
+    pub cursor_id: Option<String>,
+    /// Context of the query, to send with the requests continuing the cursor
+    pub context: QueryContext,
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+impl<T> PartialResults<T> {
+    /// Whether all results were fetched.
+    pub fn is_complete(&self) -> bool {
+        self.cursor_id.is_none()
+    }
+}
 
-    #[test]
-    fn aql_query_builder_bind_var() {
-        let q = r#"FOR i in test_collection FILTER i.username==@username AND i.password==@password return i"#;
-        let aql = AqlQuery::builder()
-            .query(q)
-            // test the first bind
-            .bind_var("username", "test2")
-            // test the second bind
-            .bind_var("password", "test2_pwd")
-            .count(true)
-            .batch_size(256)
-            .cache(false)
-            .memory_limit(100)
-            .ttl(10)
-            .build();
-        assert_eq!(aql.query, q);
-        assert_eq!(aql.count, Some(true));
-        assert_eq!(aql.batch_size, Some(256u32));
-        assert_eq!(aql.cache, Some(false));
-        assert_eq!(aql.memory_limit, Some(100));
-        assert_eq!(aql.ttl, Some(10));
-        assert_eq!(aql.options, None);
+/// Fetch the further batches of a cursor of `db` with `context` until
+/// `deadline`, see `Database::aql_query_within`.
+#[maybe_async]
+pub(crate) async fn fetch_within<C, R>(
+    db: &Database<C>,
+    response: Cursor<R>,
+    policy: WarningPolicy,
+    context: QueryContext,
+    deadline: Instant,
+) -> Result<PartialResults<R>, ClientError>
+where
+    C: ClientExt,
+    R: DeserializeOwned,
+{
+    let mut response_cursor = response;
+    let mut results: Vec<R> = Vec::new();
+    let mut guard = CursorGuard::new(
+        db.session(),
+        db.url().clone(),
+        response_cursor.id.clone().filter(|_| db.cursor_cleanup()),
+        Arc::clone(&db.pending_cursors),
+    );
+    loop {
+        results.extend(response_cursor.result);
+        match response_cursor.id {
+            Some(id) if response_cursor.more => {
+                if Instant::now() >= deadline {
+                    // the caller continues with the cursor
+                    guard.disarm();
+                    return Ok(PartialResults {
+                        result: results,
+                        cursor_id: Some(id),
+                        context,
+                    });
+                }
+                response_cursor = db.next_batch(&id, policy, &context).await?;
+            }
+            _ => break,
+        }
+    }
+    guard.disarm();
+    Ok(PartialResults {
+        result: results,
+        cursor_id: None,
+        context,
+    })
+}
 
-        assert_eq!(
-            aql.bind_vars.get("username"),
-            Some(&Value::String("test2".to_owned()))
-        );
-        assert_eq!(
-            aql.bind_vars.get("password"),
-            Some(&Value::String("test2_pwd".to_owned()))
-        );
+/// Continue fetching the batches of the cursor `cursor_id` of `db` until
+/// `deadline`, see `Database::aql_next_batches_within`.
+#[maybe_async]
+pub(crate) async fn next_batches_within<C, R>(
+    db: &Database<C>,
+    cursor_id: &str,
+    policy: WarningPolicy,
+    context: &QueryContext,
+    deadline: Instant,
+) -> Result<PartialResults<R>, ClientError>
+where
+    C: ClientExt,
+    R: DeserializeOwned,
+{
+    let response = db.next_batch(cursor_id, policy, context).await?;
+    fetch_within(db, response, policy, context.clone(), deadline).await
+}
+
+/// A batch of a cursor kept as the JSON of its response, whose results are
+/// decoded one at a time.
+///
+/// Decoding a [`Cursor`] builds the whole `Vec` of results on top of the
+/// body. A `RawBatch` only holds the body and a position in its `result`
+/// array, so the memory used is the body plus one result.
+///
+/// # Example
+/// ```rust
+/// use arangors::aql::RawBatch;
+///
+/// let body = r#"{"result":[{"n":1},{"n":2}],"hasMore":false,"cached":false}"#;
+/// let batch = RawBatch::<serde_json::Value>::parse(body.to_owned()).unwrap();
+/// assert_eq!(batch.more(), false);
+/// let total: i64 = batch.map(|doc| doc.unwrap()["n"].as_i64().unwrap()).sum();
+/// assert_eq!(total, 3);
+/// ```
+#[derive(Debug)]
+pub struct RawBatch<R> {
+    body: String,
+    /// Next byte of the `result` array to decode
+    position: usize,
+    /// Position of the closing bracket of the `result` array
+    end: usize,
+    count: Option<usize>,
+    cached: bool,
+    more: bool,
+    id: Option<String>,
+    extra: Option<QueryExtra>,
+    result: PhantomData<fn() -> R>,
+}
+
+/// The fields of a cursor response, borrowing the results.
+#[derive(Deserialize)]
+struct RawCursor<'a> {
+    #[serde(borrow)]
+    result: &'a RawValue,
+    count: Option<usize>,
+    cached: bool,
+    #[serde(rename = "hasMore")]
+    more: bool,
+    id: Option<String>,
+    extra: Option<QueryExtra>,
+}
+
+impl<R> RawBatch<R>
+where
+    R: DeserializeOwned,
+{
+    /// Parse the body of a cursor response, without decoding the results.
+    pub fn parse(body: String) -> Result<Self, ClientError> {
+        let cursor: RawCursor = serde_json::from_str(&body)?;
+        let result = cursor.result.get();
+        if !result.starts_with('[') {
+            return Err(ClientError::InvalidArgument(
+                "the result of a cursor should be an array".to_owned(),
+            ));
+        }
+        // the raw value borrows the body, its offset locates the results
+        let position = result.as_ptr() as usize - body.as_ptr() as usize + 1;
+        let end = position + result.len() - 2;
+        Ok(RawBatch {
+            position,
+            end,
+            count: cursor.count,
+            cached: cursor.cached,
+            more: cursor.more,
+            id: cursor.id,
+            extra: cursor.extra,
+            body,
+            result: PhantomData,
+        })
     }
 
-    #[test]
-    fn aql_query_builder_try_bind() {
-        #[derive(Serialize, Deserialize, Debug)]
-        struct User {
-            pub username: String,
-            pub password: String,
+    /// Decode the body of a cursor response, or return the error of the
+    /// server.
+    pub(crate) fn from_response(resp: http::Response<String>) -> Result<Self, ClientError> {
+        if !resp.status().is_success() {
+            deserialize_response::<Value>(&resp)?;
         }
-        let user = User {
-            username: "test2".to_owned(),
-            password: "test2_pwd".to_owned(),
-        };
-        let q = r#"FOR i in test_collection FILTER i==@user return i"#;
-        let aql = AqlQuery::builder()
-            .query(q)
-            .try_bind("user", user)
-            .unwrap()
-            .build();
+        Self::parse(resp.into_body())
+    }
 
-        assert_eq!(aql.query, q);
-        assert_eq!(aql.count, None);
-        assert_eq!(aql.batch_size, None);
+    /// The total number of results, when the query was run with `count`.
+    ///
+    /// Not named `count`, which is the one of `Iterator`.
+    pub fn total_count(&self) -> Option<usize> {
+        self.count
+    }
 
-        let mut map = serde_json::Map::new();
-        map.insert("username".into(), "test2".into());
-        map.insert("password".into(), "test2_pwd".into());
+    /// Whether the results were served from the query cache.
+    pub fn cached(&self) -> bool {
+        self.cached
+    }
 
-        assert_eq!(aql.bind_vars.get("user"), Some(&Value::Object(map)));
+    /// Whether the server cursor has more batches.
+    pub fn more(&self) -> bool {
+        self.more
+    }
 
-        let aql = AqlQuery::builder()
-            .query(r#"FOR i in test_collection FILTER i.username==@username AND i.password==@password return i"#)
-            // test the first bind
-            .try_bind("username", "test2")
-            .unwrap()
-            // test the second bind
-            .try_bind("password", "test2_pwd")
-            .unwrap()
-            .build();
+    /// Id of the server cursor.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
 
-        assert_eq!(
-            aql.bind_vars.get("username"),
-            Some(&Value::String("test2".to_owned()))
-        );
-        assert_eq!(
-            aql.bind_vars.get("password"),
-            Some(&Value::String("test2_pwd".to_owned()))
+    /// Extra information of the batch, like the stats and warnings.
+    pub fn extra(&self) -> Option<&QueryExtra> {
+        self.extra.as_ref()
+    }
+}
+
+impl<R> Iterator for RawBatch<R>
+where
+    R: DeserializeOwned,
+{
+    type Item = Result<R, ClientError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // the array was checked when parsed, only separators are left between
+        // results
+        let rest = self.body[self.position..self.end]
+            .trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+        if rest.is_empty() {
+            self.position = self.end;
+            return None;
+        }
+        let start = self.end - rest.len();
+        let mut results = serde_json::Deserializer::from_str(rest).into_iter::<R>();
+        match results.next()? {
+            Ok(result) => {
+                self.position = start + results.byte_offset();
+                Some(Ok(result))
+            }
+            Err(e) => {
+                self.position = self.end;
+                Some(Err(e.into()))
+            }
+        }
+    }
+}
+
+/// Lazily fetched results of an AQL query, see
+/// [`Database::aql_query_stream`](crate::Database::aql_query_stream).
+///
+/// Only one batch is held in memory at a time, as the body of its response,
+/// and a result is decoded when it is read. Dropping the stream before the
+/// end deletes the server cursor.
+///
+/// In blocking mode, `CursorStream` is also an `Iterator`, with the async
+/// HTTP clients it is also a `futures_lite::Stream`.
+#[derive(Debug)]
+pub struct CursorStream<C: ClientExt, R> {
+    session: Arc<C>,
+    base_url: Url,
+    context: QueryContext,
+    policy: WarningPolicy,
+    batch: RawBatch<R>,
+    guard: CursorGuard<C>,
+    #[cfg(all(
+        not(feature = "blocking"),
+        any(feature = "reqwest_async", feature = "surf_async", feature = "tower")
+    ))]
+    fetching: Option<NextBatch>,
+}
+
+impl<C, R> CursorStream<C, R>
+where
+    C: ClientExt,
+    R: DeserializeOwned,
+{
+    pub(crate) fn new(
+        session: Arc<C>,
+        base_url: Url,
+        context: QueryContext,
+        policy: WarningPolicy,
+        batch: RawBatch<R>,
+        guard: CursorGuard<C>,
+    ) -> Self {
+        CursorStream {
+            session,
+            base_url,
+            context,
+            policy,
+            batch,
+            guard,
+            #[cfg(all(
+                not(feature = "blocking"),
+                any(feature = "reqwest_async", feature = "surf_async", feature = "tower")
+            ))]
+            fetching: None,
+        }
+    }
+
+    /// The total number of results, when the query was run with `count`.
+    pub fn total_count(&self) -> Option<usize> {
+        self.batch.total_count()
+    }
+
+    /// Extra information of the current batch, like the stats and warnings.
+    pub fn extra(&self) -> Option<&QueryExtra> {
+        self.batch.extra()
+    }
+
+    /// Get the next result, fetching the next batch from the server when
+    /// the current one is exhausted. Returns `None` at the end.
+    ///
+    /// # Note
+    /// this function would make a request to arango server when the current
+    /// batch is exhausted.
+    #[maybe_async]
+    pub async fn next_result(&mut self) -> Result<Option<R>, ClientError> {
+        loop {
+            if let Some(result) = self.batch.next() {
+                return result.map(Some);
+            }
+            let req = match self.next_batch_request()? {
+                Some(req) => req,
+                None => return Ok(None),
+            };
+            let resp = self.session.request(req).await?;
+            self.replace_batch(resp)?;
+        }
+    }
+
+    /// The request of the batch after the current one, if there is one.
+    fn next_batch_request(&self) -> Result<Option<Request<String>>, ClientError> {
+        let id = match self.batch.id() {
+            Some(id) if self.batch.more() => id,
+            _ => return Ok(None),
+        };
+        let url = self.base_url.join(&format!("_api/cursor/{}", id)).unwrap();
+        self.context
+            .request(Method::PUT, url, String::new())
+            .map(Some)
+    }
+
+    fn replace_batch(&mut self, resp: http::Response<String>) -> Result<(), ClientError> {
+        // free the previous body before the next one is parsed
+        self.batch.body = String::new();
+        self.batch = RawBatch::from_response(resp)?;
+        if !self.batch.more() {
+            self.guard.disarm();
+        }
+        check_warnings(self.policy, self.batch.extra())
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<C, R> Iterator for CursorStream<C, R>
+where
+    C: ClientExt,
+    R: DeserializeOwned,
+{
+    type Item = Result<R, ClientError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_result().transpose()
+    }
+}
+
+/// Request of the next batch of a [`CursorStream`] polled as a `Stream`.
+#[cfg(all(
+    not(feature = "blocking"),
+    any(feature = "reqwest_async", feature = "surf_async", feature = "tower")
+))]
+struct NextBatch(NextBatchFuture);
+
+#[cfg(all(
+    not(feature = "blocking"),
+    not(target_arch = "wasm32"),
+    any(feature = "reqwest_async", feature = "surf_async", feature = "tower")
+))]
+type NextBatchFuture = std::pin::Pin<
+    Box<dyn std::future::Future<Output = Result<http::Response<String>, ClientError>> + Send>,
+>;
+
+#[cfg(all(
+    not(feature = "blocking"),
+    target_arch = "wasm32",
+    any(feature = "reqwest_async", feature = "surf_async", feature = "tower")
+))]
+type NextBatchFuture = std::pin::Pin<
+    Box<dyn std::future::Future<Output = Result<http::Response<String>, ClientError>>>,
+>;
+
+#[cfg(all(
+    not(feature = "blocking"),
+    any(feature = "reqwest_async", feature = "surf_async", feature = "tower")
+))]
+impl fmt::Debug for NextBatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NextBatch")
+    }
+}
+
+#[cfg(all(
+    not(feature = "blocking"),
+    any(feature = "reqwest_async", feature = "surf_async", feature = "tower")
+))]
+impl<C, R> futures_lite::Stream for CursorStream<C, R>
+where
+    C: ClientExt + Send + 'static,
+    R: DeserializeOwned,
+{
+    type Item = Result<R, ClientError>;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use std::task::Poll;
+
+        let this = self.get_mut();
+        loop {
+            if let Some(fetching) = &mut this.fetching {
+                let resp = futures_lite::ready!(fetching.0.as_mut().poll(cx));
+                this.fetching = None;
+                if let Err(e) = resp.and_then(|resp| this.replace_batch(resp)) {
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+            if let Some(result) = this.batch.next() {
+                return Poll::Ready(Some(result));
+            }
+            match this.next_batch_request() {
+                Ok(Some(req)) => {
+                    let session = Arc::clone(&this.session);
+                    this.fetching = Some(NextBatch(Box::pin(
+                        async move { session.request(req).await },
+                    )));
+                }
+                Ok(None) => return Poll::Ready(None),
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            }
+        }
+    }
+}
+
+/// Cursor metadata without the result documents, used to decide on the next
+/// batch before the current one is decoded.
+#[derive(Deserialize, Debug)]
+pub(crate) struct CursorHeader {
+    #[serde(rename = "hasMore")]
+    pub more: bool,
+    pub id: Option<String>,
+    pub extra: Option<QueryExtra>,
+}
+
+impl CursorHeader {
+    /// Read the metadata of the cursor in `resp`, skipping over the results.
+    /// When the body is not a cursor, the server error is returned, or the
+    /// error of decoding a `Cursor<R>`.
+    pub(crate) fn from_response<R>(resp: &http::Response<String>) -> Result<Self, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        serde_json::from_str(resp.body()).map_err(|e| {
+            deserialize_response::<Cursor<R>>(resp)
+                .err()
+                .unwrap_or_else(|| e.into())
+        })
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct QueryExtra {
+    // TODO
+    pub stats: Option<QueryStats>,
+    pub warnings: Option<Vec<Warning>>,
+
+    /// Duration of each query phase in seconds, like `parsing` or
+    /// `executing`, when the query is profiled.
+    pub profile: Option<HashMap<String, f64>>,
+
+    /// The executed plan, with profile level 2.
+    pub plan: Option<Value>,
+}
+
+/// An AQL query slower than the threshold of a [`SlowQueryLog`].
+#[derive(Debug)]
+pub struct SlowQuery<'a> {
+    /// The query string
+    pub query: &'a str,
+    /// The bind values, `None` when redacted
+    pub bind_vars: Option<&'a HashMap<Cow<'a, str>, Value>>,
+    /// Time until the first batch of results was received
+    pub duration: Duration,
+    /// Stats of the query, when the server sent them
+    pub stats: Option<&'a QueryStats>,
+}
+
+type SlowQueryCallback = dyn Fn(&SlowQuery<'_>) + Send + Sync;
+
+/// Report the AQL queries of a database taking longer than a threshold, set
+/// with `Database::set_slow_query_log`.
+///
+/// The duration is measured on the client, from sending the query until
+/// its first batch of results is received, so it includes the network and
+/// the queue of the server. Slow queries are logged at `warn` level, or
+/// emitted as `tracing` events on the `arangors::slow_query` target with the
+/// `tracing` feature, unless a [`callback`](SlowQueryLog::callback) is set.
+///
+/// Bind values are redacted by default, as they may hold personal data.
+///
+/// # Example
+/// ```rust, ignore
+/// let log = SlowQueryLog::new(Duration::from_millis(500))
+///     .callback(|slow| metrics::counter!("slow_queries").increment(1));
+/// db.set_slow_query_log(Some(log));
+/// ```
+#[derive(Clone)]
+pub struct SlowQueryLog {
+    threshold: Duration,
+    redact_bind_vars: bool,
+    callback: Option<Arc<SlowQueryCallback>>,
+}
+
+impl fmt::Debug for SlowQueryLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlowQueryLog")
+            .field("threshold", &self.threshold)
+            .field("redact_bind_vars", &self.redact_bind_vars)
+            .field("callback", &self.callback.is_some())
+            .finish()
+    }
+}
+
+impl SlowQueryLog {
+    /// Report the queries taking at least `threshold`.
+    pub fn new(threshold: Duration) -> Self {
+        SlowQueryLog {
+            threshold,
+            redact_bind_vars: true,
+            callback: None,
+        }
+    }
+
+    /// Call `callback` with the slow queries instead of logging them.
+    pub fn callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&SlowQuery<'_>) + Send + Sync + 'static,
+    {
+        self.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Whether the bind values are left out of the reports, true by
+    /// default.
+    pub fn redact_bind_vars(mut self, redact: bool) -> Self {
+        self.redact_bind_vars = redact;
+        self
+    }
+
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    pub(crate) fn record(
+        &self,
+        aql: &AqlQuery<'_>,
+        duration: Duration,
+        stats: Option<&QueryStats>,
+    ) {
+        if duration < self.threshold {
+            return;
+        }
+        let slow = SlowQuery {
+            query: aql.query,
+            bind_vars: Some(&aql.bind_vars).filter(|_| !self.redact_bind_vars),
+            duration,
+            stats,
+        };
+        match &self.callback {
+            Some(callback) => callback(&slow),
+            None => emit_slow_query(&slow),
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn emit_slow_query(slow: &SlowQuery<'_>) {
+    tracing::warn!(
+        target: "arangors::slow_query",
+        query = slow.query,
+        bind_vars = ?slow.bind_vars,
+        duration_ms = slow.duration.as_millis() as u64,
+        execution_time = ?slow.stats.map(|stats| stats.execution_time),
+        peak_memory_usage = ?slow.stats.and_then(|stats| stats.peak_memory_usage),
+        "slow AQL query"
+    );
+}
+
+#[cfg(not(feature = "tracing"))]
+fn emit_slow_query(slow: &SlowQuery<'_>) {
+    match slow.bind_vars {
+        Some(bind_vars) => warn!(
+            "Slow AQL query ({:?}): {} with {:?}",
+            slow.duration, slow.query, bind_vars
+        ),
+        None => warn!("Slow AQL query ({:?}): {}", slow.duration, slow.query),
+    }
+}
+
+/// A warning raised by the server while executing an AQL query, e.g. on a
+/// division by zero or the use of a deprecated feature.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// Error number of the warning
+    pub code: u32,
+    pub message: String,
+}
+
+/// What to do with the warnings returned with an AQL query result.
+///
+/// Set for a database or transaction with `set_warning_policy`, and for a
+/// single query with [`AqlOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WarningPolicy {
+    /// Drop the warnings, as earlier versions did.
+    #[default]
+    Ignore,
+    /// Log each warning at `warn` level and return the result.
+    Log,
+    /// Fail with `ClientError::AqlWarnings` instead of returning the result.
+    Fail,
+}
+
+/// Escape the wildcards of a `LIKE` pattern, to match `input` literally.
+///
+/// `%` and `_` are wildcards of the AQL `LIKE` operator and function, and a
+/// backslash escapes them. Escape user input before embedding it into a
+/// pattern, and pass the pattern as a bind parameter rather than
+/// formatting it into the query:
+///
+/// ```rust
+/// use arangors::{aql::escape_like, AqlQuery};
+///
+/// let search = "50%_off";
+/// let aql = AqlQuery::builder()
+///     .query("FOR p IN products FILTER p.name LIKE @pattern RETURN p")
+///     .bind_var("pattern", format!("%{}%", escape_like(search)))
+///     .build();
+/// assert_eq!(escape_like(search), r"50\%\_off");
+/// ```
+pub fn escape_like(input: &str) -> String {
+    escape(input, &['\\', '%', '_'])
+}
+
+/// Escape the metacharacters of a regular expression, to match `input`
+/// literally.
+///
+/// Use it for user input embedded into the patterns of `=~`, `REGEX_TEST`,
+/// `REGEX_MATCHES`, `REGEX_SPLIT` and `REGEX_REPLACE`, passed as a bind
+/// parameter like with [`escape_like`]:
+///
+/// ```rust
+/// use arangors::{aql::escape_regex, AqlQuery};
+///
+/// let domain = "example.com";
+/// let aql = AqlQuery::builder()
+///     .query("FOR u IN users FILTER REGEX_TEST(u.email, @pattern, true) RETURN u")
+///     .bind_var("pattern", format!("@{}$", escape_regex(domain)))
+///     .build();
+/// assert_eq!(escape_regex(domain), r"example\.com");
+/// ```
+pub fn escape_regex(input: &str) -> String {
+    escape(
+        input,
+        &[
+            '\\', '^', '$', '.', '|', '?', '*', '+', '(', ')', '[', ']', '{', '}',
+        ],
+    )
+}
+
+/// Prefix the `special` characters of `input` with a backslash.
+fn escape(input: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        if special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Apply the warning policy to the extra information of a cursor batch.
+pub(crate) fn check_warnings(
+    policy: WarningPolicy,
+    extra: Option<&QueryExtra>,
+) -> Result<(), ClientError> {
+    let warnings = match extra.and_then(|e| e.warnings.as_ref()) {
+        Some(warnings) if !warnings.is_empty() => warnings,
+        _ => return Ok(()),
+    };
+    match policy {
+        WarningPolicy::Ignore => Ok(()),
+        WarningPolicy::Log => {
+            for warning in warnings {
+                warn!("AQL warning {}: {}", warning.code, warning.message);
+            }
+            Ok(())
+        }
+        WarningPolicy::Fail => Err(ClientError::AqlWarnings(warnings.clone())),
+    }
+}
+
+/// Ids of server cursors abandoned before they were exhausted, waiting to be
+/// deleted on the next query.
+pub(crate) type PendingCursors = Arc<Mutex<Vec<String>>>;
+
+/// Best-effort deletion of a server cursor when fetching all results of a
+/// query is cancelled or fails midway.
+///
+/// In blocking mode the cursor is deleted right away on drop. In async mode a
+/// request cannot be awaited in `Drop`, so the cursor id is queued and deleted
+/// by [`delete_pending_cursors`] before the next query on the same database,
+/// or by `Database::close`.
+#[derive(Debug)]
+pub(crate) struct CursorGuard<C: ClientExt> {
+    #[cfg_attr(not(feature = "blocking"), allow(dead_code))]
+    session: Arc<C>,
+    #[cfg_attr(not(feature = "blocking"), allow(dead_code))]
+    base_url: Url,
+    id: Option<String>,
+    #[cfg_attr(feature = "blocking", allow(dead_code))]
+    pending: PendingCursors,
+}
+
+impl<C: ClientExt> CursorGuard<C> {
+    /// `id` is `None` when there is nothing to clean up, e.g. when cursor
+    /// cleanup is turned off.
+    pub(crate) fn new(
+        session: Arc<C>,
+        base_url: Url,
+        id: Option<String>,
+        pending: PendingCursors,
+    ) -> Self {
+        CursorGuard {
+            session,
+            base_url,
+            id,
+            pending,
+        }
+    }
+
+    /// The cursor has been exhausted and is already removed by the server.
+    pub(crate) fn disarm(&mut self) {
+        self.id = None;
+    }
+}
+
+impl<C: ClientExt> Drop for CursorGuard<C> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            trace!("Cursor {} abandoned before exhausted", id);
+            #[cfg(feature = "blocking")]
+            {
+                let url = self.base_url.join(&format!("_api/cursor/{}", id)).unwrap();
+                let _ = self.session.delete(url, "");
+            }
+            #[cfg(not(feature = "blocking"))]
+            {
+                if let Ok(mut pending) = self.pending.lock() {
+                    pending.push(id);
+                }
+            }
+        }
+    }
+}
+
+/// Delete the cursors queued by dropped [`CursorGuard`]s, ignoring failures as
+/// the server removes them after their TTL anyway.
+#[maybe_async]
+pub(crate) async fn delete_pending_cursors<C: ClientExt>(
+    session: &C,
+    base_url: &Url,
+    pending: &PendingCursors,
+) {
+    let ids: Vec<String> = match pending.lock() {
+        Ok(mut pending) => pending.drain(..).collect(),
+        Err(_) => return,
+    };
+    for id in ids {
+        let url = base_url.join(&format!("_api/cursor/{}", id)).unwrap();
+        if let Err(e) = session.delete(url, "").await {
+            trace!("Failed to delete abandoned cursor {}: {}", id, e);
+        }
+    }
+}
+
+/// Sends the requests fetching the next batches of a cursor, so that
+/// decoding a batch overlaps with the network round trip of the next one, see
+/// [`with_batch_prefetcher`].
+#[cfg(not(feature = "blocking"))]
+pub(crate) struct BatchPrefetcher<'a, C> {
+    session: &'a C,
+}
+
+/// Sends the requests fetching the next batches of a cursor from a worker
+/// thread, so that decoding a batch overlaps with the network round trip of
+/// the next one, see [`with_batch_prefetcher`].
+#[cfg(feature = "blocking")]
+pub(crate) struct BatchPrefetcher {
+    requests: std::sync::mpsc::Sender<Request<String>>,
+    responses: std::sync::mpsc::Receiver<Result<http::Response<String>, ClientError>>,
+}
+
+/// Call `run` with a [`BatchPrefetcher`] sending its requests with `session`.
+///
+/// The requests are sent concurrently on the current task, no task is
+/// spawned.
+#[maybe_async::async_impl]
+pub(crate) async fn with_batch_prefetcher<'a, C, F, Fut>(session: &'a C, run: F) -> Fut::Output
+where
+    C: ClientExt,
+    F: FnOnce(BatchPrefetcher<'a, C>) -> Fut,
+    Fut: std::future::Future,
+{
+    run(BatchPrefetcher { session }).await
+}
+
+/// Call `run` with a [`BatchPrefetcher`] sending its requests with `session`.
+///
+/// A single worker thread sends all the requests of `run`, and stops when it
+/// returns.
+#[maybe_async::sync_impl]
+pub(crate) fn with_batch_prefetcher<C, F, T>(session: &C, run: F) -> T
+where
+    C: ClientExt,
+    F: FnOnce(BatchPrefetcher) -> T,
+{
+    use std::sync::mpsc;
+
+    let (requests, worker_requests) = mpsc::channel::<Request<String>>();
+    let (worker_responses, responses) = mpsc::channel();
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            for request in worker_requests {
+                if worker_responses.send(session.request(request)).is_err() {
+                    break;
+                }
+            }
+        });
+        // dropping the prefetcher closes the channel and stops the worker
+        run(BatchPrefetcher {
+            requests,
+            responses,
+        })
+    })
+}
+
+#[cfg(not(feature = "blocking"))]
+impl<C: ClientExt> BatchPrefetcher<'_, C> {
+    /// Send `request` fetching the next cursor batch while running `work` on
+    /// the current task.
+    pub(crate) async fn fetch_while<F, T>(
+        &self,
+        request: Request<String>,
+        work: F,
+    ) -> (Result<http::Response<String>, ClientError>, T)
+    where
+        F: FnOnce() -> T,
+    {
+        use std::{
+            future::{poll_fn, Future},
+            pin::pin,
+            task::Poll,
+        };
+
+        let mut request = pin!(self.session.request(request));
+        let mut work = pin!(async { work() });
+        let mut response = None;
+        let mut output = None;
+        poll_fn(|cx| {
+            if response.is_none() {
+                if let Poll::Ready(r) = request.as_mut().poll(cx) {
+                    response = Some(r);
+                }
+            }
+            if output.is_none() {
+                if let Poll::Ready(o) = work.as_mut().poll(cx) {
+                    output = Some(o);
+                }
+            }
+            match (response.take(), output.take()) {
+                (Some(r), Some(o)) => Poll::Ready((r, o)),
+                (r, o) => {
+                    response = r;
+                    output = o;
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl BatchPrefetcher {
+    /// Send `request` fetching the next cursor batch from the worker thread
+    /// while running `work` on the current thread.
+    pub(crate) fn fetch_while<F, T>(
+        &self,
+        request: Request<String>,
+        work: F,
+    ) -> (Result<http::Response<String>, ClientError>, T)
+    where
+        F: FnOnce() -> T,
+    {
+        // the worker only stops once the prefetcher is dropped, or by
+        // panicking, which the scope propagates
+        self.requests
+            .send(request)
+            .expect("the prefetch worker stopped");
+        let output = work();
+        let response = self.responses.recv().expect("the prefetch worker stopped");
+        (response, output)
+    }
+}
+
+/// Call `run` with each of `items`, with at most `limit` calls running at
+/// once, and return their outputs in the order of `items`.
+///
+/// The calls run concurrently on the current task, no task is spawned.
+#[maybe_async::async_impl]
+pub(crate) async fn run_bounded<I, F, Fut>(items: Vec<I>, limit: usize, run: F) -> Vec<Fut::Output>
+where
+    F: Fn(I) -> Fut,
+    Fut: std::future::Future,
+{
+    use futures_util::stream::{self, StreamExt};
+
+    stream::iter(items)
+        .map(run)
+        .buffered(limit.max(1))
+        .collect()
+        .await
+}
+
+/// Call `run` with each of `items`, with at most `limit` calls running at
+/// once on scoped threads, and return their outputs in the order of `items`.
+#[maybe_async::sync_impl]
+pub(crate) fn run_bounded<I, F, T>(items: Vec<I>, limit: usize, run: F) -> Vec<T>
+where
+    I: Send,
+    F: Fn(I) -> T + Sync,
+    T: Send,
+{
+    let outputs: Mutex<Vec<Option<T>>> = Mutex::new(items.iter().map(|_| None).collect());
+    let workers = limit.clamp(1, items.len().max(1));
+    let pending = Mutex::new(items.into_iter().enumerate());
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let next = pending.lock().unwrap().next();
+                let Some((i, item)) = next else { break };
+                let output = run(item);
+                outputs.lock().unwrap()[i] = Some(output);
+            });
+        }
+    });
+    outputs
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(Option::unwrap)
+        .collect()
+}
+
+/// Build an [`AqlQuery`] whose bind parameters are checked at compile time.
+///
+/// The first argument must be a string literal holding the AQL query, followed
+/// by `"key" => value` pairs where each value implements
+/// `Into<serde_json::Value>`. Every `@param` and `@@collection` placeholder in
+/// the query must have a matching binding (use `"@collection"` as key for
+/// collection parameters), and every binding must be used by the query.
+/// Mismatches are reported as compile errors instead of failing on the server.
+///
+/// Placeholders inside string literals and comments are ignored.
+///
+/// # Example
+/// ```rust
+/// use arangors::aql;
+///
+/// let name = "test2";
+/// let query = aql!(
+///     "FOR u IN @@collection FILTER u.username == @name RETURN u",
+///     "@collection" => "test_collection",
+///     "name" => name,
+/// );
+/// ```
+///
+/// A typo in a placeholder fails the build:
+/// ```rust, compile_fail
+/// use arangors::aql;
+///
+/// let query = aql!("FOR u IN users FILTER u.username == @nmae RETURN u", "name" => "test2");
+/// ```
+#[cfg(feature = "aql_macro")]
+#[macro_export]
+macro_rules! aql {
+    ($query:literal $(, $key:literal => $value:expr)* $(,)?) => {{
+        const _: () = $crate::aql::validate_bind_vars($query, &[$($key),*]);
+        $crate::AqlQuery::builder()
+            .query($query)
+            $(.bind_var($key, $value))*
+            .build()
+    }};
+}
+
+/// Check that the bind parameters referenced in `query` and the supplied
+/// `keys` match one to one.
+///
+/// This is evaluated in const context by [`aql!`], so a mismatch becomes a
+/// compile error.
+#[cfg(feature = "aql_macro")]
+#[doc(hidden)]
+pub const fn validate_bind_vars(query: &str, keys: &[&str]) {
+    let query = query.as_bytes();
+
+    let mut pos = 0;
+    while let Some((start, end)) = next_bind_param(query, pos) {
+        let mut found = false;
+        let mut i = 0;
+        while i < keys.len() {
+            if bytes_eq_range(keys[i].as_bytes(), query, start, end) {
+                found = true;
+            }
+            i += 1;
+        }
+        if !found {
+            panic!("AQL query uses a bind parameter that has no binding");
+        }
+        pos = end;
+    }
+
+    let mut i = 0;
+    while i < keys.len() {
+        let key = keys[i].as_bytes();
+        let mut used = false;
+        let mut pos = 0;
+        while let Some((start, end)) = next_bind_param(query, pos) {
+            if bytes_eq_range(key, query, start, end) {
+                used = true;
+            }
+            pos = end;
+        }
+        if !used {
+            panic!("binding is not used by any bind parameter of the AQL query");
+        }
+        i += 1;
+    }
+}
+
+/// Find the next bind parameter at or after `pos`, returning the byte range of
+/// its name without the leading `@` (but including the second `@` of a
+/// collection parameter).
+#[cfg(feature = "aql_macro")]
+const fn next_bind_param(query: &[u8], mut pos: usize) -> Option<(usize, usize)> {
+    let len = query.len();
+    while pos < len {
+        let c = query[pos];
+        if c == b'\'' || c == b'"' || c == b'`' {
+            // skip string literals and quoted names
+            pos += 1;
+            while pos < len && query[pos] != c {
+                if query[pos] == b'\\' {
+                    pos += 1;
+                }
+                pos += 1;
+            }
+            pos += 1;
+        } else if c == b'/' && pos + 1 < len && query[pos + 1] == b'/' {
+            while pos < len && query[pos] != b'\n' {
+                pos += 1;
+            }
+        } else if c == b'/' && pos + 1 < len && query[pos + 1] == b'*' {
+            pos += 2;
+            while pos + 1 < len && !(query[pos] == b'*' && query[pos + 1] == b'/') {
+                pos += 1;
+            }
+            pos += 2;
+        } else if c == b'@' {
+            let start = pos + 1;
+            let mut end = start;
+            if end < len && query[end] == b'@' {
+                end += 1;
+            }
+            while end < len && (query[end].is_ascii_alphanumeric() || query[end] == b'_') {
+                end += 1;
+            }
+            return Some((start, end));
+        } else {
+            pos += 1;
+        }
+    }
+    None
+}
+
+#[cfg(feature = "aql_macro")]
+const fn bytes_eq_range(key: &[u8], query: &[u8], start: usize, end: usize) -> bool {
+    if key.len() != end - start {
+        return false;
+    }
+    let mut i = 0;
+    while i < key.len() {
+        if key[i] != query[start + i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::error::codes;
+
+    #[test]
+    fn escape_patterns() {
+        assert_eq!(escape_like(r"100% _sure\"), r"100\% \_sure\\");
+        assert_eq!(escape_like("plain"), "plain");
+        assert_eq!(
+            escape_regex(r"a.b*(c)[d]{2}|^e$+f?\"),
+            r"a\.b\*\(c\)\[d\]\{2\}\|\^e\$\+f\?\\"
+        );
+        assert_eq!(escape_regex("ünïcode-ok"), "ünïcode-ok");
+    }
+
+    #[test]
+    fn analyze_explain() {
+        let explained: ExplainedQuery = serde_json::from_value(serde_json::json!({
+            "plan": {
+                "nodes": [
+                    { "type": "SingletonNode", "id": 1 },
+                    {
+                        "type": "IndexNode",
+                        "id": 2,
+                        "collection": "users",
+                        "indexes": [{
+                            "id": "12",
+                            "name": "idx_name",
+                            "type": "persistent",
+                            "fields": ["name"],
+                            "unique": false
+                        }]
+                    },
+                    {
+                        "type": "SubqueryNode",
+                        "id": 3,
+                        "subquery": {
+                            "nodes": [
+                                { "type": "EnumerateCollectionNode", "id": 4, "collection": "logs" }
+                            ]
+                        }
+                    },
+                    { "type": "EnumerateCollectionNode", "id": 5, "collection": "logs" },
+                    { "type": "ReturnNode", "id": 6 }
+                ],
+                "rules": ["use-indexes", "remove-unnecessary-calculations"],
+                "estimatedCost": 12.5,
+                "estimatedNrItems": 3
+            },
+            "warnings": [],
+            "cacheable": true
+        }))
+        .unwrap();
+        let analysis = QueryAnalysis::try_from(explained).unwrap();
+        assert_eq!(
+            analysis.indexes_used,
+            vec![IndexUsage {
+                collection: "users".to_owned(),
+                id: "12".to_owned(),
+                name: "idx_name".to_owned(),
+                index_type: "persistent".to_owned(),
+                fields: vec!["name".to_owned()],
+            }]
+        );
+        assert_eq!(analysis.full_scans, vec!["logs".to_owned()]);
+        assert_eq!(analysis.rules.len(), 2);
+        assert_eq!(analysis.estimated_nr_items, 3);
+        assert!(matches!(
+            analysis.deny_full_scans(),
+            Err(ClientError::FullCollectionScan(scans)) if scans == ["logs"]
+        ));
+    }
+
+    #[test]
+    fn explained_query() {
+        let explained: ExplainedQuery = serde_json::from_value(serde_json::json!({
+            "plan": {
+                "nodes": [
+                    {
+                        "type": "SingletonNode",
+                        "dependencies": [],
+                        "id": 1,
+                        "estimatedCost": 1,
+                        "estimatedNrItems": 1
+                    },
+                    {
+                        "type": "EnumerateCollectionNode",
+                        "dependencies": [1],
+                        "id": 2,
+                        "estimatedCost": 12,
+                        "estimatedNrItems": 10,
+                        "database": "test_db",
+                        "collection": "users",
+                        "outVariable": { "id": 0, "name": "u" },
+                        "random": false
+                    },
+                    {
+                        "type": "ReturnNode",
+                        "dependencies": [2],
+                        "id": 3,
+                        "estimatedCost": 22,
+                        "estimatedNrItems": 10,
+                        "inVariable": { "id": 0, "name": "u" }
+                    }
+                ],
+                "rules": [],
+                "collections": [{ "name": "users", "type": "read" }],
+                "variables": [{ "id": 0, "name": "u" }],
+                "estimatedCost": 22,
+                "estimatedNrItems": 10,
+                "isModificationQuery": false
+            },
+            "warnings": [],
+            "stats": {
+                "rulesExecuted": 40,
+                "rulesSkipped": 0,
+                "plansCreated": 1,
+                "peakMemoryUsage": 32768,
+                "executionTime": 0.0002
+            },
+            "cacheable": true,
+            "error": false,
+            "code": 200
+        }))
+        .unwrap();
+        let plan = &explained.plan;
+        assert_eq!(plan.nodes.len(), 3);
+        assert_eq!(plan.nodes[1].node_type, "EnumerateCollectionNode");
+        assert_eq!(plan.nodes[1].dependencies, vec![1]);
+        assert_eq!(plan.nodes[1].collection(), Some("users"));
+        assert_eq!(plan.nodes[1].details["outVariable"]["name"], "u");
+        assert_eq!(plan.nodes[2].collection(), None);
+        assert_eq!(
+            plan.collections,
+            vec![PlanCollection {
+                name: "users".to_owned(),
+                access_type: "read".to_owned(),
+            }]
+        );
+        assert_eq!(plan.variables[0].name, "u");
+        assert_eq!(plan.estimated_cost, 22.0);
+        assert!(!plan.is_modification_query);
+        assert!(explained.cacheable);
+        assert_eq!(explained.stats.unwrap().plans_created, 1);
+    }
+
+    #[test]
+    fn aql_query_builder_bind_var() {
+        let q = r#"FOR i in test_collection FILTER i.username==@username AND i.password==@password return i"#;
+        let aql = AqlQuery::builder()
+            .query(q)
+            // test the first bind
+            .bind_var("username", "test2")
+            // test the second bind
+            .bind_var("password", "test2_pwd")
+            .count(true)
+            .batch_size(256)
+            .cache(false)
+            .memory_limit(100)
+            .ttl(10)
+            .build();
+        assert_eq!(aql.query, q);
+        assert_eq!(aql.count, Some(true));
+        assert_eq!(aql.batch_size, Some(256u32));
+        assert_eq!(aql.cache, Some(false));
+        assert_eq!(aql.memory_limit, Some(100));
+        assert_eq!(aql.ttl, Some(10));
+        assert_eq!(aql.options, None);
+
+        assert_eq!(
+            aql.bind_vars.get("username"),
+            Some(&Value::String("test2".to_owned()))
+        );
+        assert_eq!(
+            aql.bind_vars.get("password"),
+            Some(&Value::String("test2_pwd".to_owned()))
+        );
+    }
+
+    #[test]
+    fn aql_query_builder_bind_vars_owned_keys() {
+        let q = r#"FOR i in test_collection FILTER i.username==@username return i"#;
+        let mut borrowed: HashMap<&str, Value> = HashMap::new();
+        borrowed.insert("username", "test2".into());
+        let aql = AqlQuery::builder().query(q).bind_vars(borrowed).build();
+        assert_eq!(aql.bind_vars.get("username"), Some(&Value::from("test2")));
+
+        let key = format!("user{}", "name");
+        let aql = AqlQuery::builder()
+            .query(q)
+            .bind_vars(vec![(key.clone(), Value::from("test2"))])
+            .bind_var(format!("pass{}", "word"), "test2_pwd")
+            .build();
+        assert_eq!(aql.bind_vars.get("username"), Some(&Value::from("test2")));
+        assert_eq!(
+            aql.bind_vars.get("password"),
+            Some(&Value::from("test2_pwd"))
+        );
+    }
+
+    #[test]
+    fn aql_options_use_plan_cache() {
+        let options = AqlOptions::builder().use_plan_cache(true).build();
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({ "usePlanCache": true })
+        );
+    }
+
+    #[test]
+    fn prepared_query_bind() {
+        let prepared = PreparedQuery::new(
+            AqlQuery::builder()
+                .query("FOR u IN users FILTER u.age > @age RETURN u")
+                .batch_size(10)
+                .options(AqlOptions::builder().use_plan_cache(true).build())
+                .build(),
+        );
+        let aql = prepared.bind([("age", Value::from(30))]);
+        assert_eq!(
+            serde_json::to_value(&aql).unwrap(),
+            serde_json::json!({
+                "query": "FOR u IN users FILTER u.age > @age RETURN u",
+                "bindVars": { "age": 30 },
+                "batchSize": 10,
+                "options": { "usePlanCache": true },
+            })
+        );
+    }
+
+    #[maybe_async::maybe_async]
+    async fn tracked(i: usize, running: &AtomicUsize, max_running: &AtomicUsize) -> usize {
+        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+        max_running.fetch_max(now, Ordering::SeqCst);
+        crate::util::sleep(Duration::from_millis(5)).await;
+        running.fetch_sub(1, Ordering::SeqCst);
+        i * 2
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn run_bounded_in_order() {
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        let outputs =
+            run_bounded((0..10).collect(), 3, |i| tracked(i, &running, &max_running)).await;
+        assert_eq!(outputs, (0..10).map(|i| i * 2).collect::<Vec<_>>());
+        assert!(max_running.load(Ordering::SeqCst) <= 3);
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn slow_query_log() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reported);
+        let log = SlowQueryLog::new(Duration::from_millis(100)).callback(move |slow| {
+            sink.lock().unwrap().push((
+                slow.query.to_owned(),
+                slow.bind_vars.is_some(),
+                slow.duration,
+            ));
+        });
+        let aql = AqlQuery::builder()
+            .query("FOR u IN users FILTER u.email == @email RETURN u")
+            .bind_var("email", "alice@example.com")
+            .build();
+
+        log.record(&aql, Duration::from_millis(99), None);
+        log.record(&aql, Duration::from_millis(250), None);
+        log.clone()
+            .redact_bind_vars(false)
+            .record(&aql, Duration::from_millis(100), None);
+        assert_eq!(
+            *reported.lock().unwrap(),
+            vec![
+                (aql.query.to_owned(), false, Duration::from_millis(250)),
+                (aql.query.to_owned(), true, Duration::from_millis(100)),
+            ]
+        );
+    }
+
+    #[test]
+    fn aql_options_profile() {
+        let options = AqlOptions::builder().profile(true).build();
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({ "profile": 1 })
+        );
+        let options = AqlOptions::builder().profile(2).build();
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({ "profile": 2 })
+        );
+        assert_eq!(ProfileLevel::from(false), ProfileLevel::Disabled);
+        assert_eq!(ProfileLevel::from(7), ProfileLevel::Nodes);
+    }
+
+    #[test]
+    fn query_extra_profile() {
+        let extra: QueryExtra = serde_json::from_value(serde_json::json!({
+            "stats": {
+                "writesExecuted": 0,
+                "writesIgnored": 0,
+                "scannedFull": 3,
+                "scannedIndex": 0,
+                "filtered": 0,
+                "httpRequests": 0,
+                "executionTime": 0.001,
+                "peakMemoryUsage": 32768,
+                "nodes": [{ "id": 1, "calls": 1, "items": 3, "runtime": 0.0001 }]
+            },
+            "warnings": [],
+            "profile": { "parsing": 0.0002, "executing": 0.0004 },
+            "plan": { "nodes": [] }
+        }))
+        .unwrap();
+        let stats = extra.stats.unwrap();
+        assert_eq!(stats.memory_usage, None);
+        assert_eq!(stats.peak_memory_usage, Some(32768));
+        assert_eq!(stats.nodes.unwrap()[0].items, 3);
+        assert_eq!(extra.profile.unwrap()["executing"], 0.0004);
+        assert!(extra.plan.is_some());
+    }
+
+    #[test]
+    fn aql_query_builder_try_bind() {
+        #[derive(Serialize, Deserialize, Debug)]
+        struct User {
+            pub username: String,
+            pub password: String,
+        }
+        let user = User {
+            username: "test2".to_owned(),
+            password: "test2_pwd".to_owned(),
+        };
+        let q = r#"FOR i in test_collection FILTER i==@user return i"#;
+        let aql = AqlQuery::builder()
+            .query(q)
+            .try_bind("user", user)
+            .unwrap()
+            .build();
+
+        assert_eq!(aql.query, q);
+        assert_eq!(aql.count, None);
+        assert_eq!(aql.batch_size, None);
+
+        let mut map = serde_json::Map::new();
+        map.insert("username".into(), "test2".into());
+        map.insert("password".into(), "test2_pwd".into());
+
+        assert_eq!(aql.bind_vars.get("user"), Some(&Value::Object(map)));
+
+        let aql = AqlQuery::builder()
+            .query(r#"FOR i in test_collection FILTER i.username==@username AND i.password==@password return i"#)
+            // test the first bind
+            .try_bind("username", "test2")
+            .unwrap()
+            // test the second bind
+            .try_bind("password", "test2_pwd")
+            .unwrap()
+            .build();
+
+        assert_eq!(
+            aql.bind_vars.get("username"),
+            Some(&Value::String("test2".to_owned()))
+        );
+        assert_eq!(
+            aql.bind_vars.get("password"),
+            Some(&Value::String("test2_pwd".to_owned()))
+        );
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn cursor_guard_deletes_abandoned_cursor() {
+        use crate::{client::mock::RecordingClient, Database};
+
+        let session = Arc::new(RecordingClient::default());
+        let arango_url: Url = "http://localhost:8529/".parse().unwrap();
+        let db = Database::new("test_db", &arango_url, Arc::clone(&session));
+
+        let guard = CursorGuard::new(
+            Arc::clone(&session),
+            db.url().clone(),
+            Some("1234".to_owned()),
+            Arc::clone(&db.pending_cursors),
+        );
+        drop(guard);
+        let mut guard = CursorGuard::new(
+            Arc::clone(&session),
+            db.url().clone(),
+            Some("5678".to_owned()),
+            Arc::clone(&db.pending_cursors),
+        );
+        guard.disarm();
+        drop(guard);
+
+        // deleted on drop in blocking mode, on close in async mode
+        #[cfg(not(feature = "blocking"))]
+        {
+            assert_eq!(*db.pending_cursors.lock().unwrap(), vec!["1234".to_owned()]);
+            assert!(session.take_requests().is_empty());
+        }
+        db.close().await;
+        let requests = session.take_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method(), Method::DELETE);
+        assert_eq!(
+            requests[0].uri(),
+            "http://localhost:8529/_db/test_db/_api/cursor/1234"
+        );
+        assert!(db.pending_cursors.lock().unwrap().is_empty());
+
+        // nothing is left to delete
+        db.close().await;
+        assert!(session.take_requests().is_empty());
+    }
+
+    #[cfg(all(
+        not(feature = "blocking"),
+        any(feature = "reqwest_async", feature = "surf_async", feature = "tower")
+    ))]
+    #[tokio::test]
+    async fn cursor_stream_as_stream() {
+        use futures_lite::StreamExt;
+
+        use crate::{client::mock::RecordingClient, Database};
+
+        let session = Arc::new(RecordingClient::default());
+        let arango_url: Url = "http://localhost:8529/".parse().unwrap();
+        let db = Database::new("test_db", &arango_url, Arc::clone(&session));
+        session.respond(
+            201,
+            r#"{"result":[1,2],"hasMore":true,"id":"42","cached":false,"error":false,"code":201}"#,
+        );
+        session.respond(
+            200,
+            r#"{"result":[3],"hasMore":false,"id":"42","cached":false,"error":false,"code":200}"#,
+        );
+
+        let aql = AqlQuery::builder().query("FOR i IN 1..3 RETURN i").build();
+        let stream = db.aql_query_stream::<u32>(aql).await.unwrap();
+        let results: Vec<u32> = stream.map(Result::unwrap).collect().await;
+        assert_eq!(results, vec![1, 2, 3]);
+        let requests = session.take_requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[1].uri(),
+            "http://localhost:8529/_db/test_db/_api/cursor/42"
+        );
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn prefetch_batches() {
+        use crate::{client::mock::RecordingClient, Database};
+
+        let session = Arc::new(RecordingClient::default());
+        let arango_url: Url = "http://localhost:8529/".parse().unwrap();
+        let mut db = Database::new("test_db", &arango_url, Arc::clone(&session));
+        db.set_batch_prefetch(true);
+
+        session.respond(
+            201,
+            r#"{"result":[1,2],"hasMore":true,"id":"42","cached":false,"error":false,"code":201}"#,
+        );
+        session.respond(
+            200,
+            r#"{"result":[3],"hasMore":false,"id":"42","cached":false,"error":false,"code":200}"#,
+        );
+        let results: Vec<u32> = db.aql_str("FOR i IN 1..3 RETURN i").await.unwrap();
+        assert_eq!(results, vec![1, 2, 3]);
+        let requests = session.take_requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method(), Method::PUT);
+        assert_eq!(
+            requests[1].uri(),
+            "http://localhost:8529/_db/test_db/_api/cursor/42"
+        );
+
+        session.respond(
+            400,
+            r#"{"error":true,"code":400,"errorNum":1501,"errorMessage":"syntax error"}"#,
+        );
+        match db.aql_str::<u32>("FOR i IN").await {
+            Err(ClientError::Arango(err)) => assert_eq!(err.error_num(), codes::ERROR_QUERY_PARSE),
+            other => panic!("expected the server error, got {:?}", other),
+        }
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn prefetch_overlaps_decoding() {
+        use std::time::{Duration, Instant};
+
+        use crate::{client::mock::RecordingClient, Database};
+
+        const LATENCY: Duration = Duration::from_millis(40);
+        const BATCHES: u32 = 5;
+
+        /// A result taking as long to decode as a round trip to the server.
+        #[derive(Debug, PartialEq)]
+        struct Slow(u32);
+
+        impl<'de> Deserialize<'de> for Slow {
+            fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                std::thread::sleep(LATENCY);
+                u32::deserialize(d).map(Slow)
+            }
+        }
+
+        #[maybe_async::maybe_async]
+        async fn fetch_all(db: &Database<RecordingClient>, session: &RecordingClient) -> Duration {
+            for i in 1..=BATCHES {
+                session.respond(
+                    200,
+                    format!(
+                        r#"{{"result":[{}],"hasMore":{},"id":"42","cached":false,"error":false,"code":200}}"#,
+                        i,
+                        i < BATCHES
+                    ),
+                );
+            }
+            let start = Instant::now();
+            let results: Vec<Slow> = db.aql_str("FOR i IN 1..5 RETURN i").await.unwrap();
+            let elapsed = start.elapsed();
+            assert_eq!(results, (1..=BATCHES).map(Slow).collect::<Vec<_>>());
+            // no cursor deleted while it is being read
+            let requests = session.take_requests();
+            assert_eq!(requests.len(), BATCHES as usize);
+            assert!(requests.iter().all(|r| r.method() != Method::DELETE));
+            elapsed
+        }
+
+        let session = Arc::new(RecordingClient::with_latency(LATENCY));
+        let arango_url: Url = "http://localhost:8529/".parse().unwrap();
+        let mut db = Database::new("test_db", &arango_url, Arc::clone(&session));
+        // one round trip and one decoding per batch
+        let sequential = fetch_all(&db, &session).await;
+        db.set_batch_prefetch(true);
+        // the round trips after the first one overlap with the decoding
+        let prefetch = fetch_all(&db, &session).await;
+        assert!(
+            prefetch + LATENCY * 2 < sequential,
+            "prefetch {:?}, sequential {:?}",
+            prefetch,
+            sequential
+        );
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn next_batches_carry_the_context() {
+        use crate::{client::mock::RecordingClient, Database};
+
+        let session = Arc::new(RecordingClient::default());
+        let arango_url: Url = "http://localhost:8529/".parse().unwrap();
+        let db = Database::new("test_db", &arango_url, Arc::clone(&session));
+
+        session.respond(
+            201,
+            r#"{"result":[1],"hasMore":true,"id":"42","cached":false,"error":false,"code":201}"#,
+        );
+        session.respond(
+            200,
+            r#"{"result":[2],"hasMore":true,"id":"42","cached":false,"error":false,"code":200}"#,
+        );
+        session.respond(
+            200,
+            r#"{"result":[3],"hasMore":false,"id":"42","cached":false,"error":false,"code":200}"#,
+        );
+        let context = QueryContext::builder()
+            .transaction_id("1234")
+            .allow_dirty_read(true)
+            .build();
+        let aql = AqlQuery::builder()
+            .query("FOR i IN 1..3 RETURN i")
+            .batch_size(1)
+            .options(AqlOptions::builder().context(context.clone()).build())
+            .build();
+        let results: Vec<u32> = db.aql_query(aql).await.unwrap();
+        assert_eq!(results, vec![1, 2, 3]);
+
+        let requests = session.take_requests();
+        assert_eq!(requests.len(), 3);
+        for request in &requests {
+            assert_eq!(request.headers()[TRANSACTION_HEADER], "1234");
+            assert_eq!(request.headers()[DIRTY_READ_HEADER], "true");
+        }
+
+        session.respond(
+            200,
+            r#"{"result":[],"hasMore":false,"cached":false,"error":false,"code":200}"#,
+        );
+        let _: Cursor<u32> = db.aql_next_batch_with("42", &context).await.unwrap();
+        let requests = session.take_requests();
+        assert_eq!(requests[0].method(), Method::PUT);
+        assert_eq!(requests[0].headers()[TRANSACTION_HEADER], "1234");
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn query_within_keeps_the_context() {
+        use crate::{client::mock::RecordingClient, Database};
+
+        let session = Arc::new(RecordingClient::default());
+        let arango_url: Url = "http://localhost:8529/".parse().unwrap();
+        let db = Database::new("test_db", &arango_url, Arc::clone(&session));
+
+        session.respond(
+            201,
+            r#"{"result":[1],"hasMore":true,"id":"42","cached":false,"error":false,"code":201}"#,
+        );
+        session.respond(
+            200,
+            r#"{"result":[2],"hasMore":false,"id":"42","cached":false,"error":false,"code":200}"#,
+        );
+        let context = QueryContext::builder().transaction_id("1234").build();
+        let aql = AqlQuery::builder()
+            .query("FOR i IN 1..2 RETURN i")
+            .batch_size(1)
+            .options(AqlOptions::builder().context(context.clone()).build())
+            .build();
+        let partial: PartialResults<u32> = db.aql_query_within(aql, Duration::ZERO).await.unwrap();
+        assert_eq!(partial.result, vec![1]);
+        assert_eq!(partial.context, context);
+
+        let cursor_id = partial.cursor_id.unwrap();
+        let budget = Duration::from_secs(60);
+        let rest: PartialResults<u32> = db
+            .aql_next_batches_within(&cursor_id, &partial.context, budget)
+            .await
+            .unwrap();
+        assert_eq!(rest.result, vec![2]);
+        assert!(rest.is_complete());
+        for request in session.take_requests() {
+            assert_eq!(request.headers()[TRANSACTION_HEADER], "1234");
+        }
+    }
+
+    #[cfg(feature = "aql_macro")]
+    #[test]
+    fn aql_macro_bind_vars() {
+        let aql = crate::aql!(
+            "FOR u IN @@collection FILTER u.username == @username RETURN u",
+            "@collection" => "test_collection",
+            "username" => "test2",
+        );
+        assert_eq!(
+            aql.bind_vars.get("@collection"),
+            Some(&Value::String("test_collection".to_owned()))
+        );
+        assert_eq!(
+            aql.bind_vars.get("username"),
+            Some(&Value::String("test2".to_owned()))
+        );
+
+        let aql = crate::aql!("FOR u IN test_collection FILTER u.name == '@ignored' RETURN u");
+        assert!(aql.bind_vars.is_empty());
+    }
+
+    #[test]
+    fn warning_policy() {
+        let extra: QueryExtra = serde_json::from_str(
+            r#"{"stats":null,"warnings":[{"code":1562,"message":"division by zero"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(WarningPolicy::default(), WarningPolicy::Ignore);
+        assert!(check_warnings(WarningPolicy::Ignore, Some(&extra)).is_ok());
+        assert!(check_warnings(WarningPolicy::Log, Some(&extra)).is_ok());
+        match check_warnings(WarningPolicy::Fail, Some(&extra)) {
+            Err(ClientError::AqlWarnings(warnings)) => {
+                assert_eq!(warnings[0].code, 1562);
+                assert_eq!(warnings[0].message, "division by zero");
+            }
+            other => panic!("expected AQL warnings, got {:?}", other),
+        }
+
+        let extra: QueryExtra = serde_json::from_str(r#"{"warnings":[]}"#).unwrap();
+        assert!(check_warnings(WarningPolicy::Fail, Some(&extra)).is_ok());
+        assert!(check_warnings(WarningPolicy::Fail, None).is_ok());
+    }
+
+    #[test]
+    fn query_context_headers() {
+        let url = Url::parse("http://localhost:8529/_api/cursor").unwrap();
+        let req = QueryContext::default()
+            .request(Method::POST, url.clone(), String::new())
+            .unwrap();
+        assert!(req.headers().is_empty());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-arango-trx-id", HeaderValue::from_static("1"));
+        headers.insert("x-arango-queue-time-seconds", HeaderValue::from_static("5"));
+        let context = QueryContext::builder()
+            .transaction_id("42")
+            .allow_dirty_read(true)
+            .headers(headers)
+            .build();
+        let aql = AqlQuery::builder()
+            .query("RETURN 1")
+            .options(AqlOptions::builder().context(context.clone()).build())
+            .build();
+        assert_eq!(aql.context(), context);
+
+        let req = context.request(Method::PUT, url, String::new()).unwrap();
+        assert_eq!(req.method(), Method::PUT);
+        assert_eq!(req.headers()[TRANSACTION_HEADER], "42");
+        assert_eq!(req.headers()[DIRTY_READ_HEADER], "true");
+        assert_eq!(req.headers()["x-arango-queue-time-seconds"], "5");
+    }
+
+    #[cfg(feature = "aql_macro")]
+    #[test]
+    #[should_panic]
+    fn validate_bind_vars_missing_binding() {
+        validate_bind_vars("FOR u IN users FILTER u.name == @nmae RETURN u", &["name"]);
+    }
+
+    #[cfg(feature = "aql_macro")]
+    #[test]
+    #[should_panic]
+    fn validate_bind_vars_unused_binding() {
+        validate_bind_vars("FOR u IN @@users RETURN u", &["@users", "name"]);
+    }
+
+    #[test]
+    fn raw_batch_decodes_one_result_at_a_time() {
+        let body = r#"{"result": [ 1,2 , {"a": [3, "]"]},
+            "x" ], "hasMore": true, "id": "42", "count": 4, "cached": false,
+            "extra": {"warnings": []}}"#;
+        let mut batch = RawBatch::<Value>::parse(body.to_owned()).unwrap();
+        assert!(batch.more());
+        assert_eq!(batch.id(), Some("42"));
+        assert_eq!(batch.total_count(), Some(4));
+        assert!(batch.extra().is_some());
+        assert_eq!(batch.next().unwrap().unwrap(), Value::from(1));
+        assert_eq!(batch.next().unwrap().unwrap(), Value::from(2));
+        assert_eq!(
+            batch.next().unwrap().unwrap(),
+            serde_json::json!({"a": [3, "]"]})
+        );
+        assert_eq!(batch.next().unwrap().unwrap(), Value::from("x"));
+        assert!(batch.next().is_none());
+        assert!(batch.next().is_none());
+
+        let empty = r#"{"result":[],"hasMore":false,"cached":true}"#;
+        let mut batch = RawBatch::<Value>::parse(empty.to_owned()).unwrap();
+        assert!(batch.cached());
+        assert!(batch.next().is_none());
+
+        let mut batch = RawBatch::<u8>::parse(
+            r#"{"result":[1,"a",3],"hasMore":false,"cached":false}"#.to_owned(),
+        )
+        .unwrap();
+        assert_eq!(batch.next().unwrap().unwrap(), 1);
+        assert!(batch.next().unwrap().is_err());
+        assert!(batch.next().is_none());
+
+        let not_array = r#"{"result":{},"hasMore":false,"cached":false}"#;
+        assert!(RawBatch::<Value>::parse(not_array.to_owned()).is_err());
+    }
+
+    #[test]
+    fn raw_batch_from_error_response() {
+        let resp = http::Response::builder()
+            .status(400)
+            .body(
+                r#"{"error":true,"code":400,"errorNum":1501,"errorMessage":"syntax error"}"#
+                    .to_owned(),
+            )
+            .unwrap();
+        let err = RawBatch::<Value>::from_response(resp).unwrap_err();
+        assert!(
+            matches!(err, ClientError::Arango(err) if err.error_num() == codes::ERROR_QUERY_PARSE)
         );
     }
 }
@@ -0,0 +1,340 @@
+//! HTTP client wrapper for active-failover deployments
+//!
+//! Followers of an active-failover deployment answer requests with
+//! `503 Service Unavailable` and advertise the current leader in the
+//! `X-Arango-Endpoint` header. [`FailoverClient`] retries such requests
+//! against the advertised leader. With a [`ReadPreference`] it can also let
+//! followers answer reads.
+use std::sync::{Arc, RwLock};
+
+use http::{
+    header::HeaderMap, uri::PathAndQuery, HeaderValue, Method, Request, Response, StatusCode, Uri,
+    Version,
+};
+
+use super::ClientExt;
+use crate::{aql::DIRTY_READ_HEADER, ClientError};
+
+/// Header advertising the endpoint of the leader
+pub const ENDPOINT_HEADER: &str = "x-arango-endpoint";
+
+/// Where the reads of a [`FailoverClient`] are sent.
+///
+/// Reads are the `GET` and `HEAD` requests, like reading documents or
+/// collections, and the requests allowing dirty reads with the
+/// `x-arango-allow-dirty-read` header. To let followers answer an AQL query
+/// and the next batches of its cursor, set `allow_dirty_read` on its
+/// `QueryContext`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadPreference {
+    /// Send all requests to the leader.
+    #[default]
+    LeaderOnly,
+    /// Let the endpoint of the connection answer reads even if it is a
+    /// follower, possibly with outdated data. A read failing with a server
+    /// error or without a response is sent again to the leader.
+    PreferFollower,
+}
+
+/// Wrap an HTTP client to follow the leader of an active-failover
+/// deployment.
+///
+/// A request answered by a follower is sent again once to the leader it
+/// advertises. By default the leader is remembered and used for all later
+/// requests of the connection, database and collection handles sharing this
+/// client, as if their base url was updated. Use
+/// [`set_follow_leader`](FailoverClient::set_follow_leader) to only retry
+/// the redirected requests.
+///
+/// Reads go to the leader as well unless the [`ReadPreference`] is changed
+/// with [`set_read_preference`](FailoverClient::set_read_preference). The
+/// preference is shared by all clones of the client, so it applies to the
+/// whole connection and can be changed through `Connection::session()`.
+///
+/// # Example
+/// ```rust, ignore
+/// use arangors::{client::{failover::FailoverClient, reqwest::ReqwestClient}, GenericConnection};
+///
+/// let conn = GenericConnection::<FailoverClient<ReqwestClient>>::establish_jwt(
+///     "http://follower:8529",
+///     "username",
+///     "password",
+/// )
+/// .await
+/// .unwrap();
+/// conn.session().set_read_preference(ReadPreference::PreferFollower);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FailoverClient<C: ClientExt> {
+    client: C,
+    follow_leader: bool,
+    leader: Arc<RwLock<Option<Uri>>>,
+    read_preference: Arc<RwLock<ReadPreference>>,
+}
+
+impl<C: ClientExt> FailoverClient<C> {
+    /// Wrap an existing client.
+    pub fn wrap(client: C) -> Self {
+        FailoverClient {
+            client,
+            follow_leader: true,
+            leader: Arc::new(RwLock::new(None)),
+            read_preference: Arc::new(RwLock::new(ReadPreference::default())),
+        }
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.client
+    }
+
+    /// Whether the last advertised leader is used for all requests.
+    pub fn follow_leader(&self) -> bool {
+        self.follow_leader
+    }
+
+    /// Enable or disable sending all requests to the last advertised leader.
+    /// Enabled by default.
+    pub fn set_follow_leader(&mut self, enabled: bool) {
+        self.follow_leader = enabled;
+    }
+
+    /// The last leader advertised by a follower, as `scheme://host:port`.
+    pub fn leader(&self) -> Option<Uri> {
+        self.leader.read().unwrap().clone()
+    }
+
+    /// Where reads are sent.
+    pub fn read_preference(&self) -> ReadPreference {
+        *self.read_preference.read().unwrap()
+    }
+
+    /// Set where reads are sent, for this client and all its clones.
+    pub fn set_read_preference(&self, preference: ReadPreference) {
+        *self.read_preference.write().unwrap() = preference;
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async::maybe_async)]
+#[cfg_attr(target_arch = "wasm32", maybe_async::maybe_async(?Send))]
+impl<C: ClientExt> ClientExt for FailoverClient<C> {
+    fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+        C::new(headers).map(FailoverClient::wrap)
+    }
+
+    fn headers(&mut self) -> &mut HeaderMap<HeaderValue> {
+        self.client.headers()
+    }
+
+    fn set_authorization(&self, value: HeaderValue) -> Result<(), ClientError> {
+        self.client.set_authorization(value)
+    }
+
+    fn with_authorization(&self, value: HeaderValue) -> Result<Self, ClientError> {
+        Ok(FailoverClient {
+            client: self.client.with_authorization(value)?,
+            ..self.clone()
+        })
+    }
+
+    async fn request(&self, request: Request<String>) -> Result<Response<String>, ClientError> {
+        let (parts, body) = request.into_parts();
+        let (method, version, headers) = (parts.method, parts.version, parts.headers);
+
+        let mut headers = headers;
+        let dirty_read = headers
+            .get(DIRTY_READ_HEADER)
+            .is_some_and(|value| value == "true");
+        let read = dirty_read || [Method::GET, Method::HEAD].contains(&method);
+        if read && self.read_preference() == ReadPreference::PreferFollower {
+            headers.insert(DIRTY_READ_HEADER, HeaderValue::from_static("true"));
+            let request = rebuild(&method, parts.uri.clone(), version, &headers, body.clone());
+            match self.client.request(request).await {
+                Ok(resp) if !resp.status().is_server_error() => return Ok(resp),
+                // fall back to the leader, which needs no dirty read
+                _ => {
+                    headers.remove(DIRTY_READ_HEADER);
+                }
+            }
+        }
+
+        let uri = match self.leader().filter(|_| self.follow_leader) {
+            Some(leader) => with_endpoint(&parts.uri, &leader)?,
+            None => parts.uri,
+        };
+        let resp = self
+            .client
+            .request(rebuild(
+                &method,
+                uri.clone(),
+                version,
+                &headers,
+                body.clone(),
+            ))
+            .await?;
+
+        match leader_endpoint(&resp)? {
+            Some(leader) => {
+                let uri = with_endpoint(&uri, &leader)?;
+                if self.follow_leader {
+                    *self.leader.write().unwrap() = Some(leader);
+                }
+                self.client
+                    .request(rebuild(&method, uri, version, &headers, body))
+                    .await
+            }
+            None => Ok(resp),
+        }
+    }
+}
+
+fn rebuild(
+    method: &Method,
+    uri: Uri,
+    version: Version,
+    headers: &HeaderMap,
+    body: String,
+) -> Request<String> {
+    let mut request = Request::new(body);
+    *request.method_mut() = method.clone();
+    *request.uri_mut() = uri;
+    *request.version_mut() = version;
+    *request.headers_mut() = headers.clone();
+    request
+}
+
+/// The leader advertised by a follower refusing a request.
+fn leader_endpoint(resp: &Response<String>) -> Result<Option<Uri>, ClientError> {
+    if resp.status() != StatusCode::SERVICE_UNAVAILABLE {
+        return Ok(None);
+    }
+    resp.headers()
+        .get(ENDPOINT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(parse_endpoint)
+        .transpose()
+}
+
+/// Convert an ArangoDB endpoint like `tcp://host:port` or `ssl://host:port`
+/// into an HTTP uri.
+fn parse_endpoint(endpoint: &str) -> Result<Uri, ClientError> {
+    let endpoint = match endpoint.split_once("://") {
+        Some(("tcp", rest)) => format!("http://{}", rest),
+        Some(("ssl", rest)) => format!("https://{}", rest),
+        _ => endpoint.to_owned(),
+    };
+    endpoint
+        .parse()
+        .map_err(|_| ClientError::InvalidServer(format!("invalid leader endpoint: {}", endpoint)))
+}
+
+/// Replace scheme and authority of `uri` with the ones of `endpoint`.
+fn with_endpoint(uri: &Uri, endpoint: &Uri) -> Result<Uri, ClientError> {
+    let mut parts = endpoint.clone().into_parts();
+    parts.path_and_query = uri
+        .path_and_query()
+        .cloned()
+        .or_else(|| Some(PathAndQuery::from_static("/")));
+    Uri::from_parts(parts).map_err(|e| ClientError::HttpClient(format!("{:?}", e)))
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Client failing the dirty reads and recording the requests.
+    #[derive(Debug, Clone, Default)]
+    struct FollowerDownClient {
+        headers: HeaderMap,
+        dirty_reads: Arc<Mutex<Vec<bool>>>,
+    }
+
+    #[maybe_async::maybe_async]
+    impl ClientExt for FollowerDownClient {
+        fn new<U: Into<Option<HeaderMap>>>(_: U) -> Result<Self, ClientError> {
+            Ok(FollowerDownClient::default())
+        }
+
+        fn headers(&mut self) -> &mut HeaderMap<HeaderValue> {
+            &mut self.headers
+        }
+
+        async fn request(&self, request: Request<String>) -> Result<Response<String>, ClientError> {
+            let dirty = request.headers().contains_key(DIRTY_READ_HEADER);
+            self.dirty_reads.lock().unwrap().push(dirty);
+            let status = if dirty { 500 } else { 200 };
+            Ok(Response::builder()
+                .status(status)
+                .body(String::new())
+                .unwrap())
+        }
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn read_preference_falls_back_to_leader() {
+        let inner = FollowerDownClient::default();
+        let client = FailoverClient::wrap(inner.clone());
+        client
+            .clone()
+            .set_read_preference(ReadPreference::PreferFollower);
+        assert_eq!(client.read_preference(), ReadPreference::PreferFollower);
+
+        let get = Request::get("http://follower:8529/_api/version")
+            .body(String::new())
+            .unwrap();
+        let resp = client.request(get).await.unwrap();
+        assert_eq!(resp.status(), 200);
+        assert_eq!(*inner.dirty_reads.lock().unwrap(), vec![true, false]);
+
+        inner.dirty_reads.lock().unwrap().clear();
+        let post = Request::post("http://follower:8529/_api/cursor")
+            .body(String::new())
+            .unwrap();
+        client.request(post).await.unwrap();
+        assert_eq!(*inner.dirty_reads.lock().unwrap(), vec![false]);
+
+        // a cursor allowing dirty reads is read from the follower as well
+        inner.dirty_reads.lock().unwrap().clear();
+        let next_batch = Request::put("http://follower:8529/_api/cursor/1234")
+            .header(DIRTY_READ_HEADER, "true")
+            .body(String::new())
+            .unwrap();
+        let resp = client.request(next_batch).await.unwrap();
+        assert_eq!(resp.status(), 200);
+        assert_eq!(*inner.dirty_reads.lock().unwrap(), vec![true, false]);
+    }
+
+    #[test]
+    fn leader_endpoint_from_follower() {
+        let resp = Response::builder()
+            .status(503)
+            .header(ENDPOINT_HEADER, "tcp://10.0.0.2:8529")
+            .body(String::new())
+            .unwrap();
+        let leader = leader_endpoint(&resp).unwrap().unwrap();
+        assert_eq!(leader, "http://10.0.0.2:8529/");
+
+        let uri: Uri = "http://10.0.0.1:8529/_db/test_db/_api/cursor?x=1"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            with_endpoint(&uri, &leader).unwrap(),
+            "http://10.0.0.2:8529/_db/test_db/_api/cursor?x=1"
+        );
+
+        let leader = parse_endpoint("ssl://leader:8530").unwrap();
+        assert_eq!(
+            with_endpoint(&uri, &leader).unwrap(),
+            "https://leader:8530/_db/test_db/_api/cursor?x=1"
+        );
+
+        let resp = Response::builder()
+            .status(200)
+            .header(ENDPOINT_HEADER, "tcp://10.0.0.2:8529")
+            .body(String::new())
+            .unwrap();
+        assert!(leader_endpoint(&resp).unwrap().is_none());
+    }
+}
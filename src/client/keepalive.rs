@@ -0,0 +1,256 @@
+//! HTTP client wrapper re-establishing idle connections
+//!
+//! Load balancers and firewalls often drop idle TCP connections silently. The
+//! next request on such a pooled connection then fails with a connection
+//! reset. [`KeepAliveClient`] checks on use how long the client has been idle
+//! and starts over with fresh connections after the configured idle timeout.
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::Duration,
+};
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+use http::{header::HeaderMap, HeaderValue, Method, Request, Response};
+
+use super::{ClientExt, SharedAuthorization};
+use crate::ClientError;
+
+/// Wrap an HTTP client to re-establish its connections after an idle period.
+///
+/// The idle check is disabled by default, enable it with
+/// [`set_idle_timeout`](KeepAliveClient::set_idle_timeout). The setting is
+/// shared by all clones of the client, so it can be changed through
+/// `Connection::session()`.
+///
+/// When enabled, the first request after the idle timeout is sent with a new
+/// inner client. Clients built with [`ClientExt::new`] start over with
+/// `C::new(None)`, use [`wrap`](KeepAliveClient::wrap) to keep the settings of
+/// a configured client. Idempotent requests (`GET`, `HEAD` and `OPTIONS`) failing
+/// with an HTTP client error are also retried once with a new inner client.
+///
+/// # Example
+/// ```rust, ignore
+/// use std::time::Duration;
+/// use arangors::{client::{keepalive::KeepAliveClient, reqwest::ReqwestClient}, GenericConnection};
+///
+/// let conn = GenericConnection::<KeepAliveClient<ReqwestClient>>::establish_jwt(
+///     "http://localhost:8529",
+///     "username",
+///     "password",
+/// )
+/// .await
+/// .unwrap();
+/// conn.session().set_idle_timeout(Some(Duration::from_secs(60)));
+/// ```
+#[derive(Clone)]
+pub struct KeepAliveClient<C: ClientExt + Send + 'static> {
+    client: Arc<RwLock<C>>,
+    factory: Arc<dyn Fn() -> Result<C, ClientError> + Send + Sync>,
+    headers: HeaderMap,
+    authorization: SharedAuthorization,
+    /// idle timeout in milliseconds, 0 when disabled
+    idle_timeout: Arc<AtomicU64>,
+    last_used: Arc<Mutex<Instant>>,
+}
+
+impl<C: ClientExt + Send + 'static> KeepAliveClient<C> {
+    /// Wrap an existing client, building its replacements with `factory`.
+    ///
+    /// ```rust, ignore
+    /// use arangors::client::{
+    ///     keepalive::KeepAliveClient,
+    ///     reqwest::{ReqwestClient, ReqwestOptions},
+    /// };
+    ///
+    /// let options = ReqwestOptions::builder()
+    ///     .timeout(Duration::from_secs(10))
+    ///     .build();
+    /// let client = ReqwestClient::with_options(None, options.clone())?;
+    /// let client = KeepAliveClient::wrap(client, move || {
+    ///     ReqwestClient::with_options(None, options.clone())
+    /// });
+    /// ```
+    pub fn wrap<F>(client: C, factory: F) -> Self
+    where
+        F: Fn() -> Result<C, ClientError> + Send + Sync + 'static,
+    {
+        KeepAliveClient {
+            client: Arc::new(RwLock::new(client)),
+            factory: Arc::new(factory),
+            headers: HeaderMap::new(),
+            authorization: SharedAuthorization::default(),
+            idle_timeout: Arc::new(AtomicU64::new(0)),
+            last_used: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// The idle period after which connections are re-established, `None`
+    /// when disabled.
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        match self.idle_timeout.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        }
+    }
+
+    /// Set the idle period after which connections are re-established, or
+    /// `None` to disable the check.
+    pub fn set_idle_timeout(&self, timeout: Option<Duration>) {
+        let ms = timeout.map_or(0, |t| t.as_millis().max(1) as u64);
+        self.idle_timeout.store(ms, Ordering::Relaxed);
+    }
+
+    /// Replace the inner client, dropping its pooled connections.
+    fn reconnect(&self) -> Result<C, ClientError> {
+        let client = (self.factory)()?;
+        *self.client.write().unwrap() = client.clone();
+        Ok(client)
+    }
+
+    /// The inner client to use for the next request.
+    fn client(&self) -> Result<C, ClientError> {
+        let idle = {
+            let mut last_used = self.last_used.lock().unwrap();
+            let idle = last_used.elapsed();
+            *last_used = Instant::now();
+            idle
+        };
+        match self.idle_timeout() {
+            Some(timeout) if idle >= timeout => self.reconnect(),
+            _ => Ok(self.client.read().unwrap().clone()),
+        }
+    }
+}
+
+impl<C: ClientExt + Send + fmt::Debug + 'static> fmt::Debug for KeepAliveClient<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeepAliveClient")
+            .field("client", &self.client)
+            .field("headers", &self.headers)
+            .field("authorization", &self.authorization)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("last_used", &self.last_used)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async::maybe_async)]
+#[cfg_attr(target_arch = "wasm32", maybe_async::maybe_async(?Send))]
+impl<C: ClientExt + Send + 'static> ClientExt for KeepAliveClient<C> {
+    fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+        let mut client = KeepAliveClient::wrap(C::new(None)?, || C::new(None));
+        client.headers = headers.into().unwrap_or_default();
+        Ok(client)
+    }
+
+    fn headers(&mut self) -> &mut HeaderMap<HeaderValue> {
+        &mut self.headers
+    }
+
+    fn set_authorization(&self, value: HeaderValue) -> Result<(), ClientError> {
+        self.authorization.set(value);
+        Ok(())
+    }
+
+    fn with_authorization(&self, value: HeaderValue) -> Result<Self, ClientError> {
+        Ok(KeepAliveClient {
+            authorization: SharedAuthorization::detached(value),
+            ..self.clone()
+        })
+    }
+
+    async fn request(&self, mut request: Request<String>) -> Result<Response<String>, ClientError> {
+        let headers = request.headers_mut();
+        self.authorization.apply(headers);
+        for (header, value) in self.headers.iter() {
+            if !headers.contains_key(header) {
+                headers.insert(header, value.clone());
+            }
+        }
+
+        let idempotent = [Method::GET, Method::HEAD, Method::OPTIONS].contains(request.method());
+        if !idempotent || self.idle_timeout().is_none() {
+            return self.client()?.request(request).await;
+        }
+
+        let retry = clone_request(&request);
+        match self.client()?.request(request).await {
+            Err(ClientError::HttpClient(_)) => self.reconnect()?.request(retry).await,
+            resp => resp,
+        }
+    }
+}
+
+fn clone_request(request: &Request<String>) -> Request<String> {
+    let mut clone = Request::new(request.body().clone());
+    *clone.method_mut() = request.method().clone();
+    *clone.uri_mut() = request.uri().clone();
+    *clone.version_mut() = request.version();
+    *clone.headers_mut() = request.headers().clone();
+    clone
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Default)]
+    struct NoopClient {
+        headers: HeaderMap,
+    }
+
+    #[maybe_async::maybe_async]
+    impl ClientExt for NoopClient {
+        fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+            Ok(NoopClient {
+                headers: headers.into().unwrap_or_default(),
+            })
+        }
+
+        fn headers(&mut self) -> &mut HeaderMap<HeaderValue> {
+            &mut self.headers
+        }
+
+        async fn request(&self, _: Request<String>) -> Result<Response<String>, ClientError> {
+            Ok(Response::new(String::new()))
+        }
+    }
+
+    #[test]
+    fn idle_timeout_shared_by_clones() {
+        let client = KeepAliveClient::<NoopClient>::new(None).unwrap();
+        assert_eq!(client.idle_timeout(), None);
+
+        let clone = client.clone();
+        clone.set_idle_timeout(Some(Duration::from_secs(30)));
+        assert_eq!(client.idle_timeout(), Some(Duration::from_secs(30)));
+
+        client.set_idle_timeout(None);
+        assert_eq!(clone.idle_timeout(), None);
+    }
+
+    #[test]
+    fn reconnect_with_factory() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-custom", HeaderValue::from_static("1"));
+        let configured = NoopClient::new(headers.clone()).unwrap();
+        let client = KeepAliveClient::wrap(configured, move || NoopClient::new(headers.clone()));
+
+        client.client.write().unwrap().headers.clear();
+        let mut reconnected = client.reconnect().unwrap();
+        assert!(reconnected.headers().contains_key("x-custom"));
+        assert!(client
+            .client
+            .read()
+            .unwrap()
+            .headers
+            .contains_key("x-custom"));
+    }
+}
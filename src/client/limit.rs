@@ -0,0 +1,336 @@
+//! HTTP client wrapper limiting concurrent requests
+//!
+//! A burst of parallel requests, like many futures awaited with `join_all`,
+//! can overwhelm a small coordinator. [`LimitClient`] lets at most the
+//! configured number of requests of a connection run at once, the others
+//! wait in a queue for a free slot. [`LimitStats`] reports how long they
+//! waited.
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::{
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+use async_lock::{Semaphore, SemaphoreGuardArc};
+use http::{header::HeaderMap, HeaderValue, Request, Response};
+
+use super::ClientExt;
+use crate::ClientError;
+
+/// Wrap an HTTP client to limit the number of requests running at once.
+///
+/// There is no limit by default, set one with
+/// [`set_max_concurrent`](LimitClient::set_max_concurrent). The limit and the
+/// [`stats`](LimitClient::stats) are shared by all clones of the client, so
+/// they apply to the whole connection and can be changed through
+/// `Connection::session()`.
+///
+/// # Example
+/// ```rust, ignore
+/// use arangors::{client::{limit::LimitClient, reqwest::ReqwestClient}, GenericConnection};
+///
+/// let conn = GenericConnection::<LimitClient<ReqwestClient>>::establish_jwt(
+///     "http://localhost:8529",
+///     "username",
+///     "password",
+/// )
+/// .await
+/// .unwrap();
+/// conn.session().set_max_concurrent(Some(8));
+/// // ...
+/// let stats = conn.session().stats();
+/// println!("waited {:?} for {} requests", stats.queue_wait(), stats.requests());
+/// ```
+#[derive(Debug, Clone)]
+pub struct LimitClient<C: ClientExt> {
+    client: C,
+    limit: Arc<RwLock<Option<Limit>>>,
+    stats: LimitStats,
+}
+
+#[derive(Debug)]
+struct Limit {
+    max: usize,
+    semaphore: Arc<Semaphore>,
+}
+
+impl<C: ClientExt> LimitClient<C> {
+    /// Wrap an existing client, without a limit.
+    pub fn wrap(client: C) -> Self {
+        LimitClient {
+            client,
+            limit: Arc::new(RwLock::new(None)),
+            stats: LimitStats::default(),
+        }
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.client
+    }
+
+    /// The maximum number of requests running at once, `None` when not
+    /// limited.
+    pub fn max_concurrent(&self) -> Option<usize> {
+        self.limit.read().unwrap().as_ref().map(|limit| limit.max)
+    }
+
+    /// Set the maximum number of requests running at once, at least 1, or
+    /// `None` to remove the limit.
+    ///
+    /// Requests already running or waiting keep the limit they started with.
+    pub fn set_max_concurrent(&self, max: Option<usize>) {
+        *self.limit.write().unwrap() = max.map(|max| {
+            let max = max.max(1);
+            Limit {
+                max,
+                semaphore: Arc::new(Semaphore::new(max)),
+            }
+        });
+    }
+
+    /// Statistics of the requests of this client and all its clones.
+    pub fn stats(&self) -> LimitStats {
+        self.stats.clone()
+    }
+
+    /// Wait for a free slot when the requests are limited.
+    #[maybe_async::maybe_async]
+    async fn permit(&self) -> Option<SemaphoreGuardArc> {
+        let semaphore = self
+            .limit
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|limit| limit.semaphore.clone())?;
+        if let Some(permit) = semaphore.try_acquire_arc() {
+            return Some(permit);
+        }
+
+        let _queued = Counted::new(&self.stats.inner.queued);
+        let start = Instant::now();
+        let permit = acquire(&semaphore).await;
+        self.stats.record_wait(start.elapsed());
+        Some(permit)
+    }
+}
+
+#[maybe_async::async_impl]
+async fn acquire(semaphore: &Arc<Semaphore>) -> SemaphoreGuardArc {
+    semaphore.acquire_arc().await
+}
+
+#[maybe_async::sync_impl]
+fn acquire(semaphore: &Arc<Semaphore>) -> SemaphoreGuardArc {
+    semaphore.acquire_arc_blocking()
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async::maybe_async)]
+#[cfg_attr(target_arch = "wasm32", maybe_async::maybe_async(?Send))]
+impl<C: ClientExt> ClientExt for LimitClient<C> {
+    fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+        C::new(headers).map(LimitClient::wrap)
+    }
+
+    fn headers(&mut self) -> &mut HeaderMap<HeaderValue> {
+        self.client.headers()
+    }
+
+    fn set_authorization(&self, value: HeaderValue) -> Result<(), ClientError> {
+        self.client.set_authorization(value)
+    }
+
+    fn with_authorization(&self, value: HeaderValue) -> Result<Self, ClientError> {
+        Ok(LimitClient {
+            client: self.client.with_authorization(value)?,
+            ..self.clone()
+        })
+    }
+
+    async fn request(&self, request: Request<String>) -> Result<Response<String>, ClientError> {
+        let _permit = self.permit().await;
+        self.stats.inner.requests.fetch_add(1, Ordering::Relaxed);
+        let _in_flight = Counted::new(&self.stats.inner.in_flight);
+        self.client.request(request).await
+    }
+}
+
+/// Counts itself in a gauge until dropped, also when the request future is.
+struct Counted<'a>(&'a AtomicUsize);
+
+impl<'a> Counted<'a> {
+    fn new(gauge: &'a AtomicUsize) -> Self {
+        gauge.fetch_add(1, Ordering::Relaxed);
+        Counted(gauge)
+    }
+}
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Shared handle on the request statistics of a [`LimitClient`].
+///
+/// Durations are totals since the client was created, divide by
+/// [`requests`](LimitStats::requests) for an average.
+#[derive(Debug, Clone, Default)]
+pub struct LimitStats {
+    inner: Arc<StatsInner>,
+}
+
+#[derive(Debug, Default)]
+struct StatsInner {
+    requests: AtomicU64,
+    in_flight: AtomicUsize,
+    queued: AtomicUsize,
+    /// total queue wait in nanoseconds
+    queue_wait: AtomicU64,
+    /// longest queue wait in nanoseconds
+    max_queue_wait: AtomicU64,
+}
+
+impl LimitStats {
+    /// Number of requests sent.
+    pub fn requests(&self) -> u64 {
+        self.inner.requests.load(Ordering::Relaxed)
+    }
+
+    /// Number of requests running.
+    pub fn in_flight(&self) -> usize {
+        self.inner.in_flight.load(Ordering::Relaxed)
+    }
+
+    /// Number of requests waiting for a free slot.
+    pub fn queued(&self) -> usize {
+        self.inner.queued.load(Ordering::Relaxed)
+    }
+
+    /// Total time requests waited for a free slot.
+    pub fn queue_wait(&self) -> Duration {
+        Duration::from_nanos(self.inner.queue_wait.load(Ordering::Relaxed))
+    }
+
+    /// Longest time a request waited for a free slot.
+    pub fn max_queue_wait(&self) -> Duration {
+        Duration::from_nanos(self.inner.max_queue_wait.load(Ordering::Relaxed))
+    }
+
+    fn record_wait(&self, wait: Duration) {
+        let nanos = wait.as_nanos().min(u64::MAX as u128) as u64;
+        self.inner.queue_wait.fetch_add(nanos, Ordering::Relaxed);
+        self.inner
+            .max_queue_wait
+            .fetch_max(nanos, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Records the most requests running at once, each running a while.
+    #[derive(Debug, Clone, Default)]
+    struct TrackingClient {
+        headers: HeaderMap,
+        in_flight: Arc<AtomicUsize>,
+        max_in_flight: Arc<AtomicUsize>,
+    }
+
+    #[maybe_async::async_impl]
+    async fn pause() {
+        for _ in 0..100 {
+            futures_lite::future::yield_now().await;
+        }
+    }
+
+    #[maybe_async::sync_impl]
+    fn pause() {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    #[maybe_async::maybe_async]
+    impl ClientExt for TrackingClient {
+        fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+            Ok(TrackingClient {
+                headers: headers.into().unwrap_or_default(),
+                ..Default::default()
+            })
+        }
+
+        fn headers(&mut self) -> &mut HeaderMap<HeaderValue> {
+            &mut self.headers
+        }
+
+        async fn request(&self, _: Request<String>) -> Result<Response<String>, ClientError> {
+            let running = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(running, Ordering::SeqCst);
+            pause().await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(Response::new(String::new()))
+        }
+    }
+
+    #[test]
+    fn limit_and_stats_shared_by_clones() {
+        let client = LimitClient::<TrackingClient>::new(None).unwrap();
+        assert_eq!(client.max_concurrent(), None);
+
+        let clone = client.clone();
+        clone.set_max_concurrent(Some(0));
+        assert_eq!(client.max_concurrent(), Some(1));
+
+        clone.stats.record_wait(Duration::from_millis(30));
+        client.stats.record_wait(Duration::from_millis(10));
+        let stats = client.stats();
+        assert_eq!(stats.queue_wait(), Duration::from_millis(40));
+        assert_eq!(stats.max_queue_wait(), Duration::from_millis(30));
+
+        client.set_max_concurrent(None);
+        assert_eq!(clone.max_concurrent(), None);
+    }
+
+    #[maybe_async::async_impl]
+    async fn send_all(client: &LimitClient<TrackingClient>, count: usize) {
+        let tasks: Vec<_> = (0..count)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move { client.request(Request::new(String::new())).await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+    }
+
+    #[maybe_async::sync_impl]
+    fn send_all(client: &LimitClient<TrackingClient>, count: usize) {
+        std::thread::scope(|scope| {
+            for _ in 0..count {
+                scope.spawn(|| client.request(Request::new(String::new())).unwrap());
+            }
+        });
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn requests_stay_within_the_limit() {
+        let client = LimitClient::<TrackingClient>::new(None).unwrap();
+        client.set_max_concurrent(Some(3));
+
+        send_all(&client, 10).await;
+
+        assert_eq!(client.inner().max_in_flight.load(Ordering::SeqCst), 3);
+        let stats = client.stats();
+        assert_eq!(stats.requests(), 10);
+        assert_eq!(stats.in_flight(), 0);
+        assert_eq!(stats.queued(), 0);
+        assert!(stats.max_queue_wait() > Duration::ZERO);
+    }
+}
@@ -0,0 +1,149 @@
+//! HTTP client wrapper recording request metrics
+//!
+//! [`MetricsClient`] reports every request through the
+//! [`metrics`](https://docs.rs/metrics) facade. Install any recorder, like
+//! `metrics-exporter-prometheus`, to scrape them; without a recorder the
+//! metrics are discarded.
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+use http::{header::HeaderMap, HeaderValue, Request, Response};
+use serde::Deserialize;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+use super::ClientExt;
+use crate::ClientError;
+
+/// Counter of requests, labeled by `method` and `status`.
+pub const REQUEST_COUNT: &str = "arangors_request_count";
+/// Histogram of request durations in seconds, labeled by `method`.
+pub const REQUEST_DURATION: &str = "arangors_request_duration_seconds";
+/// Counter of failed requests, labeled by `method`, `status` and
+/// `error_num`.
+///
+/// `status` is the HTTP status code, or `transport` when no response was
+/// received. `error_num` is the ArangoDB error number of the response body,
+/// when there is one.
+pub const REQUEST_ERRORS: &str = "arangors_request_errors";
+
+/// Wrap an HTTP client to record metrics of its requests.
+///
+/// # Example
+/// ```rust, ignore
+/// use arangors::{client::{metrics::MetricsClient, reqwest::ReqwestClient}, GenericConnection};
+///
+/// metrics_exporter_prometheus::PrometheusBuilder::new()
+///     .install()
+///     .unwrap();
+/// let conn = GenericConnection::<MetricsClient<ReqwestClient>>::establish_jwt(
+///     "http://localhost:8529",
+///     "username",
+///     "password",
+/// )
+/// .await
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct MetricsClient<C: ClientExt> {
+    client: C,
+}
+
+impl<C: ClientExt> MetricsClient<C> {
+    /// Wrap an existing client.
+    pub fn wrap(client: C) -> Self {
+        MetricsClient { client }
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.client
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async::maybe_async)]
+#[cfg_attr(target_arch = "wasm32", maybe_async::maybe_async(?Send))]
+impl<C: ClientExt> ClientExt for MetricsClient<C> {
+    fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+        C::new(headers).map(MetricsClient::wrap)
+    }
+
+    fn headers(&mut self) -> &mut HeaderMap<HeaderValue> {
+        self.client.headers()
+    }
+
+    fn set_authorization(&self, value: HeaderValue) -> Result<(), ClientError> {
+        self.client.set_authorization(value)
+    }
+
+    fn with_authorization(&self, value: HeaderValue) -> Result<Self, ClientError> {
+        self.client
+            .with_authorization(value)
+            .map(MetricsClient::wrap)
+    }
+
+    async fn request(&self, request: Request<String>) -> Result<Response<String>, ClientError> {
+        let method = request.method().to_string();
+        let start = Instant::now();
+        let result = self.client.request(request).await;
+        ::metrics::histogram!(REQUEST_DURATION, "method" => method.clone())
+            .record(start.elapsed().as_secs_f64());
+
+        let status = match &result {
+            Ok(resp) => resp.status().as_u16().to_string(),
+            Err(_) => "transport".to_owned(),
+        };
+        ::metrics::counter!(REQUEST_COUNT, "method" => method.clone(), "status" => status.clone())
+            .increment(1);
+
+        match &result {
+            Ok(resp) if resp.status().is_client_error() || resp.status().is_server_error() => {
+                let error_num = error_num(resp.body())
+                    .map(|n| n.to_string())
+                    .unwrap_or_default();
+                ::metrics::counter!(
+                    REQUEST_ERRORS,
+                    "method" => method,
+                    "status" => status,
+                    "error_num" => error_num
+                )
+                .increment(1);
+            }
+            Ok(_) => {}
+            Err(_) => {
+                ::metrics::counter!(
+                    REQUEST_ERRORS,
+                    "method" => method,
+                    "status" => status,
+                    "error_num" => ""
+                )
+                .increment(1);
+            }
+        }
+        result
+    }
+}
+
+/// The ArangoDB error number of an error response body.
+fn error_num(body: &str) -> Option<u16> {
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        #[serde(rename = "errorNum")]
+        error_num: u16,
+    }
+    serde_json::from_str::<ErrorBody>(body)
+        .ok()
+        .map(|e| e.error_num)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn error_num_from_body() {
+        let body = r#"{"code":404,"error":true,"errorMessage":"collection or view not found","errorNum":1203}"#;
+        assert_eq!(error_num(body), Some(1203));
+        assert_eq!(error_num("<html>Bad Gateway</html>"), None);
+    }
+}
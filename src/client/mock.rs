@@ -0,0 +1,79 @@
+//! HTTP client answering the requests of unit tests without a server
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use http::{header::CONTENT_TYPE, HeaderMap, Request, Response};
+
+use super::ClientExt;
+use crate::ClientError;
+
+/// Client recording the requests it is sent and answering them with the
+/// queued responses, or with an empty successful JSON object when none is
+/// left, after the latency if one is set. Clones share the requests and
+/// responses.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RecordingClient {
+    headers: HeaderMap,
+    latency: Option<Duration>,
+    requests: Arc<Mutex<Vec<Request<String>>>>,
+    responses: Arc<Mutex<VecDeque<(u16, String)>>>,
+}
+
+impl RecordingClient {
+    /// A client waiting `latency` before answering each request, like a
+    /// server over a slow network.
+    pub(crate) fn with_latency(latency: Duration) -> Self {
+        RecordingClient {
+            latency: Some(latency),
+            ..Default::default()
+        }
+    }
+
+    /// Queue the response to a coming request.
+    pub(crate) fn respond(&self, status: u16, body: impl Into<String>) {
+        self.responses
+            .lock()
+            .unwrap()
+            .push_back((status, body.into()));
+    }
+
+    /// The requests sent so far, clearing them.
+    pub(crate) fn take_requests(&self) -> Vec<Request<String>> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+}
+
+#[maybe_async::maybe_async]
+impl ClientExt for RecordingClient {
+    fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+        Ok(RecordingClient {
+            headers: headers.into().unwrap_or_default(),
+            ..Default::default()
+        })
+    }
+
+    fn headers(&mut self) -> &mut HeaderMap {
+        &mut self.headers
+    }
+
+    async fn request(&self, request: Request<String>) -> Result<Response<String>, ClientError> {
+        self.requests.lock().unwrap().push(request);
+        if let Some(latency) = self.latency {
+            crate::util::sleep(latency).await;
+        }
+        let (status, body) = self
+            .responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| (200, "{}".to_owned()));
+        Ok(Response::builder()
+            .status(status)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body)
+            .unwrap())
+    }
+}
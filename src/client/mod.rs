@@ -1,4 +1,6 @@
-use http::{HeaderMap, Request, Response};
+use std::sync::{Arc, RwLock};
+
+use http::{header::AUTHORIZATION, HeaderMap, HeaderValue, Request, Response};
 use url::Url;
 
 use crate::ClientError;
@@ -6,17 +8,77 @@ use crate::ClientError;
 #[cfg(any(all(feature = "reqwest_async", feature = "reqwest_blocking"),))]
 compile_error!(r#"Enabling both async and blocking version of reqwest client is not allowed."#);
 
+#[cfg(all(feature = "tower", feature = "blocking"))]
+compile_error!(
+    r#"tower services are async, the tower feature can not be used with blocking clients."#
+);
+
+pub mod failover;
+pub mod keepalive;
+pub mod limit;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(test)]
+pub(crate) mod mock;
 #[cfg(any(feature = "reqwest_async", feature = "reqwest_blocking",))]
 pub mod reqwest;
 #[cfg(any(feature = "surf_async"))]
 pub mod surf;
+#[cfg(all(feature = "tower", not(feature = "blocking")))]
+pub mod tower;
 
-#[maybe_async::maybe_async]
+/// HTTP client sending the requests of a connection.
+///
+/// This is the trait to implement for a custom client: it only sends a
+/// [`Request`] and manages the default headers. The shortcuts by HTTP method
+/// are provided for every client by [`RequestExt`].
+///
+/// The futures of the HTTP clients of the browser are not `Send`, so on
+/// wasm32 the async methods are not required to be `Send` either: implement
+/// the trait with `#[maybe_async::maybe_async(?Send)]` there.
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async::maybe_async)]
+#[cfg_attr(target_arch = "wasm32", maybe_async::maybe_async(?Send))]
 pub trait ClientExt: Sync + Clone {
     fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError>;
 
     fn headers(&mut self) -> &mut HeaderMap;
 
+    /// Replace the `Authorization` header sent by this client and all its
+    /// clones, see `GenericConnection::set_auth`.
+    ///
+    /// Clients keeping their headers per instance do not support it and fail
+    /// with `ClientError::InvalidArgument`. Use [`SharedAuthorization`] to
+    /// support it in a custom client.
+    fn set_authorization(&self, value: HeaderValue) -> Result<(), ClientError> {
+        let _ = value;
+        Err(ClientError::InvalidArgument(
+            "the HTTP client can not change its authorization".to_owned(),
+        ))
+    }
+
+    /// A clone of this client sending `value` as its `Authorization` header,
+    /// leaving this client and its other clones unchanged, see
+    /// `GenericConnection::as_user`.
+    ///
+    /// The header is not changed by a later
+    /// [`set_authorization`](ClientExt::set_authorization) on the other
+    /// clones. The default implementation replaces the header in the headers
+    /// of the clone, clients with a [`SharedAuthorization`] must detach it
+    /// instead.
+    fn with_authorization(&self, value: HeaderValue) -> Result<Self, ClientError> {
+        let mut client = self.clone();
+        client.headers().insert(AUTHORIZATION, value);
+        Ok(client)
+    }
+
+    async fn request(&self, request: Request<String>) -> Result<Response<String>, ClientError>;
+}
+
+/// Shortcuts sending a request with the HTTP methods used by ArangoDB,
+/// implemented for every [`ClientExt`].
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async::maybe_async)]
+#[cfg_attr(target_arch = "wasm32", maybe_async::maybe_async(?Send))]
+pub trait RequestExt: ClientExt {
     #[inline]
     async fn get<T>(&self, url: Url, text: T) -> Result<Response<String>, ClientError>
     where
@@ -57,42 +119,66 @@ pub trait ClientExt: Sync + Clone {
         self.request(Request::patch(url.to_string()).body(text.into()).unwrap())
             .await
     }
+}
 
-    #[inline]
-    async fn connect<T>(&self, url: Url, text: T) -> Result<Response<String>, ClientError>
-    where
-        T: Into<String> + Send,
-    {
-        self.request(Request::connect(url.to_string()).body(text.into()).unwrap())
-            .await
-    }
+impl<C: ClientExt> RequestExt for C {}
 
-    #[inline]
-    async fn head<T>(&self, url: Url, text: T) -> Result<Response<String>, ClientError>
-    where
-        T: Into<String> + Send,
-    {
-        self.request(Request::head(url.to_string()).body(text.into()).unwrap())
-            .await
+/// `Authorization` header shared by a client and all its clones, so that
+/// credentials can be rotated while database and collection handles keep
+/// using their clone of the client.
+#[derive(Debug, Clone, Default)]
+pub struct SharedAuthorization(Arc<RwLock<Option<HeaderValue>>>);
+
+impl SharedAuthorization {
+    /// A header not shared with any existing client, for
+    /// [`ClientExt::with_authorization`].
+    pub fn detached(value: HeaderValue) -> Self {
+        SharedAuthorization(Arc::new(RwLock::new(Some(value))))
     }
 
-    #[inline]
-    async fn options<T>(&self, url: Url, text: T) -> Result<Response<String>, ClientError>
-    where
-        T: Into<String> + Send,
-    {
-        self.request(Request::options(url.to_string()).body(text.into()).unwrap())
-            .await
+    /// Replace the header for all clones.
+    pub fn set(&self, value: HeaderValue) {
+        *self.0.write().unwrap() = Some(value);
     }
 
-    #[inline]
-    async fn trace<T>(&self, url: Url, text: T) -> Result<Response<String>, ClientError>
-    where
-        T: Into<String> + Send,
-    {
-        self.request(Request::trace(url.to_string()).body(text.into()).unwrap())
-            .await
+    /// Add the header to the headers of a request, unless the request sets
+    /// its own. Call it before adding the default headers of the client, so
+    /// that it takes precedence over them.
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        if headers.contains_key(AUTHORIZATION) {
+            return;
+        }
+        if let Some(value) = self.0.read().unwrap().as_ref() {
+            headers.insert(AUTHORIZATION, value.clone());
+        }
     }
+}
 
-    async fn request(&self, request: Request<String>) -> Result<Response<String>, ClientError>;
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shared_authorization() {
+        let authorization = SharedAuthorization::default();
+        let mut headers = HeaderMap::new();
+        authorization.apply(&mut headers);
+        assert!(headers.is_empty());
+
+        let clone = authorization.clone();
+        clone.set(HeaderValue::from_static("Bearer new"));
+        authorization.apply(&mut headers);
+        assert_eq!(headers[AUTHORIZATION], "Bearer new");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Basic own"));
+        authorization.apply(&mut headers);
+        assert_eq!(headers[AUTHORIZATION], "Basic own");
+
+        let detached = SharedAuthorization::detached(HeaderValue::from_static("Bearer user"));
+        authorization.set(HeaderValue::from_static("Bearer newer"));
+        let mut headers = HeaderMap::new();
+        detached.apply(&mut headers);
+        assert_eq!(headers[AUTHORIZATION], "Bearer user");
+    }
 }
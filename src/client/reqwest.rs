@@ -1,5 +1,5 @@
 //! Reqwest HTTP client
-use std::convert::TryInto;
+use std::{convert::TryInto, time::Duration};
 
 #[cfg(any(feature = "reqwest_blocking"))]
 use ::reqwest::blocking::Client;
@@ -7,22 +7,140 @@ use ::reqwest::blocking::Client;
 #[cfg(any(feature = "reqwest_async"))]
 use ::reqwest::Client;
 
+#[cfg(not(target_arch = "wasm32"))]
+use ::reqwest::{Certificate, Proxy};
 use http::header::HeaderMap;
+use typed_builder::TypedBuilder;
 
-use super::ClientExt;
-use crate::ClientError;
+use super::{ClientExt, SharedAuthorization};
+use crate::{response::Endpoint, ClientError};
 use http::HeaderValue;
 
+/// Proxy and timeout settings of a [`ReqwestClient`].
+///
+/// Use them with [`ReqwestClient::with_options`], and establish the
+/// connection with the client through
+/// [`GenericConnection::establish_with_client`](crate::GenericConnection::establish_with_client).
+///
+/// On wasm, requests are sent by the browser with its own proxy, timeout and
+/// TLS settings, and only the default options are accepted.
+///
+/// # Example
+/// ```rust, ignore
+/// use std::time::Duration;
+/// use arangors::{client::reqwest::{ReqwestClient, ReqwestOptions}, connection::Auth, Connection};
+///
+/// let options = ReqwestOptions::builder()
+///     .proxy("http://proxy.corp:3128")
+///     .connect_timeout(Duration::from_secs(5))
+///     .timeout(Duration::from_secs(60))
+///     .build();
+/// let client = ReqwestClient::with_options(None, options).unwrap();
+/// let conn = Connection::establish_with_client(
+///     client,
+///     "http://localhost:8529",
+///     Auth::jwt("username", "password"),
+/// )
+/// .await
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(doc)]
+pub struct ReqwestOptions {
+    /// Proxy url for all requests, e.g. `http://proxy:3128`.
+    #[builder(default, setter(into, strip_option))]
+    pub(crate) proxy: Option<String>,
+
+    /// Whether the `HTTP_PROXY`, `HTTPS_PROXY` and `ALL_PROXY` environment
+    /// variables are honored when no proxy is set. Enabled by default.
+    #[builder(default = true)]
+    pub(crate) system_proxy: bool,
+
+    /// Timeout of establishing a connection.
+    #[builder(default, setter(strip_option))]
+    pub(crate) connect_timeout: Option<Duration>,
+
+    /// Timeout of a request until its response is read. The blocking client
+    /// applies it to each connect, read and write operation, and defaults to
+    /// 30 seconds.
+    #[builder(default, setter(strip_option))]
+    pub(crate) timeout: Option<Duration>,
+
+    /// PEM encoded certificate of an additional certificate authority to
+    /// trust, e.g. the one signing the certificates of the server.
+    #[builder(default, setter(into, strip_option))]
+    pub(crate) root_certificate: Option<Vec<u8>>,
+
+    /// Accept invalid TLS certificates, including self-signed and expired
+    /// ones. Only for tests, this makes TLS connections insecure.
+    #[builder(default)]
+    pub(crate) accept_invalid_certs: bool,
+}
+
+impl Default for ReqwestOptions {
+    fn default() -> ReqwestOptions {
+        Self::builder().build()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ReqwestClient {
     pub client: Client,
     headers: HeaderMap,
+    authorization: SharedAuthorization,
 }
 
-#[maybe_async::maybe_async]
-impl ClientExt for ReqwestClient {
-    fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
-        let client = Client::builder().gzip(true);
+impl ReqwestClient {
+    /// Build a client with proxy and timeout settings.
+    pub fn with_options<U: Into<Option<HeaderMap>>>(
+        headers: U,
+        options: ReqwestOptions,
+    ) -> Result<Self, ClientError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let client = {
+            let mut client = Client::builder().gzip(true);
+            if let Some(proxy) = options.proxy {
+                let proxy = Proxy::all(&proxy).map_err(|e| {
+                    ClientError::InvalidArgument(format!("invalid proxy {}: {}", proxy, e))
+                })?;
+                client = client.proxy(proxy);
+            } else if !options.system_proxy {
+                client = client.no_proxy();
+            }
+            if let Some(timeout) = options.connect_timeout {
+                client = client.connect_timeout(timeout);
+            }
+            if let Some(timeout) = options.timeout {
+                client = client.timeout(timeout);
+            }
+            if let Some(pem) = options.root_certificate {
+                let certificate = Certificate::from_pem(&pem).map_err(|e| {
+                    ClientError::InvalidArgument(format!("invalid root certificate: {}", e))
+                })?;
+                client = client.add_root_certificate(certificate);
+            }
+            if options.accept_invalid_certs {
+                client = client.danger_accept_invalid_certs(true);
+            }
+            client
+        };
+        // fetch requests of the browser are compressed, proxied and verified
+        // by the browser itself
+        #[cfg(target_arch = "wasm32")]
+        let client = {
+            if options.proxy.is_some()
+                || !options.system_proxy
+                || options.connect_timeout.is_some()
+                || options.timeout.is_some()
+                || options.root_certificate.is_some()
+                || options.accept_invalid_certs
+            {
+                return Err(ClientError::InvalidArgument(
+                    "proxy, timeout and TLS options are not supported on wasm".to_owned(),
+                ));
+            }
+            Client::builder()
+        };
         let headers = match headers.into() {
             Some(h) => h,
             None => HeaderMap::new(),
@@ -30,24 +148,50 @@ impl ClientExt for ReqwestClient {
 
         client
             .build()
-            .map(|c| ReqwestClient { client: c, headers })
+            .map(|c| ReqwestClient {
+                client: c,
+                headers,
+                authorization: SharedAuthorization::default(),
+            })
             .map_err(|e| ClientError::HttpClient(format!("{:?}", e)))
     }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async::maybe_async)]
+#[cfg_attr(target_arch = "wasm32", maybe_async::maybe_async(?Send))]
+impl ClientExt for ReqwestClient {
+    fn new<U: Into<Option<HeaderMap>>>(headers: U) -> Result<Self, ClientError> {
+        ReqwestClient::with_options(headers, ReqwestOptions::default())
+    }
 
     fn headers(&mut self) -> &mut HeaderMap<HeaderValue> {
         &mut self.headers
     }
 
+    fn set_authorization(&self, value: HeaderValue) -> Result<(), ClientError> {
+        self.authorization.set(value);
+        Ok(())
+    }
+
+    fn with_authorization(&self, value: HeaderValue) -> Result<Self, ClientError> {
+        Ok(ReqwestClient {
+            authorization: SharedAuthorization::detached(value),
+            ..self.clone()
+        })
+    }
+
     async fn request(
         &self,
         mut request: http::Request<String>,
     ) -> Result<http::Response<String>, ClientError> {
         let headers = request.headers_mut();
+        self.authorization.apply(headers);
         for (header, value) in self.headers.iter() {
             if !headers.contains_key(header) {
                 headers.insert(header, value.clone());
             }
         }
+        let endpoint = Endpoint::of(&request);
         let req = request.try_into().unwrap();
 
         let resp = self
@@ -72,7 +216,36 @@ impl ClientExt for ReqwestClient {
         build
             .status(status_code)
             .version(version)
+            .extension(endpoint)
             .body(content)
             .map_err(|e| ClientError::HttpClient(format!("{:?}", e)))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn with_options() {
+        let options = ReqwestOptions::builder()
+            .proxy("http://proxy.example:3128")
+            .connect_timeout(Duration::from_secs(5))
+            .timeout(Duration::from_secs(60))
+            .build();
+        assert!(ReqwestClient::with_options(None, options).is_ok());
+
+        let options = ReqwestOptions::builder().system_proxy(false).build();
+        assert!(ReqwestClient::with_options(None, options).is_ok());
+
+        let options = ReqwestOptions::builder().proxy("not a proxy").build();
+        let result = ReqwestClient::with_options(None, options);
+        assert!(matches!(result, Err(ClientError::InvalidArgument(_))));
+
+        let options = ReqwestOptions::builder()
+            .root_certificate(b"not a certificate".to_vec())
+            .build();
+        let result = ReqwestClient::with_options(None, options);
+        assert!(matches!(result, Err(ClientError::InvalidArgument(_))));
+    }
+}
@@ -6,12 +6,13 @@ use http::{
     Method, StatusCode, Version,
 };
 
-use super::ClientExt;
-use crate::ClientError;
+use super::{ClientExt, SharedAuthorization};
+use crate::{response::Endpoint, ClientError};
 
 #[derive(Debug, Clone)]
 pub struct SurfClient {
     headers: HeaderMap,
+    authorization: SharedAuthorization,
 }
 
 #[async_trait::async_trait]
@@ -22,19 +23,37 @@ impl ClientExt for SurfClient {
             None => HeaderMap::new(),
         };
 
-        Ok(SurfClient { headers })
+        Ok(SurfClient {
+            headers,
+            authorization: SharedAuthorization::default(),
+        })
     }
 
     fn headers(&mut self) -> &mut HeaderMap<HeaderValue> {
         &mut self.headers
     }
 
+    fn set_authorization(&self, value: HeaderValue) -> Result<(), ClientError> {
+        self.authorization.set(value);
+        Ok(())
+    }
+
+    fn with_authorization(&self, value: HeaderValue) -> Result<Self, ClientError> {
+        Ok(SurfClient {
+            authorization: SharedAuthorization::detached(value),
+            ..self.clone()
+        })
+    }
+
     async fn request(
         &self,
-        request: http::Request<String>,
+        mut request: http::Request<String>,
     ) -> Result<http::Response<String>, ClientError> {
         use ::surf::http::headers::HeaderName as SurfHeaderName;
 
+        self.authorization.apply(request.headers_mut());
+
+        let endpoint = Endpoint::of(&request);
         let method = request.method().clone();
         let url = request.uri().to_owned().to_string();
         let text = request.body();
@@ -96,8 +115,9 @@ impl ClientExt for SurfClient {
             _ => unreachable!(),
         });
 
-        let mut resp =
-            http::response::Builder::from(build).status(StatusCode::from_u16(status).unwrap());
+        let mut resp = http::response::Builder::from(build)
+            .status(StatusCode::from_u16(status).unwrap())
+            .extension(endpoint);
         if version.is_some() {
             resp = resp.version(http_version.unwrap());
         }
@@ -0,0 +1,185 @@
+//! HTTP client adapter for `tower` services
+//!
+//! [`TowerClient`] sends the requests of a connection through a
+//! `tower::Service`, so the retry, timeout, rate limit or tracing layers
+//! already used by an application also apply to its ArangoDB requests. The
+//! service at the bottom of the stack sends the requests, e.g. a hyper
+//! client converting the `String` bodies.
+//!
+//! Services are asynchronous, so this adapter is only available without the
+//! `blocking` feature.
+use std::{error::Error, future::poll_fn};
+
+use http::{header::HeaderMap, HeaderValue, Request, Response};
+use tower_service::Service;
+
+use super::{ClientExt, SharedAuthorization};
+use crate::{response::Endpoint, ClientError};
+
+/// Wrap a `tower::Service` sending HTTP requests into an HTTP client.
+///
+/// The service is cloned for each request, as required to send requests
+/// concurrently through `&self`, so it should be cheap to clone, like the
+/// services built with `tower::ServiceBuilder` usually are. Wrap services
+/// that are not `Clone` in `tower::buffer::Buffer`.
+///
+/// A service can not be built from headers alone, so
+/// [`ClientExt::new`] fails for this client. Establish connections with
+/// `GenericConnection::establish_with_client` instead.
+///
+/// # Example
+/// ```rust, ignore
+/// use arangors::{client::tower::TowerClient, connection::Auth, GenericConnection};
+/// use tower::ServiceBuilder;
+///
+/// let service = ServiceBuilder::new()
+///     .timeout(Duration::from_secs(10))
+///     .service(http_client);
+/// let conn = GenericConnection::establish_with_client(
+///     TowerClient::wrap(service),
+///     "http://localhost:8529",
+///     Auth::jwt("username", "password"),
+/// )
+/// .await
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct TowerClient<S> {
+    service: S,
+    headers: HeaderMap,
+    authorization: SharedAuthorization,
+}
+
+impl<S> TowerClient<S> {
+    /// Wrap a service, without default headers.
+    pub fn wrap(service: S) -> Self {
+        TowerClient {
+            service,
+            headers: HeaderMap::new(),
+            authorization: SharedAuthorization::default(),
+        }
+    }
+
+    /// The wrapped service.
+    pub fn inner(&self) -> &S {
+        &self.service
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async::maybe_async)]
+#[cfg_attr(target_arch = "wasm32", maybe_async::maybe_async(?Send))]
+impl<S> ClientExt for TowerClient<S>
+where
+    S: Service<Request<String>, Response = Response<String>> + Clone + Send + Sync,
+    S::Future: Send,
+    S::Error: Into<Box<dyn Error + Send + Sync>>,
+{
+    fn new<U: Into<Option<HeaderMap>>>(_: U) -> Result<Self, ClientError> {
+        Err(ClientError::InvalidArgument(
+            "a TowerClient is built from a service with TowerClient::wrap".to_owned(),
+        ))
+    }
+
+    fn headers(&mut self) -> &mut HeaderMap<HeaderValue> {
+        &mut self.headers
+    }
+
+    fn set_authorization(&self, value: HeaderValue) -> Result<(), ClientError> {
+        self.authorization.set(value);
+        Ok(())
+    }
+
+    fn with_authorization(&self, value: HeaderValue) -> Result<Self, ClientError> {
+        Ok(TowerClient {
+            authorization: SharedAuthorization::detached(value),
+            ..self.clone()
+        })
+    }
+
+    async fn request(&self, mut request: Request<String>) -> Result<Response<String>, ClientError> {
+        let headers = request.headers_mut();
+        self.authorization.apply(headers);
+        for (header, value) in self.headers.iter() {
+            if !headers.contains_key(header) {
+                headers.insert(header, value.clone());
+            }
+        }
+        let endpoint = Endpoint::of(&request);
+
+        let mut service = self.service.clone();
+        poll_fn(|cx| service.poll_ready(cx))
+            .await
+            .map_err(service_error)?;
+        let mut resp = service.call(request).await.map_err(service_error)?;
+        if resp.extensions().get::<Endpoint>().is_none() {
+            resp.extensions_mut().insert(endpoint);
+        }
+        Ok(resp)
+    }
+}
+
+fn service_error<E: Into<Box<dyn Error + Send + Sync>>>(err: E) -> ClientError {
+    ClientError::HttpClient(err.into().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        future::{ready, Ready},
+        task::{Context, Poll},
+    };
+
+    use http::header::AUTHORIZATION;
+
+    use super::*;
+    use crate::client::RequestExt;
+
+    /// Answers with the authorization header of the request.
+    #[derive(Debug, Clone)]
+    struct EchoAuthorization;
+
+    impl Service<Request<String>> for EchoAuthorization {
+        type Response = Response<String>;
+        type Error = std::io::Error;
+        type Future = Ready<Result<Response<String>, std::io::Error>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), std::io::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: Request<String>) -> Self::Future {
+            let body = match request.headers().get(AUTHORIZATION) {
+                Some(value) => value.to_str().unwrap().to_owned(),
+                None => String::new(),
+            };
+            ready(Ok(Response::new(body)))
+        }
+    }
+
+    #[tokio::test]
+    async fn request_through_service() {
+        let mut client = TowerClient::wrap(EchoAuthorization);
+        assert!(TowerClient::<EchoAuthorization>::new(None).is_err());
+        client
+            .headers()
+            .insert(AUTHORIZATION, HeaderValue::from_static("Basic default"));
+
+        let url = "http://localhost:8529/_api/version".parse().unwrap();
+        let resp = client.get(url, "").await.unwrap();
+        assert_eq!(resp.body(), "Basic default");
+        assert!(resp.extensions().get::<Endpoint>().is_some());
+
+        let user = client
+            .with_authorization(HeaderValue::from_static("Bearer user"))
+            .unwrap();
+        client
+            .set_authorization(HeaderValue::from_static("Bearer rotated"))
+            .unwrap();
+        let url = "http://localhost:8529/_api/version".parse().unwrap();
+        let resp = client.get(url, "").await.unwrap();
+        assert_eq!(resp.body(), "Bearer rotated");
+        let url = "http://localhost:8529/_api/version".parse().unwrap();
+        let resp = user.get(url, "").await.unwrap();
+        assert_eq!(resp.body(), "Bearer user");
+    }
+}
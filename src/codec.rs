@@ -0,0 +1,106 @@
+//! Encoding of document payloads
+//!
+//! The document methods of a [`Collection`](crate::Collection) encode the
+//! documents sent and decode the documents received with a
+//! [`PayloadCodec`], [`JsonCodec`] unless another one is set with
+//! [`Collection::with_codec`](crate::Collection::with_codec).
+//!
+//! The HTTP clients send text bodies, so a codec produces text. Documents
+//! already serialized, like `Box<serde_json::value::RawValue>` with the
+//! `raw_value` feature of `serde_json`, are written as is by [`JsonCodec`],
+//! without being parsed again.
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{response::deserialize_response, ClientError};
+
+/// Encode and decode the documents of a collection.
+///
+/// Every document body of a collection, merge patches included, goes through
+/// the codec. Bodies are `String`s like the requests of
+/// [`ClientExt`](crate::client::ClientExt), so a binary format such as
+/// VelocyPack needs byte bodies in the clients before it can be a codec.
+///
+/// # Example
+/// ```rust, ignore
+/// /// Documents logged before being sent.
+/// #[derive(Debug, Clone)]
+/// struct Logged;
+///
+/// impl PayloadCodec for Logged {
+///     fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<String, ClientError> {
+///         let body = JsonCodec.encode(value)?;
+///         log::debug!("sending {}", body);
+///         Ok(body)
+///     }
+///
+///     fn decode<T: DeserializeOwned>(
+///         &self,
+///         resp: &http::Response<String>,
+///     ) -> Result<T, ClientError> {
+///         JsonCodec.decode(resp)
+///     }
+/// }
+///
+/// let collection = db.collection("users").await?.with_codec(Logged);
+/// ```
+pub trait PayloadCodec: Clone + Send + Sync {
+    /// Encode the body of a request.
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<String, ClientError>;
+
+    /// Decode the body of a response.
+    ///
+    /// The errors of the server are JSON whatever the codec, a codec can
+    /// leave them to [`JsonCodec`], which returns them as
+    /// `ClientError::Arango`.
+    fn decode<T: DeserializeOwned>(&self, resp: &http::Response<String>) -> Result<T, ClientError>;
+}
+
+/// JSON payloads, the encoding of the whole crate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonCodec;
+
+impl PayloadCodec for JsonCodec {
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<String, ClientError> {
+        Ok(serde_json::to_string(value)?)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, resp: &http::Response<String>) -> Result<T, ClientError> {
+        deserialize_response(resp)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{value::RawValue, Value};
+
+    use super::*;
+    use crate::error::codes;
+
+    #[test]
+    fn json_codec() {
+        let raw = RawValue::from_string(r#"{"_key":"a",  "n":1}"#.to_owned()).unwrap();
+        assert_eq!(
+            JsonCodec.encode(&[raw]).unwrap(),
+            r#"[{"_key":"a",  "n":1}]"#
+        );
+
+        let resp = http::Response::builder()
+            .status(202)
+            .body(r#"[{"_key":"a"},{"error":true,"errorNum":1210}]"#.to_owned())
+            .unwrap();
+        let items: Vec<Value> = JsonCodec.decode(&resp).unwrap();
+        assert_eq!(items.len(), 2);
+
+        let resp = http::Response::builder()
+            .status(404)
+            .body(
+                r#"{"error":true,"code":404,"errorNum":1203,"errorMessage":"not found"}"#
+                    .to_owned(),
+            )
+            .unwrap();
+        let err = JsonCodec.decode::<Vec<Value>>(&resp).unwrap_err();
+        assert!(
+            matches!(err, ClientError::Arango(err) if err.error_num() == codes::ERROR_ARANGO_DATA_SOURCE_NOT_FOUND)
+        );
+    }
+}
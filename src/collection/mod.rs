@@ -2,27 +2,43 @@
 //!
 //! This mod contains struct and type of colleciton info and management, as well
 //! as document related operations.
-use std::{convert::TryFrom, sync::Arc};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::{
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
 
-use http::Request;
+use http::{Method, Request};
+use log::warn;
 use maybe_async::maybe_async;
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use serde_json::json;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
 use url::Url;
 
 use options::*;
 use response::*;
 
 use crate::{
-    client::ClientExt,
+    aql::AqlQuery,
+    client::{ClientExt, RequestExt},
+    codec::{JsonCodec, PayloadCodec},
+    connection::features::FeaturesCache,
     document::{
         options::{InsertOptions, ReadOptions, RemoveOptions, ReplaceOptions, UpdateOptions},
+        projection,
         response::DocumentResponse,
         Header,
     },
-    response::{deserialize_response, ArangoResult},
+    error::codes,
+    query,
+    response::{deserialize_response, ResponseEnvelope, ResponseMeta},
     transaction::Transaction,
-    ClientError,
+    util::sleep,
+    ArangoError, ClientError,
 };
 
 use super::{Database, Document};
@@ -41,64 +57,118 @@ pub mod response;
 /// Collections have a type
 /// that is specified by the user when the collection is created. There are
 /// currently two types: document and edge. The default type is document.
+///
+/// `Collection<C>` is `Send + Sync` when `C` is. Cloning it shares the HTTP
+/// client through an `Arc`, only the name, id and urls are copied. No request
+/// is made.
+///
+/// The documents are JSON, unless another [`PayloadCodec`] is set with
+/// [`with_codec`](Collection::with_codec).
 #[derive(Debug, Clone)]
-pub struct Collection<C: ClientExt> {
+pub struct Collection<C: ClientExt, S: PayloadCodec = JsonCodec> {
     id: String,
     name: String,
+    globally_unique_id: String,
+    status: Status,
     collection_type: CollectionType,
     base_url: Url,
     document_base_url: Url,
     session: Arc<C>,
+    codec: S,
+    default_wait_for_sync: Option<bool>,
+    server_features: FeaturesCache,
 }
 
-impl<'a, C: ClientExt> Collection<C> {
+impl<C: ClientExt> Collection<C> {
     /// Construct Collection given collection info from server
     ///
     /// Base url should be like `http://server:port/_db/mydb/_api/collection/{collection-name}`
     /// Document root should be like: http://server:port/_db/mydb/_api/document/
-    pub(crate) fn new<T: Into<String>, S: Into<String>>(
-        name: T,
-        id: S,
-        collection_type: CollectionType,
-        db_url: &Url,
-        session: Arc<C>,
-    ) -> Collection<C> {
-        let name = name.into();
+    pub(crate) fn new(info: &Info, db_url: &Url, session: Arc<C>) -> Collection<C> {
+        let name = info.name.clone();
         let path = format!("_api/collection/{}/", &name);
         let url = db_url.join(&path).unwrap();
         let document_path = format!("_api/document/{}/", &name);
         let document_base_url = db_url.join(&document_path).unwrap();
         Collection {
             name,
-            id: id.into(),
+            id: info.id.clone(),
+            globally_unique_id: info.globally_unique_id.clone(),
+            status: info.status,
             session,
             base_url: url,
             document_base_url,
-            collection_type,
+            collection_type: info.collection_type,
+            codec: JsonCodec,
+            default_wait_for_sync: None,
+            server_features: Arc::new(Mutex::new(None)),
         }
     }
 
     pub(crate) fn from_response(database: &Database<C>, collection: &Info) -> Collection<C> {
-        Self::new(
-            &collection.name,
-            &collection.id,
-            collection.collection_type,
-            database.url(),
-            database.session(),
-        )
+        let mut collection = Self::new(collection, database.url(), database.session());
+        collection.default_wait_for_sync = database.default_wait_for_sync();
+        collection.server_features = Arc::clone(&database.server_features);
+        collection
     }
 
     pub(crate) fn from_transaction_response(
         transaction: &Transaction<C>,
         collection: &Info,
     ) -> Collection<C> {
-        Self::new(
-            &collection.name,
-            &collection.id,
-            collection.collection_type,
-            transaction.url(),
-            transaction.session(),
-        )
+        let mut collection = Self::new(collection, transaction.url(), transaction.session());
+        collection.server_features = Arc::clone(&transaction.database().server_features);
+        collection
+    }
+}
+
+impl<C: ClientExt, S: PayloadCodec> Collection<C, S> {
+    /// This collection, encoding and decoding its documents with `codec`.
+    ///
+    /// Only the document methods use the codec, the other requests, imports
+    /// included, are JSON.
+    pub fn with_codec<F: PayloadCodec>(self, codec: F) -> Collection<C, F> {
+        Collection {
+            id: self.id,
+            name: self.name,
+            globally_unique_id: self.globally_unique_id,
+            status: self.status,
+            collection_type: self.collection_type,
+            base_url: self.base_url,
+            document_base_url: self.document_base_url,
+            session: self.session,
+            codec,
+            default_wait_for_sync: self.default_wait_for_sync,
+            server_features: self.server_features,
+        }
+    }
+
+    /// The codec of the documents.
+    pub fn codec(&self) -> &S {
+        &self.codec
+    }
+
+    /// `waitForSync` of the document writes which do not set it, see
+    /// [`set_default_wait_for_sync`](Collection::set_default_wait_for_sync).
+    pub fn default_wait_for_sync(&self) -> Option<bool> {
+        self.default_wait_for_sync
+    }
+
+    /// Set the `waitForSync` of the document writes of this handle whose
+    /// options do not set it, or `None` to leave it to the collection.
+    ///
+    /// Handles obtained from a [`Database`] start with its
+    /// [`default_wait_for_sync`](Database::default_wait_for_sync).
+    pub fn set_default_wait_for_sync(&mut self, wait_for_sync: Option<bool>) {
+        self.default_wait_for_sync = wait_for_sync;
+    }
+
+    /// Keep the fields of `info` which may change on the server.
+    fn update(&mut self, info: &Info) {
+        self.id = info.id.clone();
+        self.globally_unique_id = info.globally_unique_id.clone();
+        self.status = info.status;
+        self.collection_type = info.collection_type;
     }
 
     pub fn collection_type(&self) -> CollectionType {
@@ -137,6 +207,43 @@ impl<'a, C: ClientExt> Collection<C> {
         self.name.as_str()
     }
 
+    /// The globally unique identifier of the collection, the same on all
+    /// servers of a cluster, unlike the [`id`](Collection::id) when
+    /// replicating between deployments.
+    pub fn globally_unique_id(&self) -> &str {
+        self.globally_unique_id.as_str()
+    }
+
+    /// The status of the collection when the handle was obtained or last
+    /// [`reload`](Collection::reload)ed.
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Fetch the basic information of the collection, like its id, status
+    /// and type.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn info(&self) -> Result<Info, ClientError> {
+        let url = self.base_url.join("").unwrap();
+        let resp: Info = deserialize_response(&self.session.get(url, "").await?)?;
+        Ok(resp)
+    }
+
+    /// Fetch the information of the collection and update the handle with
+    /// it, e.g. its [`status`](Collection::status).
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn reload(&mut self) -> Result<Info, ClientError> {
+        let info = self.info().await?;
+        self.update(&info);
+        Ok(info)
+    }
+
     /// Collection url: http://server:port/_db/mydb/_api/collection/{collection-name}
     ///
     /// This url is used to work on the collection itself
@@ -155,15 +262,28 @@ impl<'a, C: ClientExt> Collection<C> {
         Arc::clone(&self.session)
     }
 
+    /// Url of `path` relative to the database of this collection, like
+    /// `_api/import`.
+    fn db_url(&self, path: &str) -> Url {
+        // Base url should be like `http://server:port/_db/mydb/_api/collection/{collection-name}/`,
+        // possibly behind the path prefix of a reverse proxy
+        self.base_url.join("../../../").unwrap().join(path).unwrap()
+    }
+
     /// Get the db of current collection
     pub fn db(&self) -> Database<C> {
-        // Base url should be like `http://server:port/_db/mydb/_api/collection/{collection-name}`
-        let mut paths = self.base_url.path_segments().unwrap();
-        // must be `_db`
-        paths.next();
-        // must be db name
-        let name = paths.next().unwrap();
-        Database::new(name, &self.url().join("/").unwrap(), self.session())
+        let db_url = self.db_url("");
+        // the last segment is empty, the one before is the db name
+        let name = db_url
+            .path_segments()
+            .unwrap()
+            .rev()
+            .nth(1)
+            .unwrap()
+            .to_owned();
+        let mut database = Database::with_url(name, db_url, self.session());
+        database.server_features = Arc::clone(&self.server_features);
+        database
     }
 
     /// Drop a collection
@@ -180,7 +300,7 @@ impl<'a, C: ClientExt> Collection<C> {
         }
 
         let resp: DropCollectionResponse =
-            deserialize_response(self.session.delete(url, "").await?.body())?;
+            deserialize_response(&self.session.delete(url, "").await?)?;
         Ok(resp.id)
     }
 
@@ -191,7 +311,7 @@ impl<'a, C: ClientExt> Collection<C> {
     #[maybe_async]
     pub async fn truncate(&self) -> Result<Info, ClientError> {
         let url = self.base_url.join("truncate").unwrap();
-        let resp: Info = deserialize_response(self.session.put(url, "").await?.body())?;
+        let resp: Info = deserialize_response(&self.session.put(url, "").await?)?;
         Ok(resp)
     }
 
@@ -202,7 +322,7 @@ impl<'a, C: ClientExt> Collection<C> {
     #[maybe_async]
     pub async fn properties(&self) -> Result<Properties, ClientError> {
         let url = self.base_url.join("properties").unwrap();
-        let resp: Properties = deserialize_response(self.session.get(url, "").await?.body())?;
+        let resp: Properties = deserialize_response(&self.session.get(url, "").await?)?;
         Ok(resp)
     }
 
@@ -213,7 +333,7 @@ impl<'a, C: ClientExt> Collection<C> {
     #[maybe_async]
     pub async fn document_count(&self) -> Result<Properties, ClientError> {
         let url = self.base_url.join("count").unwrap();
-        let resp: Properties = deserialize_response(self.session.get(url, "").await?.body())?;
+        let resp: Properties = deserialize_response(&self.session.get(url, "").await?)?;
         Ok(resp)
     }
     /// Fetch the statistics of a collection
@@ -247,7 +367,24 @@ impl<'a, C: ClientExt> Collection<C> {
     #[maybe_async]
     pub async fn statistics(&self) -> Result<Statistics, ClientError> {
         let url = self.base_url.join("figures").unwrap();
-        let resp: Statistics = deserialize_response(self.session.get(url, "").await?.body())?;
+        let resp: Statistics = deserialize_response(&self.session.get(url, "").await?)?;
+        Ok(resp)
+    }
+
+    /// Fetch the statistics of a collection with the detailed figures of the
+    /// storage engine, like the number of entries of each index, in
+    /// `figures.engine`.
+    ///
+    /// Gathering the details can be expensive for large collections.
+    /// Supported since ArangoDB 3.8, older servers leave `figures.engine`
+    /// out.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn detailed_statistics(&self) -> Result<Statistics, ClientError> {
+        let url = self.base_url.join("figures?details=true").unwrap();
+        let resp: Statistics = deserialize_response(&self.session.get(url, "").await?)?;
         Ok(resp)
     }
 
@@ -262,9 +399,54 @@ impl<'a, C: ClientExt> Collection<C> {
     #[maybe_async]
     pub async fn revision_id(&self) -> Result<Revision, ClientError> {
         let url = self.base_url.join("revision").unwrap();
-        let resp: Revision = deserialize_response(self.session.get(url, "").await?.body())?;
+        let resp: Revision = deserialize_response(&self.session.get(url, "").await?)?;
         Ok(resp)
     }
+
+    /// Wait until the revision of the collection differs from `current_rev`,
+    /// e.g. to invalidate a cache of its documents.
+    ///
+    /// The revision is polled with [`revision_id`](Collection::revision_id)
+    /// every `poll_interval`. Returns the new revision once it changed, or
+    /// `None` when `timeout` elapsed first.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let mut rev = collection.revision_id().await?.revision;
+    /// loop {
+    ///     if let Some(new) = collection
+    ///         .wait_for_revision_change(&rev, Duration::from_secs(1), Duration::from_secs(60))
+    ///         .await?
+    ///     {
+    ///         cache.clear();
+    ///         rev = new.revision;
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Note
+    /// this function would make requests to arango server until the revision
+    /// changes or the timeout elapses.
+    #[maybe_async]
+    pub async fn wait_for_revision_change(
+        &self,
+        current_rev: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<Option<Revision>, ClientError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let revision = self.revision_id().await?;
+            if revision.revision != current_rev {
+                return Ok(Some(revision));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            sleep(poll_interval.min(deadline - now)).await;
+        }
+    }
     /// Fetch a checksum for the specified collection
     ///
     /// Will calculate a checksum of the meta-data (keys and optionally
@@ -320,10 +502,10 @@ impl<'a, C: ClientExt> Collection<C> {
         options: ChecksumOptions,
     ) -> Result<Checksum, ClientError> {
         let mut url = self.base_url.join("checksum").unwrap();
-        let query = serde_qs::to_string(&options).unwrap();
+        let query = query::to_string(&options)?;
         url.set_query(Some(query.as_str()));
 
-        let resp: Checksum = deserialize_response(self.session.get(url, "").await?.body())?;
+        let resp: Checksum = deserialize_response(&self.session.get(url, "").await?)?;
         Ok(resp)
     }
 
@@ -346,8 +528,7 @@ impl<'a, C: ClientExt> Collection<C> {
     pub async fn load(&self, count: bool) -> Result<Info, ClientError> {
         let url = self.base_url.join("load").unwrap();
         let body = json!({ "count": count });
-        let resp: Info =
-            deserialize_response(self.session.put(url, body.to_string()).await?.body())?;
+        let resp: Info = deserialize_response(&self.session.put(url, body.to_string()).await?)?;
         Ok(resp)
     }
 
@@ -366,7 +547,7 @@ impl<'a, C: ClientExt> Collection<C> {
     #[maybe_async]
     pub async fn unload(&self) -> Result<Info, ClientError> {
         let url = self.base_url.join("unload").unwrap();
-        let resp: Info = deserialize_response(self.session.put(url, "").await?.body())?;
+        let resp: Info = deserialize_response(&self.session.put(url, "").await?)?;
         Ok(resp)
     }
 
@@ -397,9 +578,8 @@ impl<'a, C: ClientExt> Collection<C> {
     #[maybe_async]
     pub async fn load_indexes(&self) -> Result<bool, ClientError> {
         let url = self.base_url.join("loadIndexesIntoMemory").unwrap();
-        let resp: ArangoResult<bool> =
-            deserialize_response(self.session.put(url, "").await?.body())?;
-        Ok(resp.unwrap())
+        let resp: ResponseEnvelope<bool> = deserialize_response(&self.session.put(url, "").await?)?;
+        Ok(resp.into_result())
     }
 
     /// Change the properties of a collection
@@ -411,10 +591,11 @@ impl<'a, C: ClientExt> Collection<C> {
         &self,
         properties: PropertiesOptions,
     ) -> Result<Properties, ClientError> {
+        properties.validate()?;
         let url = self.base_url.join("properties").unwrap();
 
         let body = serde_json::to_string(&properties).unwrap();
-        let resp: Properties = deserialize_response(self.session.put(url, body).await?.body())?;
+        let resp: Properties = deserialize_response(&self.session.put(url, body).await?)?;
         Ok(resp)
     }
 
@@ -426,26 +607,31 @@ impl<'a, C: ClientExt> Collection<C> {
     pub async fn rename(&mut self, name: &str) -> Result<Info, ClientError> {
         let url = self.base_url.join("rename").unwrap();
         let body = json!({ "name": name });
-        let resp: Info =
-            deserialize_response(self.session.put(url, body.to_string()).await?.body())?;
+        let resp: Info = deserialize_response(&self.session.put(url, body.to_string()).await?)?;
         self.name = name.to_string();
         self.base_url = self.base_url.join(&format!("../{}/", name)).unwrap();
+        self.update(&resp);
         Ok(resp)
     }
 
     /// Recalculate the document count of a collection
     ///
-    /// **Note**: this method is specific for the RocksDB storage engine
+    /// **Note**: this method is specific for the RocksDB storage engine, other
+    /// engines fail with `ClientError::UnsupportedByServer`.
     ///
     /// # Note
-    /// this function would make a request to arango server.
-    #[cfg(feature = "rocksdb")]
+    /// this function would make requests to arango server, checking the
+    /// storage engine first unless it is already known.
     #[maybe_async]
     pub async fn recalculate_count(&self) -> Result<bool, ClientError> {
+        if !self.db().server_features().await?.rocksdb() {
+            return Err(ClientError::UnsupportedByServer(
+                "recalculate count requires the RocksDB storage engine".to_owned(),
+            ));
+        }
         let url = self.base_url.join("recalculateCount").unwrap();
-        let resp: ArangoResult<bool> =
-            deserialize_response(self.session.put(url, "").await?.body())?;
-        Ok(resp.unwrap())
+        let resp: ResponseEnvelope<bool> = deserialize_response(&self.session.put(url, "").await?)?;
+        Ok(resp.into_result())
     }
     /// Rotate the journal of a collection
     ///
@@ -460,17 +646,22 @@ impl<'a, C: ClientExt> Collection<C> {
     /// This methods is not documented on 3.7
     ///
     /// **Note**: this method is specific for the MMFiles storage engine, and
-    /// there it is not available in a cluster.
+    /// there it is not available in a cluster. Other engines fail with
+    /// `ClientError::UnsupportedByServer`.
     ///
     /// # Note
-    /// this function would make a request to arango server.
-    #[cfg(feature = "mmfiles")]
+    /// this function would make requests to arango server, checking the
+    /// storage engine first unless it is already known.
     #[maybe_async]
     pub async fn rotate_journal(&self) -> Result<bool, ClientError> {
+        if !self.db().server_features().await?.mmfiles() {
+            return Err(ClientError::UnsupportedByServer(
+                "rotate journal requires the MMFiles storage engine".to_owned(),
+            ));
+        }
         let url = self.base_url.join("rotate").unwrap();
-        let resp: ArangoResult<bool> =
-            deserialize_response(self.session.put(url, "").await?.body())?;
-        Ok(resp.unwrap())
+        let resp: ResponseEnvelope<bool> = deserialize_response(&self.session.put(url, "").await?)?;
+        Ok(resp.into_result())
     }
 
     /// Create a new document from the document given in the body, unless
@@ -511,6 +702,9 @@ impl<'a, C: ClientExt> Collection<C> {
     /// document, the complete new document is returned under the new attribute
     /// in the result.
     ///
+    /// With [`InsertOptions::with_generated_key`], a document without `_key`
+    /// gets a key generated on the client, which makes retries safe.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
@@ -519,16 +713,306 @@ impl<'a, C: ClientExt> Collection<C> {
         doc: T,
         insert_options: InsertOptions,
     ) -> Result<DocumentResponse<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let body = match insert_options.key() {
+            Some(key) => {
+                let mut value = serde_json::to_value(&doc)?;
+                if let Some(obj) = value.as_object_mut() {
+                    obj.entry("_key").or_insert_with(|| key.into());
+                }
+                self.codec.encode(&value)?
+            }
+            None => self.codec.encode(&doc)?,
+        };
+        self.insert(body, &insert_options).await
+    }
+
+    /// Create a document already serialized as JSON, e.g. passed through
+    /// from another service, without parsing it again.
+    ///
+    /// Takes the same options as [`create_document`](Self::create_document),
+    /// except a generated key, which can not be added without parsing the
+    /// document. The document is sent as is, whatever the codec, and the
+    /// server reports invalid JSON. A `serde_json::value::RawValue` is
+    /// passed with [`get`](serde_json::value::RawValue::get).
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_document_raw<T>(
+        &self,
+        json: &str,
+        insert_options: InsertOptions,
+    ) -> Result<DocumentResponse<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        if insert_options.key().is_some() {
+            return Err(ClientError::InvalidArgument(
+                "a generated key can not be added to a raw document".to_owned(),
+            ));
+        }
+        self.insert(json.to_owned(), &insert_options).await
+    }
+
+    #[maybe_async]
+    async fn insert<T>(
+        &self,
+        body: String,
+        insert_options: &InsertOptions,
+    ) -> Result<DocumentResponse<T>, ClientError>
     where
         T: Serialize + DeserializeOwned,
     {
         let mut url = self.document_base_url.join("").unwrap();
-        let body = serde_json::to_string(&doc)?;
-        let query = serde_qs::to_string(&insert_options).unwrap();
+        let insert_options = insert_options
+            .clone()
+            .or_wait_for_sync(self.default_wait_for_sync)?;
+        let query = query::to_string(&insert_options)?;
         url.set_query(Some(query.as_str()));
-        let resp: DocumentResponse<T> =
-            deserialize_response(self.session.post(url, body).await?.body())?;
-        Ok(resp)
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(url.as_str())
+            .body(body)
+            .unwrap();
+        let resp = self.session.request(req).await?;
+        let mut result: DocumentResponse<T> = self.codec.decode(&resp)?;
+        if insert_options.reports_ignored() {
+            result = result.into_ignored_without_new();
+        }
+        Ok(result.with_headers(resp.headers()))
+    }
+
+    /// Create multiple documents in one request
+    ///
+    /// Takes the same options as [`create_document`](Self::create_document),
+    /// which apply to every document. With `overwrite_mode` set to `update`
+    /// or `replace` this makes an upsert-style bulk load, and `keep_null`
+    /// and `merge_objects` control how existing documents are updated.
+    ///
+    /// The result holds one entry per document, in the order of `docs`. An
+    /// entry is an error when that single document could not be written,
+    /// the other documents are written anyway. When `return_new` or
+    /// `return_old` is set, the documents are available from each entry.
+    ///
+    /// If `silent` is set, the server only reports the failed documents and
+    /// the result is empty when all of them were written. Without documents
+    /// the result is empty and no request is made.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_documents<T>(
+        &self,
+        docs: Vec<T>,
+        insert_options: InsertOptions,
+    ) -> Result<Vec<Result<DocumentResponse<T>, ArangoError>>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        if insert_options.key().is_some() {
+            return Err(ClientError::InvalidArgument(
+                "a generated key applies to a single document, set the _key of each document"
+                    .to_owned(),
+            ));
+        }
+        if docs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body = self.codec.encode(&docs)?;
+        self.insert_many(body, &insert_options).await
+    }
+
+    /// Create multiple documents already serialized as JSON in one request,
+    /// see [`create_document_raw`](Self::create_document_raw).
+    ///
+    /// The documents are joined into a JSON array without being parsed. The
+    /// result is the same as the one of
+    /// [`create_documents`](Self::create_documents).
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_documents_raw<T, I>(
+        &self,
+        docs: I,
+        insert_options: InsertOptions,
+    ) -> Result<Vec<Result<DocumentResponse<T>, ArangoError>>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        if insert_options.key().is_some() {
+            return Err(ClientError::InvalidArgument(
+                "a generated key can not be added to a raw document".to_owned(),
+            ));
+        }
+        let mut docs = docs.into_iter().peekable();
+        if docs.peek().is_none() {
+            return Ok(Vec::new());
+        }
+        self.insert_many(json_array(docs), &insert_options).await
+    }
+
+    #[maybe_async]
+    async fn insert_many<T>(
+        &self,
+        body: String,
+        insert_options: &InsertOptions,
+    ) -> Result<Vec<Result<DocumentResponse<T>, ArangoError>>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let mut url = self.document_base_url.join("").unwrap();
+        let insert_options = insert_options
+            .clone()
+            .or_wait_for_sync(self.default_wait_for_sync)?;
+        let query = query::to_string(&insert_options)?;
+        url.set_query(Some(query.as_str()));
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(url.as_str())
+            .body(body)
+            .unwrap();
+        let resp = self.session.request(req).await?;
+        // items are returned as a bare array, errors of the whole request as
+        // an object
+        let items: Vec<Value> = self.codec.decode(&resp)?;
+        let reports_ignored = insert_options.reports_ignored();
+        items
+            .into_iter()
+            .map(|item| {
+                if item.get("error") == Some(&Value::Bool(true)) {
+                    Ok(Err(serde_json::from_value(item)?))
+                } else {
+                    let result: DocumentResponse<T> = serde_json::from_value(item)?;
+                    if reports_ignored {
+                        Ok(Ok(result.into_ignored_without_new()))
+                    } else {
+                        Ok(Ok(result))
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Import documents in batches of `batch_size` documents, see
+    /// [`ImportOptions`], with the import API.
+    ///
+    /// The documents are serialized as they are consumed from `docs`, so a
+    /// lazy iterator imports any number of them without holding them all in
+    /// memory. `on_progress` is called after each request.
+    ///
+    /// The import stops at the first request failing as a whole, the
+    /// batches sent before it are kept. Documents failing on their own are
+    /// only counted in the returned summary.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let docs = (0..10_000_000).map(|i| json!({ "_key": i.to_string(), "value": i }));
+    /// let options = ImportOptions::builder()
+    ///     .on_duplicate(OnDuplicate::Replace)
+    ///     .batch_size(10_000)
+    ///     .build();
+    /// let summary = collection
+    ///     .import_documents(docs, options, |progress| {
+    ///         println!("{} documents sent", progress.documents)
+    ///     })
+    ///     .await?;
+    /// ```
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async]
+    pub async fn import_documents<T, I, P>(
+        &self,
+        docs: I,
+        options: ImportOptions,
+        on_progress: P,
+    ) -> Result<ImportSummary, ClientError>
+    where
+        T: Serialize,
+        I: IntoIterator<Item = T>,
+        P: FnMut(&ImportProgress),
+    {
+        let mut batches = ImportBatches::new(self, options, on_progress);
+        for doc in docs {
+            batches.push(&serde_json::to_string(&doc)?).await?;
+        }
+        batches.finish().await
+    }
+
+    /// Import the documents read from a source of JSON lines, one document
+    /// per line, like a JSONL dump file, in batches like
+    /// [`import_documents`](Self::import_documents).
+    ///
+    /// The lines are sent as they are read, without being parsed, and blank
+    /// lines are skipped. The source is a `futures_lite::AsyncBufRead`, like
+    /// a file of `async-std` wrapped in a `BufReader`. Wrap a reader of
+    /// `tokio` with `tokio_util::compat` first. It needs one of the async
+    /// client features.
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[cfg(any(feature = "reqwest_async", feature = "surf_async", feature = "tower"))]
+    #[maybe_async::async_impl]
+    pub async fn import_json_lines<R, P>(
+        &self,
+        mut reader: R,
+        options: ImportOptions,
+        on_progress: P,
+    ) -> Result<ImportSummary, ClientError>
+    where
+        R: futures_lite::AsyncBufRead + Unpin,
+        P: FnMut(&ImportProgress),
+    {
+        use futures_lite::AsyncBufReadExt;
+
+        let mut batches = ImportBatches::new(self, options, on_progress);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await.map_err(read_error)? == 0 {
+                break;
+            }
+            batches.push(line.trim_end()).await?;
+        }
+        batches.finish().await
+    }
+
+    /// Import the documents read from a source of JSON lines, one document
+    /// per line, like a JSONL dump file, in batches like
+    /// [`import_documents`](Self::import_documents).
+    ///
+    /// The lines are sent as they are read, without being parsed, and blank
+    /// lines are skipped.
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async::sync_impl]
+    pub fn import_json_lines<R, P>(
+        &self,
+        mut reader: R,
+        options: ImportOptions,
+        on_progress: P,
+    ) -> Result<ImportSummary, ClientError>
+    where
+        R: std::io::BufRead,
+        P: FnMut(&ImportProgress),
+    {
+        let mut batches = ImportBatches::new(self, options, on_progress);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).map_err(read_error)? == 0 {
+                break;
+            }
+            batches.push(line.trim_end())?;
+        }
+        batches.finish()
     }
 
     /// Read a single document with `_key`
@@ -563,6 +1047,25 @@ impl<'a, C: ClientExt> Collection<C> {
         _key: &str,
         read_options: ReadOptions,
     ) -> Result<Document<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        self.document_with_meta(_key, read_options)
+            .await
+            .map(|(doc, _)| doc)
+    }
+
+    /// Read a single document with options, along with the metadata of the
+    /// response like whether it may be a dirty read.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_with_meta<T>(
+        &self,
+        _key: &str,
+        read_options: ReadOptions,
+    ) -> Result<(Document<T>, ResponseMeta), ClientError>
     where
         T: Serialize + DeserializeOwned,
     {
@@ -574,8 +1077,192 @@ impl<'a, C: ClientExt> Collection<C> {
             build = build.header(h.0, h.1)
         }
         let req = build.body("".to_string()).unwrap();
-        let resp: Document<T> = deserialize_response(self.session.request(req).await?.body())?;
-        Ok(resp)
+        let resp = self.session.request(req).await?;
+        let doc: Document<T> = self.codec.decode(&resp)?;
+        Ok((doc, ResponseMeta::of(&resp)))
+    }
+
+    /// Read the given attributes of a single document with `_key`.
+    ///
+    /// The document is reduced to `attributes` on the server with the AQL
+    /// query `RETURN KEEP(doc, @attrs)`, to save transfer for wide documents.
+    /// The system attributes `_id`, `_key` and `_rev` are always kept. `T`
+    /// only has to deserialize from the projected attributes. Fails with
+    /// `ClientError::NotFound` if there is no such document.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_with_projection<T, A>(
+        &self,
+        _key: &str,
+        attributes: &[A],
+    ) -> Result<Document<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+        A: AsRef<str>,
+    {
+        let aql = AqlQuery::builder()
+            .query(
+                "LET doc = DOCUMENT(@@collection, @key) FILTER doc != null \
+                 RETURN KEEP(doc, @attrs)",
+            )
+            .bind_var("@collection", self.name.as_str())
+            .bind_var("key", _key)
+            .bind_var("attrs", projection(attributes))
+            .build();
+        let mut result: Vec<Document<T>> = self.db().aql_query(aql).await?;
+        result
+            .pop()
+            .ok_or_else(|| ClientError::NotFound(format!("document {}/{}", self.name, _key)))
+    }
+
+    /// Read a random document, or `None` if the collection is empty
+    ///
+    /// The document is picked with an AQL query sorting the collection by
+    /// `RAND()`, which reads every document of the collection. Avoid it on
+    /// large collections in hot paths, it is meant for occasional sampling,
+    /// e.g. in data quality jobs.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn random_document<T>(&self) -> Result<Option<Document<T>>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let aql = AqlQuery::builder()
+            .query("FOR doc IN @@collection SORT RAND() LIMIT 1 RETURN doc")
+            .bind_var("@collection", self.name.as_str())
+            .build();
+        let mut result: Vec<Document<T>> = self.db().aql_query(aql).await?;
+        Ok(result.pop())
+    }
+
+    /// Read the documents with the given `_key`s in one request.
+    ///
+    /// Documents found are returned in no particular order, the keys without
+    /// a document are listed in [`DocumentsByKeys::missing`]. The lookup uses
+    /// `PUT /_api/simple/lookup-by-keys`, and falls back to the AQL query
+    /// `DOCUMENT(@@collection, @keys)` on servers without the simple query
+    /// API.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn documents_by_keys<T, K>(
+        &self,
+        keys: &[K],
+    ) -> Result<DocumentsByKeys<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+        K: AsRef<str>,
+    {
+        let keys: Vec<&str> = keys.iter().map(AsRef::as_ref).collect();
+        let url = self.db_url("_api/simple/lookup-by-keys");
+        let body = json!({ "collection": self.name, "keys": keys });
+        let resp = self.session.put(url, body.to_string()).await?;
+        let found = match deserialize_response::<LookupByKeys<T>>(&resp) {
+            Ok(lookup) => lookup.documents,
+            Err(ClientError::Arango(err)) if err.error_num() == codes::ERROR_HTTP_NOT_FOUND => {
+                let aql = AqlQuery::builder()
+                    .query("FOR doc IN DOCUMENT(@@collection, @keys) RETURN doc")
+                    .bind_var("@collection", self.name.as_str())
+                    .bind_var("keys", keys.clone())
+                    .build();
+                self.db().aql_query(aql).await?
+            }
+            Err(err) => return Err(err),
+        };
+        Ok(DocumentsByKeys::new(&keys, found))
+    }
+
+    /// Read the given attributes of the documents with the given `_key`s in
+    /// one request.
+    ///
+    /// Like [`documents_by_keys`](Self::documents_by_keys), but each document
+    /// is reduced to `attributes` on the server with the AQL query
+    /// `RETURN KEEP(doc, @attrs)`, to save transfer for wide documents. The
+    /// system attributes `_id`, `_key` and `_rev` are always kept. `T` only
+    /// has to deserialize from the projected attributes.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn documents_by_keys_with_projection<T, K, A>(
+        &self,
+        keys: &[K],
+        attributes: &[A],
+    ) -> Result<DocumentsByKeys<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+        K: AsRef<str>,
+        A: AsRef<str>,
+    {
+        let keys: Vec<&str> = keys.iter().map(AsRef::as_ref).collect();
+        let aql = AqlQuery::builder()
+            .query("FOR doc IN DOCUMENT(@@collection, @keys) RETURN KEEP(doc, @attrs)")
+            .bind_var("@collection", self.name.as_str())
+            .bind_var("keys", keys.clone())
+            .bind_var("attrs", projection(attributes))
+            .build();
+        let found = self.db().aql_query(aql).await?;
+        Ok(DocumentsByKeys::new(&keys, found))
+    }
+
+    /// Read all documents matching `example`.
+    ///
+    /// A document matches when it has all attributes of `example` with equal
+    /// values, like the AQL function `MATCHES`.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn documents_by_example<T, E>(
+        &self,
+        example: E,
+    ) -> Result<Vec<Document<T>>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+        E: Serialize,
+    {
+        let aql = AqlQuery::builder()
+            .query("FOR doc IN @@collection FILTER MATCHES(doc, @example) RETURN doc")
+            .bind_var("@collection", self.name.as_str())
+            .bind_var("example", serde_json::to_value(example)?)
+            .build();
+        self.db().aql_query(aql).await
+    }
+
+    /// Read the given attributes of all documents matching `example`.
+    ///
+    /// Like [`documents_by_example`](Self::documents_by_example), but each
+    /// document is reduced to `attributes` as in
+    /// [`documents_by_keys_with_projection`](Self::documents_by_keys_with_projection).
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn documents_by_example_with_projection<T, E, A>(
+        &self,
+        example: E,
+        attributes: &[A],
+    ) -> Result<Vec<Document<T>>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+        E: Serialize,
+        A: AsRef<str>,
+    {
+        let aql = AqlQuery::builder()
+            .query(
+                "FOR doc IN @@collection FILTER MATCHES(doc, @example) \
+                 RETURN KEEP(doc, @attrs)",
+            )
+            .bind_var("@collection", self.name.as_str())
+            .bind_var("example", serde_json::to_value(example)?)
+            .bind_var("attrs", projection(attributes))
+            .build();
+        self.db().aql_query(aql).await
     }
 
     /// Read a single document header
@@ -614,7 +1301,7 @@ impl<'a, C: ClientExt> Collection<C> {
             build = build.header(h.0, h.1)
         }
         let req = build.body("".to_string()).unwrap();
-        let resp: Header = deserialize_response(self.session.request(req).await?.body())?;
+        let resp: Header = deserialize_response(&self.session.request(req).await?)?;
         Ok(resp)
     }
     /// Partially update a document
@@ -631,14 +1318,113 @@ impl<'a, C: ClientExt> Collection<C> {
     where
         T: Serialize + DeserializeOwned,
     {
+        self.update_document_as(_key, doc, update_options).await
+    }
+
+    /// Partially update a document with `patch`, decoding the new document
+    /// into `N` and the old one into `O`.
+    ///
+    /// Unlike [`update_document`](Self::update_document), the patch, the new
+    /// and the old documents can have different types, e.g. when a
+    /// migration changes the shape of documents.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let migrated = users
+    ///     .update_document_as::<_, UserV2, UserV1>(
+    ///         "alice",
+    ///         json!({ "first_name": "Alice", "name": null }),
+    ///         UpdateOptions::builder()
+    ///             .keep_null(false)
+    ///             .return_old(true)
+    ///             .return_new(true)
+    ///             .build(),
+    ///     )
+    ///     .await?;
+    /// let before: &UserV1 = migrated.old_doc().unwrap();
+    /// ```
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn update_document_as<P, N, O>(
+        &self,
+        _key: &str,
+        patch: P,
+        update_options: UpdateOptions,
+    ) -> Result<DocumentResponse<N, O>, ClientError>
+    where
+        P: Serialize,
+        N: DeserializeOwned,
+        O: DeserializeOwned,
+    {
+        let mut url = self.document_base_url.join(_key).unwrap();
+        let body = self.codec.encode(&patch)?;
+        let update_options = update_options.or_wait_for_sync(self.default_wait_for_sync)?;
+        let query = query::to_string(&update_options)?;
+        url.set_query(Some(query.as_str()));
+
+        let req = Request::builder()
+            .method(Method::PATCH)
+            .uri(url.as_str())
+            .body(body)
+            .unwrap();
+        let resp = self.session.request(req).await?;
+        let result: DocumentResponse<N, O> = self.codec.decode(&resp)?;
+        Ok(result.with_headers(resp.headers()))
+    }
+
+    /// Apply a JSON Merge Patch ([RFC 7386]) to a document and return the
+    /// patched document.
+    ///
+    /// Members of `patch` set to `null` are removed from the document, nested
+    /// objects are merged recursively and any other value, arrays included,
+    /// replaces the existing one. This is an update with `keepNull=false` and
+    /// `mergeObjects=true`, see [`update_document`](Self::update_document)
+    /// for other combinations.
+    ///
+    /// Unlike RFC 7386, `patch` must be an object, as a document can not be
+    /// replaced by another JSON value, and system attributes like `_key` are
+    /// never removed.
+    ///
+    /// [RFC 7386]: https://www.rfc-editor.org/rfc/rfc7386
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn merge_patch_document<T, P>(
+        &self,
+        _key: &str,
+        patch: P,
+    ) -> Result<DocumentResponse<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+        P: Serialize,
+    {
+        let patch = serde_json::to_value(patch)?;
+        if !patch.is_object() {
+            return Err(ClientError::InvalidArgument(
+                "a merge patch of a document must be an object".to_owned(),
+            ));
+        }
         let mut url = self.document_base_url.join(_key).unwrap();
-        let body = serde_json::to_string(&doc)?;
-        let query = serde_qs::to_string(&update_options).unwrap();
+        let options = UpdateOptions::builder()
+            .keep_null(false)
+            .merge_objects(true)
+            .return_new(true)
+            .build()
+            .or_wait_for_sync(self.default_wait_for_sync)?;
+        let query = query::to_string(&options)?;
         url.set_query(Some(query.as_str()));
 
-        let resp: DocumentResponse<T> =
-            deserialize_response(self.session.patch(url, body).await?.body())?;
-        Ok(resp)
+        let req = Request::builder()
+            .method(Method::PATCH)
+            .uri(url.as_str())
+            .body(self.codec.encode(&patch)?)
+            .unwrap();
+        let resp = self.session.request(req).await?;
+        let result: DocumentResponse<T> = self.codec.decode(&resp)?;
+        Ok(result.with_headers(resp.headers()))
     }
 
     /// Replace a document
@@ -704,13 +1490,36 @@ impl<'a, C: ClientExt> Collection<C> {
     ) -> Result<DocumentResponse<T>, ClientError>
     where
         T: Serialize + DeserializeOwned,
+    {
+        self.replace_document_as(_key, doc, replace_options, if_match_header)
+            .await
+    }
+
+    /// Replace a document, decoding the new document into `N` and the old
+    /// one into `O`, see [`update_document_as`](Self::update_document_as).
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn replace_document_as<D, N, O>(
+        &self,
+        _key: &str,
+        doc: D,
+        replace_options: ReplaceOptions,
+        if_match_header: Option<String>,
+    ) -> Result<DocumentResponse<N, O>, ClientError>
+    where
+        D: Serialize,
+        N: DeserializeOwned,
+        O: DeserializeOwned,
     {
         let mut url = self.document_base_url.join(_key).unwrap();
-        let body = serde_json::to_string(&doc)?;
-        let query = serde_qs::to_string(&replace_options).unwrap();
+        let body = self.codec.encode(&doc)?;
+        let replace_options = replace_options.or_wait_for_sync(self.default_wait_for_sync)?;
+        let query = query::to_string(&replace_options)?;
         url.set_query(Some(query.as_str()));
 
-        let mut build = Request::put(url.to_string());
+        let mut build = Request::builder().method(Method::PUT).uri(url.as_str());
 
         if let Some(if_match_value) = if_match_header {
             build = build.header("If-Match", if_match_value);
@@ -718,9 +1527,9 @@ impl<'a, C: ClientExt> Collection<C> {
 
         let req = build.body(body).unwrap();
 
-        let resp: DocumentResponse<T> =
-            deserialize_response(self.session.request(req).await?.body())?;
-        Ok(resp)
+        let resp = self.session.request(req).await?;
+        let result: DocumentResponse<N, O> = self.codec.decode(&resp)?;
+        Ok(result.with_headers(resp.headers()))
     }
 
     /// Remove a document
@@ -757,10 +1566,11 @@ impl<'a, C: ClientExt> Collection<C> {
         T: Serialize + DeserializeOwned,
     {
         let mut url = self.document_base_url.join(_key).unwrap();
-        let query = serde_qs::to_string(&remove_options).unwrap();
+        let remove_options = remove_options.or_wait_for_sync(self.default_wait_for_sync)?;
+        let query = query::to_string(&remove_options)?;
         url.set_query(Some(query.as_str()));
 
-        let mut build = Request::delete(url.to_string());
+        let mut build = Request::builder().method(Method::DELETE).uri(url.as_str());
 
         if let Some(if_match_value) = if_match_header {
             build = build.header("If-Match", if_match_value);
@@ -768,11 +1578,97 @@ impl<'a, C: ClientExt> Collection<C> {
 
         let req = build.body("".to_string()).unwrap();
 
-        let resp: DocumentResponse<T> =
-            deserialize_response(self.session.request(req).await?.body())?;
+        let resp: DocumentResponse<T> = self.codec.decode(&self.session.request(req).await?)?;
         Ok(resp)
     }
 
+    /// Remove the documents with the given `_key`s in one request.
+    ///
+    /// Keys without a document are counted in [`RemovedByKeys::ignored`]
+    /// rather than failing the request. Of `remove_options`, only
+    /// `wait_for_sync` and the durability apply. The removal uses
+    /// `PUT /_api/simple/remove-by-keys`, and falls back to an AQL `REMOVE`
+    /// on servers without the simple query API.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn remove_by_keys<K>(
+        &self,
+        keys: &[K],
+        remove_options: RemoveOptions,
+    ) -> Result<RemovedByKeys, ClientError>
+    where
+        K: AsRef<str>,
+    {
+        let keys: Vec<&str> = keys.iter().map(AsRef::as_ref).collect();
+        let wait_for_sync = remove_options
+            .or_wait_for_sync(self.default_wait_for_sync)?
+            .wait_for_sync();
+        let url = self.db_url("_api/simple/remove-by-keys");
+        let body = json!({
+            "collection": self.name,
+            "keys": keys,
+            "options": { "waitForSync": wait_for_sync },
+        });
+        let resp = self.session.put(url, body.to_string()).await?;
+        match deserialize_response::<RemovedByKeys>(&resp) {
+            Err(ClientError::Arango(err)) if err.error_num() == codes::ERROR_HTTP_NOT_FOUND => {
+                let aql = AqlQuery::builder()
+                    .query(
+                        "FOR key IN @keys REMOVE key IN @@collection \
+                         OPTIONS { ignoreErrors: true, waitForSync: @waitForSync } RETURN 1",
+                    )
+                    .bind_var("@collection", self.name.as_str())
+                    .bind_var("keys", keys.clone())
+                    .bind_var("waitForSync", wait_for_sync)
+                    .build();
+                let removed = self.db().aql_query::<Value>(aql).await?.len();
+                Ok(RemovedByKeys {
+                    removed,
+                    ignored: keys.len() - removed,
+                })
+            }
+            result => result,
+        }
+    }
+
+    /// Remove all documents matching `example`, and return the number of
+    /// documents removed.
+    ///
+    /// A document matches when it has all attributes of `example` with equal
+    /// values, like the AQL function `MATCHES`. The removal uses
+    /// `PUT /_api/simple/remove-by-example`, and falls back to an AQL
+    /// `REMOVE` on servers without the simple query API.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn remove_by_example<E>(&self, example: E) -> Result<usize, ClientError>
+    where
+        E: Serialize,
+    {
+        let example = serde_json::to_value(example)?;
+        let url = self.db_url("_api/simple/remove-by-example");
+        let body = json!({ "collection": self.name, "example": example });
+        let resp = self.session.put(url, body.to_string()).await?;
+        match deserialize_response::<RemovedByExample>(&resp) {
+            Ok(removed) => Ok(removed.deleted),
+            Err(ClientError::Arango(err)) if err.error_num() == codes::ERROR_HTTP_NOT_FOUND => {
+                let aql = AqlQuery::builder()
+                    .query(
+                        "FOR doc IN @@collection FILTER MATCHES(doc, @example) \
+                         REMOVE doc IN @@collection RETURN 1",
+                    )
+                    .bind_var("@collection", self.name.as_str())
+                    .bind_var("example", example)
+                    .build();
+                Ok(self.db().aql_query::<Value>(aql).await?.len())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     /// Returns a new Collection with its `session` updated with the transaction
     /// id
     pub fn clone_with_transaction(&self, transaction_id: String) -> Result<Self, ClientError> {
@@ -787,6 +1683,111 @@ impl<'a, C: ClientExt> Collection<C> {
     }
 }
 
+/// Documents of an import waiting to be sent in the next batch.
+struct ImportBatches<'a, C: ClientExt, S: PayloadCodec, P> {
+    collection: &'a Collection<C, S>,
+    options: ImportOptions,
+    /// JSON lines of the batch
+    body: String,
+    documents: usize,
+    progress: ImportProgress,
+    on_progress: P,
+}
+
+impl<'a, C: ClientExt, S: PayloadCodec, P: FnMut(&ImportProgress)> ImportBatches<'a, C, S, P> {
+    fn new(collection: &'a Collection<C, S>, options: ImportOptions, on_progress: P) -> Self {
+        ImportBatches {
+            collection,
+            options,
+            body: String::new(),
+            documents: 0,
+            progress: ImportProgress::default(),
+            on_progress,
+        }
+    }
+
+    /// Add a document to the batch. The batch is sent first when the
+    /// document would make it larger than `batch_bytes`, and after it when
+    /// it is full.
+    #[maybe_async]
+    async fn push(&mut self, line: &str) -> Result<(), ClientError> {
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+        if matches!(self.options.batch_bytes(), Some(max) if self.body.len() + line.len() + 1 > max)
+        {
+            self.flush().await?;
+        }
+        self.body.push_str(line);
+        self.body.push('\n');
+        self.documents += 1;
+        if self.documents >= self.options.batch_size() {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Send the batch, if not empty.
+    #[maybe_async]
+    async fn flush(&mut self) -> Result<(), ClientError> {
+        if self.documents == 0 {
+            return Ok(());
+        }
+        let mut url = self.collection.db_url("_api/import");
+        let query = query::to_string(&self.options)?;
+        url.set_query(Some(query.as_str()));
+        url.query_pairs_mut()
+            .append_pair("collection", self.collection.name())
+            .append_pair("type", "documents");
+        let body = std::mem::take(&mut self.body);
+        let bytes = body.len() as u64;
+        let resp = self.collection.session.post(url, body).await?;
+        let summary: ImportSummary = deserialize_response(&resp)?;
+
+        self.options.next_batch();
+        self.progress.requests += 1;
+        self.progress.documents += std::mem::take(&mut self.documents);
+        self.progress.bytes += bytes;
+        self.progress.summary.add(summary);
+        (self.on_progress)(&self.progress);
+        Ok(())
+    }
+
+    /// Send the last batch, returns the counts of all the requests.
+    #[maybe_async]
+    async fn finish(mut self) -> Result<ImportSummary, ClientError> {
+        self.flush().await?;
+        Ok(self.progress.summary)
+    }
+}
+
+/// Join documents serialized as JSON into a JSON array.
+fn json_array<I>(docs: I) -> String
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut body = String::from("[");
+    for (i, doc) in docs.into_iter().enumerate() {
+        if i > 0 {
+            body.push(',');
+        }
+        body.push_str(doc.as_ref().trim());
+    }
+    body.push(']');
+    body
+}
+
+#[cfg(any(
+    feature = "blocking",
+    feature = "reqwest_async",
+    feature = "surf_async",
+    feature = "tower"
+))]
+fn read_error(err: std::io::Error) -> ClientError {
+    ClientError::InvalidArgument(format!("failed to read the documents to import: {}", err))
+}
+
 /// Create header name and header value from read_options
 fn make_header_from_options(
     document_read_options: ReadOptions,
@@ -806,11 +1807,256 @@ fn make_header_from_options(
     }
 }
 
-#[derive(
-    Debug, Clone, PartialEq, Eq, Copy, serde_repr::Serialize_repr, serde_repr::Deserialize_repr,
-)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
 #[repr(u8)]
 pub enum CollectionType {
     Document = 2,
     Edge = 3,
+    /// A type code unknown to this crate, sent by a newer server
+    Unknown = 0,
+}
+
+impl From<u8> for CollectionType {
+    fn from(code: u8) -> Self {
+        match code {
+            2 => CollectionType::Document,
+            3 => CollectionType::Edge,
+            _ => CollectionType::Unknown,
+        }
+    }
+}
+
+impl Serialize for CollectionType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+impl<'de> Deserialize<'de> for CollectionType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = u8::deserialize(deserializer)?;
+        let collection_type = CollectionType::from(code);
+        if collection_type == CollectionType::Unknown {
+            warn!("Unknown collection type {}", code);
+        }
+        Ok(collection_type)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::mock::RecordingClient;
+
+    /// A `docs` collection of the database at `db_url`, answered by the
+    /// returned client.
+    fn test_collection(db_url: &str) -> (Collection<RecordingClient>, Arc<RecordingClient>) {
+        let session = Arc::new(RecordingClient::default());
+        let info: Info = serde_json::from_str(
+            r#"{"id":"1","name":"docs","globallyUniqueId":"g1","isSystem":false,"status":3,"type":2}"#,
+        )
+        .unwrap();
+        let collection = Collection::new(&info, &db_url.parse().unwrap(), Arc::clone(&session));
+        (collection, session)
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn import_batches_stay_under_batch_bytes() {
+        let (collection, session) = test_collection("http://localhost:8529/_db/test_db/");
+        for _ in 0..4 {
+            session.respond(
+                201,
+                r#"{"created":1,"errors":0,"empty":0,"updated":0,"ignored":0}"#,
+            );
+        }
+
+        let docs =
+            ["a", "b", "abcdefghijklmnop", "c"].map(|key| serde_json::json!({ "_key": key }));
+        let options = ImportOptions::builder().batch_bytes(20).build();
+        let summary = collection
+            .import_documents(docs, options, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(summary.created, 4);
+
+        let bodies: Vec<String> = session
+            .take_requests()
+            .into_iter()
+            .map(|request| request.into_body())
+            .collect();
+        assert_eq!(
+            bodies,
+            [
+                "{\"_key\":\"a\"}\n",
+                "{\"_key\":\"b\"}\n",
+                "{\"_key\":\"abcdefghijklmnop\"}\n",
+                "{\"_key\":\"c\"}\n",
+            ]
+        );
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn create_no_documents() {
+        let (collection, session) = test_collection("http://localhost:8529/_db/test_db/");
+
+        let created = collection
+            .create_documents::<Value>(Vec::new(), InsertOptions::default())
+            .await
+            .unwrap();
+        assert!(created.is_empty());
+        let created = collection
+            .create_documents_raw::<Value, _>(Vec::<String>::new(), InsertOptions::default())
+            .await
+            .unwrap();
+        assert!(created.is_empty());
+        assert!(session.take_requests().is_empty());
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn requests_behind_a_proxy_prefix() {
+        let (collection, session) = test_collection("http://proxy/arango/_db/test_db/");
+        assert_eq!(collection.db().name(), "test_db");
+        assert_eq!(
+            collection.db().url().as_str(),
+            "http://proxy/arango/_db/test_db/"
+        );
+
+        session.respond(
+            201,
+            r#"{"result":[],"hasMore":false,"cached":false,"error":false,"code":201}"#,
+        );
+        let random: Option<Document<Value>> = collection.random_document().await.unwrap();
+        assert!(random.is_none());
+
+        let paths: Vec<String> = session
+            .take_requests()
+            .iter()
+            .map(|request| request.uri().path().to_owned())
+            .collect();
+        assert_eq!(paths, ["/arango/_db/test_db/_api/cursor"]);
+
+        // lookup-by-keys, then the AQL fallback of servers without it
+        session.respond(
+            404,
+            r#"{"error":true,"code":404,"errorNum":404,"errorMessage":"unknown path"}"#,
+        );
+        session.respond(
+            201,
+            r#"{"result":[],"hasMore":false,"cached":false,"error":false,"code":201}"#,
+        );
+        let found: DocumentsByKeys<Value> = collection.documents_by_keys(&["a"]).await.unwrap();
+        assert_eq!(found.missing, ["a"]);
+        session.respond(
+            201,
+            r#"{"result":[],"hasMore":false,"cached":false,"error":false,"code":201}"#,
+        );
+        let _: DocumentsByKeys<Value> = collection
+            .documents_by_keys_with_projection(&["a"], &["name"])
+            .await
+            .unwrap();
+
+        let paths: Vec<String> = session
+            .take_requests()
+            .iter()
+            .map(|request| request.uri().path().to_owned())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "/arango/_db/test_db/_api/simple/lookup-by-keys",
+                "/arango/_db/test_db/_api/cursor",
+                "/arango/_db/test_db/_api/cursor",
+            ]
+        );
+
+        session.respond(200, r#"{"removed":1,"ignored":0,"error":false,"code":200}"#);
+        let removed = collection
+            .remove_by_keys(&["a"], RemoveOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(removed.removed, 1);
+        session.respond(200, r#"{"deleted":2,"error":false,"code":200}"#);
+        let removed = collection
+            .remove_by_example(json!({ "name": "a" }))
+            .await
+            .unwrap();
+        assert_eq!(removed, 2);
+
+        let paths: Vec<String> = session
+            .take_requests()
+            .iter()
+            .map(|request| request.uri().path().to_owned())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "/arango/_db/test_db/_api/simple/remove-by-keys",
+                "/arango/_db/test_db/_api/simple/remove-by-example",
+            ]
+        );
+
+        session.respond(
+            201,
+            r#"{"created":1,"errors":0,"empty":0,"updated":0,"ignored":0}"#,
+        );
+        collection
+            .import_documents([json!({ "_key": "a" })], ImportOptions::default(), |_| {})
+            .await
+            .unwrap();
+        let requests = session.take_requests();
+        assert_eq!(requests[0].uri().path(), "/arango/_db/test_db/_api/import");
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn projected_reads() {
+        let (collection, session) = test_collection("http://localhost:8529/_db/test_db/");
+
+        session.respond(
+            201,
+            r#"{"result":[{"_id":"docs/a","_key":"a","_rev":"1","name":"A"}],"hasMore":false,
+                "cached":false,"error":false,"code":201}"#,
+        );
+        let doc: Document<Value> = collection
+            .document_with_projection("a", &["name"])
+            .await
+            .unwrap();
+        assert_eq!(doc.header._key, "a");
+        assert_eq!(doc.document["name"], "A");
+        session.respond(
+            201,
+            r#"{"result":[],"hasMore":false,"cached":false,"error":false,"code":201}"#,
+        );
+        let missing = collection
+            .document_with_projection::<Value, _>("b", &["name"])
+            .await;
+        assert!(matches!(missing, Err(ClientError::NotFound(_))));
+        session.respond(
+            201,
+            r#"{"result":[],"hasMore":false,"cached":false,"error":false,"code":201}"#,
+        );
+        let found: Vec<Document<Value>> = collection
+            .documents_by_example_with_projection(json!({ "city": "Paris" }), &["name"])
+            .await
+            .unwrap();
+        assert!(found.is_empty());
+
+        let bodies: Vec<Value> = session
+            .take_requests()
+            .iter()
+            .map(|request| serde_json::from_str(request.body()).unwrap())
+            .collect();
+        assert_eq!(bodies.len(), 3);
+        for body in &bodies {
+            assert!(body["query"]
+                .as_str()
+                .unwrap()
+                .contains("KEEP(doc, @attrs)"));
+            assert_eq!(
+                body["bindVars"]["attrs"],
+                json!(["_id", "_key", "_rev", "name"])
+            );
+        }
+        assert_eq!(bodies[0]["bindVars"]["key"], "a");
+        assert_eq!(bodies[2]["bindVars"]["example"], json!({ "city": "Paris" }));
+    }
 }
@@ -1,43 +1,76 @@
 //! Types of response related to collection
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{
+    de::{Error as DeError, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 use typed_builder::TypedBuilder;
 
-use crate::collection::CollectionType;
+use crate::{
+    collection::CollectionType,
+    naming::{validate_collection_name, NamingConvention},
+    ClientError,
+};
 
-/// Options for create a collection
-#[derive(Serialize, PartialEq, TypedBuilder, Clone)]
+/// Query parameters of a collection creation, which only apply to a cluster.
+///
+/// Parameters left unset are not sent, and the server applies its default.
+/// They are sent as `0` or `1`, as documented by the server.
+#[derive(Debug, Serialize, PartialEq, TypedBuilder, Clone)]
 #[builder(doc)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateParameters {
-    /// Default is 1 which means the server will only report success back to the
-    /// client if all replicas have created the collection. Set to 0 if you want
-    /// faster server responses and don’t care about full replication.
-    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "bool2int")]
+    /// Whether the server only reports success back to the client once all
+    /// replicas have created the collection, true by default. Set to false
+    /// for faster server responses when full replication is not needed yet.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "bool_as_int"
+    )]
     #[builder(default, setter(strip_option))]
     wait_for_sync_replication: Option<bool>,
-    /// Default is 1 which means the server will check if there are enough
-    /// replicas available at creation time and bail out otherwise. Set to 0 to
-    /// disable this extra check.
-    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "bool2int")]
+    /// Whether the server checks that there are enough DB-Servers for the
+    /// replication factor at creation time and fails otherwise, true by
+    /// default. Set to false to create the collection anyway, it is then
+    /// replicated once enough servers are available.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "bool_as_int"
+    )]
     #[builder(default, setter(strip_option))]
     enforce_replication_factor: Option<bool>,
 }
+
 impl Default for CreateParameters {
     fn default() -> Self {
         Self::builder().build()
     }
 }
 
-fn bool2int<S>(v: &Option<bool>, ser: S) -> Result<S::Ok, S::Error>
+impl CreateParameters {
+    /// The `waitForSyncReplication` parameter, `None` for the default of the
+    /// server.
+    pub fn wait_for_sync_replication(&self) -> Option<bool> {
+        self.wait_for_sync_replication
+    }
+
+    /// The `enforceReplicationFactor` parameter, `None` for the default of
+    /// the server.
+    pub fn enforce_replication_factor(&self) -> Option<bool> {
+        self.enforce_replication_factor
+    }
+}
+
+/// Serialize a set flag as `0` or `1`, unset flags are skipped before.
+fn bool_as_int<S>(v: &Option<bool>, ser: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
-    if v.is_none() || *v.as_ref().unwrap() {
-        ser.serialize_i8(1)
-    } else {
-        ser.serialize_i8(0)
+    match v {
+        Some(v) => ser.serialize_u8(u8::from(*v)),
+        None => ser.serialize_none(),
     }
 }
+
 /// Options for create a collection
 #[derive(Serialize, PartialEq, TypedBuilder, Clone)]
 #[builder(doc)]
@@ -108,15 +141,13 @@ pub struct CreateOptions<'a> {
 
     /// whether or not the collection will be compacted (default is true) This
     /// option is meaningful for the MMFiles storage engine only.
-    #[cfg(feature = "mmfiles")]
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "doCompact", skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     do_compat: Option<bool>,
 
     /// The maximal size of a journal or datafile in bytes. The value must be at
     /// least 1048576 (1 MiB). (The default is a configuration parameter) This
     /// option is meaningful for the MMFiles storage engine only.
-    #[cfg(feature = "mmfiles")]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     journal_size: Option<usize>,
@@ -130,7 +161,6 @@ pub struct CreateOptions<'a> {
     /// This option should therefore be used for cache-type collections only,
     /// and not for data that cannot be re-created otherwise. (The default is
     /// false) This option is meaningful for the MMFiles storage engine only.
-    #[cfg(feature = "mmfiles")]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     is_volatile: Option<bool>,
@@ -217,6 +247,21 @@ pub struct CreateOptions<'a> {
     smart_join_attribute: Option<String>,
 }
 
+impl<'a> CreateOptions<'a> {
+    /// Check the options before they are sent to the server.
+    ///
+    /// Fails when the name is invalid, see [`validate_collection_name`], or
+    /// when both `write_concern` and `replication_factor` are set and the
+    /// write concern is larger, which the server rejects with an opaque
+    /// error.
+    pub fn validate(&self) -> Result<(), ClientError> {
+        validate_collection_name(self.name, NamingConvention::Extended)?;
+        #[cfg(feature = "cluster")]
+        check_write_concern(self.write_concern, self.replication_factor)?;
+        Ok(())
+    }
+}
+
 fn is_true(x: &bool) -> bool {
     *x
 }
@@ -270,7 +315,7 @@ pub struct ChecksumOptions {
     /// By setting the optional query parameter withRevisions to true, then
     /// revision ids (_rev system attributes) are included in the
     /// checksumming.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "withRevisions", skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     with_revision: Option<bool>,
     /// By providing the optional query parameter withData with a value of true,
@@ -290,6 +335,65 @@ impl Default for ChecksumOptions {
     }
 }
 
+/// Number of copies of each shard kept in a cluster.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ReplicationFactor {
+    /// Keep this many copies on different DB-Servers
+    Number(u32),
+    /// One copy on every DB-Server, for a SatelliteCollection
+    Satellite,
+}
+
+impl ReplicationFactor {
+    /// The number of copies, `None` for a SatelliteCollection.
+    pub fn number(self) -> Option<u32> {
+        match self {
+            ReplicationFactor::Number(n) => Some(n),
+            ReplicationFactor::Satellite => None,
+        }
+    }
+}
+
+impl From<u32> for ReplicationFactor {
+    fn from(n: u32) -> Self {
+        ReplicationFactor::Number(n)
+    }
+}
+
+impl Serialize for ReplicationFactor {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            ReplicationFactor::Number(n) => serializer.serialize_u32(*n),
+            ReplicationFactor::Satellite => serializer.serialize_str("satellite"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ReplicationFactor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Number(u32),
+            Name(String),
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(n) => Ok(ReplicationFactor::Number(n)),
+            Repr::Name(name) if name == "satellite" => Ok(ReplicationFactor::Satellite),
+            Repr::Name(name) => Err(D::Error::invalid_value(
+                Unexpected::Str(&name),
+                &"a number or \"satellite\"",
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, TypedBuilder, Clone)]
 #[builder(doc)]
 #[serde(rename_all = "camelCase")]
@@ -299,9 +403,70 @@ pub struct PropertiesOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     wait_for_sync: Option<bool>,
-    /* TODO need to implement this with feature gate between versions maybe
-     *  for ArangoDB 3.7
-     * schema: Option<SchemaRules>, */
+
+    /// Whether the in-memory hash cache for documents should be enabled for
+    /// this collection. This option is meaningful for the RocksDB storage
+    /// engine only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    cache_enabled: Option<bool>,
+
+    /// Optional object that specifies the collection level schema for
+    /// documents. The attribute keys rule, level and message must follow the
+    /// rules documented in Document Schema Validation <https://www.arangodb.com/docs/devel/document-schema-validation.html>
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    schema: Option<serde_json::Value>,
+
+    /// In a cluster, this attribute determines how many copies of each shard
+    /// are kept on different DB-Servers, or
+    /// [`ReplicationFactor::Satellite`] for a SatelliteCollection. Changing
+    /// it to or from a SatelliteCollection is not possible.
+    #[cfg(feature = "cluster")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    replication_factor: Option<ReplicationFactor>,
+
+    /// Write concern for this collection.
+    ///
+    /// It determines how many copies of each shard are required to be in sync
+    /// on the different DB-Servers. The value of writeConcern can not be
+    /// larger than replicationFactor. (cluster only)
+    #[cfg(feature = "cluster")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    write_concern: Option<usize>,
+}
+
+impl PropertiesOptions {
+    /// Check the options before they are sent to the server, see
+    /// [`CreateOptions::validate`].
+    pub fn validate(&self) -> Result<(), ClientError> {
+        #[cfg(feature = "cluster")]
+        check_write_concern(
+            self.write_concern,
+            self.replication_factor
+                .and_then(ReplicationFactor::number)
+                .map(|n| n as usize),
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "cluster")]
+fn check_write_concern(
+    write_concern: Option<usize>,
+    replication_factor: Option<usize>,
+) -> Result<(), ClientError> {
+    match (write_concern, replication_factor) {
+        (Some(write_concern), Some(replication_factor)) if write_concern > replication_factor => {
+            Err(ClientError::InvalidArgument(format!(
+                "writeConcern ({}) can not be larger than replicationFactor ({})",
+                write_concern, replication_factor
+            )))
+        }
+        _ => Ok(()),
+    }
 }
 
 impl Default for PropertiesOptions {
@@ -309,3 +474,199 @@ impl Default for PropertiesOptions {
         Self::builder().build()
     }
 }
+
+/// Options of [`Collection::import_documents`](crate::Collection::import_documents)
+/// and [`Collection::import_json_lines`](crate::Collection::import_json_lines).
+#[derive(Debug, Serialize, PartialEq, TypedBuilder, Clone)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOptions {
+    /// What to do with a document whose `_key` exists already, reported as
+    /// an error by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    on_duplicate: Option<OnDuplicate>,
+    /// Remove all documents of the collection before the import. Only sent
+    /// with the first request, so that later batches do not remove the
+    /// documents imported before them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    overwrite: Option<bool>,
+    /// Wait until the documents have been synced to disk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    wait_for_sync: Option<bool>,
+    /// Abort the request and import none of its documents when one of them
+    /// fails. This applies to each request, the batches sent before are kept.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    complete: Option<bool>,
+    /// Report a message for each document that failed in
+    /// [`ImportSummary::details`](crate::collection::response::ImportSummary::details).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    details: Option<bool>,
+    /// Prefix added to the `_from` values of edges without a collection name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    from_prefix: Option<String>,
+    /// Prefix added to the `_to` values of edges without a collection name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    to_prefix: Option<String>,
+    /// Maximum number of documents sent per request, 1000 by default. Not
+    /// sent as a query parameter.
+    #[serde(skip)]
+    #[builder(default = 1000)]
+    batch_size: usize,
+    /// Maximum size of the body of a request in bytes, not limited by
+    /// default. A document larger than it is sent alone. Not sent as a query
+    /// parameter.
+    #[serde(skip)]
+    #[builder(default, setter(strip_option))]
+    batch_bytes: Option<usize>,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl ImportOptions {
+    /// Maximum number of documents sent per request, at least 1.
+    pub(crate) fn batch_size(&self) -> usize {
+        self.batch_size.max(1)
+    }
+
+    /// Maximum size of the body of a request in bytes.
+    pub(crate) fn batch_bytes(&self) -> Option<usize> {
+        self.batch_bytes
+    }
+
+    /// The options of the requests after the first one.
+    pub(crate) fn next_batch(&mut self) {
+        self.overwrite = None;
+    }
+}
+
+/// What an import does with a document whose `_key` exists already.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum OnDuplicate {
+    /// Count the document as an error
+    Error,
+    /// Update the existing document with the attributes of the new one
+    Update,
+    /// Replace the existing document with the new one
+    Replace,
+    /// Keep the existing document and count the new one as ignored
+    Ignore,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn create_parameters_as_int() {
+        let encode = |parameters: &CreateParameters| crate::query::to_string(parameters).unwrap();
+        assert_eq!(encode(&CreateParameters::default()), "");
+        assert_eq!(
+            encode(
+                &CreateParameters::builder()
+                    .wait_for_sync_replication(false)
+                    .build()
+            ),
+            "waitForSyncReplication=0"
+        );
+        assert_eq!(
+            encode(
+                &CreateParameters::builder()
+                    .wait_for_sync_replication(true)
+                    .enforce_replication_factor(false)
+                    .build()
+            ),
+            "enforceReplicationFactor=0&waitForSyncReplication=1"
+        );
+    }
+
+    #[cfg(feature = "cluster")]
+    #[test]
+    fn write_concern_larger_than_replication_factor() {
+        let options = CreateOptions::builder()
+            .name("test")
+            .replication_factor(2)
+            .write_concern(3)
+            .build();
+        match options.validate() {
+            Err(ClientError::InvalidArgument(msg)) => assert_eq!(
+                msg,
+                "writeConcern (3) can not be larger than replicationFactor (2)"
+            ),
+            other => panic!("expected an invalid argument, got {:?}", other),
+        }
+
+        let options = CreateOptions::builder()
+            .name("test")
+            .replication_factor(3)
+            .write_concern(3)
+            .build();
+        assert!(options.validate().is_ok());
+
+        // the server defaults are not known, only both set is checked
+        let options = CreateOptions::builder()
+            .name("test")
+            .write_concern(3)
+            .build();
+        assert!(options.validate().is_ok());
+
+        let properties = PropertiesOptions::builder()
+            .replication_factor(1)
+            .write_concern(2)
+            .build();
+        assert!(properties.validate().is_err());
+
+        let properties = PropertiesOptions::builder()
+            .replication_factor(ReplicationFactor::Satellite)
+            .write_concern(2)
+            .build();
+        assert!(properties.validate().is_ok());
+    }
+
+    #[cfg(not(feature = "cluster"))]
+    #[test]
+    fn validate_on_single_server() {
+        // without the cluster options there is nothing to compare
+        let options = CreateOptions::builder().name("test").build();
+        assert!(options.validate().is_ok());
+        assert_eq!(
+            serde_json::to_string(&options).unwrap(),
+            r#"{"name":"test"}"#
+        );
+        assert!(PropertiesOptions::default().validate().is_ok());
+    }
+
+    #[cfg(feature = "cluster")]
+    #[test]
+    fn replication_factor() {
+        let properties = PropertiesOptions::builder()
+            .replication_factor(ReplicationFactor::Satellite)
+            .build();
+        assert_eq!(
+            serde_json::to_string(&properties).unwrap(),
+            r#"{"replicationFactor":"satellite"}"#
+        );
+        let properties = PropertiesOptions::builder().replication_factor(2).build();
+        assert_eq!(
+            serde_json::to_string(&properties).unwrap(),
+            r#"{"replicationFactor":2}"#
+        );
+
+        let decoded: ReplicationFactor = serde_json::from_str("\"satellite\"").unwrap();
+        assert_eq!(decoded, ReplicationFactor::Satellite);
+        let decoded: ReplicationFactor = serde_json::from_str("3").unwrap();
+        assert_eq!(decoded, ReplicationFactor::Number(3));
+        assert!(serde_json::from_str::<ReplicationFactor>("\"flexible\"").is_err());
+    }
+}
@@ -1,10 +1,20 @@
 //! Types of response related to collection
-use crate::collection::{options::KeyOptions, CollectionType};
+use std::collections::HashSet;
+
+use log::warn;
 use serde::{
-    de::{Deserializer, Error as DeError},
+    de::{DeserializeOwned, Deserializer, Error as DeError},
     Deserialize,
 };
 
+use crate::{
+    collection::{
+        options::{KeyOptions, ReplicationFactor},
+        CollectionType,
+    },
+    Document,
+};
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Info {
@@ -19,6 +29,7 @@ pub struct Info {
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[repr(u8)]
 pub enum Status {
     NewBorn = 1,
     Unloaded = 2,
@@ -26,6 +37,22 @@ pub enum Status {
     Unloading = 4,
     Deleted = 5,
     Loading = 6,
+    /// A status code unknown to this crate, sent by a newer server
+    Unknown = 0,
+}
+
+impl From<u8> for Status {
+    fn from(code: u8) -> Self {
+        match code {
+            1 => Status::NewBorn,
+            2 => Status::Unloaded,
+            3 => Status::Loaded,
+            4 => Status::Unloading,
+            5 => Status::Deleted,
+            6 => Status::Loading,
+            _ => Status::Unknown,
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for Status {
@@ -33,19 +60,12 @@ impl<'de> Deserialize<'de> for Status {
     where
         D: Deserializer<'de>,
     {
-        let value = u8::deserialize(deserializer)?;
-        match value {
-            1 => Ok(Status::NewBorn),
-            2 => Ok(Status::Unloaded),
-            3 => Ok(Status::Loaded),
-            4 => Ok(Status::Unloading),
-            5 => Ok(Status::Deleted),
-            6 => Ok(Status::Loading),
-            _ => Err(DeError::custom(
-                "Undefined behavior. If the crate breaks after an upgrade of ArangoDB, please \
-                 contact the author.",
-            )),
+        let code = u8::deserialize(deserializer)?;
+        let status = Status::from(code);
+        if status == Status::Unknown {
+            warn!("Unknown collection status {}", code);
         }
+        Ok(status)
     }
 }
 
@@ -65,18 +85,30 @@ pub struct Details {
     pub key_options: KeyOptions,
     pub wait_for_sync: bool,
     pub write_concern: u16,
-    #[cfg(rocksdb)]
-    pub cache_enabled: bool,
-    #[cfg(rocksdb)]
-    pub object_id: String,
-    #[cfg(mmfiles)]
-    pub is_volatile: bool,
-    #[cfg(mmfiles)]
-    pub do_compact: bool,
-    #[cfg(mmfiles)]
-    pub journal_size: usize,
-    #[cfg(mmfiles)]
-    pub index_buckets: usize,
+    /// Collection level schema validating the documents, if any
+    #[serde(default)]
+    pub schema: Option<serde_json::Value>,
+    /// Number of copies of each shard, only reported by a cluster
+    #[serde(default)]
+    pub replication_factor: Option<ReplicationFactor>,
+    /// Whether the in-memory hash cache is enabled, RocksDB only
+    #[serde(default)]
+    pub cache_enabled: Option<bool>,
+    /// RocksDB only
+    #[serde(default)]
+    pub object_id: Option<String>,
+    /// MMFiles only
+    #[serde(default)]
+    pub is_volatile: Option<bool>,
+    /// MMFiles only
+    #[serde(default)]
+    pub do_compact: Option<bool>,
+    /// MMFiles only
+    #[serde(default)]
+    pub journal_size: Option<usize>,
+    /// MMFiles only
+    #[serde(default)]
+    pub index_buckets: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -90,6 +122,54 @@ pub struct ArangoIndex {
 #[serde(rename_all = "camelCase")]
 pub struct Figures {
     pub indexes: ArangoIndex,
+    /// Total size of the documents in bytes, RocksDB only
+    pub documents_size: Option<u64>,
+    /// Whether the in-memory cache of the collection is in use
+    pub cache_in_use: Option<bool>,
+    /// Size of the in-memory cache in bytes
+    pub cache_size: Option<u64>,
+    /// Used part of the in-memory cache in bytes
+    pub cache_usage: Option<u64>,
+    /// Figures of the storage engine, only returned by
+    /// [`Collection::detailed_statistics`](crate::Collection::detailed_statistics)
+    pub engine: Option<EngineFigures>,
+}
+
+/// Detailed figures of the storage engine for a collection.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineFigures {
+    /// Number of documents stored
+    pub documents: Option<u64>,
+    /// Figures of each index
+    #[serde(default)]
+    pub indexes: Vec<IndexFigures>,
+}
+
+/// Figures of one index of a collection.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexFigures {
+    /// Index id, without the collection name
+    #[serde(deserialize_with = "id_string")]
+    pub id: String,
+    /// Index type, like `primary` or `persistent`
+    #[serde(rename = "type")]
+    pub index_type: String,
+    /// Number of entries in the index
+    pub count: Option<u64>,
+}
+
+/// Index ids are numbers in engine figures and strings elsewhere.
+fn id_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(id) => Ok(id),
+        serde_json::Value::Number(id) => Ok(id.to_string()),
+        other => Err(DeError::custom(format!("invalid index id: {}", other))),
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -128,3 +208,114 @@ pub struct Checksum {
     #[serde(flatten)]
     pub info: Info,
 }
+
+/// Counts of an import, for a single request or all the requests of
+/// [`Collection::import_documents`](crate::Collection::import_documents).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ImportSummary {
+    /// Number of documents imported
+    pub created: usize,
+    /// Number of documents that failed
+    pub errors: usize,
+    /// Number of empty lines found
+    pub empty: usize,
+    /// Number of existing documents updated or replaced
+    pub updated: usize,
+    /// Number of documents ignored because their `_key` exists already
+    pub ignored: usize,
+    /// Messages of the documents that failed, when the `details` option is
+    /// set
+    #[serde(default)]
+    pub details: Vec<String>,
+}
+
+impl ImportSummary {
+    pub(crate) fn add(&mut self, other: ImportSummary) {
+        self.created += other.created;
+        self.errors += other.errors;
+        self.empty += other.empty;
+        self.updated += other.updated;
+        self.ignored += other.ignored;
+        self.details.extend(other.details);
+    }
+}
+
+/// Progress of an import, passed to the callback after each request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportProgress {
+    /// Number of requests sent
+    pub requests: usize,
+    /// Number of documents sent
+    pub documents: usize,
+    /// Number of bytes sent
+    pub bytes: u64,
+    /// Counts of all the requests sent
+    pub summary: ImportSummary,
+}
+
+/// Counts of
+/// [`Collection::remove_by_keys`](crate::Collection::remove_by_keys)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct RemovedByKeys {
+    /// Number of documents removed
+    pub removed: usize,
+    /// Number of keys without a document
+    pub ignored: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RemovedByExample {
+    pub deleted: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+pub(crate) struct LookupByKeys<T> {
+    pub documents: Vec<Document<T>>,
+}
+
+/// Documents read by
+/// [`Collection::documents_by_keys`](crate::Collection::documents_by_keys)
+#[derive(Debug)]
+pub struct DocumentsByKeys<T> {
+    /// Documents found, in no particular order
+    pub found: Vec<Document<T>>,
+    /// Requested keys without a document, in the order they were requested
+    pub missing: Vec<String>,
+}
+
+impl<T> DocumentsByKeys<T> {
+    pub(crate) fn new(keys: &[&str], found: Vec<Document<T>>) -> Self {
+        let present: HashSet<&str> = found.iter().map(|doc| doc.header._key.as_str()).collect();
+        let mut requested = HashSet::new();
+        let missing = keys
+            .iter()
+            .filter(|key| !present.contains(*key) && requested.insert(**key))
+            .map(|key| key.to_string())
+            .collect();
+        DocumentsByKeys { found, missing }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unknown_status_and_type() {
+        let info: Info = serde_json::from_str(
+            r#"{"id": "1", "name": "future", "globallyUniqueId": "h1/1", "isSystem": false,
+                "status": 7, "type": 4}"#,
+        )
+        .unwrap();
+        assert_eq!(info.status, Status::Unknown);
+        assert_eq!(info.collection_type, CollectionType::Unknown);
+
+        assert_eq!(Status::from(3), Status::Loaded);
+        assert_eq!(Status::Loaded as u8, 3);
+        let edge: CollectionType = serde_json::from_str("3").unwrap();
+        assert_eq!(edge, CollectionType::Edge);
+        assert_eq!(serde_json::to_string(&edge).unwrap(), "3");
+        assert_eq!(CollectionType::Edge as u8, 3);
+    }
+}
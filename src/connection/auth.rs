@@ -5,6 +5,10 @@
 /// - JWT
 /// - no auth
 ///
+/// A JWT token issued beforehand, e.g. the long-lived tokens of managed
+/// deployments, can be used as is with `Bearer`, and a token expected by a
+/// gateway in front of the server with `Header`.
+///
 /// And this enum provides an abstraction to these methods.
 ///
 /// Auth is then used when initialize `Connection`.
@@ -15,15 +19,21 @@
 ///
 /// let basic_auth = Auth::basic("username", "password");
 /// let jwt_auth = Auth::jwt("username", "password");
+/// let bearer_auth = Auth::bearer("eyJhbGciOiJIUzI1NiJ9...");
+/// let api_key_auth = Auth::header("x-api-key", "secret");
 /// let no_auth = Auth::None;
 /// let no_auth = Auth::default();
 /// ```
 #[derive(Debug, Clone, Default)]
-pub(crate) enum Auth<'a> {
+pub enum Auth<'a> {
     /// Basic auth
     Basic(Credential<'a>),
     /// JSON Web Token (JWT) auth
     Jwt(Credential<'a>),
+    /// Static bearer token sent as is, without login
+    Bearer(&'a str),
+    /// Static token sent in a custom header, without login
+    Header { name: &'a str, value: &'a str },
     /// no auth
     #[default]
     None,
@@ -37,11 +47,19 @@ impl<'a> Auth<'a> {
     pub fn jwt(username: &'a str, password: &'a str) -> Auth<'a> {
         Auth::Jwt(Credential { username, password })
     }
+
+    pub fn bearer(token: &'a str) -> Auth<'a> {
+        Auth::Bearer(token)
+    }
+
+    pub fn header(name: &'a str, value: &'a str) -> Auth<'a> {
+        Auth::Header { name, value }
+    }
 }
 
 /// Username and password holder for authentication
 #[derive(Debug, Clone, Hash)]
-pub(crate) struct Credential<'a> {
+pub struct Credential<'a> {
     /// username
     pub username: &'a str,
     /// password
@@ -0,0 +1,140 @@
+//! Capabilities of the connected server
+//!
+//! Options only understood by some server versions or storage engines are
+//! always available in this crate. Servers ignore the options they do not
+//! know, so the same binary runs against any deployment. [`ServerFeatures`]
+//! tells at runtime what the connected server supports, to pick a code path
+//! or to skip an option deliberately.
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+
+/// Storage engine of a server
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageEngine {
+    RocksDb,
+    /// Removed in ArangoDB 3.7
+    MmFiles,
+    #[serde(other)]
+    Other,
+}
+
+/// Version and storage engine of the connected server, see
+/// [`Database::server_features`](crate::Database::server_features).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerFeatures {
+    /// Major, minor and patch version, e.g. `(3, 12, 4)`
+    pub version: (u32, u32, u32),
+    pub engine: StorageEngine,
+}
+
+impl ServerFeatures {
+    pub(crate) fn new(version: &str, engine: StorageEngine) -> Self {
+        ServerFeatures {
+            version: parse_version(version),
+            engine,
+        }
+    }
+
+    /// Whether the server version is at least `major.minor`.
+    pub fn at_least(&self, major: u32, minor: u32) -> bool {
+        (self.version.0, self.version.1) >= (major, minor)
+    }
+
+    /// Whether `overwriteMode`, `keepNull` and `mergeObjects` are honored on
+    /// document insertion, since ArangoDB 3.7.
+    pub fn overwrite_mode(&self) -> bool {
+        self.at_least(3, 7)
+    }
+
+    /// Whether the server runs the RocksDB storage engine, which honors the
+    /// intermediate commit options of AQL queries and supports
+    /// `Collection::recalculate_count`.
+    pub fn rocksdb(&self) -> bool {
+        self.engine == StorageEngine::RocksDb
+    }
+
+    /// Whether the server runs the MMFiles storage engine, which honors the
+    /// journal and volatile options of collections and supports
+    /// `Collection::rotate_journal`.
+    pub fn mmfiles(&self) -> bool {
+        self.engine == StorageEngine::MmFiles
+    }
+}
+
+/// Features of the server once fetched, shared by a database, its clones
+/// and its collections.
+pub(crate) type FeaturesCache = Arc<Mutex<Option<ServerFeatures>>>;
+
+/// Parse versions like `3.12.4` or `3.12.0-devel`, missing or invalid parts
+/// are 0.
+fn parse_version(version: &str) -> (u32, u32, u32) {
+    let mut parts = version.split('.').map(|part| {
+        part.chars()
+            .take_while(char::is_ascii_digit)
+            .collect::<String>()
+            .parse()
+            .unwrap_or(0)
+    });
+    let mut next = || parts.next().unwrap_or(0);
+    (next(), next(), next())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn server_features_from_version() {
+        let features = ServerFeatures::new("3.12.0-devel", StorageEngine::RocksDb);
+        assert_eq!(features.version, (3, 12, 0));
+        assert!(features.overwrite_mode());
+        assert!(features.rocksdb());
+        assert!(!features.mmfiles());
+
+        let features = ServerFeatures::new("3.6.12", StorageEngine::MmFiles);
+        assert!(!features.overwrite_mode());
+        assert!(features.at_least(3, 6));
+        assert!(features.mmfiles());
+
+        assert_eq!(parse_version("4"), (4, 0, 0));
+
+        let engine: StorageEngine = serde_json::from_str(r#""rocksdb""#).unwrap();
+        assert_eq!(engine, StorageEngine::RocksDb);
+        let engine: StorageEngine = serde_json::from_str(r#""inmemory""#).unwrap();
+        assert_eq!(engine, StorageEngine::Other);
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn server_features_are_cached() {
+        use url::Url;
+
+        use crate::{client::mock::RecordingClient, collection::Collection, Database};
+
+        let session = Arc::new(RecordingClient::default());
+        let arango_url: Url = "http://localhost:8529/".parse().unwrap();
+        let db = Database::new("test_db", &arango_url, Arc::clone(&session));
+        session.respond(
+            200,
+            r#"{"server":"arango","version":"3.11.2","license":"community"}"#,
+        );
+        session.respond(200, r#"{"name":"rocksdb"}"#);
+
+        let features = db.server_features().await.unwrap();
+        assert_eq!(features.version, (3, 11, 2));
+        assert_eq!(session.take_requests().len(), 2);
+
+        // clones and collections share the features fetched once
+        let cloned = db.clone().server_features().await.unwrap();
+        assert_eq!(cloned, features);
+        let info = serde_json::from_str(
+            r#"{"id":"1","name":"users","globallyUniqueId":"h1/1","isSystem":false,"status":3,"type":2}"#,
+        )
+        .unwrap();
+        let collection = Collection::from_response(&db, &info);
+        let shared = collection.db().server_features().await.unwrap();
+        assert_eq!(shared, features);
+        assert!(session.take_requests().is_empty());
+    }
+}
@@ -2,9 +2,10 @@
 //! asynchronous), arango URL, and buffered accessible databases object.
 //!
 //! ## Establishing connections
-//! There is three way to establish connections:
+//! There is four way to establish connections:
 //! - jwt
 //! - basic auth
+//! - bearer token issued beforehand
 //! - no authentication
 //!
 //! So are the `arangors` API:
@@ -37,37 +38,63 @@
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
 use base64::{engine::general_purpose, Engine as _};
-use http::header::{HeaderMap, AUTHORIZATION, SERVER};
+use http::header::{HeaderName, HeaderValue, AUTHORIZATION, SERVER};
 use log::{debug, trace};
 use maybe_async::maybe_async;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use url::Url;
 
-use crate::{client::ClientExt, response::ArangoResult, ClientError};
+use crate::{
+    client::{ClientExt, RequestExt},
+    naming::{validate_database_name, NamingConvention},
+    read_only::ReadOnlyDatabase,
+    response::ResponseEnvelope,
+    user::{User, UserOptions, UserResponse},
+    ClientError,
+};
 
 use super::{database::Database, response::deserialize_response};
 
 #[cfg(feature = "cluster")]
 use self::options::{ClusterHealth, CreateDatabase, CreateDatabaseOptions};
 
+pub use self::auth::{Auth, Credential};
 use self::{
-    auth::Auth,
-    role::{Admin, Normal},
+    options::{ClusterEndpoints, ConnectionOptions, TtlProperties, TtlStatistics},
+    role::{Admin, Normal, ReadOnly, Writable},
 };
 
 mod auth;
+pub mod features;
 pub mod options;
+#[cfg(any(feature = "toml", feature = "yaml"))]
+pub mod profile;
 
 pub mod role {
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub struct Normal;
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub struct Admin;
+
+    /// Role of a connection only handing out read-only database handles,
+    /// see `GenericConnection::into_read_only`.
+    #[derive(Debug, Clone)]
+    pub struct ReadOnly;
+
+    /// Roles allowed to write, whose connections hand out full database
+    /// handles.
+    pub trait Writable {}
+
+    impl Writable for Normal {}
+
+    impl Writable for Admin {}
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+/// Access level on a database or collection, ordered from `NoAccess` to
+/// `ReadWrite`.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Permission {
     #[serde(rename = "none")]
     NoAccess,
@@ -81,7 +108,58 @@ pub enum Permission {
 pub struct Version {
     pub server: String,
     pub version: String,
-    pub license: String,
+    pub license: License,
+    /// Build and platform details, only returned by
+    /// [`Database::arango_version_details`](crate::Database::arango_version_details)
+    pub details: Option<VersionDetails>,
+}
+
+/// Edition of a server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum License {
+    Community,
+    Enterprise,
+    /// An edition unknown to this crate, sent by a newer server
+    #[serde(other)]
+    Unknown,
+}
+
+/// Build and platform details of a server.
+///
+/// The details vary with the version and platform of the server, the ones
+/// reported by most servers are typed, all the others are kept in `other`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct VersionDetails {
+    /// CPU architecture, like `64bit`
+    pub architecture: Option<String>,
+    /// Operating system, like `linux`
+    pub platform: Option<String>,
+    /// Host identifier of the server
+    pub host: Option<String>,
+    /// Role of the server, like `server` for a single server or
+    /// `coordinator` in a cluster
+    pub mode: Option<String>,
+    /// When the server was built
+    pub build_date: Option<String>,
+    /// Source revision the server was built from
+    pub build_repository: Option<String>,
+    /// Version of the bundled RocksDB
+    pub rocksdb_version: Option<String>,
+    /// Version of OpenSSL used at runtime
+    pub openssl_version_run_time: Option<String>,
+    /// Version of the bundled ICU
+    pub icu_version: Option<String>,
+    /// Whether the server is a debug build, reported as `"true"` or
+    /// `"false"`
+    pub debug: Option<String>,
+    /// Whether the server runs in maintainer mode, reported as `"true"` or
+    /// `"false"`
+    pub maintainer_mode: Option<String>,
+    /// All the other details
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
 }
 
 #[cfg(any(feature = "reqwest_async", feature = "reqwest_blocking"))]
@@ -90,13 +168,16 @@ pub type Connection = GenericConnection<crate::client::reqwest::ReqwestClient>;
 #[cfg(feature = "surf_async")]
 pub type Connection = GenericConnection<crate::client::surf::SurfClient>;
 
+/// The user name and the authentication header of an authentication method.
+type Authorization = (Option<String>, Option<(HeaderName, HeaderValue)>);
+
 /// Connection is the top level API for this crate.
 /// It contains a http client, information about authentication, arangodb url.
 #[derive(Debug, Clone)]
 pub struct GenericConnection<C: ClientExt, S = Normal> {
     session: Arc<C>,
     arango_url: Url,
-    username: String,
+    username: Option<String>,
     #[allow(dead_code)]
     state: S,
 }
@@ -104,39 +185,36 @@ pub struct GenericConnection<C: ClientExt, S = Normal> {
 impl<S, C: ClientExt> GenericConnection<C, S> {
     /// Validate the server at given arango url
     ///
+    /// Returns the version of the server when it is readable without
+    /// authentication, `None` otherwise.
+    ///
     /// Cast `ClientError` if
-    /// - Invalid url
     /// - Connection failed
     /// - SERVER header in response header is not `ArangoDB` or empty
     #[maybe_async]
-    pub async fn validate_server(arango_url: &str) -> Result<(), ClientError> {
-        let client = C::new(None)?;
-        let resp = client.get(arango_url.parse().unwrap(), "").await?;
-        // have `Server` in header
-        match resp.headers().get(SERVER) {
-            Some(server) => {
-                // value of `Server` is `ArangoDB`
-                let server_value = server.to_str().unwrap();
-                if server_value.eq_ignore_ascii_case("ArangoDB") {
-                    trace!("Validate arangoDB server done.");
-                    Ok(())
-                } else {
-                    Err(ClientError::InvalidServer(server_value.to_owned()))
-                }
-            }
-            None => Err(ClientError::InvalidServer("Unknown".to_owned())),
-        }
+    pub async fn validate_server(arango_url: &Url) -> Result<Option<String>, ClientError> {
+        Self::validate_server_with(&C::new(None)?, arango_url).await
     }
 
-    /// Get url for remote arangoDB server.
-    pub fn url(&self) -> &Url {
-        &self.arango_url
+    #[maybe_async]
+    async fn validate_server_with(
+        client: &C,
+        arango_url: &Url,
+    ) -> Result<Option<String>, ClientError> {
+        let url = arango_url
+            .join("/_api/version")
+            .map_err(|_| ClientError::InvalidServer(format!("invalid url: {}", arango_url)))?;
+        let resp = client.get(url, "").await?;
+        let version = check_server(&resp)?;
+        trace!("Validate arangoDB server done.");
+        Ok(version)
     }
 
     /// Get HTTP session.
     ///
     /// Users can use this method to get a authorized session to access
-    /// arbitrary path on arangoDB Server.
+    /// arbitrary path on arangoDB Server, e.g. to configure a wrapper client.
+    /// Requests sent with it bypass the checks of the read-only role.
     ///
     /// TODO This method should only be public in this crate when all features
     ///     are implemented.
@@ -144,15 +222,134 @@ impl<S, C: ClientExt> GenericConnection<C, S> {
         Arc::clone(&self.session)
     }
 
-    /// Get database object with name.
+    /// Get url for remote arangoDB server.
+    pub fn url(&self) -> &Url {
+        &self.arango_url
+    }
+
+    /// Name of the user authenticated on this connection.
+    ///
+    /// It is `None` for a bearer token or a custom header that is not a JWT
+    /// token with a `preferred_username` claim. It is kept by [`into_admin`](GenericConnection::into_admin) and
+    /// [`into_normal`](GenericConnection::into_normal), and changed by
+    /// [`set_auth`](GenericConnection::set_auth) and
+    /// [`as_user`](GenericConnection::as_user).
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
+    /// Turn into a connection that only hands out read-only database
+    /// handles, see [`read_only`](crate::read_only).
+    ///
+    /// There is no way back to a role allowed to write, write operations do
+    /// not compile:
+    ///
+    /// ```rust, compile_fail
+    /// use arangors::{client::ClientExt, connection::role::ReadOnly, GenericConnection};
+    ///
+    /// fn write<C: ClientExt>(conn: &GenericConnection<C, ReadOnly>) {
+    ///     let _ = conn.db_unchecked("test_db").create_collection("test_collection");
+    /// }
+    /// ```
+    pub fn into_read_only(self) -> GenericConnection<C, ReadOnly> {
+        GenericConnection {
+            arango_url: self.arango_url,
+            session: self.session,
+            username: self.username,
+            state: ReadOnly,
+        }
+    }
+
+    /// Rotate the credentials of a live connection.
+    ///
+    /// The new `Authorization` header is used by this connection and all the
+    /// database, collection and transaction handles made from it, as they
+    /// share its HTTP client. A JWT token is fetched with the new credentials
+    /// first, and the connection is left unchanged when the login fails.
+    ///
+    /// Fails with `ClientError::InvalidArgument` for `Auth::None` and
+    /// `Auth::Header` with another header than `Authorization`, or when
+    /// the HTTP client does not support changing its authorization, see
+    /// [`ClientExt::set_authorization`].
     ///
     /// # Note
-    /// this function would make a request to arango server.
+    /// this function would make a request to arango server with JWT
+    /// authentication.
     #[maybe_async]
-    pub async fn db(&self, name: &str) -> Result<Database<C>, ClientError> {
-        let db = Database::new(name, self.url(), self.session());
-        db.info().await?;
-        Ok(db)
+    pub async fn set_auth(&mut self, auth: Auth<'_>) -> Result<(), ClientError> {
+        let (username, authorization) =
+            GenericConnection::<C, Normal>::authorization(&self.session, &self.arango_url, auth)
+                .await?;
+        let authorization = match authorization {
+            Some((name, value)) if name == AUTHORIZATION => value,
+            Some((name, _)) => {
+                return Err(ClientError::InvalidArgument(format!(
+                    "can not rotate the {} header, only Authorization",
+                    name
+                )))
+            }
+            None => {
+                return Err(ClientError::InvalidArgument(
+                    "can not rotate to no authentication".to_owned(),
+                ))
+            }
+        };
+        self.session.set_authorization(authorization)?;
+        self.username = username;
+        Ok(())
+    }
+
+    /// A connection acting as another user, e.g. to serve the users of a
+    /// multi-user backend from one connection.
+    ///
+    /// The returned connection and the handles made from it send the
+    /// `Authorization` header of `auth` instead of the one of this
+    /// connection. Their HTTP client is a clone, so this connection, the
+    /// handles already made from it and other tasks using it are left
+    /// unchanged, and a later [`set_auth`](GenericConnection::set_auth) on
+    /// this connection does not affect the returned one. The returned
+    /// connection still shares the pool of network connections.
+    ///
+    /// Fails with `ClientError::InvalidArgument` for `Auth::None` and
+    /// `Auth::Header` with another header than `Authorization`.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let alice = conn.as_user(Auth::bearer(&alice_token)).await?;
+    /// let orders = alice.db("shop").await?.collection("orders").await?;
+    /// ```
+    ///
+    /// # Note
+    /// this function would make a request to arango server with JWT
+    /// authentication.
+    #[maybe_async]
+    pub async fn as_user(&self, auth: Auth<'_>) -> Result<Self, ClientError>
+    where
+        S: Clone,
+    {
+        let (username, authorization) =
+            GenericConnection::<C, Normal>::authorization(&self.session, &self.arango_url, auth)
+                .await?;
+        let authorization = match authorization {
+            Some((name, value)) if name == AUTHORIZATION => value,
+            Some((name, _)) => {
+                return Err(ClientError::InvalidArgument(format!(
+                    "can not act as a user with the {} header, only Authorization",
+                    name
+                )))
+            }
+            None => {
+                return Err(ClientError::InvalidArgument(
+                    "can not act as a user without authentication".to_owned(),
+                ))
+            }
+        };
+        Ok(GenericConnection {
+            session: Arc::new(self.session.with_authorization(authorization)?),
+            arango_url: self.arango_url.clone(),
+            username,
+            state: self.state.clone(),
+        })
     }
 
     /// Get a list of accessible database
@@ -160,17 +357,97 @@ impl<S, C: ClientExt> GenericConnection<C, S> {
     /// This function uses the API that is used to retrieve a list of
     /// all databases the current user can access.
     ///
+    /// Fails with `ClientError::InvalidArgument` when the user of the
+    /// connection is unknown, see [`username`](GenericConnection::username).
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn accessible_databases(&self) -> Result<HashMap<String, Permission>, ClientError> {
+        let username = self.username.as_deref().ok_or_else(|| {
+            ClientError::InvalidArgument(
+                "the user of the connection is unknown, name it with accessible_databases_of"
+                    .to_owned(),
+            )
+        })?;
+        self.databases_of(username).await
+    }
+
+    #[maybe_async]
+    async fn databases_of(
+        &self,
+        username: &str,
+    ) -> Result<HashMap<String, Permission>, ClientError> {
+        let url = self
+            .arango_url
+            .join(&format!("/_api/user/{}/database", username))
+            .unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: ResponseEnvelope<HashMap<String, Permission>> = deserialize_response(&resp)?;
+        Ok(result.into_result())
+    }
+
+    /// Get the access level of a user on a database.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn database_permission(
+        &self,
+        username: &str,
+        db_name: &str,
+    ) -> Result<Permission, ClientError> {
+        let url = self
+            .arango_url
+            .join(&format!("/_api/user/{}/database/{}", username, db_name))
+            .unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: ResponseEnvelope<Permission> = deserialize_response(&resp)?;
+        Ok(result.into_result())
+    }
+
+    /// Get the access level of a user on a collection.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn collection_permission(
+        &self,
+        username: &str,
+        db_name: &str,
+        collection: &str,
+    ) -> Result<Permission, ClientError> {
         let url = self
             .arango_url
-            .join(&format!("/_api/user/{}/database", &self.username))
+            .join(&format!(
+                "/_api/user/{}/database/{}/{}",
+                username, db_name, collection
+            ))
             .unwrap();
         let resp = self.session.get(url, "").await?;
-        let result: ArangoResult<HashMap<String, Permission>> = deserialize_response(resp.body())?;
-        Ok(result.unwrap())
+        let result: ResponseEnvelope<Permission> = deserialize_response(&resp)?;
+        Ok(result.into_result())
+    }
+
+    /// Returns the current time of the server, in seconds since the Unix
+    /// epoch with sub-second precision.
+    ///
+    /// See [`datetime`](crate::datetime) to convert timestamps in documents.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn server_time(&self) -> Result<f64, ClientError> {
+        #[derive(Deserialize)]
+        struct ServerTime {
+            time: f64,
+        }
+
+        let url = self.arango_url.join("/_admin/time").unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: ServerTime = deserialize_response(&resp)?;
+
+        Ok(result.time)
     }
 
     // Returns the role of a server in a cluster. The role is returned in the role
@@ -191,11 +468,48 @@ impl<S, C: ClientExt> GenericConnection<C, S> {
     pub async fn server_role(&self) -> Result<String, ClientError> {
         let url = self.arango_url.join("/_admin/server/role").unwrap();
         let resp = self.session.get(url, "").await?;
-        let result: HashMap<String, Value> = deserialize_response(resp.body())?;
+        let result: HashMap<String, Value> = deserialize_response(&resp)?;
 
         Ok(result.get("role").unwrap().as_str().unwrap().to_owned())
     }
 
+    /// Returns the urls of the coordinators of a cluster, or of the servers
+    /// of an active failover deployment with the leader first.
+    ///
+    /// Single servers answer with an error.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn cluster_endpoints(&self) -> Result<Vec<Url>, ClientError> {
+        let url = self.arango_url.join("/_api/cluster/endpoints").unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: ClusterEndpoints = deserialize_response(&resp)?;
+        result
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint_url(&endpoint.endpoint))
+            .collect()
+    }
+
+    /// Returns the url of the current leader of an active failover
+    /// deployment, which followers answer as well, or of the first
+    /// coordinator of a cluster.
+    ///
+    /// See [`ConnectionOptions::discover_leader`] to bind a connection to the
+    /// leader when it is established.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn current_leader(&self) -> Result<Url, ClientError> {
+        self.cluster_endpoints()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ClientError::NotFound("no endpoint in the deployment".to_owned()))
+    }
+
     /// Returns the health of the cluster as assessed by the supervision
     /// (Agency)
     ///
@@ -206,12 +520,85 @@ impl<S, C: ClientExt> GenericConnection<C, S> {
     pub async fn cluster_health(&self) -> Result<ClusterHealth, ClientError> {
         let url = self.arango_url.join("/_admin/cluster/health").unwrap();
         let resp = self.session.get(url, "").await?;
-        let result: ClusterHealth = deserialize_response(resp.body())?;
+        let result: ClusterHealth = deserialize_response(&resp)?;
 
         Ok(result)
     }
 }
 
+impl<S: Writable, C: ClientExt> GenericConnection<C, S> {
+    /// Get database object with name.
+    ///
+    /// The database is checked to exist and be accessible, which costs a
+    /// request for every handle. Use [`db_unchecked`] on hot paths where the
+    /// database is known to exist.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    ///
+    /// [`db_unchecked`]: GenericConnection::db_unchecked
+    #[maybe_async]
+    pub async fn db(&self, name: &str) -> Result<Database<C>, ClientError> {
+        let db = Database::new(name, self.url(), self.session());
+        db.info().await?;
+        Ok(db)
+    }
+
+    /// Get database object with name, without checking that it exists.
+    ///
+    /// A missing or inaccessible database is only reported by the first
+    /// request made with the handle.
+    pub fn db_unchecked(&self, name: &str) -> Database<C> {
+        Database::new(name, self.url(), self.session())
+    }
+
+    /// Get database objects for all accessible databases, e.g. to run
+    /// maintenance over a database per tenant.
+    ///
+    /// Only databases the user has at least `min_permission` on are returned,
+    /// or at least read-only access when `None`. Database handles are built
+    /// from the listing without another request per database.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn databases(
+        &self,
+        min_permission: Option<Permission>,
+    ) -> Result<Vec<Database<C>>, ClientError> {
+        let min_permission = min_permission.unwrap_or(Permission::ReadOnly);
+        let mut names: Vec<String> = self
+            .accessible_databases()
+            .await?
+            .into_iter()
+            .filter(|(_, permission)| *permission >= min_permission)
+            .map(|(name, _)| name)
+            .collect();
+        names.sort();
+
+        Ok(names.iter().map(|name| self.db_unchecked(name)).collect())
+    }
+}
+
+impl<C: ClientExt> GenericConnection<C, ReadOnly> {
+    /// Get read-only database object with name.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn db(&self, name: &str) -> Result<ReadOnlyDatabase<C>, ClientError> {
+        let db = ReadOnlyDatabase::new(Database::new(name, self.url(), Arc::clone(&self.session)));
+        db.info().await?;
+        Ok(db)
+    }
+
+    /// Get read-only database object with name, without checking that it
+    /// exists.
+    pub fn db_unchecked(&self, name: &str) -> ReadOnlyDatabase<C> {
+        ReadOnlyDatabase::new(Database::new(name, self.url(), Arc::clone(&self.session)))
+    }
+}
+
 impl<C: ClientExt> GenericConnection<C, Normal> {
     /// Establish connection to ArangoDB sever with Auth.
     ///
@@ -227,48 +614,97 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
     async fn establish<T: Into<String>>(
         arango_url: T,
         auth: Auth<'_>,
+    ) -> Result<GenericConnection<C, Normal>, ClientError> {
+        Self::establish_with_client(C::new(None)?, arango_url, auth).await
+    }
+
+    /// Establish connection to ArangoDB sever with a configured HTTP client,
+    /// e.g. a [`ReqwestClient`](crate::client::reqwest::ReqwestClient) built
+    /// with proxy and timeout settings.
+    ///
+    /// The client is used for all requests, including the validation of the
+    /// server and the JWT login. The authorization header is added to its
+    /// headers.
+    ///
+    /// Example:
+    /// ```rust, ignore
+    /// use arangors::{client::reqwest::ReqwestClient, connection::Auth, Connection};
+    ///
+    /// let client = ReqwestClient::with_options(None, Default::default()).unwrap();
+    /// let conn = Connection::establish_with_client(
+    ///     client,
+    ///     "http://localhost:8529",
+    ///     Auth::basic("username", "password"),
+    /// )
+    /// .await
+    /// .unwrap();
+    /// ```
+    #[maybe_async]
+    pub async fn establish_with_client<T: Into<String>>(
+        client: C,
+        arango_url: T,
+        auth: Auth<'_>,
+    ) -> Result<GenericConnection<C, Normal>, ClientError> {
+        Self::establish_with_options(client, arango_url, auth, ConnectionOptions::default()).await
+    }
+
+    /// Establish connection to ArangoDB sever with a configured HTTP client
+    /// and [`ConnectionOptions`], e.g. to skip the validation of the server
+    /// behind a proxy stripping the `Server` header.
+    ///
+    /// Example:
+    /// ```rust, ignore
+    /// use arangors::{
+    ///     client::reqwest::ReqwestClient,
+    ///     connection::{options::ConnectionOptions, Auth},
+    ///     Connection,
+    /// };
+    ///
+    /// let conn = Connection::establish_with_options(
+    ///     ReqwestClient::new(None).unwrap(),
+    ///     "http://localhost:8529",
+    ///     Auth::jwt("username", "password"),
+    ///     ConnectionOptions::builder().validate_server(false).build(),
+    /// )
+    /// .await
+    /// .unwrap();
+    /// ```
+    #[maybe_async]
+    pub async fn establish_with_options<T: Into<String>>(
+        client: C,
+        arango_url: T,
+        auth: Auth<'_>,
+        options: ConnectionOptions,
     ) -> Result<GenericConnection<C, Normal>, ClientError> {
         let url_str = arango_url.into();
         let arango_url = Url::parse(&url_str)
-            .map_err(|_| ClientError::InvalidServer(format!("invalid url: {}", url_str)))?
-            .join("/")
-            .unwrap();
-
-        Self::validate_server(&url_str).await?;
+            .and_then(|url| url.join("/"))
+            .map_err(|_| ClientError::InvalidServer(format!("invalid url: {}", url_str)))?;
 
-        let username: String;
-        let authorization = match auth {
-            Auth::Basic(cred) => {
-                username = String::from(cred.username);
+        if options.validate_server {
+            Self::validate_server_with(&client, &arango_url).await?;
+        }
 
-                let token = general_purpose::STANDARD_NO_PAD
-                    .encode(format!("{}:{}", cred.username, cred.password));
-                Some(format!("Basic {}", token))
-            }
-            Auth::Jwt(cred) => {
-                username = String::from(cred.username);
+        let (username, authorization) = Self::authorization(&client, &arango_url, auth).await?;
 
-                let token = Self::jwt_login(&arango_url, cred.username, cred.password).await?;
-                Some(format!("Bearer {}", token))
-            }
-            Auth::None => {
-                username = String::from("root");
-                None
-            }
-        };
-
-        let mut headers = HeaderMap::new();
-        if let Some(value) = authorization {
-            headers.insert(AUTHORIZATION, value.parse().unwrap());
+        let mut client = client;
+        if let Some((name, value)) = authorization {
+            client.headers().insert(name, value);
         }
 
-        debug!("Established");
-        Ok(GenericConnection {
+        let mut conn = GenericConnection {
             arango_url,
             username,
-            session: Arc::new(C::new(headers)?),
+            session: Arc::new(client),
             state: Normal,
-        })
+        };
+        if options.discover_leader {
+            conn.arango_url = conn.current_leader().await?;
+            debug!("Bound to the leader {}", conn.arango_url);
+        }
+
+        debug!("Established");
+        Ok(conn)
     }
 
     /// Establish connection to ArangoDB sever without Authentication.
@@ -347,8 +783,86 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
         GenericConnection::establish(arango_url, Auth::jwt(username, password)).await
     }
 
+    /// Establish connection to ArangoDB sever with a JWT token issued
+    /// beforehand, like the long-lived tokens of managed deployments.
+    ///
+    /// The token is sent as is, no login happens. It is not renewed, rotate
+    /// it with [`set_auth`](GenericConnection::set_auth) before it expires.
+    ///
+    /// Example:
+    /// ```rust, ignore
+    /// use arangors::Connection;
+    ///
+    /// let conn = Connection::establish_bearer("https://example.arangodb.cloud:8529", "eyJhbGciOi...")
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    #[maybe_async]
+    pub async fn establish_bearer(
+        arango_url: &str,
+        token: &str,
+    ) -> Result<GenericConnection<C, Normal>, ClientError> {
+        trace!("Establish with bearer token");
+        GenericConnection::establish(arango_url, Auth::bearer(token)).await
+    }
+
+    /// The user name and the authentication header of an authentication
+    /// method, logging in to get a JWT token if needed.
+    ///
+    /// The user of a static token is read from the `preferred_username` claim
+    /// of a JWT token, and is unknown for other tokens. Without
+    /// authentication, all operations are performed by root.
+    #[maybe_async]
+    async fn authorization(
+        client: &C,
+        arango_url: &Url,
+        auth: Auth<'_>,
+    ) -> Result<Authorization, ClientError> {
+        let (username, authorization) = match auth {
+            Auth::Basic(cred) => {
+                let token = general_purpose::STANDARD_NO_PAD
+                    .encode(format!("{}:{}", cred.username, cred.password));
+                (
+                    Some(cred.username.to_owned()),
+                    Some((AUTHORIZATION, format!("Basic {}", token))),
+                )
+            }
+            Auth::Jwt(cred) => {
+                let token =
+                    Self::jwt_login(client, arango_url, cred.username, cred.password).await?;
+                (
+                    Some(cred.username.to_owned()),
+                    Some((AUTHORIZATION, format!("Bearer {}", token))),
+                )
+            }
+            Auth::Bearer(token) => (
+                jwt_username(token),
+                Some((AUTHORIZATION, format!("Bearer {}", token))),
+            ),
+            Auth::Header { name, value } => {
+                let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+                    ClientError::InvalidArgument(format!("invalid header name: {}", name))
+                })?;
+                let token = value.strip_prefix("Bearer ").unwrap_or(value);
+                (jwt_username(token), Some((name, value.to_owned())))
+            }
+            Auth::None => (Some("root".to_owned()), None),
+        };
+        let authorization = authorization
+            .map(|(name, value)| {
+                HeaderValue::from_str(&value)
+                    .map(|value| (name, value))
+                    .map_err(|_| {
+                        ClientError::InvalidArgument("invalid characters in credentials".to_owned())
+                    })
+            })
+            .transpose()?;
+        Ok((username, authorization))
+    }
+
     #[maybe_async]
     async fn jwt_login<T: Into<String>>(
+        client: &C,
         arango_url: &Url,
         username: T,
         password: T,
@@ -363,12 +877,8 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
         map.insert("username", username.into());
         map.insert("password", password.into());
 
-        let jwt: Jwt = deserialize_response(
-            C::new(None)?
-                .post(url, &serde_json::to_string(&map)?)
-                .await?
-                .body(),
-        )?;
+        let jwt: Jwt =
+            deserialize_response(&client.post(url, &serde_json::to_string(&map)?).await?)?;
         Ok(jwt.jwt)
     }
 
@@ -396,10 +906,15 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
     /// ```
     /// TODO tweak options on creating database
     ///
+    /// The name is checked with
+    /// [`validate_database_name`](crate::naming::validate_database_name)
+    /// first.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn create_database(&self, name: &str) -> Result<Database<C>, ClientError> {
+        validate_database_name(name, NamingConvention::Extended)?;
         let mut map = HashMap::new();
         map.insert("name", name);
         let url = self.arango_url.join("/_api/database").unwrap();
@@ -409,7 +924,7 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
             .post(url, &serde_json::to_string(&map)?)
             .await?;
 
-        deserialize_response::<ArangoResult<bool>>(resp.body())?;
+        deserialize_response::<ResponseEnvelope<bool>>(&resp)?;
         self.db(name).await
     }
 
@@ -420,6 +935,7 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
         name: &str,
         options: CreateDatabaseOptions,
     ) -> Result<Database<C>, ClientError> {
+        validate_database_name(name, NamingConvention::Extended)?;
         let url = self.arango_url.join("/_api/database").unwrap();
         let final_options = CreateDatabase::builder()
             .name(name)
@@ -431,7 +947,7 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
             .post(url, &serde_json::to_string(&final_options)?)
             .await?;
 
-        deserialize_response::<ArangoResult<bool>>(resp.body())?;
+        deserialize_response::<ResponseEnvelope<bool>>(&resp)?;
         self.db(name).await
     }
 
@@ -445,10 +961,24 @@ impl<C: ClientExt> GenericConnection<C, Normal> {
         let url = self.arango_url.join(&url_path).unwrap();
 
         let resp = self.session.delete(url, "").await?;
-        deserialize_response::<ArangoResult<bool>>(resp.body())?;
+        deserialize_response::<ResponseEnvelope<bool>>(&resp)?;
         Ok(())
     }
 
+    /// Rename database `old` to `new`.
+    ///
+    /// No released version of ArangoDB can rename a database, so this fails
+    /// with `ClientError::UnsupportedByServer` without making a request.
+    /// Create a database with the new name and copy the data over instead,
+    /// e.g. with `arangodump` and `arangorestore`.
+    #[maybe_async]
+    pub async fn rename_database(&self, old: &str, new: &str) -> Result<(), ClientError> {
+        Err(ClientError::UnsupportedByServer(format!(
+            "renaming database {} to {}",
+            old, new
+        )))
+    }
+
     #[maybe_async]
     pub async fn into_admin(self) -> Result<GenericConnection<C, Admin>, ClientError> {
         let dbs = self.accessible_databases().await?;
@@ -472,6 +1002,199 @@ impl<C: ClientExt> GenericConnection<C, Admin> {
     pub fn into_normal(self) -> GenericConnection<C, Normal> {
         self.into()
     }
+
+    /// Statistics of the TTL background thread removing expired documents.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn ttl_statistics(&self) -> Result<TtlStatistics, ClientError> {
+        let url = self.arango_url.join("/_api/ttl/statistics").unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: ResponseEnvelope<TtlStatistics> = deserialize_response(&resp)?;
+
+        Ok(result.into_result())
+    }
+
+    /// Properties of the TTL background thread.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn ttl_properties(&self) -> Result<TtlProperties, ClientError> {
+        let url = self.arango_url.join("/_api/ttl/properties").unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: ResponseEnvelope<TtlProperties> = deserialize_response(&resp)?;
+
+        Ok(result.into_result())
+    }
+
+    /// Change the properties of the TTL background thread and return the
+    /// updated properties.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn set_ttl_properties(
+        &self,
+        properties: &TtlProperties,
+    ) -> Result<TtlProperties, ClientError> {
+        let url = self.arango_url.join("/_api/ttl/properties").unwrap();
+        let resp = self
+            .session
+            .put(url, serde_json::to_string(properties)?)
+            .await?;
+        let result: ResponseEnvelope<TtlProperties> = deserialize_response(&resp)?;
+
+        Ok(result.into_result())
+    }
+
+    /// Get the databases another user can access, with the access level of
+    /// the user on each.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn accessible_databases_of(
+        &self,
+        username: &str,
+    ) -> Result<HashMap<String, Permission>, ClientError> {
+        self.databases_of(username).await
+    }
+
+    /// List all users of the server.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn list_users(&self) -> Result<Vec<User>, ClientError> {
+        let url = self.arango_url.join("/_api/user").unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: UserResponse = deserialize_response(&resp)?;
+
+        Ok(result.result)
+    }
+
+    /// Create a user without any access, see
+    /// [`grant_database_access`](Self::grant_database_access).
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_user(
+        &self,
+        username: &str,
+        options: &UserOptions,
+    ) -> Result<User, ClientError> {
+        #[derive(Serialize)]
+        struct CreateUser<'a> {
+            user: &'a str,
+            #[serde(flatten)]
+            options: &'a UserOptions,
+        }
+
+        let url = self.arango_url.join("/_api/user").unwrap();
+        let body = CreateUser {
+            user: username,
+            options,
+        };
+        let resp = self
+            .session
+            .post(url, serde_json::to_string(&body)?)
+            .await?;
+
+        deserialize_response(&resp)
+    }
+
+    /// Change the attributes of a user set in `options`, leaving the others
+    /// unchanged.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn update_user(
+        &self,
+        username: &str,
+        options: &UserOptions,
+    ) -> Result<User, ClientError> {
+        let url = self
+            .arango_url
+            .join(&format!("/_api/user/{}", username))
+            .unwrap();
+        let resp = self
+            .session
+            .patch(url, serde_json::to_string(options)?)
+            .await?;
+
+        deserialize_response(&resp)
+    }
+
+    /// Delete a user.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn delete_user(&self, username: &str) -> Result<(), ClientError> {
+        let url = self
+            .arango_url
+            .join(&format!("/_api/user/{}", username))
+            .unwrap();
+        let resp = self.session.delete(url, "").await?;
+        deserialize_response::<Value>(&resp)?;
+
+        Ok(())
+    }
+
+    /// Set the access level of a user on a database, `NoAccess` revoking it.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn grant_database_access(
+        &self,
+        username: &str,
+        db_name: &str,
+        permission: Permission,
+    ) -> Result<(), ClientError> {
+        let url = self
+            .arango_url
+            .join(&format!("/_api/user/{}/database/{}", username, db_name))
+            .unwrap();
+        self.grant(url, permission).await
+    }
+
+    /// Set the access level of a user on a collection, `NoAccess` revoking
+    /// it. The access level on the database applies to the collections
+    /// without one.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn grant_collection_access(
+        &self,
+        username: &str,
+        db_name: &str,
+        collection: &str,
+        permission: Permission,
+    ) -> Result<(), ClientError> {
+        let url = self
+            .arango_url
+            .join(&format!(
+                "/_api/user/{}/database/{}/{}",
+                username, db_name, collection
+            ))
+            .unwrap();
+        self.grant(url, permission).await
+    }
+
+    #[maybe_async]
+    async fn grant(&self, url: Url, permission: Permission) -> Result<(), ClientError> {
+        let body = serde_json::json!({ "grant": permission });
+        let resp = self.session.put(url, body.to_string()).await?;
+        deserialize_response::<Value>(&resp)?;
+
+        Ok(())
+    }
 }
 
 impl<C: ClientExt> From<GenericConnection<C, Normal>> for GenericConnection<C, Admin> {
@@ -495,3 +1218,195 @@ impl<C: ClientExt> From<GenericConnection<C, Admin>> for GenericConnection<C, No
         }
     }
 }
+
+/// The `preferred_username` claim of a JWT token, if `token` is one.
+///
+/// The signature is not checked, the server does it when the token is used.
+fn jwt_username(token: &str) -> Option<String> {
+    let payload = token.split('.').nth(1)?;
+    let payload = general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    let claims: Value = serde_json::from_slice(&payload).ok()?;
+    claims
+        .get("preferred_username")?
+        .as_str()
+        .map(str::to_owned)
+}
+
+/// Check the `Server` header of a response, and read the version of the
+/// server from its body when the request was allowed.
+fn check_server(resp: &http::Response<String>) -> Result<Option<String>, ClientError> {
+    let server = resp
+        .headers()
+        .get(SERVER)
+        .ok_or_else(|| ClientError::InvalidServer("Unknown".to_owned()))?;
+    let server_value = server
+        .to_str()
+        .map_err(|_| ClientError::InvalidServer(format!("{:?}", server)))?;
+    if !server_value.eq_ignore_ascii_case("ArangoDB") {
+        return Err(ClientError::InvalidServer(server_value.to_owned()));
+    }
+    if !resp.status().is_success() {
+        return Ok(None);
+    }
+    Ok(serde_json::from_str::<Value>(resp.body())
+        .ok()
+        .and_then(|body| body.get("version")?.as_str().map(str::to_owned)))
+}
+
+/// The url of an endpoint of the server, like `tcp://127.0.0.1:8529` or
+/// `ssl://[::1]:8530`.
+fn endpoint_url(endpoint: &str) -> Result<Url, ClientError> {
+    let (scheme, address) = endpoint
+        .split_once("://")
+        .ok_or_else(|| ClientError::InvalidServer(format!("invalid endpoint: {}", endpoint)))?;
+    let scheme = match scheme {
+        "tcp" | "http+tcp" => "http",
+        "ssl" | "http+ssl" => "https",
+        _ => {
+            return Err(ClientError::InvalidServer(format!(
+                "unsupported endpoint: {}",
+                endpoint
+            )))
+        }
+    };
+    Url::parse(&format!("{}://{}/", scheme, address.trim_end_matches('/')))
+        .map_err(|_| ClientError::InvalidServer(format!("invalid endpoint: {}", endpoint)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn server_validation() {
+        let response = |status: u16, server: Option<&str>, body: &str| {
+            let mut builder = http::Response::builder().status(status);
+            if let Some(server) = server {
+                builder = builder.header(SERVER, server);
+            }
+            builder.body(body.to_owned()).unwrap()
+        };
+
+        let version = check_server(&response(
+            200,
+            Some("ArangoDB"),
+            r#"{"server":"arango","version":"3.12.4","license":"community"}"#,
+        ));
+        assert_eq!(version.unwrap().as_deref(), Some("3.12.4"));
+        let version = check_server(&response(401, Some("ArangoDB"), ""));
+        assert_eq!(version.unwrap(), None);
+
+        let missing = check_server(&response(200, None, "{}"));
+        assert!(matches!(missing, Err(ClientError::InvalidServer(s)) if s == "Unknown"));
+        let other = check_server(&response(200, Some("nginx"), "{}"));
+        assert!(matches!(other, Err(ClientError::InvalidServer(s)) if s == "nginx"));
+    }
+
+    #[test]
+    fn endpoint_urls() {
+        let url = endpoint_url("tcp://127.0.0.1:8529").unwrap();
+        assert_eq!(url.as_str(), "http://127.0.0.1:8529/");
+        let url = endpoint_url("ssl://[::1]:8530").unwrap();
+        assert_eq!(url.as_str(), "https://[::1]:8530/");
+        let url = endpoint_url("http+tcp://leader.arango:8529").unwrap();
+        assert_eq!(url.as_str(), "http://leader.arango:8529/");
+
+        let unix = endpoint_url("unix:///tmp/arangod.sock");
+        assert!(matches!(unix, Err(ClientError::InvalidServer(_))));
+        assert!(endpoint_url("127.0.0.1:8529").is_err());
+    }
+
+    #[test]
+    fn jwt_username_claim() {
+        let encode = |claims: &str| general_purpose::URL_SAFE_NO_PAD.encode(claims);
+        let token = format!(
+            "{}.{}.signature",
+            encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+            encode(r#"{"preferred_username":"ops","iss":"arangodb"}"#)
+        );
+        assert_eq!(jwt_username(&token).as_deref(), Some("ops"));
+
+        let superuser = format!(
+            "{}.{}.signature",
+            encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+            encode(r#"{"server_id":"setup","iss":"arangodb"}"#)
+        );
+        assert_eq!(jwt_username(&superuser), None);
+        assert_eq!(jwt_username("api-key"), None);
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn accessible_databases_of_unknown_user() {
+        use crate::client::mock::RecordingClient;
+
+        let session = Arc::new(RecordingClient::default());
+        let conn = GenericConnection {
+            session: Arc::clone(&session),
+            arango_url: "http://localhost:8529/".parse().unwrap(),
+            username: None,
+            state: Admin,
+        };
+        let databases = conn.accessible_databases().await;
+        assert!(matches!(databases, Err(ClientError::InvalidArgument(_))));
+        assert!(session.take_requests().is_empty());
+
+        session.respond(
+            200,
+            r#"{"error":false,"code":200,"result":{"_system":"rw"}}"#,
+        );
+        let databases = conn.accessible_databases_of("ops").await.unwrap();
+        assert_eq!(databases.get("_system"), Some(&Permission::ReadWrite));
+        let requests = session.take_requests();
+        assert_eq!(requests[0].uri().path(), "/_api/user/ops/database");
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn rename_database_unsupported() {
+        use crate::client::mock::RecordingClient;
+
+        let session = Arc::new(RecordingClient::default());
+        let conn = GenericConnection {
+            session: Arc::clone(&session),
+            arango_url: "http://localhost:8529/".parse().unwrap(),
+            username: None,
+            state: Normal,
+        };
+        let result = conn.rename_database("test_db", "renamed_db").await;
+        assert!(matches!(result, Err(ClientError::UnsupportedByServer(_))));
+        assert!(session.take_requests().is_empty());
+    }
+
+    #[test]
+    fn version_details() {
+        let version: Version = serde_json::from_str(
+            r#"{
+                "server": "arango",
+                "version": "3.12.4",
+                "license": "enterprise",
+                "details": {
+                    "architecture": "64bit",
+                    "build-date": "2025-01-20 10:00:00",
+                    "mode": "coordinator",
+                    "rocksdb-version": "7.2.0",
+                    "maintainer-mode": "false",
+                    "zlib-version": "1.3.1"
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(version.license, License::Enterprise);
+        let details = version.details.unwrap();
+        assert_eq!(details.mode.as_deref(), Some("coordinator"));
+        assert_eq!(details.build_date.as_deref(), Some("2025-01-20 10:00:00"));
+        assert_eq!(details.maintainer_mode.as_deref(), Some("false"));
+        assert_eq!(details.other.len(), 1);
+        assert_eq!(details.other["zlib-version"], "1.3.1");
+
+        let version: Version =
+            serde_json::from_str(r#"{"server":"arango","version":"4.0.0","license":"cloud"}"#)
+                .unwrap();
+        assert_eq!(version.license, License::Unknown);
+    }
+}
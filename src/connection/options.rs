@@ -47,6 +47,32 @@ pub struct CreateDatabaseOptions {
     write_concern: Option<usize>,
 }
 
+/// Options of establishing a connection, see
+/// `GenericConnection::establish_with_options`.
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+#[builder(doc)]
+pub struct ConnectionOptions {
+    /// Whether the `Server` header of the server is checked to be
+    /// `ArangoDB` before logging in. Disable it when a proxy strips the
+    /// header.
+    #[builder(default = true)]
+    pub validate_server: bool,
+
+    /// Whether the connection is bound to the current leader of an active
+    /// failover deployment, found with
+    /// `GenericConnection::current_leader`, instead of the given url, which
+    /// may be a follower. It also works with the coordinators of a cluster,
+    /// binding the connection to the first one.
+    #[builder(default = false)]
+    pub discover_leader: bool,
+}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        ConnectionOptions::builder().build()
+    }
+}
+
 #[derive(Serialize, PartialEq, TypedBuilder)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct CreateDatabase<'a> {
@@ -119,3 +145,58 @@ pub struct ClusterHealth {
 
     pub health: HashMap<String, ServerHealth>,
 }
+
+/// Statistics of the TTL background thread, which removes expired documents
+/// of collections with a TTL index.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtlStatistics {
+    /// Number of runs of the background thread
+    pub runs: u64,
+    /// Number of documents removed
+    pub documents_removed: u64,
+    /// Number of runs that stopped on `maxTotalRemoves` or
+    /// `maxCollectionRemoves`
+    pub limit_reached: u64,
+}
+
+/// Properties of the TTL background thread.
+///
+/// When changing properties, unset fields are left as they are.
+#[derive(Debug, Clone, Serialize, Deserialize, TypedBuilder, PartialEq)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct TtlProperties {
+    /// Whether the background thread is running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub active: Option<bool>,
+    /// Milliseconds between two runs of the background thread
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub frequency: Option<u64>,
+    /// Maximum number of documents removed in a run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub max_total_removes: Option<u64>,
+    /// Maximum number of documents removed per collection in a run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub max_collection_removes: Option<u64>,
+    /// Whether only collections loaded in memory are considered, only used by
+    /// older server versions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub only_loaded_collections: Option<bool>,
+}
+
+/// Endpoint of a server, as returned by `/_api/cluster/endpoints`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ClusterEndpoint {
+    pub endpoint: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ClusterEndpoints {
+    pub endpoints: Vec<ClusterEndpoint>,
+}
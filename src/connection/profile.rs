@@ -0,0 +1,361 @@
+//! Connection profiles loaded from a file
+//!
+//! A profile file holds named connection settings, one table per profile. The
+//! format is chosen by the file extension: `.toml` with the `toml` feature,
+//! `.yaml` or `.yml` with the `yaml` feature.
+//!
+//! ```toml
+//! [prod]
+//! url = "https://arango.example.com:8529"
+//! database = "app"
+//! # in seconds
+//! connect_timeout = 5
+//! timeout = 60
+//! # skip checking the Server header, e.g. behind a proxy stripping it
+//! validate_server = false
+//! # bind to the leader of an active failover deployment
+//! discover_leader = true
+//!
+//! [prod.auth]
+//! kind = "jwt" # or "basic", "bearer", "header", "none"
+//! username = "ops"
+//! password = "secret"
+//!
+//! [prod.tls]
+//! ca_cert = "/etc/ssl/arango-ca.pem"
+//!
+//! [local]
+//! url = "http://localhost:8529"
+//! ```
+//!
+//! # Example
+//! ```rust, ignore
+//! use arangors::{connection::profile::Profile, Connection};
+//!
+//! let conn = Connection::from_profile("arango.toml", "prod").await?;
+//!
+//! // or, to use the default database of the profile
+//! let profile = Profile::load("arango.toml", "prod")?;
+//! let conn = profile.connect().await?;
+//! let db = conn.db(profile.database()).await?;
+//! ```
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{connection::Auth, ClientError};
+
+/// Connection settings of a profile.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Url of the server, e.g. `http://localhost:8529`
+    pub url: String,
+    #[serde(default)]
+    pub auth: ProfileAuth,
+    #[serde(default)]
+    pub tls: ProfileTls,
+    /// Timeout of establishing a connection, in seconds
+    pub connect_timeout: Option<u64>,
+    /// Timeout of a request until its response is read, in seconds
+    pub timeout: Option<u64>,
+    /// Database used by default, `_system` if not set
+    pub database: Option<String>,
+    /// Whether the `Server` header is checked when connecting, true if not
+    /// set
+    pub validate_server: Option<bool>,
+    /// Whether the connection is bound to the current leader, false if not
+    /// set
+    pub discover_leader: Option<bool>,
+}
+
+/// Authentication of a profile, selected by its `kind`.
+///
+/// Passwords, tokens and header values are left out of its `Debug` output.
+#[derive(Clone, Default, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase", deny_unknown_fields)]
+pub enum ProfileAuth {
+    #[default]
+    None,
+    Basic {
+        username: String,
+        password: String,
+    },
+    Jwt {
+        username: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    Header {
+        name: String,
+        value: String,
+    },
+}
+
+impl fmt::Debug for ProfileAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileAuth::None => f.write_str("None"),
+            ProfileAuth::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"<redacted>")
+                .finish(),
+            ProfileAuth::Jwt { username, .. } => f
+                .debug_struct("Jwt")
+                .field("username", username)
+                .field("password", &"<redacted>")
+                .finish(),
+            ProfileAuth::Bearer { .. } => f
+                .debug_struct("Bearer")
+                .field("token", &"<redacted>")
+                .finish(),
+            ProfileAuth::Header { name, .. } => f
+                .debug_struct("Header")
+                .field("name", name)
+                .field("value", &"<redacted>")
+                .finish(),
+        }
+    }
+}
+
+impl ProfileAuth {
+    pub fn auth(&self) -> Auth<'_> {
+        match self {
+            ProfileAuth::None => Auth::None,
+            ProfileAuth::Basic { username, password } => Auth::basic(username, password),
+            ProfileAuth::Jwt { username, password } => Auth::jwt(username, password),
+            ProfileAuth::Bearer { token } => Auth::bearer(token),
+            ProfileAuth::Header { name, value } => Auth::header(name, value),
+        }
+    }
+}
+
+/// TLS settings of a profile.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileTls {
+    /// PEM file of an additional certificate authority to trust
+    pub ca_cert: Option<PathBuf>,
+    /// Accept invalid certificates, only for tests
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+}
+
+impl Profile {
+    /// Load the profile `name` from the file at `path`.
+    ///
+    /// Returns [`ClientError::NotFound`] if the file has no such profile,
+    /// and [`ClientError::InvalidArgument`] if it can not be read or parsed.
+    pub fn load(path: impl AsRef<Path>, name: &str) -> Result<Profile, ClientError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| {
+            ClientError::InvalidArgument(format!("can not read {}: {}", path.display(), e))
+        })?;
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let mut profiles = parse(extension, &content).map_err(|e| {
+            ClientError::InvalidArgument(format!("invalid profile file {}: {}", path.display(), e))
+        })?;
+        profiles
+            .remove(name)
+            .ok_or_else(|| ClientError::NotFound(format!("profile {} in {}", name, path.display())))
+    }
+
+    /// Database used by default.
+    pub fn database(&self) -> &str {
+        self.database.as_deref().unwrap_or("_system")
+    }
+}
+
+/// Parse the profiles of a file with `extension`.
+fn parse(extension: &str, content: &str) -> Result<HashMap<String, Profile>, String> {
+    match extension {
+        #[cfg(feature = "toml")]
+        "toml" => toml::from_str(content).map_err(|e| e.to_string()),
+        #[cfg(feature = "yaml")]
+        "yaml" | "yml" => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        _ => Err(format!("unsupported file extension: {:?}", extension)),
+    }
+}
+
+#[cfg(any(feature = "reqwest_async", feature = "reqwest_blocking"))]
+mod connect {
+    use std::{fs, path::Path, time::Duration};
+
+    use maybe_async::maybe_async;
+
+    use super::Profile;
+    use crate::{
+        client::reqwest::{ReqwestClient, ReqwestOptions},
+        connection::{options::ConnectionOptions, role::Normal, GenericConnection},
+        ClientError, Connection,
+    };
+
+    impl Profile {
+        /// Settings of the HTTP client, reading the certificate file.
+        pub fn reqwest_options(&self) -> Result<ReqwestOptions, ClientError> {
+            let root_certificate = match &self.tls.ca_cert {
+                Some(path) => Some(fs::read(path).map_err(|e| {
+                    ClientError::InvalidArgument(format!("can not read {}: {}", path.display(), e))
+                })?),
+                None => None,
+            };
+            Ok(ReqwestOptions {
+                connect_timeout: self.connect_timeout.map(Duration::from_secs),
+                timeout: self.timeout.map(Duration::from_secs),
+                root_certificate,
+                accept_invalid_certs: self.tls.accept_invalid_certs,
+                ..Default::default()
+            })
+        }
+
+        /// Establish a connection with the settings of this profile.
+        ///
+        /// # Note
+        /// this function would make a request to arango server.
+        #[maybe_async]
+        pub async fn connect(&self) -> Result<Connection, ClientError> {
+            let client = ReqwestClient::with_options(None, self.reqwest_options()?)?;
+            let options = ConnectionOptions::builder()
+                .validate_server(self.validate_server.unwrap_or(true))
+                .discover_leader(self.discover_leader.unwrap_or(false))
+                .build();
+            Connection::establish_with_options(client, self.url.as_str(), self.auth.auth(), options)
+                .await
+        }
+    }
+
+    impl GenericConnection<ReqwestClient, Normal> {
+        /// Establish a connection with the profile `name` of the file at
+        /// `path`, see [`Profile`](super::Profile).
+        ///
+        /// # Note
+        /// this function would make a request to arango server.
+        #[maybe_async]
+        pub async fn from_profile(
+            path: impl AsRef<Path>,
+            name: &str,
+        ) -> Result<Connection, ClientError> {
+            Profile::load(path, name)?.connect().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn parse_toml() {
+        let profiles = parse(
+            "toml",
+            r#"
+            [prod]
+            url = "https://arango.example.com:8529"
+            database = "app"
+            connect_timeout = 5
+            discover_leader = true
+
+            [prod.auth]
+            kind = "jwt"
+            username = "ops"
+            password = "secret"
+
+            [prod.tls]
+            ca_cert = "/etc/ssl/arango-ca.pem"
+
+            [local]
+            url = "http://localhost:8529"
+            "#,
+        )
+        .unwrap();
+
+        let prod = &profiles["prod"];
+        assert_eq!(prod.url, "https://arango.example.com:8529");
+        assert_eq!(prod.database(), "app");
+        assert_eq!(prod.connect_timeout, Some(5));
+        assert_eq!(prod.discover_leader, Some(true));
+        assert!(matches!(prod.auth.auth(), Auth::Jwt(c) if c.username == "ops"));
+        assert_eq!(
+            prod.tls.ca_cert.as_deref(),
+            Some(Path::new("/etc/ssl/arango-ca.pem"))
+        );
+
+        let local = &profiles["local"];
+        assert_eq!(local.database(), "_system");
+        assert_eq!(local.discover_leader, None);
+        assert!(matches!(local.auth.auth(), Auth::None));
+
+        let unknown = parse(
+            "toml",
+            "[prod]\nurl = \"http://localhost:8529\"\nhost = \"x\"\n",
+        );
+        assert!(unknown.is_err());
+        assert!(parse("ini", "").is_err());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn parse_yaml() {
+        let profiles = parse(
+            "yml",
+            r#"
+staging:
+  url: http://staging:8529
+  timeout: 30
+  auth:
+    kind: basic
+    username: root
+    password: ""
+  tls:
+    accept_invalid_certs: true
+cloud:
+  url: https://example.arangodb.cloud:8529
+  auth:
+    kind: bearer
+    token: eyJhbGciOiJIUzI1NiJ9
+"#,
+        )
+        .unwrap();
+
+        let staging = &profiles["staging"];
+        assert_eq!(staging.timeout, Some(30));
+        assert!(staging.tls.accept_invalid_certs);
+        assert!(matches!(staging.auth.auth(), Auth::Basic(c) if c.username == "root"));
+        assert!(matches!(
+            profiles["cloud"].auth.auth(),
+            Auth::Bearer("eyJhbGciOiJIUzI1NiJ9")
+        ));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn debug_redacts_secrets() {
+        let profiles = parse(
+            "toml",
+            r#"
+            [prod]
+            url = "https://arango.example.com:8529"
+            auth = { kind = "basic", username = "ops", password = "secret" }
+
+            [cloud]
+            url = "https://example.arangodb.cloud:8529"
+            auth = { kind = "bearer", token = "eyJhbGciOiJIUzI1NiJ9" }
+            "#,
+        )
+        .unwrap();
+
+        let prod = format!("{:?}", profiles["prod"]);
+        assert!(prod.contains("ops"));
+        assert!(!prod.contains("secret"));
+        let cloud = format!("{:?}", profiles["cloud"]);
+        assert!(!cloud.contains("eyJhbGciOiJIUzI1NiJ9"));
+    }
+}
@@ -1,30 +1,62 @@
 //! struct and enum pertain to arangoDB database
 //!
 //! AQL query are all executed in database level, so Database offers AQL query.
-use std::{collections::HashMap, fmt::Debug, sync::Arc};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
 
+use http::Method;
 use log::trace;
 use maybe_async::maybe_async;
-use serde::{de::DeserializeOwned, Deserialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::value::Value;
+use typed_builder::TypedBuilder;
 use url::Url;
 
 use crate::{
-    analyzer::{AnalyzerDescription, AnalyzerInfo},
-    aql::{AqlQuery, Cursor},
-    client::ClientExt,
+    analyzer::{
+        analyzer_matches, AnalyzerDescription, AnalyzerInfo, AnalyzerSyncOptions,
+        AnalyzerSyncReport,
+    },
+    aql::{
+        check_warnings, delete_pending_cursors, fetch_within, next_batches_within, run_bounded,
+        with_batch_prefetcher, AqlQuery, Cursor, CursorGuard, CursorHeader, CursorStream,
+        ExplainedQuery, PartialResults, PendingCursors, PlanCacheEntry, PreparedQuery,
+        QueryAnalysis, QueryContext, RawBatch, SlowQueryLog, WarningPolicy,
+    },
+    client::{ClientExt, RequestExt},
     collection::{
         options::{CreateOptions, CreateParameters},
         response::{Info, Properties},
         Collection, CollectionType,
     },
-    connection::Version,
-    graph::{Graph, GraphCollection, GraphResponse, GHARIAL_API_PATH},
-    index::{DeleteIndexResponse, Index, IndexCollection, INDEX_API_PATH},
-    response::{deserialize_response, ArangoResult},
+    connection::{
+        features::{FeaturesCache, ServerFeatures, StorageEngine},
+        Version,
+    },
+    error::codes,
+    graph::{Graph, GraphCollection, GraphHandle, GraphResponse, Traversal, GHARIAL_API_PATH},
+    index::{
+        DeleteIndexResponse, EdgeDirection, Index, IndexBuild, IndexCollection, INDEX_API_PATH,
+    },
+    namespace::Namespace,
+    naming::{self, NamingConvention},
+    query,
+    response::{
+        deserialize_items, deserialize_response, deserialize_response_value, response_value,
+        unexpected, DecodedItems, ResponseEnvelope, ResponseMeta,
+    },
+    search::{Scored, SearchQuery},
     transaction::{
-        ArangoTransaction, Transaction, TransactionList, TransactionSettings, TransactionState,
-        TRANSACTION_HEADER,
+        ArangoTransaction, Transaction, TransactionBuilder, TransactionDefaults, TransactionList,
+        TransactionSettings, TransactionState, TRANSACTION_HEADER,
     },
     user::{
         access_level_enum_to_str, DeleteUserResponse, User, UserAccessLevel,
@@ -32,16 +64,30 @@ use crate::{
     },
     view::{
         ArangoSearchViewProperties, ArangoSearchViewPropertiesOptions, View, ViewDescription,
-        ViewOptions,
+        ViewHandle, ViewOptions,
     },
     ClientError,
 };
 
+/// Handle on a database.
+///
+/// `Database<C>` is `Send + Sync` when `C` is. Cloning it shares the HTTP
+/// client and the cursor and collection caches through `Arc`s, only the name
+/// and url are copied. No request is made.
 #[derive(Debug, Clone)]
 pub struct Database<C: ClientExt> {
     name: String,
     base_url: Url,
     session: Arc<C>,
+    cursor_cleanup: bool,
+    batch_prefetch: bool,
+    warning_policy: WarningPolicy,
+    slow_query_log: Option<SlowQueryLog>,
+    transaction_defaults: TransactionDefaults,
+    default_wait_for_sync: Option<bool>,
+    pub(crate) pending_cursors: PendingCursors,
+    pub(crate) server_features: FeaturesCache,
+    collections: Arc<Mutex<HashMap<String, Collection<C>>>>,
 }
 
 impl<'a, C: ClientExt> Database<C> {
@@ -49,33 +95,92 @@ impl<'a, C: ClientExt> Database<C> {
         let name = name.into();
         let path = format!("/_db/{}/", name.as_str());
         let url = arango_url.join(path.as_str()).unwrap();
+        Self::with_url(name, url, session)
+    }
+
+    /// A database at `base_url`, like `http://server:port/_db/mydb/`.
+    pub(crate) fn with_url<T: Into<String>>(
+        name: T,
+        base_url: Url,
+        session: Arc<C>,
+    ) -> Database<C> {
         Database {
-            name,
+            name: name.into(),
             session,
-            base_url: url,
+            base_url,
+            cursor_cleanup: true,
+            batch_prefetch: false,
+            warning_policy: WarningPolicy::default(),
+            slow_query_log: None,
+            transaction_defaults: TransactionDefaults::default(),
+            default_wait_for_sync: None,
+            pending_cursors: Arc::new(Mutex::new(Vec::new())),
+            server_features: Arc::new(Mutex::new(None)),
+            collections: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
     /// Retrieve all collections of this database.
     ///
+    /// Fails when the information of a collection can not be decoded, e.g.
+    /// with a field of a newer server version. Use
+    /// [`accessible_collections_decoded`](Database::accessible_collections_decoded)
+    /// to get the other collections anyway.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn accessible_collections(&self) -> Result<Vec<Info>, ClientError> {
+        self.list_collections(false).await?.into_result()
+    }
+
+    /// Retrieve the collections of this database, leaving out the system
+    /// collections, whose name starts with `_`.
+    ///
+    /// The system collections are filtered by the server. Fails when the
+    /// information of a collection can not be decoded, like
+    /// [`accessible_collections`](Database::accessible_collections).
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn non_system_collections(&self) -> Result<Vec<Info>, ClientError> {
+        self.list_collections(true).await?.into_result()
+    }
+
+    /// Retrieve all collections of this database, decoding the information
+    /// of each collection on its own and returning the collections which
+    /// could not be decoded along with the others, e.g. to skip them.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn accessible_collections_decoded(&self) -> Result<DecodedItems<Info>, ClientError> {
+        self.list_collections(false).await
+    }
+
+    #[maybe_async]
+    async fn list_collections(
+        &self,
+        exclude_system: bool,
+    ) -> Result<DecodedItems<Info>, ClientError> {
         // an invalid arango_url should never running through initialization
         // so we assume arango_url is a valid url
         // When we pass an invalid path, it should panic to eliminate the bug
         // in development.
-        let url = self.base_url.join("_api/collection").unwrap();
+        let mut url = self.base_url.join("_api/collection").unwrap();
+        if exclude_system {
+            url.set_query(Some("excludeSystem=true"));
+        }
         trace!(
             "Retrieving collections from {:?}: {}",
             self.name,
             url.as_str()
         );
         let resp = self.session.get(url, "").await?;
-        let result: ArangoResult<Vec<Info>> = deserialize_response(resp.body())?;
+        let result = deserialize_items(&resp)?;
         trace!("Collections retrieved");
-        Ok(result.unwrap())
+        Ok(result)
     }
 
     pub fn url(&self) -> &Url {
@@ -90,6 +195,139 @@ impl<'a, C: ClientExt> Database<C> {
         Arc::clone(&self.session)
     }
 
+    /// A handle on the same database sending its requests with `session`.
+    ///
+    /// The settings are copied, the cursor and collection caches are new
+    /// ones, since the cached collections send their requests with the
+    /// session of this handle.
+    pub(crate) fn with_session(&self, session: Arc<C>) -> Database<C> {
+        Database {
+            session,
+            pending_cursors: Arc::new(Mutex::new(Vec::new())),
+            collections: Arc::new(Mutex::new(HashMap::new())),
+            ..self.clone()
+        }
+    }
+
+    /// Handle on the collections of tenant `name`, whose names are prefixed
+    /// with `name_`, see [`Namespace`].
+    pub fn namespace(&self, name: &str) -> Namespace<C> {
+        Namespace::new(self.clone(), name)
+    }
+
+    /// Whether server cursors are deleted when fetching all results of an AQL
+    /// query is cancelled or fails midway.
+    pub fn cursor_cleanup(&self) -> bool {
+        self.cursor_cleanup
+    }
+
+    /// Enable or disable best-effort deletion of server cursors abandoned by
+    /// `aql_query`, `aql_str` and `aql_bind_vars`, e.g. when the future is
+    /// dropped on timeout. Enabled by default.
+    ///
+    /// Without cleanup, abandoned cursors stay on the server until their TTL
+    /// expires. In async mode, abandoned cursors are deleted before the next
+    /// AQL query on this database, or by [`close`](Database::close).
+    pub fn set_cursor_cleanup(&mut self, enabled: bool) {
+        self.cursor_cleanup = enabled;
+    }
+
+    /// Delete the server cursors abandoned by this handle and its clones.
+    ///
+    /// In async mode a cursor abandoned by a dropped future is only queued,
+    /// and deleted before the next AQL query on this database. Call `close`
+    /// when no more queries are run, e.g. on shutdown, otherwise the queued
+    /// cursors stay on the server until their TTL expires. In blocking mode
+    /// abandoned cursors are deleted right away and there is nothing left to
+    /// delete.
+    ///
+    /// Failures are ignored, the handle can still be used afterwards.
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async]
+    pub async fn close(&self) {
+        delete_pending_cursors(self.session.as_ref(), &self.base_url, &self.pending_cursors).await;
+    }
+
+    /// Whether the next cursor batch is prefetched by the fetch-all AQL
+    /// helpers.
+    pub fn batch_prefetch(&self) -> bool {
+        self.batch_prefetch
+    }
+
+    /// Enable or disable prefetching in `aql_query`, `aql_str` and
+    /// `aql_bind_vars`. Disabled by default.
+    ///
+    /// When enabled, the request for the next batch is sent while the current
+    /// batch is being deserialized, hiding network latency on large result
+    /// sets with many batches. In blocking mode the requests of a query are
+    /// sent from one worker thread, started with the query.
+    pub fn set_batch_prefetch(&mut self, enabled: bool) {
+        self.batch_prefetch = enabled;
+    }
+
+    /// What is done with the warnings returned with AQL query results.
+    pub fn warning_policy(&self) -> WarningPolicy {
+        self.warning_policy
+    }
+
+    /// Set what is done with the warnings returned with AQL query results,
+    /// unless a query sets its own policy in its options. Warnings are ignored
+    /// by default.
+    ///
+    /// Transactions begun afterwards inherit the policy.
+    pub fn set_warning_policy(&mut self, policy: WarningPolicy) {
+        self.warning_policy = policy;
+    }
+
+    /// How the slow AQL queries of this database are reported, if at all.
+    pub fn slow_query_log(&self) -> Option<&SlowQueryLog> {
+        self.slow_query_log.as_ref()
+    }
+
+    /// Report the AQL queries of this database slower than a threshold, or
+    /// `None` to stop. Not set by default.
+    ///
+    /// Queries run by `aql_query`, `aql_query_batch` and the other AQL
+    /// methods of the database are reported, not those of transactions.
+    pub fn set_slow_query_log(&mut self, log: Option<SlowQueryLog>) {
+        self.slow_query_log = log;
+    }
+
+    /// Options of the transactions begun with
+    /// [`transaction`](Database::transaction), unless its builder sets them.
+    pub fn transaction_defaults(&self) -> &TransactionDefaults {
+        &self.transaction_defaults
+    }
+
+    /// Set the options of the transactions begun with
+    /// [`transaction`](Database::transaction), e.g. a lock timeout for all
+    /// transactions of a service. They are left to the server by default.
+    ///
+    /// `begin_transaction` uses its settings as given.
+    pub fn set_transaction_defaults(&mut self, defaults: TransactionDefaults) {
+        self.transaction_defaults = defaults;
+    }
+
+    /// `waitForSync` of the document writes which do not set it, see
+    /// [`set_default_wait_for_sync`](Database::set_default_wait_for_sync).
+    pub fn default_wait_for_sync(&self) -> Option<bool> {
+        self.default_wait_for_sync
+    }
+
+    /// Set the `waitForSync` of the document inserts, updates, replacements
+    /// and removals whose options do not set it, e.g. `Some(true)` in
+    /// production and `Some(false)` in tests, or `None` to leave it to the
+    /// collections. Not set by default.
+    ///
+    /// Collection handles obtained afterwards inherit it, cached ones
+    /// included. A [`Durability::Synced`](crate::document::options::Durability::Synced)
+    /// write always waits for sync.
+    pub fn set_default_wait_for_sync(&mut self, wait_for_sync: Option<bool>) {
+        self.default_wait_for_sync = wait_for_sync;
+    }
+
     /// Get collection object with name.
     ///
     /// # Note
@@ -100,13 +338,41 @@ impl<'a, C: ClientExt> Database<C> {
             .base_url
             .join(&format!("_api/collection/{}", name))
             .unwrap();
-        let resp: Info = deserialize_response(self.session.get(url, "").await?.body())?;
+        let resp: Info = deserialize_response(&self.session.get(url, "").await?)?;
         Ok(Collection::from_response(self, &resp))
     }
 
+    /// Get collection object with name, reusing the handle of a previous call.
+    ///
+    /// Only the first call for a name checks the collection on the server,
+    /// later calls return a clone of the cached handle without a round trip.
+    /// Collections dropped through this database are removed from the cache,
+    /// but collections dropped or renamed elsewhere are not noticed.
+    ///
+    /// # Note
+    /// this function would make a request to arango server on the first call
+    /// for a name.
+    #[maybe_async]
+    pub async fn collection_cached(&self, name: &str) -> Result<Collection<C>, ClientError> {
+        if let Some(collection) = self.collections.lock().unwrap().get(name) {
+            // the cache is shared with the clones of this handle
+            let mut collection = collection.clone();
+            collection.set_default_wait_for_sync(self.default_wait_for_sync);
+            return Ok(collection);
+        }
+        let collection = self.collection(name).await?;
+        self.collections
+            .lock()
+            .unwrap()
+            .insert(name.to_owned(), collection.clone());
+        Ok(collection)
+    }
+
     /// Create a collection via HTTP request with options.
     ///
-    /// Return a collection object if success.
+    /// Return a collection object if success, built from the response of the
+    /// creation without another request. The options are checked with
+    /// [`CreateOptions::validate`] first.
     ///
     /// # Note
     /// this function would make a request to arango server.
@@ -116,16 +382,36 @@ impl<'a, C: ClientExt> Database<C> {
         options: CreateOptions<'f>,
         parameters: CreateParameters,
     ) -> Result<Collection<C>, ClientError> {
+        options.validate()?;
         let mut url = self.base_url.join("_api/collection").unwrap();
-        let query = serde_qs::to_string(&parameters).unwrap();
+        let query = query::to_string(&parameters)?;
         url.set_query(Some(query.as_str()));
 
         let resp = self
             .session
             .post(url, &serde_json::to_string(&options)?)
             .await?;
-        let result: Properties = deserialize_response(resp.body())?;
-        self.collection(&result.info.name).await
+        let result: Properties = deserialize_response(&resp)?;
+        Ok(Collection::from_response(self, &result.info))
+    }
+
+    /// Create a collection with options like
+    /// [`create_collection_with_options`](Self::create_collection_with_options),
+    /// then fetch it with [`collection`](Self::collection), e.g. to make sure
+    /// it is visible on the coordinator before using it.
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async]
+    pub async fn create_collection_and_fetch<'f>(
+        &self,
+        options: CreateOptions<'f>,
+        parameters: CreateParameters,
+    ) -> Result<Collection<C>, ClientError> {
+        let collection = self
+            .create_collection_with_options(options, parameters)
+            .await?;
+        self.collection(collection.name()).await
     }
 
     /// Create a collection via HTTP request.
@@ -170,7 +456,8 @@ impl<'a, C: ClientExt> Database<C> {
         }
 
         let resp: DropCollectionResponse =
-            deserialize_response(self.session.delete(url, "").await?.body())?;
+            deserialize_response(&self.session.delete(url, "").await?)?;
+        self.collections.lock().unwrap().remove(name);
         Ok(resp.id)
     }
 
@@ -182,10 +469,60 @@ impl<'a, C: ClientExt> Database<C> {
     pub async fn arango_version(&self) -> Result<Version, ClientError> {
         let url = self.base_url.join("_api/version").unwrap();
         let resp = self.session.get(url, "").await?;
-        let version: Version = serde_json::from_str(resp.body())?;
+        let version: Version = deserialize_response(&resp)?;
         Ok(version)
     }
 
+    /// Get the version of the server along with its build and platform
+    /// details, in `details`.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let version = db.arango_version_details().await?;
+    /// if version.license == License::Enterprise {
+    ///     // ...
+    /// }
+    /// let details = version.details.unwrap();
+    /// println!("{:?} on {:?}", details.mode, details.platform);
+    /// ```
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn arango_version_details(&self) -> Result<Version, ClientError> {
+        let url = self.base_url.join("_api/version?details=true").unwrap();
+        let resp = self.session.get(url, "").await?;
+        let version: Version = deserialize_response(&resp)?;
+        Ok(version)
+    }
+
+    /// Get the version and storage engine of the server, to check what it
+    /// supports at runtime.
+    ///
+    /// The features are fetched once, then cached and shared with the clones
+    /// of this database and its collections.
+    ///
+    /// # Note
+    /// this function would make requests to arango server the first time.
+    #[maybe_async]
+    pub async fn server_features(&self) -> Result<ServerFeatures, ClientError> {
+        #[derive(Debug, Deserialize)]
+        struct Engine {
+            name: StorageEngine,
+        }
+
+        if let Some(features) = self.server_features.lock().unwrap().as_ref() {
+            return Ok(features.clone());
+        }
+        let version = self.arango_version().await?;
+        let url = self.base_url.join("_api/engine").unwrap();
+        let resp = self.session.get(url, "").await?;
+        let engine: Engine = deserialize_response(&resp)?;
+        let features = ServerFeatures::new(&version.version, engine.name);
+        *self.server_features.lock().unwrap() = Some(features.clone());
+        Ok(features)
+    }
+
     /// Get information of current database.
     ///
     /// # Note
@@ -194,8 +531,24 @@ impl<'a, C: ClientExt> Database<C> {
     pub async fn info(&self) -> Result<DatabaseDetails, ClientError> {
         let url = self.base_url.join("_api/database/current").unwrap();
         let resp = self.session.get(url, "").await?;
-        let res: ArangoResult<DatabaseDetails> = deserialize_response(resp.body())?;
-        Ok(res.unwrap())
+        let res: ResponseEnvelope<DatabaseDetails> = deserialize_response(&resp)?;
+        Ok(res.into_result())
+    }
+
+    /// Change the properties of the current database.
+    ///
+    /// No released version of ArangoDB can change them once the database is
+    /// created, so this fails with `ClientError::UnsupportedByServer` without
+    /// making a request. Read them with [`info`](Database::info).
+    #[maybe_async]
+    pub async fn set_properties(
+        &self,
+        properties: DatabaseProperties,
+    ) -> Result<DatabaseDetails, ClientError> {
+        Err(ClientError::UnsupportedByServer(format!(
+            "changing the properties of database {} to {:?}",
+            self.name, properties
+        )))
     }
 
     /// Execute aql query, return a cursor if succeed. The major advantage of
@@ -210,20 +563,199 @@ impl<'a, C: ClientExt> Database<C> {
     where
         R: DeserializeOwned,
     {
+        self.aql_query_batch_with_meta(aql)
+            .await
+            .map(|(cursor, _)| cursor)
+    }
+
+    /// Execute aql query, return a cursor along with the metadata of the
+    /// response, like whether it may be a dirty read.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_query_batch_with_meta<R>(
+        &self,
+        aql: AqlQuery<'_>,
+    ) -> Result<(Cursor<R>, ResponseMeta), ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        delete_pending_cursors(self.session.as_ref(), &self.base_url, &self.pending_cursors).await;
+
+        let policy = aql.warning_policy().unwrap_or(self.warning_policy);
         let url = self.base_url.join("_api/cursor").unwrap();
-        let resp = self
-            .session
-            .post(url, &serde_json::to_string(&aql)?)
-            .await?;
-        deserialize_response(resp.body())
+        let req = aql
+            .context()
+            .request(Method::POST, url, serde_json::to_string(&aql)?)?;
+        let start = Instant::now();
+        let resp = self.session.request(req).await?;
+        let cursor: Cursor<R> = deserialize_response(&resp)?;
+        if let Some(log) = &self.slow_query_log {
+            let stats = cursor.extra.as_ref().and_then(|extra| extra.stats.as_ref());
+            log.record(&aql, start.elapsed(), stats);
+        }
+        if let Err(e) = check_warnings(policy, cursor.extra.as_ref()) {
+            if let Some(id) = cursor.id.filter(|_| cursor.more && self.cursor_cleanup) {
+                self.pending_cursors.lock().unwrap().push(id);
+            }
+            return Err(e);
+        }
+        Ok((cursor, ResponseMeta::of(&resp)))
+    }
+
+    /// Execute aql query and read its results lazily, one at a time.
+    ///
+    /// A batch is kept as the body of its response and each result is
+    /// decoded when read, so the memory used is bounded by the size of a
+    /// batch, set with `batch_size`, rather than by the number of results.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let aql = AqlQuery::builder()
+    ///     .query("FOR u IN users RETURN u")
+    ///     .batch_size(10_000)
+    ///     .build();
+    /// let mut users = db.aql_query_stream::<User>(aql).await?;
+    /// while let Some(user) = users.next_result().await? {
+    ///     println!("{}", user.name);
+    /// }
+    /// ```
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_query_stream<R>(
+        &self,
+        aql: AqlQuery<'_>,
+    ) -> Result<CursorStream<C, R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        delete_pending_cursors(self.session.as_ref(), &self.base_url, &self.pending_cursors).await;
+
+        let policy = aql.warning_policy().unwrap_or(self.warning_policy);
+        let context = aql.context();
+        let url = self.base_url.join("_api/cursor").unwrap();
+        let req = context.request(Method::POST, url, serde_json::to_string(&aql)?)?;
+        let start = Instant::now();
+        let resp = self.session.request(req).await?;
+        let batch: RawBatch<R> = RawBatch::from_response(resp)?;
+        if let Some(log) = &self.slow_query_log {
+            let stats = batch.extra().and_then(|extra| extra.stats.as_ref());
+            log.record(&aql, start.elapsed(), stats);
+        }
+        let guard = CursorGuard::new(
+            self.session(),
+            self.base_url.clone(),
+            batch
+                .id()
+                .filter(|_| batch.more() && self.cursor_cleanup)
+                .map(str::to_owned),
+            Arc::clone(&self.pending_cursors),
+        );
+        check_warnings(policy, batch.extra())?;
+        Ok(CursorStream::new(
+            self.session(),
+            self.base_url.clone(),
+            context,
+            policy,
+            batch,
+            guard,
+        ))
+    }
+
+    /// Execute a graph traversal query and fetch its steps lazily, one batch
+    /// at a time.
+    ///
+    /// The query must return objects with the `vertex`, `edge` and,
+    /// optionally, `path` of each step. Limit the depth of the traversal in
+    /// the query itself, and set `batch_size` to bound the memory used.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let aql = AqlQuery::builder()
+    ///     .query(
+    ///         "FOR v, e, p IN 1..3 OUTBOUND @start GRAPH @graph \
+    ///          RETURN { vertex: v, edge: e, path: p }",
+    ///     )
+    ///     .bind_var("start", "persons/alice")
+    ///     .bind_var("graph", "social")
+    ///     .batch_size(100)
+    ///     .build();
+    /// let mut traversal = db.aql_traversal::<Value, Value>(aql).await?;
+    /// while let Some(step) = traversal.next_step().await? {
+    ///     println!("{:?}", step.vertex);
+    /// }
+    /// ```
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_traversal<V, E>(
+        &self,
+        aql: AqlQuery<'_>,
+    ) -> Result<Traversal<C, V, E>, ClientError>
+    where
+        V: DeserializeOwned,
+        E: DeserializeOwned,
+    {
+        let context = aql.context();
+        let policy = aql.warning_policy().unwrap_or(self.warning_policy);
+        let cursor = self.aql_query_batch(aql).await?;
+        Ok(Traversal::new(
+            self.session(),
+            self.base_url.clone(),
+            context,
+            policy,
+            cursor,
+            self.cursor_cleanup,
+            Arc::clone(&self.pending_cursors),
+        ))
     }
 
     /// Get next batch given the cursor id.
     ///
+    /// The request is sent without the context of the query, use
+    /// [`aql_next_batch_with`](Database::aql_next_batch_with) for a query
+    /// run with one.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn aql_next_batch<R>(&self, cursor_id: &str) -> Result<Cursor<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.next_batch(cursor_id, self.warning_policy, &QueryContext::default())
+            .await
+    }
+
+    /// Get next batch given the cursor id, sending the request with the
+    /// context of the query, e.g. its transaction or dirty reads.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_next_batch_with<R>(
+        &self,
+        cursor_id: &str,
+        context: &QueryContext,
+    ) -> Result<Cursor<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.next_batch(cursor_id, self.warning_policy, context)
+            .await
+    }
+
+    #[maybe_async]
+    pub(crate) async fn next_batch<R>(
+        &self,
+        cursor_id: &str,
+        policy: WarningPolicy,
+        context: &QueryContext,
+    ) -> Result<Cursor<R>, ClientError>
     where
         R: DeserializeOwned,
     {
@@ -231,29 +763,145 @@ impl<'a, C: ClientExt> Database<C> {
             .base_url
             .join(&format!("_api/cursor/{}", cursor_id))
             .unwrap();
-        let resp = self.session.put(url, "").await?;
-        deserialize_response(resp.body())
+        let req = context.request(Method::PUT, url, String::new())?;
+        let resp = self.session.request(req).await?;
+        let cursor: Cursor<R> = deserialize_response(&resp)?;
+        check_warnings(policy, cursor.extra.as_ref())?;
+        Ok(cursor)
     }
 
     #[maybe_async]
-    async fn aql_fetch_all<R>(&self, response: Cursor<R>) -> Result<Vec<R>, ClientError>
+    async fn aql_fetch_all<R>(
+        &self,
+        response: Cursor<R>,
+        policy: WarningPolicy,
+        context: &QueryContext,
+    ) -> Result<Vec<R>, ClientError>
     where
         R: DeserializeOwned,
     {
         let mut response_cursor = response;
         let mut results: Vec<R> = Vec::new();
+        let mut guard = CursorGuard::new(
+            self.session(),
+            self.base_url.clone(),
+            response_cursor.id.clone().filter(|_| self.cursor_cleanup),
+            Arc::clone(&self.pending_cursors),
+        );
         loop {
             results.extend(response_cursor.result.into_iter());
             if response_cursor.more {
                 let id = response_cursor.id.unwrap().clone();
-                response_cursor = self.aql_next_batch(id.as_str()).await?;
+                response_cursor = self.next_batch(id.as_str(), policy, context).await?;
             } else {
                 break;
             }
         }
+        guard.disarm();
         Ok(results)
     }
 
+    /// Fetch all batches while decoding the previous one, see
+    /// `set_batch_prefetch`.
+    #[maybe_async]
+    async fn aql_fetch_all_prefetch<R>(&self, aql: AqlQuery<'_>) -> Result<Vec<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        delete_pending_cursors(self.session.as_ref(), &self.base_url, &self.pending_cursors).await;
+
+        let policy = aql.warning_policy().unwrap_or(self.warning_policy);
+        let context = aql.context();
+        let url = self.base_url.join("_api/cursor").unwrap();
+        let req = context.request(Method::POST, url, serde_json::to_string(&aql)?)?;
+        let start = Instant::now();
+        let mut resp = self.session.request(req).await?;
+        let mut header = CursorHeader::from_response::<R>(&resp)?;
+        if let Some(log) = &self.slow_query_log {
+            let stats = header.extra.as_ref().and_then(|extra| extra.stats.as_ref());
+            log.record(&aql, start.elapsed(), stats);
+        }
+
+        with_batch_prefetcher(self.session.as_ref(), |prefetcher| async move {
+            let mut results: Vec<R> = Vec::new();
+            let mut guard = CursorGuard::new(
+                self.session(),
+                self.base_url.clone(),
+                header.id.clone().filter(|_| self.cursor_cleanup),
+                Arc::clone(&self.pending_cursors),
+            );
+            loop {
+                match header.id {
+                    Some(id) if header.more => {
+                        check_warnings(policy, header.extra.as_ref())?;
+                        let url = self.base_url.join(&format!("_api/cursor/{}", id)).unwrap();
+                        let req = context.request(Method::PUT, url, String::new())?;
+                        let (next, batch) = prefetcher
+                            .fetch_while(req, || deserialize_response::<Cursor<R>>(&resp))
+                            .await;
+                        results.extend(batch?.result);
+                        resp = next?;
+                        header = CursorHeader::from_response::<R>(&resp)?;
+                    }
+                    _ => {
+                        check_warnings(policy, header.extra.as_ref())?;
+                        let batch: Cursor<R> = deserialize_response(&resp)?;
+                        results.extend(batch.result);
+                        break;
+                    }
+                }
+            }
+            guard.disarm();
+            Ok(results)
+        })
+        .await
+    }
+
+    /// Execute AQL query and fetch its batches until `budget` is exhausted,
+    /// e.g. in request handlers with a bounded latency.
+    ///
+    /// The first batch is always fetched. Further batches are only requested
+    /// while the budget is not exhausted; the results fetched so far are then
+    /// returned with the id of the cursor holding the remaining ones.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_query_within<R>(
+        &self,
+        aql: AqlQuery<'_>,
+        budget: Duration,
+    ) -> Result<PartialResults<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        let deadline = Instant::now() + budget;
+        let policy = aql.warning_policy().unwrap_or(self.warning_policy);
+        let context = aql.context();
+        let response = self.aql_query_batch(aql).await?;
+        fetch_within(self, response, policy, context, deadline).await
+    }
+
+    /// Continue fetching the batches of a cursor left by
+    /// [`aql_query_within`](Self::aql_query_within) until `budget` is
+    /// exhausted, with the [`context`](PartialResults::context) of the query.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_next_batches_within<R>(
+        &self,
+        cursor_id: &str,
+        context: &QueryContext,
+        budget: Duration,
+    ) -> Result<PartialResults<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        let deadline = Instant::now() + budget;
+        next_batches_within(self, cursor_id, self.warning_policy, context, deadline).await
+    }
+
     /// Execute AQL query fetch all results.
     ///
     /// DO NOT do this when the count of results is too large that network or
@@ -269,9 +917,14 @@ impl<'a, C: ClientExt> Database<C> {
     where
         R: DeserializeOwned,
     {
+        if self.batch_prefetch {
+            return self.aql_fetch_all_prefetch(aql).await;
+        }
+        let policy = aql.warning_policy().unwrap_or(self.warning_policy);
+        let context = aql.context();
         let response = self.aql_query_batch(aql).await?;
         if response.more {
-            self.aql_fetch_all(response).await
+            self.aql_fetch_all(response, policy, &context).await
         } else {
             Ok(response.result)
         }
@@ -294,26 +947,230 @@ impl<'a, C: ClientExt> Database<C> {
     /// Similar to `aql_query`, except that this method only accept a string of
     /// AQL query, with additional bind vars.
     ///
+    /// Bind vars can be any pairs of key and serializable value, e.g. a
+    /// `HashMap<&str, Value>` or a `Vec<(String, T)>`.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn aql_bind_vars<R>(
         &self,
         query: &str,
-        bind_vars: HashMap<&str, Value>,
+        bind_vars: impl IntoIterator<Item = (impl Into<String>, impl Serialize)>,
     ) -> Result<Vec<R>, ClientError>
     where
         R: DeserializeOwned,
     {
         let aql = AqlQuery::builder()
             .query(query)
-            .bind_vars(bind_vars)
+            .bind_vars(bind_values(bind_vars)?)
             .build();
         self.aql_query(aql).await
     }
 
+    /// Run a prepared query with `bind_vars` and fetch all its results, like
+    /// [`aql_bind_vars`](Database::aql_bind_vars).
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_prepared<R>(
+        &self,
+        prepared: &PreparedQuery<'_>,
+        bind_vars: impl IntoIterator<Item = (impl Into<String>, impl Serialize)>,
+    ) -> Result<Vec<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.aql_query(prepared.bind(bind_values(bind_vars)?)).await
+    }
+
+    /// Run a prepared query once per set of bind values, with at most `limit`
+    /// executions running at once, and fetch all their results.
+    ///
+    /// The results are in the order of `bind_sets`, an execution failing
+    /// does not stop the others. The executions share the current task.
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async::async_impl]
+    pub async fn aql_prepared_many<R, B, K, V>(
+        &self,
+        prepared: &PreparedQuery<'_>,
+        bind_sets: impl IntoIterator<Item = B>,
+        limit: usize,
+    ) -> Result<Vec<Result<Vec<R>, ClientError>>, ClientError>
+    where
+        R: DeserializeOwned,
+        B: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Serialize,
+    {
+        let bind_sets = bind_sets
+            .into_iter()
+            .map(bind_values)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(run_bounded(bind_sets, limit, |bind_vars| {
+            self.aql_query(prepared.bind(bind_vars))
+        })
+        .await)
+    }
+
+    /// Run a prepared query once per set of bind values, with at most `limit`
+    /// executions running at once on scoped threads, and fetch all their
+    /// results.
+    ///
+    /// The results are in the order of `bind_sets`, an execution failing
+    /// does not stop the others.
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async::sync_impl]
+    pub fn aql_prepared_many<R, B, K, V>(
+        &self,
+        prepared: &PreparedQuery<'_>,
+        bind_sets: impl IntoIterator<Item = B>,
+        limit: usize,
+    ) -> Result<Vec<Result<Vec<R>, ClientError>>, ClientError>
+    where
+        C: Send,
+        R: DeserializeOwned + Send,
+        B: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Serialize,
+    {
+        let bind_sets = bind_sets
+            .into_iter()
+            .map(bind_values)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(run_bounded(bind_sets, limit, |bind_vars| {
+            self.aql_query(prepared.bind(bind_vars))
+        }))
+    }
+
+    /// Search an ArangoSearch view and return the matching documents with
+    /// their relevance score, best first, see [`SearchQuery`].
+    ///
+    /// Fails with `ClientError::InvalidArgument` when a BM25 parameter is not
+    /// finite.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn search<T>(&self, query: &SearchQuery) -> Result<Vec<Scored<T>>, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        query.check()?;
+        let aql = query.aql();
+        let aql = AqlQuery::builder()
+            .query(&aql)
+            .bind_vars(query.bind_vars())
+            .build();
+        self.aql_query(aql).await
+    }
+
+    /// Explain an AQL query without running it, returning the execution plan
+    /// chosen by the optimizer with its nodes, variables, estimated costs and
+    /// the optimizer rules applied.
+    ///
+    /// Set `max_plans` and `optimizer` rules in the
+    /// [`AqlOptions`](crate::aql::AqlOptions) of the query to compare plans
+    /// while tuning it.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let aql = AqlQuery::builder()
+    ///     .query("FOR u IN users FILTER u.age > @age RETURN u")
+    ///     .bind_var("age", 30)
+    ///     .build();
+    /// let explained = db.explain_query(aql).await?;
+    /// for node in &explained.plan.nodes {
+    ///     println!("{} {}: {}", node.id, node.node_type, node.estimated_cost);
+    /// }
+    /// ```
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn explain_query(&self, aql: AqlQuery<'_>) -> Result<ExplainedQuery, ClientError> {
+        let url = self.base_url.join("_api/explain").unwrap();
+        let req = aql
+            .context()
+            .request(Method::POST, url, aql.explain_body()?)?;
+        let resp = self.session.request(req).await?;
+        deserialize_response(&resp)
+    }
+
+    /// Explain an AQL query and report the indexes its plan uses, the
+    /// collections it reads in full and the optimizer rules applied, without
+    /// running it.
+    ///
+    /// Call [`deny_full_scans`](QueryAnalysis::deny_full_scans) on the report
+    /// to fail when the query scans a collection, e.g. to gate CI on queries
+    /// keeping their indexes.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let aql = AqlQuery::builder()
+    ///     .query("FOR u IN users FILTER u.name == @name RETURN u")
+    ///     .bind_var("name", "alice")
+    ///     .build();
+    /// let analysis = db.analyze_query(aql).await?.deny_full_scans()?;
+    /// for index in analysis.indexes_used {
+    ///     println!("{} on {}", index.name, index.collection);
+    /// }
+    /// ```
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn analyze_query(&self, aql: AqlQuery<'_>) -> Result<QueryAnalysis, ClientError> {
+        let url = self.base_url.join("_api/explain").unwrap();
+        let req = aql
+            .context()
+            .request(Method::POST, url, aql.explain_body()?)?;
+        let resp = self.session.request(req).await?;
+        let explained: ExplainedQuery = deserialize_response(&resp)?;
+        Ok(QueryAnalysis::try_from(explained)?)
+    }
+
+    /// List the entries of the query plan cache of this database.
+    ///
+    /// Plans are only cached for queries run with the `use_plan_cache` AQL
+    /// option. Supported since ArangoDB 3.12.4.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn plan_cache_entries(&self) -> Result<Vec<PlanCacheEntry>, ClientError> {
+        let url = self.base_url.join("_api/query-plan-cache").unwrap();
+        let resp = self.session.get(url, "").await?;
+
+        // entries are returned as a bare array, errors as an object
+        match response_value(&resp)? {
+            entries @ Value::Array(_) => Ok(serde_json::from_value(entries)?),
+            other => deserialize_response_value(other),
+        }
+    }
+
+    /// Clear the query plan cache of this database.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn clear_plan_cache(&self) -> Result<(), ClientError> {
+        let url = self.base_url.join("_api/query-plan-cache").unwrap();
+        let resp = self.session.delete(url, "").await?;
+        deserialize_response::<Value>(&resp)?;
+        Ok(())
+    }
+
     /// Create a new index on a collection.
     ///
+    /// Fails without a request when the index is invalid, see
+    /// [`Index::validate`].
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
@@ -322,6 +1179,7 @@ impl<'a, C: ClientExt> Database<C> {
         collection: &str,
         index: &Index,
     ) -> Result<Index, ClientError> {
+        index.validate()?;
         let mut url = self.base_url.join(INDEX_API_PATH).unwrap();
         url.set_query(Some(&format!("collection={}", collection)));
 
@@ -330,11 +1188,91 @@ impl<'a, C: ClientExt> Database<C> {
             .post(url, &serde_json::to_string(&index)?)
             .await?;
 
-        let result: Index = deserialize_response::<Index>(resp.body())?;
+        let result: Index = deserialize_response::<Index>(&resp)?;
 
         Ok(result)
     }
 
+    /// Create a new index on a collection without blocking writes to the
+    /// collection, setting `inBackground` on the index.
+    ///
+    /// The creation runs as an async job on the server and this function
+    /// returns right away. Use [`IndexBuild::poll_progress`] on the returned
+    /// handle to report progress, or [`IndexBuild::wait`] to wait until the
+    /// index is ready.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_index_in_background(
+        &self,
+        collection: &str,
+        index: &Index,
+    ) -> Result<IndexBuild<C>, ClientError> {
+        index.validate()?;
+        let mut index = index.clone();
+        index.in_background = Some(true);
+
+        let mut url = self.base_url.join(INDEX_API_PATH).unwrap();
+        url.set_query(Some(&format!("collection={}", collection)));
+        let req = http::Request::post(url.to_string())
+            .header("x-arango-async", "store")
+            .body(serde_json::to_string(&index)?)
+            .unwrap();
+        let resp = self.session.request(req).await?;
+
+        let job_id = resp
+            .headers()
+            .get("x-arango-async-id")
+            .and_then(|job_id| job_id.to_str().ok())
+            .filter(|job_id| !job_id.is_empty());
+        match job_id {
+            Some(job_id) => Ok(IndexBuild::new(
+                job_id.to_owned(),
+                index.name,
+                collection,
+                self.base_url.clone(),
+                self.session(),
+            )),
+            // no job was stored, surface the server error if there is one
+            None => match deserialize_response::<Value>(&resp) {
+                Err(err @ ClientError::Arango(_)) => Err(err),
+                _ => Err(unexpected(&resp)),
+            },
+        }
+    }
+
+    /// Create a vertex-centric index on an edge collection, see
+    /// [`Index::vertex_centric`].
+    ///
+    /// Fails with `ClientError::InvalidArgument` when `collection` is not an
+    /// edge collection.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_vertex_centric_index<S>(
+        &self,
+        collection: &str,
+        name: &str,
+        direction: EdgeDirection,
+        attributes: impl IntoIterator<Item = S>,
+    ) -> Result<Index, ClientError>
+    where
+        S: Into<String>,
+    {
+        let edges = self.collection(collection).await?;
+        if edges.collection_type() != CollectionType::Edge {
+            return Err(ClientError::InvalidArgument(format!(
+                "vertex-centric index requires an edge collection, {} is not",
+                collection
+            )));
+        }
+
+        let index = Index::vertex_centric(name, direction, attributes);
+        self.create_index(collection, &index).await
+    }
+
     /// Retrieve an index by id
     ///
     /// # Note
@@ -348,7 +1286,7 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.get(url, "").await?;
 
-        let result: Index = deserialize_response::<Index>(resp.body())?;
+        let result: Index = deserialize_response::<Index>(&resp)?;
 
         Ok(result)
     }
@@ -364,12 +1302,14 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.get(url, "").await?;
 
-        let result: IndexCollection = deserialize_response::<IndexCollection>(resp.body())?;
+        let result: IndexCollection = deserialize_response::<IndexCollection>(&resp)?;
 
         Ok(result)
     }
 
-    /// Delete an index by id.
+    /// Delete an index by id, in the `collection/id` form.
+    ///
+    /// Returns [`ClientError::NotFound`] if the index does not exist.
     ///
     /// # Note
     /// this function would make a request to arango server.
@@ -381,9 +1321,39 @@ impl<'a, C: ClientExt> Database<C> {
             .unwrap();
         let resp = self.session.delete(url, "").await?;
 
-        let result: DeleteIndexResponse = deserialize_response::<DeleteIndexResponse>(resp.body())?;
+        deserialize_response::<DeleteIndexResponse>(&resp).map_err(|err| match err {
+            ClientError::Arango(err) if err.error_num() == codes::ERROR_ARANGO_INDEX_NOT_FOUND => {
+                ClientError::NotFound(format!("index {}", id))
+            }
+            err => err,
+        })
+    }
 
-        Ok(result)
+    /// Delete an index of `collection` by name, plain id or `collection/id`
+    /// handle.
+    ///
+    /// Returns [`ClientError::NotFound`] if the collection has no such
+    /// index.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn delete_index_by_name(
+        &self,
+        collection: &str,
+        name: &str,
+    ) -> Result<DeleteIndexResponse, ClientError> {
+        let indexes = self.indexes(collection).await?;
+        let id = match indexes.find(collection, name) {
+            Some(index) => index.id.clone(),
+            None => {
+                return Err(ClientError::NotFound(format!(
+                    "index {} in collection {}",
+                    name, collection
+                )))
+            }
+        };
+        self.delete_index(&id).await
     }
 
     /// Create a new graph in the graph module.
@@ -409,7 +1379,7 @@ impl<'a, C: ClientExt> Database<C> {
             .post(url, &serde_json::to_string(&graph)?)
             .await?;
 
-        let result: GraphResponse = deserialize_response::<GraphResponse>(resp.body())?;
+        let result: GraphResponse = deserialize_response::<GraphResponse>(&resp)?;
 
         Ok(result.graph)
     }
@@ -427,11 +1397,27 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.get(url, "").await?;
 
-        let result: GraphResponse = deserialize_response::<GraphResponse>(resp.body())?;
+        let result: GraphResponse = deserialize_response::<GraphResponse>(&resp)?;
 
         Ok(result.graph)
     }
 
+    /// Check whether a graph exists.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn exists_graph(&self, name: &str) -> Result<bool, ClientError> {
+        exists(self.graph(name).await)
+    }
+
+    /// Get a handle on a graph to operate on it, its vertices and edges.
+    ///
+    /// No request is made, so the graph is not checked to exist.
+    pub fn graph_handle(&self, name: &str) -> GraphHandle<C> {
+        GraphHandle::new(name, &self.base_url, self.session())
+    }
+
     /// Retrieve the list of created graphs.
     ///
     /// # Note
@@ -442,7 +1428,7 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.get(url, "").await?;
 
-        let result: GraphCollection = deserialize_response::<GraphCollection>(resp.body())?;
+        let result: GraphCollection = deserialize_response::<GraphCollection>(&resp)?;
 
         Ok(result)
     }
@@ -481,7 +1467,7 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.get(url, "").await?;
 
-        let result: TransactionList = deserialize_response(resp.body())?;
+        let result: TransactionList = deserialize_response(&resp)?;
         Ok(result.transactions)
     }
 
@@ -502,8 +1488,8 @@ impl<'a, C: ClientExt> Database<C> {
             .post(url, &serde_json::to_string(&transaction_settings)?)
             .await?;
 
-        let result: ArangoResult<ArangoTransaction> = deserialize_response(resp.body())?;
-        let transaction = result.unwrap();
+        let result: ResponseEnvelope<ArangoTransaction> = deserialize_response(&resp)?;
+        let transaction = result.into_result();
         let tx_id = transaction.id.clone();
 
         let mut session = (*self.session).clone();
@@ -513,11 +1499,118 @@ impl<'a, C: ClientExt> Database<C> {
 
         Ok(Transaction::<C>::new(
             transaction,
-            Arc::new(session),
-            self.base_url.clone(),
+            self.with_session(Arc::new(session)),
         ))
     }
 
+    /// Build a stream transaction, starting from the
+    /// [`transaction_defaults`](Database::transaction_defaults) of the
+    /// database.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let trx = db
+    ///     .transaction()
+    ///     .write(["accounts"])
+    ///     .exclusive(["ledger"])
+    ///     .wait_for_sync(true)
+    ///     .begin()
+    ///     .await?;
+    /// ```
+    pub fn transaction(&self) -> TransactionBuilder<'_, C> {
+        TransactionBuilder::new(self, self.transaction_defaults.clone())
+    }
+
+    /// Run `operations` in a stream transaction and commit it, running them
+    /// again in a new transaction when it fails with a write-write conflict,
+    /// a lock timeout or an abort by the server, up to `max_attempts` times
+    /// in total.
+    ///
+    /// A shorthand for [`transaction`](Database::transaction) with
+    /// [`max_attempts`](TransactionBuilder::max_attempts) and
+    /// [`run`](TransactionBuilder::run). Options left unset in
+    /// `transaction_settings` are taken from the
+    /// [`transaction_defaults`](Database::transaction_defaults).
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let settings = TransactionSettings::builder()
+    ///     .collections(TransactionCollections::builder().write(vec!["accounts".to_owned()]).build())
+    ///     .build();
+    /// let balance: u64 = db
+    ///     .run_transaction_with_retry(settings, 5, |trx| async move {
+    ///         let accounts = trx.collection("accounts").await?;
+    ///         let mut account: Document<Account> = accounts.document("alice").await?;
+    ///         account.document.balance += 10;
+    ///         accounts
+    ///             .replace_document("alice", account.document.clone(), Default::default(), None)
+    ///             .await?;
+    ///         Ok(account.document.balance)
+    ///     })
+    ///     .await?;
+    /// ```
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async::async_impl]
+    pub async fn run_transaction_with_retry<T, F, Fut>(
+        &self,
+        transaction_settings: TransactionSettings,
+        max_attempts: u32,
+        operations: F,
+    ) -> Result<T, ClientError>
+    where
+        F: FnMut(Transaction<C>) -> Fut,
+        Fut: std::future::Future<Output = Result<T, ClientError>>,
+    {
+        TransactionBuilder::from_settings(self, transaction_settings, &self.transaction_defaults)
+            .max_attempts(max_attempts)
+            .run(operations)
+            .await
+    }
+
+    /// Run `operations` in a stream transaction and commit it, running them
+    /// again in a new transaction when it fails with a write-write conflict,
+    /// a lock timeout or an abort by the server, up to `max_attempts` times
+    /// in total.
+    ///
+    /// A shorthand for [`transaction`](Database::transaction) with
+    /// [`max_attempts`](TransactionBuilder::max_attempts) and
+    /// [`run`](TransactionBuilder::run). Options left unset in
+    /// `transaction_settings` are taken from the
+    /// [`transaction_defaults`](Database::transaction_defaults).
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let settings = TransactionSettings::builder()
+    ///     .collections(TransactionCollections::builder().write(vec!["accounts".to_owned()]).build())
+    ///     .build();
+    /// let balance: u64 = db.run_transaction_with_retry(settings, 5, |trx| {
+    ///     let accounts = trx.collection("accounts")?;
+    ///     let mut account: Document<Account> = accounts.document("alice")?;
+    ///     account.document.balance += 10;
+    ///     accounts.replace_document("alice", account.document.clone(), Default::default(), None)?;
+    ///     Ok(account.document.balance)
+    /// })?;
+    /// ```
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async::sync_impl]
+    pub fn run_transaction_with_retry<T, F>(
+        &self,
+        transaction_settings: TransactionSettings,
+        max_attempts: u32,
+        operations: F,
+    ) -> Result<T, ClientError>
+    where
+        F: FnMut(Transaction<C>) -> Result<T, ClientError>,
+    {
+        TransactionBuilder::from_settings(self, transaction_settings, &self.transaction_defaults)
+            .max_attempts(max_attempts)
+            .run(operations)
+    }
+
     /// Returns an object containing a listing of all Views in a database,
     /// regardless of their typ
     ///
@@ -529,16 +1622,19 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.get(url, "").await?;
 
-        let result: ArangoResult<Vec<ViewDescription>> = deserialize_response(resp.body())?;
-        Ok(result.unwrap())
+        let result: ResponseEnvelope<Vec<ViewDescription>> = deserialize_response(&resp)?;
+        Ok(result.into_result())
     }
 
     /// Creates an ArangoSearch View
     ///
+    /// The name is checked with [`naming::validate_view_name`] first.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn create_view(&self, view_options: ViewOptions) -> Result<View, ClientError> {
+        naming::validate_view_name(view_options.name(), NamingConvention::Extended)?;
         let url = self.base_url.join("_api/view").unwrap();
 
         let resp = self
@@ -546,7 +1642,7 @@ impl<'a, C: ClientExt> Database<C> {
             .post(url, &serde_json::to_string(&view_options)?)
             .await?;
 
-        let result: View = deserialize_response(resp.body())?;
+        let result: View = deserialize_response(&resp)?;
         Ok(result)
     }
 
@@ -563,10 +1659,26 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.get(url, "").await?;
 
-        let result: ViewDescription = deserialize_response(resp.body())?;
+        let result: ViewDescription = deserialize_response(&resp)?;
         Ok(result)
     }
 
+    /// Check whether a View exists.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn exists_view(&self, view_name: &str) -> Result<bool, ClientError> {
+        exists(self.view(view_name).await)
+    }
+
+    /// Get a handle on a View to read or drop it.
+    ///
+    /// No request is made, so the View is not checked to exist.
+    pub fn view_handle(&self, name: &str) -> ViewHandle<C> {
+        ViewHandle::new(name, &self.base_url, self.session())
+    }
+
     /// Read properties of a View
     ///
     /// # Note
@@ -583,7 +1695,7 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.get(url, "").await?;
 
-        let result: ArangoSearchViewProperties = deserialize_response(resp.body())?;
+        let result: ArangoSearchViewProperties = deserialize_response(&resp)?;
         Ok(result)
     }
 
@@ -607,7 +1719,7 @@ impl<'a, C: ClientExt> Database<C> {
             .put(url, &serde_json::to_string(&properties)?)
             .await?;
 
-        let result: View = deserialize_response(resp.body())?;
+        let result: View = deserialize_response(&resp)?;
         Ok(result)
     }
 
@@ -631,7 +1743,7 @@ impl<'a, C: ClientExt> Database<C> {
             .patch(url, &serde_json::to_string(&properties)?)
             .await?;
 
-        let result: View = deserialize_response(resp.body())?;
+        let result: View = deserialize_response(&resp)?;
         Ok(result)
     }
 
@@ -648,8 +1760,8 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.delete(url, "").await?;
 
-        let result: ArangoResult<bool> = deserialize_response(resp.body())?;
-        Ok(result.unwrap())
+        let result: ResponseEnvelope<bool> = deserialize_response(&resp)?;
+        Ok(result.into_result())
     }
 
     #[maybe_async]
@@ -658,8 +1770,8 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.get(url, "").await?;
 
-        let result: ArangoResult<Vec<AnalyzerInfo>> = deserialize_response(resp.body())?;
-        Ok(result.unwrap())
+        let result: ResponseEnvelope<Vec<AnalyzerInfo>> = deserialize_response(&resp)?;
+        Ok(result.into_result())
     }
 
     /// Create an Analyzer with the supplied definition
@@ -678,7 +1790,7 @@ impl<'a, C: ClientExt> Database<C> {
             .post(url, &serde_json::to_string(&analyzer)?)
             .await?;
 
-        let result: AnalyzerInfo = deserialize_response(resp.body())?;
+        let result: AnalyzerInfo = deserialize_response(&resp)?;
         Ok(result)
     }
 
@@ -695,10 +1807,19 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.get(url, "").await?;
 
-        let result: AnalyzerInfo = deserialize_response(resp.body())?;
+        let result: AnalyzerInfo = deserialize_response(&resp)?;
         Ok(result)
     }
 
+    /// Check whether an Analyzer exists.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn exists_analyzer(&self, analyzer_name: &str) -> Result<bool, ClientError> {
+        exists(self.analyzer(analyzer_name).await)
+    }
+
     ///Removes an Analyzer configuration identified by analyzer_name.
     ///
     /// # Note
@@ -708,17 +1829,91 @@ impl<'a, C: ClientExt> Database<C> {
         &self,
         analyzer_name: &str,
     ) -> Result<AnalyzerDescription, ClientError> {
-        let url = self
+        self.delete_analyzer(analyzer_name, false).await
+    }
+
+    #[maybe_async]
+    async fn delete_analyzer(
+        &self,
+        analyzer_name: &str,
+        force: bool,
+    ) -> Result<AnalyzerDescription, ClientError> {
+        let mut url = self
             .base_url
             .join(&format!("_api/analyzer/{}", analyzer_name))
             .unwrap();
+        if force {
+            url.set_query(Some("force=true"));
+        }
 
         let resp = self.session.delete(url, "").await?;
 
-        let result: AnalyzerDescription = deserialize_response(resp.body())?;
+        let result: AnalyzerDescription = deserialize_response(&resp)?;
         Ok(result)
     }
 
+    /// Make the Analyzers of this database match the `desired` definitions,
+    /// e.g. when deploying to several environments.
+    ///
+    /// Missing Analyzers are created. Analyzers whose definition differs are
+    /// dropped and created again, as Analyzers can not be modified. With
+    /// `remove_extra`, the Analyzers of this database not in `desired` are
+    /// dropped. Names are given without the database prefix, and only the
+    /// properties set in `desired` are compared, see
+    /// [`AnalyzerSyncOptions`].
+    ///
+    /// Dropping an Analyzer used by a View or an inverted index fails unless
+    /// `force` is set. The sync stops at the first failure, leaving the
+    /// changes made so far.
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async]
+    pub async fn sync_analyzers(
+        &self,
+        desired: Vec<AnalyzerInfo>,
+        options: AnalyzerSyncOptions,
+    ) -> Result<AnalyzerSyncReport, ClientError> {
+        let prefix = format!("{}::", self.name);
+        let bare = |name: &str| name.strip_prefix(&prefix).unwrap_or(name).to_owned();
+        let mut current: HashMap<String, AnalyzerInfo> = self
+            .list_analyzers()
+            .await?
+            .into_iter()
+            .filter(|analyzer| analyzer.name().starts_with(&prefix))
+            .map(|analyzer| (bare(analyzer.name()), analyzer))
+            .collect();
+
+        let mut report = AnalyzerSyncReport::default();
+        for analyzer in desired {
+            let name = bare(analyzer.name());
+            match current.remove(&name) {
+                Some(existing) if analyzer_matches(&analyzer, &existing) => {
+                    report.unchanged.push(name);
+                }
+                Some(_) => {
+                    self.delete_analyzer(&name, options.force).await?;
+                    self.create_analyzer(analyzer).await?;
+                    report.updated.push(name);
+                }
+                None => {
+                    self.create_analyzer(analyzer).await?;
+                    report.created.push(name);
+                }
+            }
+        }
+
+        if options.remove_extra {
+            let mut extra: Vec<String> = current.into_keys().collect();
+            extra.sort();
+            for name in extra {
+                self.delete_analyzer(&name, options.force).await?;
+                report.removed.push(name);
+            }
+        }
+        Ok(report)
+    }
+
     /// List available users
     ///
     /// Fetches data about all users. You need the Administrate server access
@@ -733,7 +1928,7 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.get(url, "").await?;
 
-        let result: UserResponse = deserialize_response(resp.body())?;
+        let result: UserResponse = deserialize_response(&resp)?;
         Ok(result.result)
     }
 
@@ -750,7 +1945,7 @@ impl<'a, C: ClientExt> Database<C> {
             .post(url, &serde_json::to_string(&user)?)
             .await?;
 
-        let result = deserialize_response(resp.body())?;
+        let result = deserialize_response(&resp)?;
         Ok(result)
     }
 
@@ -770,7 +1965,7 @@ impl<'a, C: ClientExt> Database<C> {
             .put(url, &serde_json::to_string(&user)?)
             .await?;
 
-        let result = deserialize_response(resp.body())?;
+        let result = deserialize_response(&resp)?;
         Ok(result)
     }
 
@@ -787,7 +1982,7 @@ impl<'a, C: ClientExt> Database<C> {
 
         let resp = self.session.delete(url, "").await?;
 
-        let _: DeleteUserResponse = deserialize_response(resp.body())?;
+        let _: DeleteUserResponse = deserialize_response(&resp)?;
         Ok(())
     }
 
@@ -807,7 +2002,7 @@ impl<'a, C: ClientExt> Database<C> {
             .unwrap();
         let resp = self.session.get(url, "").await?;
 
-        let result = deserialize_response(resp.body())?;
+        let result = deserialize_response(&resp)?;
         Ok(result)
     }
 
@@ -827,7 +2022,7 @@ impl<'a, C: ClientExt> Database<C> {
             .unwrap();
         let resp = self.session.get(url, "").await?;
 
-        let result = deserialize_response(resp.body())?;
+        let result = deserialize_response(&resp)?;
         Ok(result)
     }
 
@@ -857,7 +2052,7 @@ impl<'a, C: ClientExt> Database<C> {
             )
             .await?;
 
-        let result = deserialize_response(resp.body())?;
+        let result = deserialize_response(&resp)?;
         Ok(result)
     }
 
@@ -880,7 +2075,7 @@ impl<'a, C: ClientExt> Database<C> {
             .unwrap();
         let resp = self.session.get(url, "").await?;
 
-        let result = deserialize_response(resp.body())?;
+        let result = deserialize_response(&resp)?;
         Ok(result)
     }
 
@@ -913,7 +2108,7 @@ impl<'a, C: ClientExt> Database<C> {
             )
             .await?;
 
-        let result = deserialize_response(resp.body())?;
+        let result = deserialize_response(&resp)?;
         Ok(result)
     }
 }
@@ -926,3 +2121,50 @@ pub struct DatabaseDetails {
     pub path: String,
     pub is_system: bool,
 }
+
+/// Properties of a database, see [`Database::set_properties`].
+#[derive(Debug, Clone, PartialEq, Serialize, TypedBuilder)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseProperties {
+    /// The sharding method of new collections, `""`, `"flexible"` or
+    /// `"single"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    pub sharding: Option<String>,
+
+    /// Default number of copies of each shard of new collections.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub replication_factor: Option<usize>,
+
+    /// Default number of copies of each shard required to be in sync for
+    /// writes to new collections.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub write_concern: Option<usize>,
+}
+
+/// Map the result of reading an object to whether it exists, a 404 meaning
+/// it does not.
+fn exists<T>(result: Result<T, ClientError>) -> Result<bool, ClientError> {
+    match result {
+        Ok(_) => Ok(true),
+        Err(ClientError::Arango(err)) if err.code() == 404 => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Serialize the values of bind parameters.
+pub(crate) fn bind_values<K, V>(
+    bind_vars: impl IntoIterator<Item = (K, V)>,
+) -> Result<Vec<(String, Value)>, serde_json::Error>
+where
+    K: Into<String>,
+    V: Serialize,
+{
+    bind_vars
+        .into_iter()
+        .map(|(k, v)| Ok((k.into(), serde_json::to_value(v)?)))
+        .collect()
+}
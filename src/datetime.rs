@@ -0,0 +1,274 @@
+//! Serde helpers for dates stored in documents.
+//!
+//! ArangoDB has no date type, dates are stored either as ISO 8601 strings
+//! like `"2021-05-04T10:20:30.123Z"`, as returned by `DATE_ISO8601()`, or as
+//! numeric timestamps. TTL indexes accept both, with timestamps in seconds.
+//!
+//! Enable the `chrono` feature for helpers on `chrono::DateTime<Utc>` and the
+//! `time` feature for helpers on `time::OffsetDateTime`. Each helper has an
+//! `option` submodule for optional fields.
+//!
+//! # Example
+//! ```rust, ignore
+//! use chrono::{DateTime, Utc};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Session {
+//!     #[serde(with = "arangors::datetime::chrono::iso8601")]
+//!     created_at: DateTime<Utc>,
+//!     #[serde(with = "arangors::datetime::chrono::timestamp::option")]
+//!     expires_at: Option<DateTime<Utc>>,
+//! }
+//! ```
+
+/// Helpers for `chrono::DateTime<Utc>`
+#[cfg(feature = "chrono")]
+pub mod chrono {
+    /// ISO 8601 strings with milliseconds, in the format of `DATE_ISO8601()`
+    pub mod iso8601 {
+        use ::chrono::{DateTime, SecondsFormat, Utc};
+        use serde::{de::Error, Deserialize, Deserializer, Serializer};
+        use std::borrow::Cow;
+
+        pub fn serialize<S: Serializer>(date: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_str(&date.to_rfc3339_opts(SecondsFormat::Millis, true))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<DateTime<Utc>, D::Error> {
+            let text = Cow::<str>::deserialize(d)?;
+            DateTime::parse_from_rfc3339(&text)
+                .map(|date| date.with_timezone(&Utc))
+                .map_err(Error::custom)
+        }
+
+        /// Same as [`iso8601`](self) for optional fields
+        pub mod option {
+            use ::chrono::{DateTime, Utc};
+            use serde::{Deserialize, Deserializer, Serializer};
+
+            pub fn serialize<S: Serializer>(
+                date: &Option<DateTime<Utc>>,
+                s: S,
+            ) -> Result<S::Ok, S::Error> {
+                match date {
+                    Some(date) => super::serialize(date, s),
+                    None => s.serialize_none(),
+                }
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                d: D,
+            ) -> Result<Option<DateTime<Utc>>, D::Error> {
+                #[derive(Deserialize)]
+                struct Wrapper(#[serde(with = "super")] DateTime<Utc>);
+                Ok(Option::<Wrapper>::deserialize(d)?.map(|w| w.0))
+            }
+        }
+    }
+
+    /// Numeric timestamps in seconds, as used by TTL indexes
+    pub mod timestamp {
+        use ::chrono::{DateTime, Utc};
+        use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(date: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error> {
+            match date.timestamp_subsec_millis() {
+                0 => s.serialize_i64(date.timestamp()),
+                _ => s.serialize_f64(date.timestamp_millis() as f64 / 1000.0),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<DateTime<Utc>, D::Error> {
+            let seconds = f64::deserialize(d)?;
+            DateTime::from_timestamp_millis((seconds * 1000.0).round() as i64)
+                .ok_or_else(|| Error::custom(format!("timestamp out of range: {}", seconds)))
+        }
+
+        /// Same as [`timestamp`](self) for optional fields
+        pub mod option {
+            use ::chrono::{DateTime, Utc};
+            use serde::{Deserialize, Deserializer, Serializer};
+
+            pub fn serialize<S: Serializer>(
+                date: &Option<DateTime<Utc>>,
+                s: S,
+            ) -> Result<S::Ok, S::Error> {
+                match date {
+                    Some(date) => super::serialize(date, s),
+                    None => s.serialize_none(),
+                }
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                d: D,
+            ) -> Result<Option<DateTime<Utc>>, D::Error> {
+                #[derive(Deserialize)]
+                struct Wrapper(#[serde(with = "super")] DateTime<Utc>);
+                Ok(Option::<Wrapper>::deserialize(d)?.map(|w| w.0))
+            }
+        }
+    }
+}
+
+/// Helpers for `time::OffsetDateTime`
+#[cfg(feature = "time")]
+pub mod time {
+    /// ISO 8601 strings with milliseconds in UTC, in the format of
+    /// `DATE_ISO8601()`
+    pub mod iso8601 {
+        use ::time::{
+            format_description::{well_known::Rfc3339, FormatItem},
+            macros::format_description,
+            OffsetDateTime, UtcOffset,
+        };
+        use serde::{de::Error, Deserialize, Deserializer, Serializer};
+        use std::borrow::Cow;
+
+        const FORMAT: &[FormatItem<'static>] = format_description!(
+            "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
+        );
+
+        pub fn serialize<S: Serializer>(date: &OffsetDateTime, s: S) -> Result<S::Ok, S::Error> {
+            let text = date
+                .to_offset(UtcOffset::UTC)
+                .format(FORMAT)
+                .map_err(serde::ser::Error::custom)?;
+            s.serialize_str(&text)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<OffsetDateTime, D::Error> {
+            let text = Cow::<str>::deserialize(d)?;
+            OffsetDateTime::parse(&text, &Rfc3339).map_err(Error::custom)
+        }
+
+        /// Same as [`iso8601`](self) for optional fields
+        pub mod option {
+            use ::time::OffsetDateTime;
+            use serde::{Deserialize, Deserializer, Serializer};
+
+            pub fn serialize<S: Serializer>(
+                date: &Option<OffsetDateTime>,
+                s: S,
+            ) -> Result<S::Ok, S::Error> {
+                match date {
+                    Some(date) => super::serialize(date, s),
+                    None => s.serialize_none(),
+                }
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                d: D,
+            ) -> Result<Option<OffsetDateTime>, D::Error> {
+                #[derive(Deserialize)]
+                struct Wrapper(#[serde(with = "super")] OffsetDateTime);
+                Ok(Option::<Wrapper>::deserialize(d)?.map(|w| w.0))
+            }
+        }
+    }
+
+    /// Numeric timestamps in seconds, as used by TTL indexes
+    pub mod timestamp {
+        use ::time::OffsetDateTime;
+        use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(date: &OffsetDateTime, s: S) -> Result<S::Ok, S::Error> {
+            match date.millisecond() {
+                0 => s.serialize_i64(date.unix_timestamp()),
+                _ => s.serialize_f64((date.unix_timestamp_nanos() / 1_000_000) as f64 / 1000.0),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<OffsetDateTime, D::Error> {
+            let seconds = f64::deserialize(d)?;
+            let millis = (seconds * 1000.0).round() as i128;
+            OffsetDateTime::from_unix_timestamp_nanos(millis * 1_000_000).map_err(Error::custom)
+        }
+
+        /// Same as [`timestamp`](self) for optional fields
+        pub mod option {
+            use ::time::OffsetDateTime;
+            use serde::{Deserialize, Deserializer, Serializer};
+
+            pub fn serialize<S: Serializer>(
+                date: &Option<OffsetDateTime>,
+                s: S,
+            ) -> Result<S::Ok, S::Error> {
+                match date {
+                    Some(date) => super::serialize(date, s),
+                    None => s.serialize_none(),
+                }
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                d: D,
+            ) -> Result<Option<OffsetDateTime>, D::Error> {
+                #[derive(Deserialize)]
+                struct Wrapper(#[serde(with = "super")] OffsetDateTime);
+                Ok(Option::<Wrapper>::deserialize(d)?.map(|w| w.0))
+            }
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "chrono", feature = "time")))]
+mod test {
+    use serde_json::json;
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_helpers() {
+        use ::chrono::{DateTime, TimeZone, Utc};
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Session {
+            #[serde(with = "super::chrono::iso8601")]
+            created_at: DateTime<Utc>,
+            #[serde(with = "super::chrono::timestamp::option")]
+            expires_at: Option<DateTime<Utc>>,
+        }
+
+        let session = Session {
+            created_at: Utc.with_ymd_and_hms(2021, 5, 4, 10, 20, 30).unwrap(),
+            expires_at: Some(Utc.with_ymd_and_hms(2021, 5, 5, 0, 0, 0).unwrap()),
+        };
+        let value = serde_json::to_value(&session).unwrap();
+        assert_eq!(
+            value,
+            json!({"created_at": "2021-05-04T10:20:30.000Z", "expires_at": 1620172800})
+        );
+        assert_eq!(serde_json::from_value::<Session>(value).unwrap(), session);
+
+        let value = json!({"created_at": "2021-05-04T12:20:30+02:00", "expires_at": null});
+        let parsed = serde_json::from_value::<Session>(value).unwrap();
+        assert_eq!(parsed.created_at, session.created_at);
+        assert_eq!(parsed.expires_at, None);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_helpers() {
+        use ::time::{macros::datetime, OffsetDateTime};
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Session {
+            #[serde(with = "super::time::iso8601")]
+            created_at: OffsetDateTime,
+            #[serde(with = "super::time::timestamp::option")]
+            expires_at: Option<OffsetDateTime>,
+        }
+
+        let session = Session {
+            created_at: datetime!(2021-05-04 12:20:30.5 +02:00),
+            expires_at: Some(datetime!(2021-05-05 0:00 UTC)),
+        };
+        let value = serde_json::to_value(&session).unwrap();
+        assert_eq!(
+            value,
+            json!({"created_at": "2021-05-04T10:20:30.500Z", "expires_at": 1620172800})
+        );
+        assert_eq!(serde_json::from_value::<Session>(value).unwrap(), session);
+    }
+}
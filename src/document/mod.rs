@@ -21,6 +21,22 @@ pub struct Header {
     pub _rev: String,
 }
 
+/// Attributes kept by a projection to `attributes`, for `KEEP(doc, @attrs)`
+/// in AQL. The system attributes of the [`Header`] come first and are always
+/// kept.
+pub(crate) fn projection<A: AsRef<str>>(attributes: &[A]) -> Vec<String> {
+    let mut kept: Vec<String> = ["_id", "_key", "_rev"]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+    for attribute in attributes {
+        if !kept.iter().any(|a| a == attribute.as_ref()) {
+            kept.push(attribute.as_ref().to_owned());
+        }
+    }
+    kept
+}
+
 /// Structure that represents a document within its content and header
 #[derive(Serialize, Debug)]
 pub struct Document<T> {
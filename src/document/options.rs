@@ -2,6 +2,8 @@
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
+use crate::ClientError;
+
 /// Options for document insertion.
 #[derive(Debug, Serialize, Deserialize, PartialEq, TypedBuilder, Clone)]
 #[builder(doc)]
@@ -34,8 +36,12 @@ pub struct InsertOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     overwrite: Option<bool>,
-    /// TODO add nice formatted documentation from official doc
-    #[cfg(feature = "arango3_7")]
+    /// What to do when a document with the same `_key` exists already, see
+    /// [`OverwriteMode`]. Takes precedence over `overwrite`.
+    ///
+    /// Since ArangoDB 3.7, like `keep_null` and `merge_objects`. Older servers
+    /// ignore these options, see
+    /// [`ServerFeatures::overwrite_mode`](crate::connection::features::ServerFeatures::overwrite_mode).
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     overwrite_mode: Option<OverwriteMode>,
@@ -46,7 +52,6 @@ pub struct InsertOptions {
     /// remove any attributes from the existing document that are contained
     /// in the patch document with an attribute value of null. This option
     /// controls the update-insert behavior only.
-    #[cfg(feature = "arango3_7")]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     keep_null: Option<bool>,
@@ -57,10 +62,21 @@ pub struct InsertOptions {
     /// existing document’s value. If set to true, objects will be merged.
     /// The default is true. This option controls the update-insert behavior
     /// only.
-    #[cfg(feature = "arango3_7")]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     merge_objects: Option<bool>,
+
+    /// Key given to a document without `_key`, see
+    /// [`with_generated_key`](InsertOptions::with_generated_key). Not sent as
+    /// a query parameter.
+    #[serde(skip)]
+    #[builder(default, setter(strip_option, into))]
+    key: Option<String>,
+    /// Durability of this write, see [`Durability`]. Not sent as a query
+    /// parameter.
+    #[serde(skip)]
+    #[builder(default)]
+    durability: Durability,
 }
 
 impl Default for InsertOptions {
@@ -69,6 +85,74 @@ impl Default for InsertOptions {
     }
 }
 
+impl InsertOptions {
+    /// These options with `wait_for_sync` set to `default` when not set, see
+    /// `Database::set_default_wait_for_sync`, and to true for a
+    /// [`Durability::Synced`] write.
+    pub(crate) fn or_wait_for_sync(mut self, default: Option<bool>) -> Result<Self, ClientError> {
+        self.wait_for_sync = self.durability.wait_for_sync(self.wait_for_sync, default)?;
+        Ok(self)
+    }
+
+    /// Whether an ignored insertion can be told from the response, see
+    /// [`DocumentResponse::Ignored`](crate::document::response::DocumentResponse::Ignored).
+    pub(crate) fn reports_ignored(&self) -> bool {
+        self.overwrite_mode == Some(OverwriteMode::Ignore) && self.return_new == Some(true)
+    }
+
+    /// Give the inserted document a key generated on the client, unless it
+    /// has a `_key` already.
+    ///
+    /// The key is generated once, here. Sending the same options again after
+    /// a network error inserts the same key, so the document is never
+    /// duplicated: a unique constraint violation (error 1210) on the retry
+    /// means the first attempt succeeded.
+    ///
+    /// The key applies to a single document, `Collection::create_documents`
+    /// rejects options with a key.
+    pub fn with_generated_key(mut self, strategy: KeyStrategy) -> Self {
+        self.key = Some(strategy.generate());
+        self
+    }
+
+    /// The key given to a document without `_key`.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// Durability of this write.
+    pub fn durability(&self) -> Durability {
+        self.durability
+    }
+}
+
+/// How document keys are generated on the client.
+#[derive(Debug, Clone, Copy)]
+pub enum KeyStrategy {
+    /// Random UUID (version 4)
+    #[cfg(feature = "uuid")]
+    Uuid,
+    /// Time-ordered UUID (version 7), keeping keys roughly sorted by
+    /// insertion time
+    #[cfg(feature = "uuid")]
+    UuidV7,
+    /// Keys from a user supplied generator
+    Custom(fn() -> String),
+}
+
+impl KeyStrategy {
+    /// Generate a new key.
+    pub fn generate(&self) -> String {
+        match self {
+            #[cfg(feature = "uuid")]
+            KeyStrategy::Uuid => uuid::Uuid::new_v4().to_string(),
+            #[cfg(feature = "uuid")]
+            KeyStrategy::UuidV7 => uuid::Uuid::now_v7().to_string(),
+            KeyStrategy::Custom(generate) => generate(),
+        }
+    }
+}
+
 /// Options for document update,
 #[derive(Debug, Serialize, Deserialize, PartialEq, TypedBuilder, Clone)]
 #[builder(doc)]
@@ -116,6 +200,11 @@ pub struct UpdateOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     silent: Option<bool>,
+    /// Durability of this write, see [`Durability`]. Not sent as a query
+    /// parameter.
+    #[serde(skip)]
+    #[builder(default)]
+    durability: Durability,
 }
 
 impl Default for UpdateOptions {
@@ -124,6 +213,21 @@ impl Default for UpdateOptions {
     }
 }
 
+impl UpdateOptions {
+    /// These options with `wait_for_sync` set to `default` when not set, see
+    /// `Database::set_default_wait_for_sync`, and to true for a
+    /// [`Durability::Synced`] write.
+    pub(crate) fn or_wait_for_sync(mut self, default: Option<bool>) -> Result<Self, ClientError> {
+        self.wait_for_sync = self.durability.wait_for_sync(self.wait_for_sync, default)?;
+        Ok(self)
+    }
+
+    /// Durability of this write.
+    pub fn durability(&self) -> Durability {
+        self.durability
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum OverwriteMode {
@@ -197,6 +301,11 @@ pub struct ReplaceOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     silent: Option<bool>,
+    /// Durability of this write, see [`Durability`]. Not sent as a query
+    /// parameter.
+    #[serde(skip)]
+    #[builder(default)]
+    durability: Durability,
 }
 
 impl Default for ReplaceOptions {
@@ -205,6 +314,21 @@ impl Default for ReplaceOptions {
     }
 }
 
+impl ReplaceOptions {
+    /// These options with `wait_for_sync` set to `default` when not set, see
+    /// `Database::set_default_wait_for_sync`, and to true for a
+    /// [`Durability::Synced`] write.
+    pub(crate) fn or_wait_for_sync(mut self, default: Option<bool>) -> Result<Self, ClientError> {
+        self.wait_for_sync = self.durability.wait_for_sync(self.wait_for_sync, default)?;
+        Ok(self)
+    }
+
+    /// Durability of this write.
+    pub fn durability(&self) -> Durability {
+        self.durability
+    }
+}
+
 /// Options for document reading.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -246,6 +370,11 @@ pub struct RemoveOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     silent: Option<bool>,
+    /// Durability of this write, see [`Durability`]. Not sent as a query
+    /// parameter.
+    #[serde(skip)]
+    #[builder(default)]
+    durability: Durability,
 }
 
 impl Default for RemoveOptions {
@@ -253,3 +382,128 @@ impl Default for RemoveOptions {
         Self::builder().build()
     }
 }
+
+impl RemoveOptions {
+    /// These options with `wait_for_sync` set to `default` when not set, see
+    /// `Database::set_default_wait_for_sync`, and to true for a
+    /// [`Durability::Synced`] write.
+    pub(crate) fn or_wait_for_sync(mut self, default: Option<bool>) -> Result<Self, ClientError> {
+        self.wait_for_sync = self.durability.wait_for_sync(self.wait_for_sync, default)?;
+        Ok(self)
+    }
+
+    /// Durability of this write.
+    pub fn durability(&self) -> Durability {
+        self.durability
+    }
+
+    /// Whether the write waits for sync, once the options are completed with
+    /// `or_wait_for_sync`.
+    pub(crate) fn wait_for_sync(&self) -> bool {
+        self.wait_for_sync == Some(true)
+    }
+}
+
+/// Durability of a single document write, overriding the defaults of the
+/// collection and of the connection for this request only.
+///
+/// Writes are only accepted by the leader. In an active failover deployment
+/// a follower refuses them with HTTP 503 (error 1496) instead of applying or
+/// forwarding them, so a write that returns `Ok` has been applied on the
+/// leader. How many replicas must acknowledge a write in a cluster is set by
+/// the `writeConcern` of the collection, the server has no per-request
+/// override for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Durability {
+    /// Use the `waitForSync` option and the headers of the connection.
+    #[default]
+    Default,
+    /// Wait until the write is synced to disk, as with `waitForSync=true`.
+    /// The response is only returned once the write is durable.
+    ///
+    /// Setting `wait_for_sync` to false as well is rejected with
+    /// `ClientError::InvalidArgument`. A connection sending the
+    /// `x-arango-async` header executes writes asynchronously whatever their
+    /// durability, do not set it for synced writes.
+    Synced,
+}
+
+impl Durability {
+    /// `wait_for_sync` of a write with this durability, `default` when not
+    /// set.
+    pub(crate) fn wait_for_sync(
+        &self,
+        wait_for_sync: Option<bool>,
+        default: Option<bool>,
+    ) -> Result<Option<bool>, ClientError> {
+        match self {
+            Durability::Default => Ok(wait_for_sync.or(default)),
+            Durability::Synced if wait_for_sync == Some(false) => {
+                Err(ClientError::InvalidArgument(
+                    "a synced write can not set waitForSync to false".to_owned(),
+                ))
+            }
+            Durability::Synced => Ok(Some(true)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn synced_durability() {
+        let options = UpdateOptions::builder()
+            .return_new(true)
+            .durability(Durability::Synced)
+            .build()
+            .or_wait_for_sync(Some(false))
+            .unwrap();
+        let query = crate::query::to_string(&options).unwrap();
+        assert_eq!(query, "returnNew=true&waitForSync=true");
+
+        let options = UpdateOptions::builder()
+            .wait_for_sync(false)
+            .durability(Durability::Synced)
+            .build();
+        match options.or_wait_for_sync(None) {
+            Err(ClientError::InvalidArgument(msg)) => {
+                assert_eq!(msg, "a synced write can not set waitForSync to false")
+            }
+            other => panic!("expected an invalid argument, got {:?}", other),
+        }
+
+        let options = UpdateOptions::builder()
+            .wait_for_sync(false)
+            .build()
+            .or_wait_for_sync(Some(true))
+            .unwrap();
+        let query = crate::query::to_string(&options).unwrap();
+        assert_eq!(query, "waitForSync=false");
+    }
+
+    #[test]
+    fn default_wait_for_sync() {
+        let options = InsertOptions::default()
+            .or_wait_for_sync(Some(true))
+            .unwrap();
+        assert_eq!(
+            crate::query::to_string(&options).unwrap(),
+            "waitForSync=true"
+        );
+
+        let options = RemoveOptions::builder()
+            .wait_for_sync(false)
+            .build()
+            .or_wait_for_sync(Some(true))
+            .unwrap();
+        assert_eq!(
+            crate::query::to_string(&options).unwrap(),
+            "waitForSync=false"
+        );
+
+        let options = UpdateOptions::default().or_wait_for_sync(None).unwrap();
+        assert_eq!(crate::query::to_string(&options).unwrap(), "");
+    }
+}
@@ -1,4 +1,8 @@
 //! Types of response related to document
+use http::{
+    header::{ETAG, LOCATION},
+    HeaderMap,
+};
 use serde::{de::Error as DeError, Deserialize, Deserializer};
 
 use super::Header;
@@ -18,24 +22,48 @@ use super::Header;
 /// 412: is returned if an “If-Match” header is given and the found
 /// document has a different version. The response will also contain the found
 /// document’s current revision in the Etag header.
-pub enum DocumentResponse<T> {
+///
+/// The old document is decoded into `O`, the type of the new one unless
+/// another is given, e.g. when a migration changes the shape of documents,
+/// see [`Collection::update_document_as`](crate::Collection::update_document_as).
+pub enum DocumentResponse<T, O = T> {
     /// Silent is when there is empty object returned by the server
-    Silent,
+    Silent {
+        location: Option<String>,
+        etag: Option<String>,
+    },
     /// Contain data after CRUD
     Response {
         header: Header,
-        old: Option<T>,
+        old: Option<O>,
         new: Option<T>,
         _old_rev: Option<String>,
+        location: Option<String>,
+        etag: Option<String>,
+    },
+    /// The server accepted the request without writing anything, e.g. an
+    /// insertion with `overwriteMode` set to `ignore` on an existing document.
+    ///
+    /// The server answers such an insertion with the header of the existing
+    /// document, like a successful one, so it is only told apart when
+    /// `return_new` is set as well: the new document is then `null`.
+    Ignored {
+        location: Option<String>,
+        etag: Option<String>,
     },
 }
 
 /// Gives extra method on the DocumentResponse to quickly check what the server
 /// returns
-impl<T> DocumentResponse<T> {
+impl<T, O> DocumentResponse<T, O> {
     /// Should be true when the server send back an empty object {}
     pub fn is_silent(&self) -> bool {
-        matches!(self, DocumentResponse::Silent)
+        matches!(self, DocumentResponse::Silent { .. })
+    }
+    /// Should be true when the server ignored the write because the document
+    /// already exists
+    pub fn is_ignored(&self) -> bool {
+        matches!(self, DocumentResponse::Ignored { .. })
     }
     /// Should be true if there is a response from the server
     pub fn has_response(&self) -> bool {
@@ -51,7 +79,7 @@ impl<T> DocumentResponse<T> {
         }
     }
     /// Return the old document before changes
-    pub fn old_doc(&self) -> Option<&T> {
+    pub fn old_doc(&self) -> Option<&O> {
         if let DocumentResponse::Response { old, .. } = self {
             old.as_ref()
         } else {
@@ -67,6 +95,8 @@ impl<T> DocumentResponse<T> {
         }
     }
     /// return the old revision of the document
+    ///
+    /// Set on update and replacement, even without `return_old`.
     pub fn old_rev(&self) -> Option<&String> {
         if let DocumentResponse::Response { _old_rev, .. } = self {
             _old_rev.as_ref()
@@ -74,11 +104,77 @@ impl<T> DocumentResponse<T> {
             None
         }
     }
+    /// Return the new revision of the document
+    ///
+    /// Taken from the response body, or from the `Etag` header when the
+    /// response is silent. Available without `return_new`.
+    pub fn rev(&self) -> Option<&str> {
+        match self {
+            DocumentResponse::Response { header, .. } => Some(&header._rev),
+            DocumentResponse::Silent { etag, .. } | DocumentResponse::Ignored { etag, .. } => {
+                etag.as_deref()
+            }
+        }
+    }
+    /// Return the path of the document from the `Location` header
+    ///
+    /// Only set on single document creation and replacement.
+    pub fn location(&self) -> Option<&str> {
+        match self {
+            DocumentResponse::Silent { location, .. }
+            | DocumentResponse::Response { location, .. }
+            | DocumentResponse::Ignored { location, .. } => location.as_deref(),
+        }
+    }
+    /// Return the revision of the document from the `Etag` header, without
+    /// the surrounding quotes
+    pub fn etag(&self) -> Option<&str> {
+        match self {
+            DocumentResponse::Silent { etag, .. }
+            | DocumentResponse::Response { etag, .. }
+            | DocumentResponse::Ignored { etag, .. } => etag.as_deref(),
+        }
+    }
+
+    /// Turn a response without the new document into `Ignored`, for an
+    /// insertion with `overwriteMode` ignore and `returnNew`, which only
+    /// lacks the new document when nothing was written.
+    pub(crate) fn into_ignored_without_new(self) -> Self {
+        match self {
+            DocumentResponse::Response {
+                new: None,
+                location,
+                etag,
+                ..
+            } => DocumentResponse::Ignored { location, etag },
+            other => other,
+        }
+    }
+
+    /// Fill `Location` and `Etag` from the response headers
+    pub(crate) fn with_headers(mut self, headers: &HeaderMap) -> Self {
+        let header_value = |name| {
+            headers
+                .get(name)
+                .and_then(|v: &http::HeaderValue| v.to_str().ok())
+                .map(|v| v.trim_matches('"').to_owned())
+        };
+        match &mut self {
+            DocumentResponse::Silent { location, etag }
+            | DocumentResponse::Response { location, etag, .. }
+            | DocumentResponse::Ignored { location, etag } => {
+                *location = header_value(LOCATION);
+                *etag = header_value(ETAG);
+            }
+        }
+        self
+    }
 }
 
-impl<'de, T> Deserialize<'de> for DocumentResponse<T>
+impl<'de, T, O> Deserialize<'de> for DocumentResponse<T, O>
 where
     T: Deserialize<'de>,
+    O: Deserialize<'de>,
 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -91,7 +187,10 @@ where
             .ok_or_else(|| DeError::custom("should be a json object"))?;
 
         if json.is_empty() {
-            Ok(DocumentResponse::Silent)
+            Ok(DocumentResponse::Silent {
+                location: None,
+                etag: None,
+            })
         } else {
             let _id = json
                 .remove("_id")
@@ -110,22 +209,133 @@ where
 
             let old = json
                 .remove("old")
-                .map(T::deserialize)
+                .map(O::deserialize)
                 .transpose()
                 .map_err(DeError::custom)?;
+            // `new` is null when the write was skipped, e.g. by `overwriteMode`
+            // ignore on an existing document
             let new = json
                 .remove("new")
+                .filter(|v| !v.is_null())
                 .map(T::deserialize)
                 .transpose()
                 .map_err(DeError::custom)?;
-            let _old_rev = json.remove("_old_rev").map(|v| v.to_string());
+            let _old_rev = json
+                .remove("_oldRev")
+                .and_then(|v| v.as_str().map(ToOwned::to_owned));
 
             Ok(DocumentResponse::Response {
                 header,
                 old,
                 new,
                 _old_rev,
+                location: None,
+                etag: None,
             })
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn document_response_with_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "\"_bJNMuLe---\"".parse().unwrap());
+        headers.insert(
+            LOCATION,
+            "/_db/test_db/_api/document/test_collection/123"
+                .parse()
+                .unwrap(),
+        );
+
+        let text = r#"{"_id":"test_collection/123","_key":"123","_rev":"_bJNMuLe---"}"#;
+        let resp = serde_json::from_str::<DocumentResponse<Value>>(text)
+            .unwrap()
+            .with_headers(&headers);
+        assert_eq!(resp.etag(), Some("_bJNMuLe---"));
+        assert_eq!(
+            resp.location(),
+            Some("/_db/test_db/_api/document/test_collection/123")
+        );
+
+        let resp = serde_json::from_str::<DocumentResponse<Value>>("{}")
+            .unwrap()
+            .with_headers(&headers);
+        assert!(resp.is_silent());
+        assert_eq!(resp.etag(), Some("_bJNMuLe---"));
+    }
+
+    #[test]
+    fn document_response_revisions() {
+        let text = r#"{"_id":"test_collection/123","_key":"123","_rev":"_bJNMuLe--_","_oldRev":"_bJNMuLe---"}"#;
+        let resp = serde_json::from_str::<DocumentResponse<Value>>(text).unwrap();
+        assert_eq!(resp.rev(), Some("_bJNMuLe--_"));
+        assert_eq!(resp.old_rev().map(String::as_str), Some("_bJNMuLe---"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "\"_bJNMuLe--_\"".parse().unwrap());
+        let resp = serde_json::from_str::<DocumentResponse<Value>>("{}")
+            .unwrap()
+            .with_headers(&headers);
+        assert_eq!(resp.rev(), Some("_bJNMuLe--_"));
+        assert!(resp.old_rev().is_none());
+    }
+
+    #[test]
+    fn document_response_ignored() {
+        #[derive(Debug, Deserialize)]
+        struct User {
+            #[allow(dead_code)]
+            username: String,
+        }
+
+        let text = r#"{"_id":"test_collection/123","_key":"123","_rev":"_bJNMuLe---","new":null}"#;
+        let resp = serde_json::from_str::<DocumentResponse<User>>(text).unwrap();
+        assert!(resp.has_response());
+        assert!(resp.new_doc().is_none());
+
+        let resp = resp.into_ignored_without_new();
+        assert!(resp.is_ignored());
+        assert!(resp.header().is_none());
+
+        let text = r#"{"_id":"test_collection/123","_key":"123","_rev":"_bJNMuLe---","new":{"username":"ada"}}"#;
+        let resp = serde_json::from_str::<DocumentResponse<User>>(text)
+            .unwrap()
+            .into_ignored_without_new();
+        assert!(resp.has_response());
+
+        // neither silent nor a document
+        assert!(serde_json::from_str::<DocumentResponse<User>>(r#"{"new":null}"#).is_err());
+    }
+
+    #[test]
+    fn document_response_old_and_new_types() {
+        #[derive(Debug, Deserialize)]
+        struct UserV1 {
+            name: String,
+        }
+        #[derive(Debug, Deserialize)]
+        struct UserV2 {
+            first_name: String,
+            last_name: String,
+        }
+
+        let text = r#"{"_id":"users/1","_key":"1","_rev":"_bJNMuLe--_",
+            "old":{"name":"Ada Lovelace"},
+            "new":{"first_name":"Ada","last_name":"Lovelace"}}"#;
+        let resp = serde_json::from_str::<DocumentResponse<UserV2, UserV1>>(text).unwrap();
+        assert_eq!(resp.old_doc().unwrap().name, "Ada Lovelace");
+        let new = resp.new_doc().unwrap();
+        assert_eq!(
+            (new.first_name.as_str(), new.last_name.as_str()),
+            ("Ada", "Lovelace")
+        );
+
+        // the old document does not decode into the new type
+        assert!(serde_json::from_str::<DocumentResponse<UserV2>>(text).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+//! Error numbers of ArangoDB
+//!
+//! The `errorNum` of an [`ArangoError`](super::ArangoError) tells what
+//! failed more precisely than its HTTP status code. The constants are a
+//! selection of the `lib/Basics/errors.dat` list of ArangoDB 3.11, the errors
+//! a client is likely to handle, under the names of that list. They are
+//! written by hand, not generated, and do not cover the whole list:
+//! [`ErrorCode`] describes the numbers of the selection and displays the
+//! others as unknown. When adding a constant, copy its line from the
+//! `errors.dat` of the same release and keep the groups sorted by number:
+//!
+//! ```rust
+//! use arangors::error::codes::{self, ErrorCode};
+//!
+//! assert_eq!(
+//!     ErrorCode(codes::ERROR_ARANGO_DOCUMENT_NOT_FOUND).to_string(),
+//!     "ERROR_ARANGO_DOCUMENT_NOT_FOUND (1202): document not found"
+//! );
+//! ```
+use std::fmt;
+
+macro_rules! error_codes {
+    ($($name:ident = $code:literal, $description:literal;)*) => {
+        $(
+            #[doc = concat!($description, ".")]
+            pub const $name: u16 = $code;
+        )*
+
+        /// Number, name and description of the selected errors, by number.
+        const ERRORS: &[(u16, &str, &str)] = &[$(($code, stringify!($name), $description)),*];
+    };
+}
+
+error_codes! {
+    // general errors
+    ERROR_NO_ERROR = 0, "no error";
+    ERROR_FAILED = 1, "failed";
+    ERROR_SYS_ERROR = 2, "system error";
+    ERROR_OUT_OF_MEMORY = 3, "out of memory";
+    ERROR_INTERNAL = 4, "internal error";
+    ERROR_ILLEGAL_NUMBER = 5, "illegal number";
+    ERROR_NUMERIC_OVERFLOW = 6, "numeric overflow";
+    ERROR_ILLEGAL_OPTION = 7, "illegal option";
+    ERROR_NOT_IMPLEMENTED = 9, "not implemented";
+    ERROR_BAD_PARAMETER = 10, "bad parameter";
+    ERROR_FORBIDDEN = 11, "forbidden";
+    ERROR_TYPE_ERROR = 17, "type error";
+    ERROR_LOCK_TIMEOUT = 18, "lock timeout";
+    ERROR_REQUEST_CANCELED = 21, "canceled request";
+    ERROR_LOCKED = 28, "locked";
+    ERROR_DEADLOCK = 29, "deadlock detected";
+    ERROR_SHUTTING_DOWN = 30, "shutdown in progress";
+    ERROR_ONLY_ENTERPRISE = 31, "only enterprise version";
+    ERROR_RESOURCE_LIMIT = 32, "resource limit exceeded";
+    ERROR_INCOMPATIBLE_VERSION = 35, "incompatible server version";
+    ERROR_DISABLED = 36, "disabled";
+    ERROR_MALFORMED_JSON = 37, "malformed json";
+    ERROR_STARTING_UP = 38, "startup ongoing";
+
+    // HTTP errors
+    ERROR_HTTP_BAD_PARAMETER = 400, "bad parameter";
+    ERROR_HTTP_UNAUTHORIZED = 401, "unauthorized";
+    ERROR_HTTP_FORBIDDEN = 403, "forbidden";
+    ERROR_HTTP_NOT_FOUND = 404, "not found";
+    ERROR_HTTP_METHOD_NOT_ALLOWED = 405, "method not supported";
+    ERROR_HTTP_NOT_ACCEPTABLE = 406, "request not acceptable";
+    ERROR_HTTP_REQUEST_TIMEOUT = 408, "request timeout";
+    ERROR_HTTP_CONFLICT = 409, "conflict";
+    ERROR_HTTP_GONE = 410, "content permanently deleted";
+    ERROR_HTTP_PRECONDITION_FAILED = 412, "precondition failed";
+    ERROR_HTTP_SERVER_ERROR = 500, "internal server error";
+    ERROR_HTTP_NOT_IMPLEMENTED = 501, "not implemented";
+    ERROR_HTTP_SERVICE_UNAVAILABLE = 503, "service unavailable";
+    ERROR_HTTP_GATEWAY_TIMEOUT = 504, "gateway timeout";
+    ERROR_HTTP_CORRUPTED_JSON = 600, "invalid JSON object";
+    ERROR_HTTP_SUPERFLUOUS_SUFFICES = 601, "superfluous URL suffices";
+
+    // storage errors
+    ERROR_ARANGO_ILLEGAL_STATE = 1000, "illegal state";
+    ERROR_ARANGO_READ_ONLY = 1004, "read only";
+    ERROR_ARANGO_DUPLICATE_IDENTIFIER = 1005, "duplicate identifier";
+    ERROR_ARANGO_CONFLICT = 1200, "conflict";
+    ERROR_ARANGO_DOCUMENT_NOT_FOUND = 1202, "document not found";
+    ERROR_ARANGO_DATA_SOURCE_NOT_FOUND = 1203, "collection or view not found";
+    ERROR_ARANGO_COLLECTION_PARAMETER_MISSING = 1204, "parameter 'collection' not found";
+    ERROR_ARANGO_DOCUMENT_HANDLE_BAD = 1205, "illegal document identifier";
+    ERROR_ARANGO_DUPLICATE_NAME = 1207, "duplicate name";
+    ERROR_ARANGO_ILLEGAL_NAME = 1208, "illegal name";
+    ERROR_ARANGO_NO_INDEX = 1209, "no suitable index known";
+    ERROR_ARANGO_UNIQUE_CONSTRAINT_VIOLATED = 1210, "unique constraint violated";
+    ERROR_ARANGO_INDEX_NOT_FOUND = 1212, "index not found";
+    ERROR_ARANGO_CROSS_COLLECTION_REQUEST = 1213, "cross collection request not allowed";
+    ERROR_ARANGO_INDEX_HANDLE_BAD = 1214, "illegal index identifier";
+    ERROR_ARANGO_DOCUMENT_TOO_LARGE = 1216, "document too large";
+    ERROR_ARANGO_COLLECTION_TYPE_INVALID = 1218, "collection type invalid";
+    ERROR_ARANGO_ATTRIBUTE_PARSER_FAILED = 1220, "parsing attribute name definition failed";
+    ERROR_ARANGO_DOCUMENT_KEY_BAD = 1221, "illegal document key";
+    ERROR_ARANGO_DOCUMENT_KEY_UNEXPECTED = 1222, "unexpected document key";
+    ERROR_ARANGO_OUT_OF_KEYS = 1225, "out of keys";
+    ERROR_ARANGO_DOCUMENT_KEY_MISSING = 1226, "missing document key";
+    ERROR_ARANGO_DOCUMENT_TYPE_INVALID = 1227, "invalid document type";
+    ERROR_ARANGO_DATABASE_NOT_FOUND = 1228, "database not found";
+    ERROR_ARANGO_DATABASE_NAME_INVALID = 1229, "database name invalid";
+    ERROR_ARANGO_USE_SYSTEM_DATABASE = 1230, "operation only allowed in system database";
+    ERROR_ARANGO_INVALID_KEY_GENERATOR = 1232, "invalid key generator";
+    ERROR_ARANGO_INVALID_EDGE_ATTRIBUTE = 1233, "edge attribute missing or invalid";
+    ERROR_ARANGO_INDEX_CREATION_FAILED = 1235, "index creation failed";
+    ERROR_ARANGO_COLLECTION_TYPE_MISMATCH = 1237, "collection type mismatch";
+    ERROR_ARANGO_COLLECTION_NOT_LOADED = 1238, "collection not loaded";
+    ERROR_ARANGO_DOCUMENT_REV_BAD = 1239, "illegal document revision";
+    ERROR_ARANGO_INCOMPLETE_READ = 1240, "incomplete read";
+
+    // cluster errors
+    ERROR_CLUSTER_TIMEOUT = 1457, "timeout in cluster operation";
+    ERROR_CLUSTER_BACKEND_UNAVAILABLE = 1477, "cluster backend unavailable";
+
+    // query errors
+    ERROR_QUERY_KILLED = 1500, "query killed";
+    ERROR_QUERY_PARSE = 1501, "query parse error";
+    ERROR_QUERY_EMPTY = 1502, "query is empty";
+    ERROR_QUERY_SCRIPT = 1503, "runtime error";
+    ERROR_QUERY_NUMBER_OUT_OF_RANGE = 1504, "number out of range";
+    ERROR_QUERY_VARIABLE_NAME_INVALID = 1510, "variable name has an invalid format";
+    ERROR_QUERY_VARIABLE_REDECLARED = 1511, "variable is assigned multiple times";
+    ERROR_QUERY_VARIABLE_NAME_UNKNOWN = 1512, "unknown variable";
+    ERROR_QUERY_COLLECTION_LOCK_FAILED = 1521, "unable to read-lock collection";
+    ERROR_QUERY_TOO_MANY_COLLECTIONS = 1522, "too many collections/shards";
+    ERROR_QUERY_FUNCTION_NAME_UNKNOWN = 1540, "usage of unknown function";
+    ERROR_QUERY_FUNCTION_ARGUMENT_NUMBER_MISMATCH = 1541,
+        "invalid number of arguments for function";
+    ERROR_QUERY_FUNCTION_ARGUMENT_TYPE_MISMATCH = 1542, "invalid argument type in call to function";
+    ERROR_QUERY_INVALID_REGEX = 1543, "invalid regex value";
+    ERROR_QUERY_BIND_PARAMETERS_INVALID = 1550, "invalid structure of bind parameters";
+    ERROR_QUERY_BIND_PARAMETER_MISSING = 1551, "no value specified for declared bind parameter";
+    ERROR_QUERY_BIND_PARAMETER_UNDECLARED = 1552, "bind parameter was not declared in the query";
+    ERROR_QUERY_BIND_PARAMETER_TYPE = 1553, "bind parameter has an invalid value or type";
+    ERROR_QUERY_DIVISION_BY_ZERO = 1562, "division by zero";
+    ERROR_QUERY_ARRAY_EXPECTED = 1563, "array expected";
+    ERROR_QUERY_FAIL_CALLED = 1569, "FAIL called";
+    ERROR_QUERY_GEO_INDEX_MISSING = 1570, "no suitable geo index found for geo restriction";
+    ERROR_QUERY_INVALID_DATE_VALUE = 1572, "invalid date value";
+    ERROR_QUERY_ACCESS_AFTER_MODIFICATION = 1579, "access after data-modification";
+    ERROR_QUERY_FUNCTION_INVALID_NAME = 1580, "invalid user function name";
+    ERROR_QUERY_FUNCTION_INVALID_CODE = 1581, "invalid user function code";
+    ERROR_QUERY_FUNCTION_NOT_FOUND = 1582, "user function not found";
+    ERROR_QUERY_FUNCTION_RUNTIME_ERROR = 1583, "user function runtime error";
+    ERROR_QUERY_BAD_JSON_PLAN = 1590, "bad execution plan JSON";
+    ERROR_QUERY_NOT_FOUND = 1591, "query ID not found";
+    ERROR_QUERY_USER_ASSERT = 1593, "user assertion failed";
+    ERROR_QUERY_USER_WARN = 1594, "user warning";
+
+    // cursor errors
+    ERROR_CURSOR_NOT_FOUND = 1600, "cursor not found";
+    ERROR_CURSOR_BUSY = 1601, "cursor is busy";
+
+    // transaction errors
+    ERROR_TRANSACTION_INTERNAL = 1650, "internal transaction error";
+    ERROR_TRANSACTION_NESTED = 1651, "nested transactions detected";
+    ERROR_TRANSACTION_UNREGISTERED_COLLECTION = 1652, "unregistered collection used in transaction";
+    ERROR_TRANSACTION_DISALLOWED_OPERATION = 1653, "disallowed operation inside transaction";
+    ERROR_TRANSACTION_ABORTED = 1654, "transaction aborted";
+    ERROR_TRANSACTION_NOT_FOUND = 1655, "transaction not found";
+
+    // user management errors
+    ERROR_USER_INVALID_NAME = 1700, "invalid user name";
+    ERROR_USER_DUPLICATE = 1702, "duplicate user";
+    ERROR_USER_NOT_FOUND = 1703, "user not found";
+    ERROR_USER_EXTERNAL = 1705, "user is external";
+
+    // task errors
+    ERROR_TASK_INVALID_ID = 1850, "invalid task id";
+    ERROR_TASK_DUPLICATE_ID = 1851, "duplicate task id";
+    ERROR_TASK_NOT_FOUND = 1852, "task not found";
+
+    // graph errors
+    ERROR_GRAPH_INVALID_GRAPH = 1901, "invalid graph";
+    ERROR_GRAPH_DUPLICATE = 1920, "graph already exists";
+    ERROR_GRAPH_NOT_FOUND = 1924, "graph not found";
+}
+
+/// An error number, displayed with its name and description when it is one
+/// of the constants of this module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorCode(pub u16);
+
+impl ErrorCode {
+    fn entry(self) -> Option<&'static (u16, &'static str, &'static str)> {
+        ERRORS
+            .binary_search_by_key(&self.0, |(code, _, _)| *code)
+            .ok()
+            .map(|i| &ERRORS[i])
+    }
+
+    /// Name of the error in `errors.dat`, like
+    /// `ERROR_ARANGO_DOCUMENT_NOT_FOUND`, if it is one of the constants.
+    pub fn name(self) -> Option<&'static str> {
+        self.entry().map(|(_, name, _)| *name)
+    }
+
+    /// Short description of the error, if it is one of the constants.
+    pub fn description(self) -> Option<&'static str> {
+        self.entry().map(|(_, _, description)| *description)
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.entry() {
+            Some((code, name, description)) => write!(f, "{} ({}): {}", name, code, description),
+            None => write!(f, "unknown error ({})", self.0),
+        }
+    }
+}
+
+impl From<u16> for ErrorCode {
+    fn from(code: u16) -> Self {
+        ErrorCode(code)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sorted_and_unique() {
+        assert!(ERRORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    /// Numbers and names of the errors the crate matches on, checked against
+    /// `errors.dat` of ArangoDB 3.11. A typo in one of these constants would
+    /// silently change retries, `NotFound` mapping or index lookups.
+    #[test]
+    fn matched_codes() {
+        let matched = [
+            (ERROR_LOCK_TIMEOUT, 18, "ERROR_LOCK_TIMEOUT"),
+            (ERROR_HTTP_NOT_FOUND, 404, "ERROR_HTTP_NOT_FOUND"),
+            (ERROR_ARANGO_CONFLICT, 1200, "ERROR_ARANGO_CONFLICT"),
+            (
+                ERROR_ARANGO_DATA_SOURCE_NOT_FOUND,
+                1203,
+                "ERROR_ARANGO_DATA_SOURCE_NOT_FOUND",
+            ),
+            (
+                ERROR_ARANGO_INDEX_NOT_FOUND,
+                1212,
+                "ERROR_ARANGO_INDEX_NOT_FOUND",
+            ),
+            (ERROR_QUERY_PARSE, 1501, "ERROR_QUERY_PARSE"),
+            (ERROR_TRANSACTION_ABORTED, 1654, "ERROR_TRANSACTION_ABORTED"),
+            (
+                ERROR_TRANSACTION_NOT_FOUND,
+                1655,
+                "ERROR_TRANSACTION_NOT_FOUND",
+            ),
+        ];
+        for (constant, code, name) in matched {
+            assert_eq!(constant, code);
+            assert_eq!(ErrorCode(code).name(), Some(name));
+        }
+    }
+
+    #[test]
+    fn describe() {
+        let code = ErrorCode(ERROR_ARANGO_UNIQUE_CONSTRAINT_VIOLATED);
+        assert_eq!(code.name(), Some("ERROR_ARANGO_UNIQUE_CONSTRAINT_VIOLATED"));
+        assert_eq!(code.description(), Some("unique constraint violated"));
+        assert_eq!(ErrorCode(4242).name(), None);
+        assert_eq!(ErrorCode(4242).to_string(), "unknown error (4242)");
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{aql::Warning, connection::Permission};
+
+pub mod codes;
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("Insufficient permission ({permission:?}) to operate: {operation}")]
+    InsufficientPermission {
+        permission: Permission,
+        operation: String,
+    },
+    #[error("Server is not ArangoDB: {0}")]
+    InvalidServer(String),
+    #[error("Error from server: {0}")]
+    Arango(#[from] ArangoError),
+    #[error("Error from serde: {0}")]
+    Serde(#[from] serde_json::error::Error),
+    #[error("HTTP client error: {0}")]
+    HttpClient(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    /// The JSON of a response does not match the expected type.
+    ///
+    /// `path` leads to the mismatch, like
+    /// `result[3].extra.stats.peakMemoryUsage`, `snippet` is a preview of the
+    /// JSON there and `endpoint` the method and url of the request. The path
+    /// is only located with the `path_to_error` feature, and is `?` without.
+    #[error(
+        "Failed to deserialize {type_name} at `{path}` from {endpoint}: {source}, near: {snippet}"
+    )]
+    Deserialize {
+        type_name: &'static str,
+        endpoint: String,
+        path: String,
+        snippet: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Unexpected response with status {status}: {body_snippet}")]
+    UnexpectedResponse { status: u16, body_snippet: String },
+    #[error("Operation not supported in a stream transaction: {0}")]
+    NotTransactional(String),
+    #[error("AQL query returned warnings: {0:?}")]
+    AqlWarnings(Vec<Warning>),
+    /// An AQL query plan reads these collections without an index.
+    #[error("AQL query scans collections without an index: {0:?}")]
+    FullCollectionScan(Vec<String>),
+    #[error("Operation not supported by the server: {0}")]
+    UnsupportedByServer(String),
+    /// The requested object does not exist.
+    #[error("Not found: {0}")]
+    NotFound(String),
+}
+
+#[derive(Deserialize, Debug, Error)]
+pub struct ArangoError {
+    #[serde(default)]
+    pub(crate) code: u16,
+    #[serde(rename = "errorNum")]
+    pub(crate) error_num: u16,
+    #[serde(rename = "errorMessage")]
+    pub(crate) message: String,
+}
+
+impl fmt::Display for ArangoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", self.message, self.error_num)
+    }
+}
+
+impl ArangoError {
+    /// Get the HTTP status code of an error response.
+    ///
+    /// Errors of single items of a bulk operation carry no status code, `0`
+    /// is returned for them.
+    pub fn code(&self) -> u16 {
+        self.code
+    }
+
+    pub fn error_num(&self) -> u16 {
+        self.error_num
+    }
+
+    /// The error number, described with the names of [`codes`].
+    pub fn error_code(&self) -> codes::ErrorCode {
+        codes::ErrorCode(self.error_num)
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
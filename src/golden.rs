@@ -0,0 +1,298 @@
+//! Golden-file tests of the wire format of the request options
+//!
+//! The query string or body sent for each options struct is compared with
+//! `tests/golden/options.json`, so that a field sent under a name the server
+//! does not know, and silently ignores, shows up as a diff. Options that can
+//! be decoded are also read back from the golden file and encoded again.
+//!
+//! Run the tests with `UPDATE_GOLDEN=1` to rewrite the file after an intended
+//! change of the wire format.
+use std::{collections::BTreeMap, fs};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    aql::{AqlOptions, AqlQuery, ProfileLevel},
+    collection::{
+        options::{
+            ChecksumOptions, CreateOptions, CreateParameters, ImportOptions, KeyOptions,
+            OnDuplicate, PropertiesOptions,
+        },
+        CollectionType,
+    },
+    document::options::{
+        InsertOptions, OverwriteMode, RemoveOptions, ReplaceOptions, UpdateOptions,
+    },
+    query,
+    transaction::{TransactionCollections, TransactionSettings},
+};
+
+const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/options.json");
+
+/// The encoded options, by name.
+#[derive(Default)]
+struct Cases(BTreeMap<&'static str, Value>);
+
+impl Cases {
+    /// Options sent as URL query parameters.
+    fn query<T: Serialize>(&mut self, name: &'static str, options: &T) {
+        let query = query::to_string(options).unwrap();
+        for pair in query.split('&') {
+            let (key, _) = pair.split_once('=').unwrap();
+            assert_camel_case(name, key);
+        }
+        self.0.insert(name, Value::String(query));
+    }
+
+    /// Options sent as URL query parameters, which can also be decoded.
+    fn query_round_trip<T: Serialize + DeserializeOwned>(
+        &mut self,
+        name: &'static str,
+        options: &T,
+    ) {
+        self.query(name, options);
+        round_trip::<T>(name, serde_json::to_value(options).unwrap());
+    }
+
+    /// Options sent as a JSON body.
+    fn body<T: Serialize>(&mut self, name: &'static str, options: &T) {
+        let body = serde_json::to_value(options).unwrap();
+        assert_keys_camel_case(name, &body);
+        self.0.insert(name, body);
+    }
+
+    /// Options sent as a JSON body, which can also be decoded.
+    fn body_round_trip<T: Serialize + DeserializeOwned>(
+        &mut self,
+        name: &'static str,
+        options: &T,
+    ) {
+        self.body(name, options);
+        round_trip::<T>(name, self.0[name].clone());
+    }
+}
+
+fn round_trip<T: Serialize + DeserializeOwned>(name: &str, encoded: Value) {
+    let decoded: T = serde_json::from_value(encoded.clone())
+        .unwrap_or_else(|e| panic!("{} can not be decoded: {}", name, e));
+    assert_eq!(
+        serde_json::to_value(decoded).unwrap(),
+        encoded,
+        "{} changed in a round trip",
+        name
+    );
+}
+
+fn assert_camel_case(name: &str, key: &str) {
+    assert!(
+        !key.contains('_') && !key.starts_with(char::is_uppercase),
+        "{} sends {:?}, which is not camelCase",
+        name,
+        key
+    );
+}
+
+/// Check the keys of the options, not the ones of user values like the bind
+/// variables or the schema.
+fn assert_keys_camel_case(name: &str, value: &Value) {
+    if let Value::Object(map) = value {
+        for (key, value) in map {
+            assert_camel_case(name, key);
+            if !matches!(key.as_str(), "bindVars" | "schema") {
+                assert_keys_camel_case(name, value);
+            }
+        }
+    }
+}
+
+fn cases() -> Cases {
+    let mut cases = Cases::default();
+
+    cases.query_round_trip(
+        "InsertOptions",
+        &InsertOptions::builder()
+            .wait_for_sync(true)
+            .return_new(true)
+            .return_old(false)
+            .silent(false)
+            .overwrite(true)
+            .overwrite_mode(OverwriteMode::Update)
+            .keep_null(false)
+            .merge_objects(true)
+            .build(),
+    );
+    cases.query_round_trip(
+        "UpdateOptions",
+        &UpdateOptions::builder()
+            .keep_null(false)
+            .merge_objects(false)
+            .wait_for_sync(true)
+            .ignore_revs(false)
+            .return_new(true)
+            .return_old(true)
+            .silent(false)
+            .build(),
+    );
+    cases.query_round_trip(
+        "ReplaceOptions",
+        &ReplaceOptions::builder()
+            .wait_for_sync(true)
+            .ignore_revs(false)
+            .return_new(true)
+            .return_old(true)
+            .silent(false)
+            .build(),
+    );
+    cases.query_round_trip(
+        "RemoveOptions",
+        &RemoveOptions::builder()
+            .wait_for_sync(true)
+            .return_old(true)
+            .silent(false)
+            .build(),
+    );
+    cases.query_round_trip(
+        "ChecksumOptions",
+        &ChecksumOptions::builder()
+            .with_revision(true)
+            .with_data(false)
+            .build(),
+    );
+    cases.query(
+        "ImportOptions",
+        &ImportOptions::builder()
+            .on_duplicate(OnDuplicate::Update)
+            .overwrite(false)
+            .wait_for_sync(true)
+            .complete(true)
+            .details(true)
+            .from_prefix("users")
+            .to_prefix("groups")
+            .build(),
+    );
+    cases.query(
+        "CreateParameters",
+        &CreateParameters::builder()
+            .wait_for_sync_replication(true)
+            .enforce_replication_factor(false)
+            .build(),
+    );
+
+    let schema = json!({
+        "rule": { "properties": { "user_name": { "type": "string" } } },
+        "level": "moderate",
+        "message": "invalid user"
+    });
+    cases.body(
+        "CreateOptions",
+        &CreateOptions::builder()
+            .name("golden")
+            .collection_type(CollectionType::Edge)
+            .wait_for_sync(true)
+            .is_system(false)
+            .key_options(
+                KeyOptions::builder()
+                    .allow_user_keys(false)
+                    .key_type("autoincrement".to_owned())
+                    .increment(2)
+                    .offset(10)
+                    .build(),
+            )
+            .schema(schema.clone())
+            .sharding_strategy("hash".to_owned())
+            .do_compat(true)
+            .journal_size(1048576)
+            .is_volatile(false)
+            .build(),
+    );
+    cases.body_round_trip(
+        "PropertiesOptions",
+        &PropertiesOptions::builder()
+            .wait_for_sync(true)
+            .cache_enabled(false)
+            .schema(schema)
+            .build(),
+    );
+    cases.body_round_trip(
+        "TransactionSettings",
+        &TransactionSettings::builder()
+            .collections(
+                TransactionCollections::builder()
+                    .read(vec!["users".to_owned()])
+                    .write(vec!["orders".to_owned()])
+                    .exclusive(vec!["stock".to_owned()])
+                    .build(),
+            )
+            .wait_for_sync(true)
+            .allow_implicit(false)
+            .lock_timeout(30)
+            .max_transaction_size(1048576)
+            .build(),
+    );
+    cases.body(
+        "AqlQuery",
+        &AqlQuery::builder()
+            .query("FOR u IN @@users FILTER u.age > @age RETURN u")
+            .bind_var("@users", "users")
+            .bind_var("age", 30)
+            .count(true)
+            .batch_size(100)
+            .cache(false)
+            .memory_limit(1048576)
+            .ttl(30)
+            .options(
+                AqlOptions::builder()
+                    .fail_on_warning(true)
+                    .profile(ProfileLevel::Nodes)
+                    .max_warning_count(5)
+                    .full_count(true)
+                    .max_plans(3)
+                    .optimizer(vec!["-all".to_owned(), "+use-indexes".to_owned()])
+                    .use_plan_cache(false)
+                    .intermediate_commit_count(1000)
+                    .intermediate_commit_size(1048576)
+                    .max_transaction_size(1048576)
+                    .build(),
+            )
+            .build(),
+    );
+    cases
+}
+
+#[test]
+fn options_wire_format() {
+    let cases = cases();
+    let encoded = Value::Object(
+        cases
+            .0
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect(),
+    );
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(
+            GOLDEN,
+            serde_json::to_string_pretty(&encoded).unwrap() + "\n",
+        )
+        .unwrap();
+        return;
+    }
+    let golden: Value = serde_json::from_str(&fs::read_to_string(GOLDEN).unwrap()).unwrap();
+    for (name, expected) in golden.as_object().unwrap() {
+        assert_eq!(
+            encoded.get(name),
+            Some(expected),
+            "{} differs from {}, run with UPDATE_GOLDEN=1 if intended",
+            name,
+            GOLDEN
+        );
+    }
+    assert_eq!(
+        encoded.as_object().unwrap().len(),
+        golden.as_object().unwrap().len(),
+        "options missing from {}, run with UPDATE_GOLDEN=1",
+        GOLDEN
+    );
+}
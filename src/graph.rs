@@ -6,46 +6,195 @@
 //!
 //! For detailed information about ArangoDB named graphs, please check out the
 //! official ArangoDB [documentation](https://www.arangodb.com/docs/stable/http/gharial.html).
-use serde::{Deserialize, Serialize};
-use typed_builder::TypedBuilder;
+use std::{collections::HashSet, sync::Arc};
+
+use http::Method;
+use maybe_async::maybe_async;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    aql::{check_warnings, Cursor, CursorGuard, PendingCursors, QueryContext, WarningPolicy},
+    client::{ClientExt, RequestExt},
+    document::{Document, Header},
+    response::deserialize_response,
+    transaction::{Transaction, TRANSACTION_HEADER},
+    ClientError,
+};
 
 pub(crate) const GHARIAL_API_PATH: &str = "_api/gharial";
 
 /// Represents a Named Graph in ArangoDB.
-#[derive(Debug, Clone, Serialize, Deserialize, Default, TypedBuilder)]
+///
+/// Use [`Graph::builder`] to describe a graph to create.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Graph {
     /// Name of the graph
-    #[builder(default)]
     pub name: String,
     /// An array of definitions for the relations of the graph.
-    #[builder(default)]
     pub edge_definitions: Vec<EdgeDefinition>,
     /// An array of additional vertex collections. Documents within these
     /// collections do not have edges within this graph.
-    #[builder(default)]
     #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
     pub orphan_collections: Vec<String>,
     /// Define if the created graph should be smart (Enterprise Edition only).
-    #[builder(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_smart: Option<bool>,
     /// Whether to create a Disjoint SmartGraph instead of a regular SmartGraph
     /// (Enterprise Edition only).
-    #[builder(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_disjoint: Option<bool>,
     /// a JSON object to define options for creating collections within this
     /// graph.
-    #[builder(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<GraphOptions>,
 }
 
+impl Graph {
+    /// Create a builder for the payload of [`Database::create_graph`].
+    ///
+    /// Edge definitions and orphan collections are added one at a time with
+    /// `.edge_definition(...)` and `.orphan(...)`, collection options are set
+    /// directly on the builder.
+    ///
+    /// # Example
+    /// ```rust
+    /// use arangors::graph::{EdgeDefinition, Graph};
+    ///
+    /// let graph = Graph::builder()
+    ///     .name("social")
+    ///     .edge_definition(EdgeDefinition::new("knows", ["persons"], ["persons"]))
+    ///     .orphan("cities")
+    ///     .number_of_shards(3)
+    ///     .build();
+    /// assert_eq!(graph.edge_definitions.len(), 1);
+    /// assert_eq!(graph.orphan_collections, vec!["cities"]);
+    /// ```
+    ///
+    /// [`Database::create_graph`]: crate::Database::create_graph
+    pub fn builder() -> GraphBuilder {
+        GraphBuilder::default()
+    }
+
+    /// Names of the collections of the graph, edge collections first, then
+    /// vertex collections, each once.
+    fn collections(&self) -> Vec<&str> {
+        let edges = self.edge_definitions.iter().map(|d| d.collection.as_str());
+        let vertices = self
+            .edge_definitions
+            .iter()
+            .flat_map(|d| d.from.iter().chain(d.to.iter()))
+            .chain(self.orphan_collections.iter())
+            .map(String::as_str);
+        let mut collections: Vec<&str> = Vec::new();
+        for name in edges.chain(vertices) {
+            if !collections.contains(&name) {
+                collections.push(name);
+            }
+        }
+        collections
+    }
+}
+
+/// Builder of a [`Graph`], created with [`Graph::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct GraphBuilder {
+    graph: Graph,
+}
+
+impl GraphBuilder {
+    /// Name of the graph.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.graph.name = name.into();
+        self
+    }
+
+    /// Add a definition for the relations of the graph.
+    pub fn edge_definition(mut self, definition: EdgeDefinition) -> Self {
+        self.graph.edge_definitions.push(definition);
+        self
+    }
+
+    /// Replace all the edge definitions of the graph.
+    pub fn edge_definitions(mut self, definitions: Vec<EdgeDefinition>) -> Self {
+        self.graph.edge_definitions = definitions;
+        self
+    }
+
+    /// Add a vertex collection without edges in the graph.
+    pub fn orphan(mut self, collection: impl Into<String>) -> Self {
+        self.graph.orphan_collections.push(collection.into());
+        self
+    }
+
+    /// Replace all the orphan collections of the graph.
+    pub fn orphan_collections(mut self, collections: Vec<String>) -> Self {
+        self.graph.orphan_collections = collections;
+        self
+    }
+
+    /// Create a SmartGraph sharded by `attribute` (Enterprise Edition only).
+    ///
+    /// Sets both `isSmart` and `options.smartGraphAttribute`.
+    pub fn smart(mut self, attribute: impl Into<String>) -> Self {
+        self.graph.is_smart = Some(true);
+        self.options_mut().smart_graph_attribute = Some(attribute.into());
+        self
+    }
+
+    /// Define if the created graph should be smart (Enterprise Edition only).
+    pub fn is_smart(mut self, is_smart: Option<bool>) -> Self {
+        self.graph.is_smart = is_smart;
+        self
+    }
+
+    /// Whether to create a Disjoint SmartGraph (Enterprise Edition only).
+    pub fn is_disjoint(mut self, is_disjoint: Option<bool>) -> Self {
+        self.graph.is_disjoint = is_disjoint;
+        self
+    }
+
+    /// Number of shards of every collection created for the graph.
+    pub fn number_of_shards(mut self, number_of_shards: u32) -> Self {
+        self.options_mut().number_of_shards = Some(number_of_shards);
+        self
+    }
+
+    /// Replication factor of every collection created for the graph.
+    pub fn replication_factor(mut self, replication_factor: u32) -> Self {
+        self.options_mut().replication_factor = Some(replication_factor);
+        self
+    }
+
+    /// Write concern of every collection created for the graph.
+    pub fn write_concern(mut self, write_concern: u32) -> Self {
+        self.options_mut().write_concern = Some(write_concern);
+        self
+    }
+
+    /// Replace all the collection options of the graph.
+    pub fn options(mut self, options: Option<GraphOptions>) -> Self {
+        self.graph.options = options;
+        self
+    }
+
+    /// The graph to pass to [`Database::create_graph`].
+    ///
+    /// [`Database::create_graph`]: crate::Database::create_graph
+    pub fn build(self) -> Graph {
+        self.graph
+    }
+
+    fn options_mut(&mut self) -> &mut GraphOptions {
+        self.graph.options.get_or_insert_with(GraphOptions::default)
+    }
+}
+
 /// Represents the available options for a [`Graph`] Creation
 ///
 /// [`Graph`]: struct.Graph.html
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GraphOptions {
     /// Only has effect in Enterprise Edition and it is required if isSmart is
@@ -89,6 +238,26 @@ pub struct EdgeDefinition {
     pub to: Vec<String>,
 }
 
+impl EdgeDefinition {
+    /// Edges of `collection` going from vertices of the `from` collections to
+    /// vertices of the `to` collections.
+    pub fn new<F, T>(
+        collection: impl Into<String>,
+        from: impl IntoIterator<Item = F>,
+        to: impl IntoIterator<Item = T>,
+    ) -> Self
+    where
+        F: Into<String>,
+        T: Into<String>,
+    {
+        EdgeDefinition {
+            collection: collection.into(),
+            from: from.into_iter().map(Into::into).collect(),
+            to: to.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
 /// Represents a collection of [`Graphs`] on a database in ArangoDB.
 ///
 /// [`Graphs`]: struct.Graph.html
@@ -106,3 +275,685 @@ pub struct GraphCollection {
 pub struct GraphResponse {
     pub graph: Graph,
 }
+
+/// A named graph of a database, carrying the session to operate on it.
+///
+/// Obtained with [`Database::graph_handle`](crate::Database::graph_handle),
+/// or [`Transaction::graph`] and [`Transaction::graph_handle`] to operate on
+/// vertices and edges within a stream transaction.
+/// Unlike [`Graph`], which only describes a graph, the handle manages its
+/// vertex collections and edge definitions, and the vertices and edges
+/// through the graph module, which keeps the edges consistent with the
+/// graph definition.
+///
+/// Cloning it shares the HTTP client through an `Arc`. No request is made.
+#[derive(Debug, Clone)]
+pub struct GraphHandle<C: ClientExt> {
+    name: String,
+    base_url: Url,
+    db_url: Url,
+    session: Arc<C>,
+    transactional: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionsResponse {
+    collections: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VertexResponse<T> {
+    vertex: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct EdgeResponse<T> {
+    edge: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemovedResponse {
+    removed: bool,
+}
+
+impl<C: ClientExt> GraphHandle<C> {
+    /// Base url should be like `http://server:port/_db/mydb/`
+    pub(crate) fn new<T: Into<String>>(name: T, db_url: &Url, session: Arc<C>) -> Self {
+        let name = name.into();
+        let base_url = db_url
+            .join(&format!("{}/{}/", GHARIAL_API_PATH, name))
+            .unwrap();
+        GraphHandle {
+            name,
+            base_url,
+            db_url: db_url.clone(),
+            session,
+            transactional: false,
+        }
+    }
+
+    pub(crate) fn from_transaction<T: Into<String>>(name: T, transaction: &Transaction<C>) -> Self {
+        GraphHandle {
+            transactional: true,
+            ..Self::new(name, transaction.url(), transaction.session())
+        }
+    }
+
+    /// Returns a new handle with its `session` updated with the transaction
+    /// id, so that its vertex and edge operations are part of the transaction.
+    pub fn clone_with_transaction(&self, transaction_id: String) -> Result<Self, ClientError> {
+        let transaction_id = transaction_id.parse().map_err(|_| {
+            ClientError::InvalidArgument(format!("invalid transaction id: {}", transaction_id))
+        })?;
+        let mut session = (*self.session).clone();
+        session.headers().insert(TRANSACTION_HEADER, transaction_id);
+        Ok(Self {
+            session: Arc::new(session),
+            transactional: true,
+            ..self.clone()
+        })
+    }
+
+    /// Whether the handle was obtained from a transaction, so that its vertex
+    /// and edge operations are part of it.
+    pub fn is_transactional(&self) -> bool {
+        self.transactional
+    }
+
+    /// Fail for operations on the graph definition, which stream transactions
+    /// do not cover.
+    fn ensure_not_transactional(&self, operation: &str) -> Result<(), ClientError> {
+        if self.transactional {
+            return Err(ClientError::NotTransactional(format!(
+                "{} of graph {}",
+                operation, self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Name of the graph
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Url of the graph in the graph module
+    pub fn url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// The HTTP client shared with the database
+    pub fn session(&self) -> Arc<C> {
+        Arc::clone(&self.session)
+    }
+
+    /// Retrieve the definition of the graph
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn info(&self) -> Result<Graph, ClientError> {
+        let url = self.base_url.join("").unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: GraphResponse = deserialize_response(&resp)?;
+        Ok(result.graph)
+    }
+
+    /// Drop the graph. Optionally all collections not used by other graphs
+    /// are dropped as well.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn drop(self, drop_collections: bool) -> Result<(), ClientError> {
+        self.ensure_not_transactional("drop")?;
+        let mut url = self.base_url.join("").unwrap();
+        url.set_query(Some(&format!("dropCollections={}", drop_collections)));
+        let resp = self.session.delete(url, "").await?;
+        deserialize_response::<serde_json::Value>(&resp)?;
+        Ok(())
+    }
+
+    /// Remove all documents of the collections of the graph, edges first.
+    ///
+    /// # Note
+    /// this function would make a request per collection to arango server.
+    #[maybe_async]
+    pub async fn truncate_all(&self) -> Result<(), ClientError> {
+        let graph = self.info().await?;
+        for name in graph.collections() {
+            let url = self
+                .db_url
+                .join(&format!("_api/collection/{}/truncate", name))
+                .unwrap();
+            let resp = self.session.put(url, "").await?;
+            deserialize_response::<serde_json::Value>(&resp)?;
+        }
+        Ok(())
+    }
+
+    /// Drop the graph along with its edge and vertex collections, except the
+    /// collections used by other graphs, and return the names of the
+    /// collections dropped.
+    ///
+    /// Like [`drop`](Self::drop) with `drop_collections`, but the collections
+    /// are dropped one by one after the graph, edge collections first, so
+    /// the caller learns which ones are gone. Collections already dropped
+    /// are skipped.
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async]
+    pub async fn drop_with_collections(self) -> Result<Vec<String>, ClientError> {
+        self.ensure_not_transactional("drop")?;
+        let graph = self.info().await?;
+        let url = self.db_url.join(GHARIAL_API_PATH).unwrap();
+        let graphs: GraphCollection = deserialize_response(&self.session.get(url, "").await?)?;
+        let used: HashSet<&str> = graphs
+            .graphs
+            .iter()
+            .filter(|other| other.name != self.name)
+            .flat_map(Graph::collections)
+            .collect();
+
+        let db_url = self.db_url.clone();
+        let session = self.session();
+        self.drop(false).await?;
+
+        let mut dropped = Vec::new();
+        for name in graph.collections() {
+            if used.contains(name) {
+                continue;
+            }
+            let url = db_url.join(&format!("_api/collection/{}", name)).unwrap();
+            let resp = session.delete(url, "").await?;
+            match deserialize_response::<serde_json::Value>(&resp) {
+                Ok(_) => dropped.push(name.to_owned()),
+                Err(ClientError::Arango(err)) if err.code() == 404 => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(dropped)
+    }
+
+    /// List the vertex collections of the graph
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn vertex_collections(&self) -> Result<Vec<String>, ClientError> {
+        let url = self.base_url.join("vertex").unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: CollectionsResponse = deserialize_response(&resp)?;
+        Ok(result.collections)
+    }
+
+    /// Add a vertex collection to the orphan collections of the graph. The
+    /// collection is created if it does not exist.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn add_vertex_collection(&self, collection: &str) -> Result<Graph, ClientError> {
+        self.ensure_not_transactional("add vertex collection")?;
+        let url = self.base_url.join("vertex").unwrap();
+        let body = serde_json::json!({ "collection": collection });
+        let resp = self.session.post(url, body.to_string()).await?;
+        let result: GraphResponse = deserialize_response(&resp)?;
+        Ok(result.graph)
+    }
+
+    /// Remove a vertex collection from the orphan collections of the graph,
+    /// and optionally drop the collection.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn remove_vertex_collection(
+        &self,
+        collection: &str,
+        drop_collection: bool,
+    ) -> Result<Graph, ClientError> {
+        self.ensure_not_transactional("remove vertex collection")?;
+        let mut url = self
+            .base_url
+            .join(&format!("vertex/{}", collection))
+            .unwrap();
+        url.set_query(Some(&format!("dropCollection={}", drop_collection)));
+        let resp = self.session.delete(url, "").await?;
+        let result: GraphResponse = deserialize_response(&resp)?;
+        Ok(result.graph)
+    }
+
+    /// List the edge collections of the graph
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn edge_collections(&self) -> Result<Vec<String>, ClientError> {
+        let url = self.base_url.join("edge").unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: CollectionsResponse = deserialize_response(&resp)?;
+        Ok(result.collections)
+    }
+
+    /// Add an edge definition to the graph
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn add_edge_definition(
+        &self,
+        definition: &EdgeDefinition,
+    ) -> Result<Graph, ClientError> {
+        self.ensure_not_transactional("add edge definition")?;
+        let url = self.base_url.join("edge").unwrap();
+        let resp = self
+            .session
+            .post(url, &serde_json::to_string(definition)?)
+            .await?;
+        let result: GraphResponse = deserialize_response(&resp)?;
+        Ok(result.graph)
+    }
+
+    /// Replace the edge definition of the edge collection named in
+    /// `definition`
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn replace_edge_definition(
+        &self,
+        definition: &EdgeDefinition,
+    ) -> Result<Graph, ClientError> {
+        self.ensure_not_transactional("replace edge definition")?;
+        let url = self
+            .base_url
+            .join(&format!("edge/{}", definition.collection))
+            .unwrap();
+        let resp = self
+            .session
+            .put(url, &serde_json::to_string(definition)?)
+            .await?;
+        let result: GraphResponse = deserialize_response(&resp)?;
+        Ok(result.graph)
+    }
+
+    /// Remove the edge definition of an edge collection from the graph, and
+    /// optionally drop the collection.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn remove_edge_definition(
+        &self,
+        collection: &str,
+        drop_collection: bool,
+    ) -> Result<Graph, ClientError> {
+        self.ensure_not_transactional("remove edge definition")?;
+        let mut url = self.base_url.join(&format!("edge/{}", collection)).unwrap();
+        url.set_query(Some(&format!("dropCollections={}", drop_collection)));
+        let resp = self.session.delete(url, "").await?;
+        let result: GraphResponse = deserialize_response(&resp)?;
+        Ok(result.graph)
+    }
+
+    /// Create a vertex in a vertex collection of the graph
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_vertex<T: Serialize>(
+        &self,
+        collection: &str,
+        vertex: &T,
+    ) -> Result<Header, ClientError> {
+        let url = self
+            .base_url
+            .join(&format!("vertex/{}", collection))
+            .unwrap();
+        let resp = self
+            .session
+            .post(url, &serde_json::to_string(vertex)?)
+            .await?;
+        let result: VertexResponse<Header> = deserialize_response(&resp)?;
+        Ok(result.vertex)
+    }
+
+    /// Read a vertex of the graph
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn vertex<T: DeserializeOwned>(
+        &self,
+        collection: &str,
+        key: &str,
+    ) -> Result<Document<T>, ClientError> {
+        let url = self
+            .base_url
+            .join(&format!("vertex/{}/{}", collection, key))
+            .unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: VertexResponse<Document<T>> = deserialize_response(&resp)?;
+        Ok(result.vertex)
+    }
+
+    /// Remove a vertex and all its edges from the graph
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn remove_vertex(&self, collection: &str, key: &str) -> Result<bool, ClientError> {
+        let url = self
+            .base_url
+            .join(&format!("vertex/{}/{}", collection, key))
+            .unwrap();
+        let resp = self.session.delete(url, "").await?;
+        let result: RemovedResponse = deserialize_response(&resp)?;
+        Ok(result.removed)
+    }
+
+    /// Create an edge in an edge collection of the graph. The edge must have
+    /// `_from` and `_to` attributes allowed by the edge definition.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_edge<T: Serialize>(
+        &self,
+        collection: &str,
+        edge: &T,
+    ) -> Result<Header, ClientError> {
+        let url = self.base_url.join(&format!("edge/{}", collection)).unwrap();
+        let resp = self
+            .session
+            .post(url, &serde_json::to_string(edge)?)
+            .await?;
+        let result: EdgeResponse<Header> = deserialize_response(&resp)?;
+        Ok(result.edge)
+    }
+
+    /// Read an edge of the graph
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn edge<T: DeserializeOwned>(
+        &self,
+        collection: &str,
+        key: &str,
+    ) -> Result<Document<T>, ClientError> {
+        let url = self
+            .base_url
+            .join(&format!("edge/{}/{}", collection, key))
+            .unwrap();
+        let resp = self.session.get(url, "").await?;
+        let result: EdgeResponse<Document<T>> = deserialize_response(&resp)?;
+        Ok(result.edge)
+    }
+
+    /// Remove an edge from the graph
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn remove_edge(&self, collection: &str, key: &str) -> Result<bool, ClientError> {
+        let url = self
+            .base_url
+            .join(&format!("edge/{}/{}", collection, key))
+            .unwrap();
+        let resp = self.session.delete(url, "").await?;
+        let result: RemovedResponse = deserialize_response(&resp)?;
+        Ok(result.removed)
+    }
+}
+
+/// A step of a graph traversal, as returned by an AQL query ending with
+/// `RETURN { vertex: v, edge: e, path: p }`.
+///
+/// `edge` is `None` for the start vertex, and `path` is `None` when the query
+/// does not return it, which saves a lot of data on deep traversals.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(bound(deserialize = "V: Deserialize<'de>, E: Deserialize<'de>"))]
+pub struct TraversalStep<V, E> {
+    pub vertex: V,
+    pub edge: Option<E>,
+    #[serde(default)]
+    pub path: Option<TraversalPath<V, E>>,
+}
+
+/// Path from the start vertex to the vertex of a [`TraversalStep`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct TraversalPath<V, E> {
+    pub vertices: Vec<V>,
+    pub edges: Vec<E>,
+}
+
+/// Lazily fetched result of a graph traversal, see
+/// [`Database::aql_traversal`](crate::Database::aql_traversal).
+///
+/// Only one batch is held in memory at a time, and a batch is deserialized
+/// when it is fetched. Dropping the traversal before the end deletes the
+/// server cursor.
+///
+/// The later batches are fetched with the context of the query, like its
+/// stream transaction, and checked against its warning policy.
+///
+/// In blocking mode, `Traversal` is also an `Iterator`.
+#[derive(Debug)]
+pub struct Traversal<C: ClientExt, V, E> {
+    session: Arc<C>,
+    base_url: Url,
+    context: QueryContext,
+    policy: WarningPolicy,
+    id: Option<String>,
+    more: bool,
+    batch: std::vec::IntoIter<TraversalStep<V, E>>,
+    guard: CursorGuard<C>,
+}
+
+impl<C, V, E> Traversal<C, V, E>
+where
+    C: ClientExt,
+    V: DeserializeOwned,
+    E: DeserializeOwned,
+{
+    pub(crate) fn new(
+        session: Arc<C>,
+        base_url: Url,
+        context: QueryContext,
+        policy: WarningPolicy,
+        cursor: Cursor<TraversalStep<V, E>>,
+        cursor_cleanup: bool,
+        pending: PendingCursors,
+    ) -> Self {
+        let guard = CursorGuard::new(
+            Arc::clone(&session),
+            base_url.clone(),
+            cursor.id.clone().filter(|_| cursor_cleanup && cursor.more),
+            pending,
+        );
+        Traversal {
+            session,
+            base_url,
+            context,
+            policy,
+            id: cursor.id,
+            more: cursor.more,
+            batch: cursor.result.into_iter(),
+            guard,
+        }
+    }
+
+    /// Get the next step of the traversal, fetching the next batch from the
+    /// server when the current one is exhausted. Returns `None` at the end.
+    ///
+    /// # Note
+    /// this function would make a request to arango server when the current
+    /// batch is exhausted.
+    #[maybe_async]
+    pub async fn next_step(&mut self) -> Result<Option<TraversalStep<V, E>>, ClientError> {
+        loop {
+            if let Some(step) = self.batch.next() {
+                return Ok(Some(step));
+            }
+            let id = match self.id.as_deref() {
+                Some(id) if self.more => id,
+                _ => return Ok(None),
+            };
+            let url = self.base_url.join(&format!("_api/cursor/{}", id)).unwrap();
+            let req = self.context.request(Method::PUT, url, String::new())?;
+            let resp = self.session.request(req).await?;
+            let cursor: Cursor<TraversalStep<V, E>> = deserialize_response(&resp)?;
+            self.more = cursor.more;
+            if !self.more {
+                self.guard.disarm();
+            }
+            check_warnings(self.policy, cursor.extra.as_ref())?;
+            self.batch = cursor.result.into_iter();
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<C, V, E> Iterator for Traversal<C, V, E>
+where
+    C: ClientExt,
+    V: DeserializeOwned,
+    E: DeserializeOwned,
+{
+    type Item = Result<TraversalStep<V, E>, ClientError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_step().transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn graph_builder_payload() {
+        let graph = Graph::builder()
+            .name("social")
+            .edge_definition(EdgeDefinition::new("knows", ["persons"], ["persons"]))
+            .edge_definition(EdgeDefinition::new("lives_in", ["persons"], ["cities"]))
+            .orphan("countries")
+            .smart("region")
+            .is_disjoint(Some(true))
+            .number_of_shards(3)
+            .build();
+        assert_eq!(
+            serde_json::to_value(&graph).unwrap(),
+            json!({
+                "name": "social",
+                "edgeDefinitions": [
+                    {"collection": "knows", "from": ["persons"], "to": ["persons"]},
+                    {"collection": "lives_in", "from": ["persons"], "to": ["cities"]},
+                ],
+                "orphanCollections": ["countries"],
+                "isSmart": true,
+                "isDisjoint": true,
+                "options": {"smartGraphAttribute": "region", "numberOfShards": 3},
+            })
+        );
+
+        let graph = Graph::builder().name("empty").build();
+        assert_eq!(
+            serde_json::to_value(&graph).unwrap(),
+            json!({"name": "empty", "edgeDefinitions": []})
+        );
+    }
+
+    #[test]
+    fn clone_with_invalid_transaction_id() {
+        use crate::client::mock::RecordingClient;
+
+        let db_url: Url = "http://localhost:8529/_db/shop/".parse().unwrap();
+        let handle = GraphHandle::new("social", &db_url, Arc::new(RecordingClient::default()));
+        let cloned = handle.clone_with_transaction("1234".to_owned()).unwrap();
+        assert!(cloned.is_transactional());
+        let invalid = handle.clone_with_transaction("12\n34".to_owned());
+        assert!(matches!(invalid, Err(ClientError::InvalidArgument(_))));
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn truncate_behind_a_proxy_prefix() {
+        use crate::client::mock::RecordingClient;
+
+        let session = Arc::new(RecordingClient::default());
+        let db_url: Url = "http://proxy/arango/_db/shop/".parse().unwrap();
+        let handle = GraphHandle::new("social", &db_url, Arc::clone(&session));
+        session.respond(
+            200,
+            r#"{"graph":{"name":"social","edgeDefinitions":[
+                {"collection":"knows","from":["persons"],"to":["persons"]}]}}"#,
+        );
+
+        handle.truncate_all().await.unwrap();
+
+        let paths: Vec<String> = session
+            .take_requests()
+            .iter()
+            .map(|request| request.uri().path().to_owned())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "/arango/_db/shop/_api/gharial/social/",
+                "/arango/_db/shop/_api/collection/knows/truncate",
+                "/arango/_db/shop/_api/collection/persons/truncate",
+            ]
+        );
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn traversal_batches_carry_the_context() {
+        use crate::{
+            aql::{AqlOptions, AqlQuery},
+            client::mock::RecordingClient,
+            Database,
+        };
+
+        let session = Arc::new(RecordingClient::default());
+        let arango_url: Url = "http://localhost:8529/".parse().unwrap();
+        let db = Database::new("test_db", &arango_url, Arc::clone(&session));
+        session.respond(
+            201,
+            r#"{"result":[{"vertex":"a","edge":null}],"hasMore":true,"id":"42",
+                "cached":false,"error":false,"code":201}"#,
+        );
+        session.respond(
+            200,
+            r#"{"result":[{"vertex":"b","edge":"ab"}],"hasMore":false,"id":"42",
+                "cached":false,"error":false,"code":200,
+                "extra":{"warnings":[{"code":1562,"message":"division by zero"}]}}"#,
+        );
+
+        let context = QueryContext::builder().transaction_id("1234").build();
+        let aql = AqlQuery::builder()
+            .query("FOR v, e IN 0..1 OUTBOUND 'persons/a' knows RETURN { vertex: v, edge: e }")
+            .batch_size(1)
+            .options(
+                AqlOptions::builder()
+                    .context(context)
+                    .warning_policy(WarningPolicy::Fail)
+                    .build(),
+            )
+            .build();
+        let mut traversal = db.aql_traversal::<String, String>(aql).await.unwrap();
+        let first = traversal.next_step().await.unwrap();
+        assert_eq!(first.unwrap().vertex, "a");
+        let second = traversal.next_step().await;
+        assert!(matches!(second, Err(ClientError::AqlWarnings(_))));
+
+        let requests = session.take_requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method(), Method::PUT);
+        assert_eq!(requests[1].headers()[TRANSACTION_HEADER], "1234");
+    }
+}
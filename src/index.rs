@@ -5,6 +5,7 @@
 //! * Fulltext
 //! * Geo
 //! * Hash
+//! * Inverted
 //! * Persistent
 //! * Skiplist
 //! * Ttl (Time to live)
@@ -16,8 +17,20 @@
 //! official ArangoDB [documentation](https://www.arangodb.com/docs/stable/http/indexes.html).
 //!
 //! [Primary]: https://www.arangodb.com/docs/stable/http/indexes.html#primary-index
+use std::{sync::Arc, time::Duration};
+
+use maybe_async::maybe_async;
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
+use url::Url;
+
+use crate::{
+    client::{ClientExt, RequestExt},
+    response::deserialize_response,
+    util::sleep,
+    view::{PrimarySort, PrimarySortCompression, StoredValues},
+    ClientError,
+};
 
 pub(crate) const INDEX_API_PATH: &str = "_api/index";
 
@@ -26,6 +39,7 @@ pub(crate) const INDEX_API_PATH: &str = "_api/index";
 /// * Fulltext
 /// * Geo
 /// * Hash
+/// * Inverted
 /// * Persistent
 /// * Skiplist
 /// * Ttl (Time to live)
@@ -66,7 +80,7 @@ pub(crate) const INDEX_API_PATH: &str = "_api/index";
 /// [`Index`]: struct.Index.html
 /// [`settings`]: enum.IndexSettings.html
 #[derive(Debug, Clone, Serialize, Deserialize, Default, TypedBuilder)]
-#[serde(rename_all = "camelCase")]
+#[serde(from = "IndexRepr", into = "IndexRepr")]
 pub struct Index {
     #[builder(default)]
     pub fields: Vec<String>,
@@ -80,11 +94,106 @@ pub struct Index {
     pub selectivity_estimate: Option<f32>,
     #[builder(default)]
     pub in_background: Option<bool>,
+    /// Build progress in percent, only reported for indexes still being
+    /// created in background.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub progress: Option<f64>,
     #[serde(flatten)]
     #[builder(default)]
     pub settings: IndexSettings,
 }
 
+impl Index {
+    /// Build a vertex-centric index, a persistent index on an edge collection
+    /// starting with `_from` (outbound) or `_to` (inbound) followed by
+    /// `attributes`.
+    ///
+    /// Traversals that filter on these edge attributes, e.g. with
+    /// `FILTER p.edges[*].weight ALL < 10` or `PRUNE e.weight >= 10`, can then
+    /// look up only the matching edges of each vertex instead of scanning all
+    /// of them with the default edge index. This makes a large difference for
+    /// vertices with many edges.
+    ///
+    /// Use [`Database::create_vertex_centric_index`] to check that the
+    /// collection is an edge collection when creating it.
+    ///
+    /// [`Database::create_vertex_centric_index`]: crate::Database::create_vertex_centric_index
+    pub fn vertex_centric<S>(
+        name: impl Into<String>,
+        direction: EdgeDirection,
+        attributes: impl IntoIterator<Item = S>,
+    ) -> Index
+    where
+        S: Into<String>,
+    {
+        let mut fields = vec![direction.field().to_owned()];
+        fields.extend(attributes.into_iter().map(Into::into));
+
+        Index::builder()
+            .name(name)
+            .fields(fields)
+            .settings(IndexSettings::Persistent {
+                unique: false,
+                sparse: false,
+                deduplicate: false,
+            })
+            .build()
+    }
+
+    /// Check the index before it is sent to the server.
+    ///
+    /// Fails for geo indexes on no or more than two fields, with `geo_json`
+    /// on two fields, or with `legacy_polygons` enabled without `geo_json`,
+    /// which the server accepts or ignores while querying the index gives
+    /// wrong results.
+    pub fn validate(&self) -> Result<(), ClientError> {
+        if let IndexSettings::Geo {
+            geo_json,
+            legacy_polygons,
+        } = &self.settings
+        {
+            let invalid = |reason: &str| {
+                Err(ClientError::InvalidArgument(format!(
+                    "invalid geo index {:?}: {}",
+                    self.name, reason
+                )))
+            };
+            match self.fields.len() {
+                1 => {}
+                2 if *geo_json => {
+                    return invalid("geoJson requires a single field");
+                }
+                2 => {}
+                _ => return invalid("expected one field, or the latitude and longitude fields"),
+            }
+            if *legacy_polygons == Some(true) && !geo_json {
+                return invalid("legacyPolygons only applies with geoJson");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Direction of the edges covered by a vertex-centric index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeDirection {
+    /// Edges going out of a vertex, indexed by `_from`
+    Outbound,
+    /// Edges coming into a vertex, indexed by `_to`
+    Inbound,
+}
+
+impl EdgeDirection {
+    /// The edge attribute the index starts with.
+    pub fn field(&self) -> &'static str {
+        match self {
+            EdgeDirection::Outbound => "_from",
+            EdgeDirection::Inbound => "_to",
+        }
+    }
+}
+
 /// Settings for the different index types. This `enum` also sets the index
 /// type.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -117,14 +226,52 @@ pub enum IndexSettings {
     Ttl {
         expire_after: u32,
     },
+    /// Geo index. The meaning of the [`Index::fields`] depends on their
+    /// number and on `geo_json`:
+    ///
+    /// * two fields are the attributes of the latitude, then of the
+    ///   longitude,
+    /// * one field without `geo_json` is an attribute holding a `[latitude,
+    ///   longitude]` array,
+    /// * one field with `geo_json` is an attribute holding a GeoJSON object,
+    ///   or a `[longitude, latitude]` array as in GeoJSON.
+    ///
+    /// `legacy_polygons` uses the polygon semantics of the indexes created
+    /// before ArangoDB 3.10, where the edges of polygons are not geodesics.
+    /// It only has an effect with `geo_json`, new indexes default to `false`.
+    ///
+    /// Swapping the order returns wrong results without any error, so
+    /// [`Index::validate`] rejects the combinations the server would
+    /// misinterpret.
+    ///
+    /// # Example
+    /// ```
+    /// use arangors::index::{Index, IndexSettings};
+    ///
+    /// let index = Index::builder()
+    ///     .name("idx_location")
+    ///     .fields(vec!["location".to_string()])
+    ///     .settings(IndexSettings::Geo {
+    ///         geo_json: true,
+    ///         legacy_polygons: Some(false),
+    ///     })
+    ///     .build();
+    /// assert!(index.validate().is_ok());
+    /// ```
     #[serde(rename_all = "camelCase")]
     Geo {
         geo_json: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        legacy_polygons: Option<bool>,
     },
     #[serde(rename_all = "camelCase")]
     Fulltext {
         min_length: u32,
     },
+    /// Inverted index, for `SEARCH` queries through a `search-alias` view
+    /// or filters with the `OPTIONS { indexHint }` of a `FOR` loop. Since
+    /// ArangoDB 3.10.
+    Inverted(Box<InvertedIndexSettings>),
 }
 
 impl Default for IndexSettings {
@@ -137,6 +284,230 @@ impl Default for IndexSettings {
     }
 }
 
+/// Settings of an inverted index, see [`IndexSettings::Inverted`].
+///
+/// The settings apply to all the fields of the index, unless overridden by
+/// the settings of a field in [`fields`](InvertedIndexSettings::fields).
+///
+/// # Example
+/// ```
+/// use arangors::index::{Index, IndexSettings, InvertedIndexField, InvertedIndexSettings};
+///
+/// let index = Index::builder()
+///     .name("inv_articles")
+///     .fields(vec!["title".to_string(), "tags".to_string()])
+///     .settings(IndexSettings::Inverted(Box::new(
+///         InvertedIndexSettings::builder()
+///             .analyzer("identity")
+///             .fields(vec![InvertedIndexField::builder()
+///                 .name("title")
+///                 .analyzer("text_en")
+///                 .build()])
+///             .build(),
+///     )))
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TypedBuilder)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct InvertedIndexSettings {
+    /// Settings of single fields, sent as the objects of the `fields` of the
+    /// index. Fields without settings are only listed in
+    /// [`Index::fields`].
+    #[serde(skip)]
+    #[builder(default)]
+    pub fields: Vec<InvertedIndexField>,
+    /// Analyzer of the fields without one, `identity` by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    pub analyzer: Option<String>,
+    /// Analyzer features, like `frequency` or `position`, of the fields
+    /// without their own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub features: Option<Vec<String>>,
+    /// Index all the attributes of the documents, not only the fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub include_all_fields: Option<bool>,
+    /// Track the position of values in arrays, instead of treating them as
+    /// equal alternatives.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub track_list_positions: Option<bool>,
+    /// Index arrays and their members like an `arangosearch` view, so that
+    /// `SEARCH` queries can use the index.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub search_field: Option<bool>,
+    /// Attributes stored in the index to return them without reading the
+    /// documents.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[builder(default)]
+    pub stored_values: Vec<StoredValues>,
+    /// Order the index is sorted by, to speed up queries sorting the same
+    /// way.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub primary_sort: Option<InvertedPrimarySort>,
+    /// Keep the field normalization values in memory. Enterprise Edition
+    /// only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub cache: Option<bool>,
+    /// Keep the primary key column in memory. Enterprise Edition only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub primary_key_cache: Option<bool>,
+    /// Number of threads building the index.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub parallelism: Option<u32>,
+}
+
+/// Settings of a single field of an inverted index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypedBuilder)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct InvertedIndexField {
+    /// Attribute path of the field
+    #[builder(setter(into))]
+    pub name: String,
+    /// Analyzer of the field, instead of the one of the index
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    pub analyzer: Option<String>,
+    /// Analyzer features of the field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub features: Option<Vec<String>>,
+    /// Index all the attributes of an object field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub include_all_fields: Option<bool>,
+    /// Track the position of values in an array field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub track_list_positions: Option<bool>,
+    /// Index the field like an `arangosearch` view
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub search_field: Option<bool>,
+    /// Keep the field normalization values in memory. Enterprise Edition
+    /// only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub cache: Option<bool>,
+}
+
+/// Primary sort order of an inverted index.
+#[derive(Debug, Clone, Serialize, Deserialize, TypedBuilder)]
+#[builder(doc)]
+#[serde(rename_all = "camelCase")]
+pub struct InvertedPrimarySort {
+    /// Fields to sort by, in order
+    pub fields: Vec<PrimarySort>,
+    /// Compression of the sorted data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub compression: Option<PrimarySortCompression>,
+    /// Keep the sorted data in memory. Enterprise Edition only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub cache: Option<bool>,
+}
+
+/// Wire format of [`Index`], whose `fields` are attribute names, or objects
+/// with the settings of a field for inverted indexes.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexRepr {
+    fields: Vec<IndexField>,
+    name: String,
+    id: String,
+    is_newly_created: Option<bool>,
+    selectivity_estimate: Option<f32>,
+    in_background: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    progress: Option<f64>,
+    #[serde(flatten)]
+    settings: IndexSettings,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum IndexField {
+    Name(String),
+    Inverted(InvertedIndexField),
+}
+
+impl From<IndexRepr> for Index {
+    fn from(repr: IndexRepr) -> Self {
+        let mut settings = repr.settings;
+        let mut field_settings = Vec::new();
+        let fields = repr
+            .fields
+            .into_iter()
+            .map(|field| match field {
+                IndexField::Name(name) => name,
+                IndexField::Inverted(field) => {
+                    let name = field.name.clone();
+                    field_settings.push(field);
+                    name
+                }
+            })
+            .collect();
+        if let IndexSettings::Inverted(inverted) = &mut settings {
+            inverted.fields = field_settings;
+        }
+        Index {
+            fields,
+            name: repr.name,
+            id: repr.id,
+            is_newly_created: repr.is_newly_created,
+            selectivity_estimate: repr.selectivity_estimate,
+            in_background: repr.in_background,
+            progress: repr.progress,
+            settings,
+        }
+    }
+}
+
+impl From<Index> for IndexRepr {
+    fn from(index: Index) -> Self {
+        let mut settings = index.settings;
+        let fields = match &mut settings {
+            IndexSettings::Inverted(inverted) => {
+                // fields with settings are sent as objects, the others by name
+                let mut field_settings = std::mem::take(&mut inverted.fields);
+                let mut fields: Vec<IndexField> = index
+                    .fields
+                    .into_iter()
+                    .map(
+                        |name| match field_settings.iter().position(|f| f.name == name) {
+                            Some(i) => IndexField::Inverted(field_settings.remove(i)),
+                            None => IndexField::Name(name),
+                        },
+                    )
+                    .collect();
+                fields.extend(field_settings.into_iter().map(IndexField::Inverted));
+                fields
+            }
+            _ => index.fields.into_iter().map(IndexField::Name).collect(),
+        };
+        IndexRepr {
+            fields,
+            name: index.name,
+            id: index.id,
+            is_newly_created: index.is_newly_created,
+            selectivity_estimate: index.selectivity_estimate,
+            in_background: index.in_background,
+            progress: index.progress,
+            settings,
+        }
+    }
+}
+
 /// Represents a collection of indexes on a collection in ArangoDB.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -144,9 +515,331 @@ pub struct IndexCollection {
     pub indexes: Vec<Index>,
 }
 
+impl IndexCollection {
+    /// Find an index of `collection` by name, plain id or `collection/id`
+    /// handle.
+    pub fn find(&self, collection: &str, name_or_id: &str) -> Option<&Index> {
+        let handle = index_handle(collection, name_or_id);
+        self.indexes
+            .iter()
+            .find(|index| index.name == name_or_id || index.id == handle)
+    }
+}
+
+/// Normalize an index id of `collection` to the `collection/id` handle
+/// expected by the server. Ids already in this form are kept as is.
+pub fn index_handle(collection: &str, id: &str) -> String {
+    if id.contains('/') {
+        id.to_owned()
+    } else {
+        format!("{}/{}", collection, id)
+    }
+}
+
 /// Response from ArangoDB when deleting an index
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteIndexResponse {
     pub id: String,
 }
+
+/// State of an index created in background, as reported by
+/// [`IndexBuild::poll_progress`].
+#[derive(Debug, Clone)]
+pub enum IndexProgress {
+    /// The index is still being built, with the progress in percent if the
+    /// server reports it.
+    Building(Option<f64>),
+    /// The index is ready to use.
+    Ready(Index),
+}
+
+/// Handle on an index created with
+/// [`Database::create_index_in_background`](crate::Database::create_index_in_background).
+///
+/// The creation request runs as an async job on the server, so the handle is
+/// returned before the index is built.
+#[derive(Debug, Clone)]
+pub struct IndexBuild<C: ClientExt> {
+    job_id: String,
+    name: String,
+    collection: String,
+    ready: Option<Index>,
+    base_url: Url,
+    session: Arc<C>,
+}
+
+impl<C: ClientExt> IndexBuild<C> {
+    pub(crate) fn new(
+        job_id: String,
+        name: String,
+        collection: &str,
+        base_url: Url,
+        session: Arc<C>,
+    ) -> Self {
+        IndexBuild {
+            job_id,
+            name,
+            collection: collection.to_owned(),
+            ready: None,
+            base_url,
+            session,
+        }
+    }
+
+    /// Id of the server job creating the index.
+    pub fn job_id(&self) -> &str {
+        &self.job_id
+    }
+
+    /// Check whether the index is ready.
+    ///
+    /// Once the creation job is done, the created index or the server error is
+    /// returned. Until then, the progress is read from the `progress` field of
+    /// the collection index listing, which requires the index to be named.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn poll_progress(&mut self) -> Result<IndexProgress, ClientError> {
+        if let Some(index) = &self.ready {
+            return Ok(IndexProgress::Ready(index.clone()));
+        }
+
+        let url = self
+            .base_url
+            .join(&format!("_api/job/{}", self.job_id))
+            .unwrap();
+        let resp = self.session.put(url, "").await?;
+        // the job is still pending
+        if resp.status() != http::StatusCode::NO_CONTENT {
+            let index: Index = deserialize_response::<Index>(&resp)?;
+            self.ready = Some(index.clone());
+            return Ok(IndexProgress::Ready(index));
+        }
+        if self.name.is_empty() {
+            return Ok(IndexProgress::Building(None));
+        }
+
+        let mut url = self.base_url.join(INDEX_API_PATH).unwrap();
+        // indexes under construction are hidden from the default listing
+        url.set_query(Some(&format!(
+            "collection={}&withHidden=true",
+            self.collection
+        )));
+        let resp = self.session.get(url, "").await?;
+        let result: IndexCollection = deserialize_response::<IndexCollection>(&resp)?;
+
+        let progress = result
+            .indexes
+            .into_iter()
+            .find(|index| index.name == self.name)
+            .and_then(|index| index.progress);
+        Ok(IndexProgress::Building(progress))
+    }
+
+    /// Wait until the index is ready, checking every `interval`.
+    ///
+    /// Returns the created index, or the error the creation job failed with.
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async]
+    pub async fn wait(&mut self, interval: Duration) -> Result<Index, ClientError> {
+        loop {
+            if let IndexProgress::Ready(index) = self.poll_progress().await? {
+                return Ok(index);
+            }
+            sleep(interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn find_by_name_or_id() {
+        assert_eq!(index_handle("users", "1234"), "users/1234");
+        assert_eq!(index_handle("users", "users/1234"), "users/1234");
+
+        let indexes = IndexCollection {
+            indexes: vec![
+                Index::builder()
+                    .name("primary")
+                    .id("users/0".to_owned())
+                    .build(),
+                Index::builder()
+                    .name("idx_email")
+                    .id("users/1234".to_owned())
+                    .build(),
+            ],
+        };
+        for name_or_id in ["idx_email", "1234", "users/1234"] {
+            let index = indexes.find("users", name_or_id).unwrap();
+            assert_eq!(index.name, "idx_email");
+        }
+        assert!(indexes.find("users", "idx_missing").is_none());
+        assert!(indexes.find("users", "orders/1234").is_none());
+    }
+
+    #[test]
+    fn inverted_index_fields() {
+        let index = Index::builder()
+            .name("inv_articles")
+            .fields(vec!["title".to_owned(), "tags".to_owned()])
+            .settings(IndexSettings::Inverted(Box::new(
+                InvertedIndexSettings::builder()
+                    .analyzer("identity")
+                    .search_field(true)
+                    .fields(vec![InvertedIndexField::builder()
+                        .name("title")
+                        .analyzer("text_en")
+                        .build()])
+                    .build(),
+            )))
+            .build();
+        let json = serde_json::to_value(&index).unwrap();
+        assert_eq!(json["type"], "inverted");
+        assert_eq!(
+            json["fields"],
+            serde_json::json!([{ "name": "title", "analyzer": "text_en" }, "tags"])
+        );
+        assert_eq!(json["analyzer"], "identity");
+        assert_eq!(json["searchField"], true);
+
+        let index: Index = serde_json::from_value(json).unwrap();
+        assert_eq!(index.fields, vec!["title".to_owned(), "tags".to_owned()]);
+        match index.settings {
+            IndexSettings::Inverted(inverted) => {
+                assert_eq!(inverted.fields.len(), 1);
+                assert_eq!(inverted.fields[0].analyzer.as_deref(), Some("text_en"));
+            }
+            other => panic!("expected an inverted index, got {:?}", other),
+        }
+
+        let json = serde_json::to_value(Index::builder().name("idx").build()).unwrap();
+        assert_eq!(json["fields"], serde_json::json!([]));
+        assert_eq!(json["type"], "persistent");
+    }
+
+    #[test]
+    fn geo_index_settings() {
+        let geo = |fields: &[&str], geo_json: bool, legacy_polygons: Option<bool>| {
+            Index::builder()
+                .name("idx_geo")
+                .fields(fields.iter().map(|f| f.to_string()).collect())
+                .settings(IndexSettings::Geo {
+                    geo_json,
+                    legacy_polygons,
+                })
+                .build()
+        };
+
+        let index = geo(&["location"], true, Some(false));
+        assert!(index.validate().is_ok());
+        let json = serde_json::to_value(&index).unwrap();
+        assert_eq!(json["type"], "geo");
+        assert_eq!(json["geoJson"], true);
+        assert_eq!(json["legacyPolygons"], false);
+
+        let json = serde_json::to_value(geo(&["lat", "lng"], false, None)).unwrap();
+        assert_eq!(json["geoJson"], false);
+        assert!(json.get("legacyPolygons").is_none());
+
+        let index: Index = serde_json::from_value(serde_json::json!({
+            "id": "places/1", "name": "idx_geo", "type": "geo", "fields": ["location"],
+            "geoJson": true, "legacyPolygons": true, "sparse": true, "unique": false,
+        }))
+        .unwrap();
+        assert!(matches!(
+            index.settings,
+            IndexSettings::Geo {
+                geo_json: true,
+                legacy_polygons: Some(true),
+            }
+        ));
+        let index: Index = serde_json::from_value(serde_json::json!({
+            "id": "places/2", "name": "idx_geo", "type": "geo", "fields": ["lat", "lng"],
+            "geoJson": false,
+        }))
+        .unwrap();
+        assert!(matches!(
+            index.settings,
+            IndexSettings::Geo {
+                geo_json: false,
+                legacy_polygons: None,
+            }
+        ));
+
+        assert!(geo(&["lat", "lng"], false, None).validate().is_ok());
+        assert!(geo(&["location"], false, Some(false)).validate().is_ok());
+        assert!(geo(&[], false, None).validate().is_err());
+        assert!(geo(&["a", "b", "c"], false, None).validate().is_err());
+        assert!(geo(&["lat", "lng"], true, None).validate().is_err());
+        let err = geo(&["location"], false, Some(true))
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid argument: invalid geo index \"idx_geo\": legacyPolygons only applies with \
+             geoJson"
+        );
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn wait_for_background_index() {
+        use crate::{client::mock::RecordingClient, Database};
+
+        let session = Arc::new(RecordingClient::default());
+        let arango_url: Url = "http://localhost:8529/".parse().unwrap();
+        let db = Database::new("test_db", &arango_url, Arc::clone(&session));
+        let index = Index::builder().fields(vec!["email".to_owned()]).build();
+
+        // the server did not store a job
+        session.respond(202, "");
+        let result = db.create_index_in_background("users", &index).await;
+        assert!(matches!(
+            result,
+            Err(ClientError::UnexpectedResponse { status: 202, .. })
+        ));
+
+        let mut build = IndexBuild::new(
+            "42".to_owned(),
+            String::new(),
+            "users",
+            db.url().clone(),
+            Arc::clone(&session),
+        );
+        session.take_requests();
+        session.respond(204, "");
+        session.respond(
+            201,
+            r#"{"id":"users/1234","name":"idx_email","type":"persistent","fields":["email"],
+                "unique":false,"sparse":false,"deduplicate":true}"#,
+        );
+        let index = build.wait(Duration::from_millis(1)).await.unwrap();
+        assert_eq!(index.name, "idx_email");
+        let requests = session.take_requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[1].uri(),
+            "http://localhost:8529/_db/test_db/_api/job/42"
+        );
+
+        let mut build = IndexBuild::new(
+            "43".to_owned(),
+            String::new(),
+            "users",
+            db.url().clone(),
+            Arc::clone(&session),
+        );
+        session.respond(
+            400,
+            r#"{"error":true,"code":400,"errorNum":10,"errorMessage":"bad parameter"}"#,
+        );
+        let result = build.wait(Duration::from_millis(1)).await;
+        assert!(matches!(result, Err(ClientError::Arango(_))));
+    }
+}
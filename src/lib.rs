@@ -53,6 +53,15 @@
 //! - `reqwest_async`
 //! - `reqwest_blocking`
 //! - `surf_async`
+//! - `wasm`, `reqwest_async` on `wasm32-unknown-unknown`, for web apps talking
+//!   to ArangoDB from the browser
+//! - `tower`, any `tower::Service` sending HTTP requests, to reuse its
+//!   layers, see `client::tower`
+//!
+//! With the `tracing` feature, the slow AQL queries reported by
+//! [`SlowQueryLog`](aql::SlowQueryLog) are emitted as `tracing` events.
+//! The `testing` feature adds the temporary databases and collections of
+//! `testing` for the tests of applications.
 //!
 //! By default, `arangors` use `reqwest_async` as underling HTTP Client to
 //! connect with ArangoDB. You can switch other ecosystem in feature gate:
@@ -116,6 +125,30 @@
 //! let conn = Connection::establish_without_auth("http://localhost:8529").await.unwrap();
 //! ```
 //!
+//! ## Concurrent Usage
+//!
+//! Connections, databases and collections are cheap to clone and can be
+//! shared between tasks or threads: clones share one HTTP client, so they
+//! reuse its pool of network connections. Clone a handle for each task
+//! instead of wrapping it in a lock.
+//!
+//! The session headers of a client are not changed after a connection is
+//! established. Operations needing other headers work on a clone of the
+//! client instead, like transactions with
+//! `Collection::clone_with_transaction` or acting as another user with
+//! `GenericConnection::as_user`:
+//!
+//! ```rust, ignore
+//! use arangors::connection::Auth;
+//!
+//! let alice = conn.as_user(Auth::bearer(&alice_token)).await.unwrap();
+//! let orders = alice.db("shop").await.unwrap().collection("orders").await.unwrap();
+//! ```
+//!
+//! The only exception is `GenericConnection::set_auth`, which rotates the
+//! credentials of a connection and all its clones at once, except the ones
+//! acting as another user.
+//!
 //! ## Database && Collection
 //!
 //! To get info or operate on database or collections:
@@ -135,6 +168,15 @@
 //! # }
 //! ```
 //!
+//! The types used most often, like collection [`Info`](collection::response::Info),
+//! [`DocumentResponse`](document::response::DocumentResponse) and the options
+//! of document operations, are gathered with the [`ClientExt`](client::ClientExt)
+//! and [`RequestExt`](client::RequestExt) traits in the [`prelude`]:
+//!
+//! ```rust
+//! use arangors::prelude::*;
+//! ```
+//!
 //! ## AQL Query
 //!
 //! All [AQL](https://www.arangodb.com/docs/stable/aql/index.html) query related functions are associated with database, as AQL query
@@ -383,26 +425,50 @@ compile_error!(
 ))]
 pub use crate::connection::Connection;
 pub use crate::{
-    aql::{AqlOptions, AqlQuery, Cursor},
+    aql::{AqlOptions, AqlQuery, Cursor, QueryContext},
     collection::Collection,
     connection::GenericConnection,
     database::Database,
     document::Document,
     error::{ArangoError, ClientError},
+    response::{DecodedItems, ItemDecodeError, ResponseEnvelope, ResponseMeta},
 };
 
 pub mod analyzer;
 pub mod aql;
 pub mod client;
+pub mod codec;
 pub mod collection;
 pub mod connection;
 pub mod database;
+pub mod datetime;
 pub mod document;
 pub mod error;
+#[cfg(test)]
+mod golden;
 pub mod graph;
 pub mod index;
+pub mod namespace;
+pub mod naming;
+pub mod prelude;
 mod query;
+pub mod read_only;
 mod response;
+pub mod search;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod transaction;
 pub mod user;
+mod util;
 pub mod view;
+
+/// Handles are shared between threads and tasks, so they must stay
+/// `Send + Sync` whenever the HTTP client is.
+#[allow(dead_code)]
+fn assert_handles_send_sync<C: client::ClientExt + Send>() {
+    fn send_sync<T: Send + Sync>() {}
+    send_sync::<GenericConnection<C>>();
+    send_sync::<Database<C>>();
+    send_sync::<Collection<C>>();
+    send_sync::<transaction::Transaction<C>>();
+}
@@ -0,0 +1,185 @@
+//! Collection names prefixed per tenant
+//!
+//! Databases shared by several tenants often give each tenant its own set of
+//! collections, named `<tenant>_<collection>`. A [`Namespace`], obtained with
+//! `Database::namespace`, applies this prefix to the collection names given
+//! to its methods, so that code working on behalf of a tenant only names
+//! collections like `users` or `orders`.
+//!
+//! In AQL queries, the collection bind parameters (those starting with `@@`
+//! in the query text) are prefixed as well. Collection names written in the
+//! query text are sent unchanged, use collection bind parameters instead.
+//!
+//! # Example
+//! ```rust, ignore
+//! let tenant = db.namespace("tenant1");
+//! // collection `tenant1_users`
+//! let users = tenant.collection("users").await?;
+//!
+//! let names: Vec<String> = tenant
+//!     .aql_bind_vars(
+//!         "FOR u IN @@users RETURN u.name",
+//!         [("@users", "users")],
+//!     )
+//!     .await?;
+//! ```
+use maybe_async::maybe_async;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    aql::AqlQuery, client::ClientExt, collection::response::Info, database::bind_values,
+    ClientError, Collection, Database,
+};
+
+/// Separator between the prefix of a namespace and a collection name
+const SEPARATOR: &str = "_";
+
+/// A [`Database`] whose collection names are prefixed with a tenant name.
+#[derive(Debug, Clone)]
+pub struct Namespace<C: ClientExt> {
+    db: Database<C>,
+    prefix: String,
+}
+
+impl<C: ClientExt> Namespace<C> {
+    pub(crate) fn new(db: Database<C>, name: &str) -> Self {
+        Namespace {
+            db,
+            prefix: format!("{}{}", name, SEPARATOR),
+        }
+    }
+
+    /// The underlying database, whose methods take full collection names.
+    pub fn database(&self) -> &Database<C> {
+        &self.db
+    }
+
+    /// The prefix of collection names in this namespace, the name of the
+    /// namespace followed by `_`.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Full name of the collection `name` of this namespace.
+    pub fn collection_name(&self, name: &str) -> String {
+        format!("{}{}", self.prefix, name)
+    }
+
+    /// Name within this namespace of the collection with full name `name`,
+    /// or `None` if it belongs to another namespace.
+    pub fn local_name<'a>(&self, name: &'a str) -> Option<&'a str> {
+        name.strip_prefix(self.prefix.as_str())
+    }
+
+    /// Prefix the collection bind parameters of `aql`.
+    fn prefix_query<'a>(&self, mut aql: AqlQuery<'a>) -> AqlQuery<'a> {
+        aql.map_collections(|name| self.collection_name(name));
+        aql
+    }
+
+    /// Retrieve the collections of this namespace.
+    ///
+    /// The returned [`Info`] carry the full collection names.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn accessible_collections(&self) -> Result<Vec<Info>, ClientError> {
+        let collections = self.db.accessible_collections().await?;
+        Ok(collections
+            .into_iter()
+            .filter(|info| self.local_name(&info.name).is_some())
+            .collect())
+    }
+
+    /// Get the collection `name` of this namespace.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn collection(&self, name: &str) -> Result<Collection<C>, ClientError> {
+        self.db.collection(&self.collection_name(name)).await
+    }
+
+    /// Create the document collection `name` in this namespace.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_collection(&self, name: &str) -> Result<Collection<C>, ClientError> {
+        self.db.create_collection(&self.collection_name(name)).await
+    }
+
+    /// Create the edge collection `name` in this namespace.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn create_edge_collection(&self, name: &str) -> Result<Collection<C>, ClientError> {
+        self.db
+            .create_edge_collection(&self.collection_name(name))
+            .await
+    }
+
+    /// Drop the collection `name` of this namespace, returning its id.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn drop_collection(&self, name: &str) -> Result<String, ClientError> {
+        self.db.drop_collection(&self.collection_name(name)).await
+    }
+
+    /// Execute AQL query fetch all results, with its collection bind
+    /// parameters prefixed.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_query<R>(&self, aql: AqlQuery<'_>) -> Result<Vec<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.db.aql_query(self.prefix_query(aql)).await
+    }
+
+    /// Similar to `aql_query`, except that this method only accept a string of
+    /// AQL query, with additional bind vars.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_bind_vars<R>(
+        &self,
+        query: &str,
+        bind_vars: impl IntoIterator<Item = (impl Into<String>, impl Serialize)>,
+    ) -> Result<Vec<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        let aql = AqlQuery::builder()
+            .query(query)
+            .bind_vars(bind_values(bind_vars)?)
+            .build();
+        self.aql_query(aql).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn prefix_collection_bind_vars() {
+        let mut aql = AqlQuery::builder()
+            .query("FOR u IN @@users FILTER u.name == @name RETURN u")
+            .bind_vars([("@users", json!("users")), ("name", json!("alice"))])
+            .build();
+        aql.map_collections(|name| format!("tenant1_{}", name));
+        let body = serde_json::to_value(&aql).unwrap();
+        assert_eq!(body["bindVars"]["@users"], "tenant1_users");
+        assert_eq!(body["bindVars"]["name"], "alice");
+    }
+}
@@ -0,0 +1,199 @@
+//! Validation of database, collection and View names
+//!
+//! ArangoDB has two naming conventions:
+//!
+//! - the traditional one, only ASCII letters, digits, `_` and `-`, starting
+//!   with a letter,
+//! - the extended one of ArangoDB 3.9 and later, enabled on the server with
+//!   `--database.extended-names`, allowing most UTF-8 characters.
+//!
+//! A name breaking the rules is rejected by the server with a `400` and an
+//! error number only. The functions of this module tell why the name is
+//! invalid before any request is made. The crate checks the names of the
+//! databases, collections and Views it creates against the extended
+//! convention, which accepts every traditional name, since it does not know
+//! the setting of the server.
+//!
+//! Extended names must be in Unicode NFC form, [`normalize_name`] converts a
+//! name to it.
+//!
+//! # Example
+//! ```rust
+//! use arangors::naming::{validate_collection_name, NamingConvention};
+//!
+//! assert!(validate_collection_name("users", NamingConvention::Traditional).is_ok());
+//! assert!(validate_collection_name("utilisateurs_été", NamingConvention::Traditional).is_err());
+//! assert!(validate_collection_name("utilisateurs_été", NamingConvention::Extended).is_ok());
+//! ```
+use std::borrow::Cow;
+
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+use crate::ClientError;
+
+/// Naming convention of the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NamingConvention {
+    /// ASCII letters, digits, `_` and `-`, the default of the server.
+    #[default]
+    Traditional,
+    /// UTF-8 names, with `--database.extended-names` on ArangoDB 3.9 and
+    /// later.
+    Extended,
+}
+
+/// Maximum length in bytes of a traditional database name.
+const MAX_TRADITIONAL_DATABASE_NAME: usize = 64;
+/// Maximum length in bytes of an extended database name.
+const MAX_EXTENDED_DATABASE_NAME: usize = 128;
+/// Maximum length in bytes of a collection or View name.
+const MAX_NAME: usize = 256;
+
+/// Check the name of a database.
+///
+/// `_system` is the only database name starting with `_`.
+pub fn validate_database_name(name: &str, convention: NamingConvention) -> Result<(), ClientError> {
+    if name == "_system" {
+        return Ok(());
+    }
+    let max = match convention {
+        NamingConvention::Traditional => MAX_TRADITIONAL_DATABASE_NAME,
+        NamingConvention::Extended => MAX_EXTENDED_DATABASE_NAME,
+    };
+    check(name, convention, max, false, &[':']).map_err(|reason| invalid("database", name, reason))
+}
+
+/// Check the name of a collection.
+///
+/// Names starting with `_` are the ones of system collections.
+pub fn validate_collection_name(
+    name: &str,
+    convention: NamingConvention,
+) -> Result<(), ClientError> {
+    check(name, convention, MAX_NAME, true, &[])
+        .map_err(|reason| invalid("collection", name, reason))
+}
+
+/// Check the name of a View.
+pub fn validate_view_name(name: &str, convention: NamingConvention) -> Result<(), ClientError> {
+    check(name, convention, MAX_NAME, false, &[]).map_err(|reason| invalid("View", name, reason))
+}
+
+/// The Unicode NFC form of a name, which extended names must be in.
+///
+/// The name is borrowed when already normalized.
+pub fn normalize_name(name: &str) -> Cow<'_, str> {
+    if is_nfc(name) {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(name.nfc().collect())
+    }
+}
+
+fn invalid(kind: &str, name: &str, reason: String) -> ClientError {
+    ClientError::InvalidArgument(format!("invalid {} name {:?}: {}", kind, name, reason))
+}
+
+/// The reason why `name` is invalid, if it is.
+///
+/// `forbidden` are the characters only forbidden for this kind of object in
+/// extended names, `/` and control characters always are.
+fn check(
+    name: &str,
+    convention: NamingConvention,
+    max: usize,
+    system: bool,
+    forbidden: &[char],
+) -> Result<(), String> {
+    let first = name.chars().next().ok_or_else(|| "empty".to_owned())?;
+    if name.len() > max {
+        return Err(format!("longer than {} bytes", max));
+    }
+    if first == '_' && !system {
+        return Err("starts with `_`".to_owned());
+    }
+
+    match convention {
+        NamingConvention::Traditional => {
+            if !(first.is_ascii_alphabetic() || first == '_') {
+                return Err("does not start with a letter".to_owned());
+            }
+            match name
+                .chars()
+                .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-'))
+            {
+                Some(c) => Err(format!(
+                    "contains {:?}, only ASCII letters, digits, `_` and `-` are allowed",
+                    c
+                )),
+                None => Ok(()),
+            }
+        }
+        NamingConvention::Extended => {
+            if first.is_ascii_digit() {
+                return Err("starts with a digit".to_owned());
+            }
+            if name.starts_with(char::is_whitespace) || name.ends_with(char::is_whitespace) {
+                return Err("starts or ends with a space".to_owned());
+            }
+            if let Some(c) = name
+                .chars()
+                .find(|c| *c == '/' || c.is_control() || forbidden.contains(c))
+            {
+                return Err(format!("contains {:?}", c));
+            }
+            if !is_nfc(name) {
+                return Err("not in Unicode NFC form, see `normalize_name`".to_owned());
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn traditional_names() {
+        let convention = NamingConvention::Traditional;
+        assert!(validate_database_name("test_db-2", convention).is_ok());
+        assert!(validate_database_name("_system", convention).is_ok());
+        assert!(validate_database_name("_other", convention).is_err());
+        assert!(validate_database_name("2db", convention).is_err());
+        assert!(validate_database_name(&"d".repeat(65), convention).is_err());
+        assert!(validate_database_name("", convention).is_err());
+
+        assert!(validate_collection_name("_users", convention).is_ok());
+        assert!(validate_collection_name("a.b", convention).is_err());
+        assert!(validate_view_name("_view", convention).is_err());
+
+        let err = validate_collection_name("my collection", convention).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid argument: invalid collection name \"my collection\": contains ' ', only \
+             ASCII letters, digits, `_` and `-` are allowed"
+        );
+    }
+
+    #[test]
+    fn extended_names() {
+        let convention = NamingConvention::Extended;
+        assert!(validate_database_name("base de données", convention).is_ok());
+        assert!(validate_database_name(&"d".repeat(128), convention).is_ok());
+        assert!(validate_database_name("a:b", convention).is_err());
+        assert!(validate_collection_name("a:b", convention).is_ok());
+        assert!(validate_collection_name("a/b", convention).is_err());
+        assert!(validate_collection_name("a\tb", convention).is_err());
+        assert!(validate_collection_name(" a", convention).is_err());
+        assert!(validate_collection_name("1a", convention).is_err());
+        assert!(validate_view_name("vue", convention).is_ok());
+
+        let decomposed = "cafe\u{301}";
+        assert!(validate_collection_name(decomposed, convention).is_err());
+        let normalized = normalize_name(decomposed);
+        assert_eq!(normalized, "caf\u{e9}");
+        assert!(validate_collection_name(&normalized, convention).is_ok());
+        assert!(matches!(normalize_name("users"), Cow::Borrowed(_)));
+    }
+}
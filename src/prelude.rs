@@ -0,0 +1,36 @@
+//! Commonly used types and traits
+//!
+//! Import them all at once instead of through their modules:
+//!
+//! ```rust
+//! use arangors::prelude::*;
+//! ```
+#[cfg(any(
+    feature = "reqwest_async",
+    feature = "reqwest_blocking",
+    feature = "surf_async"
+))]
+pub use crate::connection::Connection;
+pub use crate::{
+    analyzer::{AnalyzerDescription, AnalyzerInfo},
+    aql::{AqlOptions, AqlQuery, Cursor, QueryContext},
+    client::{ClientExt, RequestExt},
+    collection::{
+        options::{CreateOptions, CreateParameters},
+        response::{Info, Properties, Status},
+        Collection, CollectionType,
+    },
+    connection::GenericConnection,
+    database::Database,
+    document::{
+        options::{
+            InsertOptions, OverwriteMode, ReadOptions, RemoveOptions, ReplaceOptions, UpdateOptions,
+        },
+        response::DocumentResponse,
+        Document, Header,
+    },
+    error::{ArangoError, ClientError},
+    graph::{EdgeDefinition, Graph},
+    index::{Index, IndexSettings},
+    view::{View, ViewDescription, ViewOptions},
+};
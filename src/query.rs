@@ -1 +1,115 @@
+//! Query string encoding of options
+//!
+//! Options sent as URL query parameters are flat structs of optional
+//! scalars. [`to_string`] encodes their fields in key order, skipping
+//! `None`, with booleans as `true`/`false` and unit enum variants by their
+//! serialized name. Nested values can not be expressed as a query parameter
+//! of the server and are rejected with `ClientError::InvalidArgument`
+//! instead of being mis-encoded.
+use serde::Serialize;
+use serde_json::Value;
+use url::form_urlencoded;
 
+use crate::ClientError;
+
+/// Encode `options` as a URL query string.
+pub(crate) fn to_string<T: Serialize>(options: &T) -> Result<String, ClientError> {
+    let fields = match serde_json::to_value(options)? {
+        Value::Object(fields) => fields,
+        Value::Null => return Ok(String::new()),
+        _ => {
+            return Err(ClientError::InvalidArgument(
+                "query parameters must be a struct or a map of scalars".to_owned(),
+            ))
+        }
+    };
+    let mut query = form_urlencoded::Serializer::new(String::new());
+    for (name, value) in fields {
+        let value = match value {
+            Value::Null => continue,
+            Value::Bool(b) => b.to_string(),
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => s,
+            Value::Array(_) | Value::Object(_) => {
+                return Err(ClientError::InvalidArgument(format!(
+                    "query parameter {} must be a scalar",
+                    name
+                )))
+            }
+        };
+        query.append_pair(&name, &value);
+    }
+    Ok(query.finish())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    enum Mode {
+        Ignore,
+        Update,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Options {
+        wait_for_sync: Option<bool>,
+        return_new: Option<bool>,
+        overwrite_mode: Option<Mode>,
+        batch_size: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        version: Option<String>,
+    }
+
+    #[test]
+    fn encode_options() {
+        let options = Options {
+            wait_for_sync: Some(false),
+            return_new: None,
+            overwrite_mode: Some(Mode::Update),
+            batch_size: Some(100),
+            version: Some("3.11 & up".to_owned()),
+        };
+        assert_eq!(
+            to_string(&options).unwrap(),
+            "batchSize=100&overwriteMode=update&version=3.11+%26+up&waitForSync=false"
+        );
+
+        let options = Options {
+            wait_for_sync: None,
+            return_new: Some(true),
+            overwrite_mode: Some(Mode::Ignore),
+            batch_size: None,
+            version: None,
+        };
+        assert_eq!(
+            to_string(&options).unwrap(),
+            "overwriteMode=ignore&returnNew=true"
+        );
+
+        let map: BTreeMap<&str, bool> = [("silent", true)].into_iter().collect();
+        assert_eq!(to_string(&map).unwrap(), "silent=true");
+        assert_eq!(to_string(&()).unwrap(), "");
+    }
+
+    #[test]
+    fn reject_nested_options() {
+        #[derive(Serialize)]
+        struct Nested {
+            keys: Vec<u32>,
+        }
+        let err = to_string(&Nested { keys: vec![1] }).unwrap_err();
+        assert!(matches!(err, ClientError::InvalidArgument(msg) if msg.contains("keys")));
+        assert!(matches!(
+            to_string(&true),
+            Err(ClientError::InvalidArgument(_))
+        ));
+    }
+}
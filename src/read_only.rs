@@ -0,0 +1,655 @@
+//! Read-only handles on databases and collections
+//!
+//! A [`GenericConnection`](crate::GenericConnection) in the
+//! [`ReadOnly`](crate::connection::role::ReadOnly) role, obtained with
+//! `into_read_only()`, only hands out [`ReadOnlyDatabase`] and
+//! [`ReadOnlyCollection`]. They expose reads of documents, collections and
+//! AQL queries, so that code holding them can not call a write operation.
+//!
+//! AQL queries are explained before they run, and data-modification queries
+//! are rejected with `ClientError::InsufficientPermission`. This costs a
+//! request per query. Connect as a user with read-only permission to have
+//! writes rejected by the server as well.
+use std::time::Duration;
+
+use maybe_async::maybe_async;
+use serde::{de::DeserializeOwned, Serialize};
+use url::Url;
+
+use crate::{
+    aql::{AqlQuery, Cursor, PartialResults, PreparedQuery, QueryContext},
+    client::ClientExt,
+    collection::{
+        options::ChecksumOptions,
+        response::{Checksum, DocumentsByKeys, Info, Properties, Revision, Statistics, Status},
+        CollectionType,
+    },
+    connection::{features::ServerFeatures, Permission},
+    database::{bind_values, DatabaseDetails},
+    document::{options::ReadOptions, Document, Header},
+    search::{Scored, SearchQuery},
+    ClientError, Collection, Database, DecodedItems, ResponseMeta,
+};
+
+/// Read-only view of a [`Database`].
+#[derive(Debug, Clone)]
+pub struct ReadOnlyDatabase<C: ClientExt> {
+    db: Database<C>,
+}
+
+impl<C: ClientExt> ReadOnlyDatabase<C> {
+    pub(crate) fn new(db: Database<C>) -> Self {
+        ReadOnlyDatabase { db }
+    }
+
+    pub fn url(&self) -> &Url {
+        self.db.url()
+    }
+
+    pub fn name(&self) -> &str {
+        self.db.name()
+    }
+
+    /// Get information of current database.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn info(&self) -> Result<DatabaseDetails, ClientError> {
+        self.db.info().await
+    }
+
+    /// Version and storage engine of the server, see
+    /// [`Database::server_features`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn server_features(&self) -> Result<ServerFeatures, ClientError> {
+        self.db.server_features().await
+    }
+
+    /// Retrieve all collections of this database.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn accessible_collections(&self) -> Result<Vec<Info>, ClientError> {
+        self.db.accessible_collections().await
+    }
+
+    /// Retrieve all collections of this database, with the collections
+    /// which could not be decoded, see
+    /// [`Database::accessible_collections_decoded`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn accessible_collections_decoded(&self) -> Result<DecodedItems<Info>, ClientError> {
+        self.db.accessible_collections_decoded().await
+    }
+
+    /// Get read-only collection object with name.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn collection(&self, name: &str) -> Result<ReadOnlyCollection<C>, ClientError> {
+        self.db.collection(name).await.map(ReadOnlyCollection::new)
+    }
+
+    /// Execute aql query, return a cursor if succeed, see
+    /// [`Database::aql_query_batch`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_query_batch<R>(&self, aql: AqlQuery<'_>) -> Result<Cursor<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.check_read_only(&aql).await?;
+        self.db.aql_query_batch(aql).await
+    }
+
+    /// Execute aql query, return a cursor along with the metadata of the
+    /// response, see [`Database::aql_query_batch_with_meta`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_query_batch_with_meta<R>(
+        &self,
+        aql: AqlQuery<'_>,
+    ) -> Result<(Cursor<R>, ResponseMeta), ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.check_read_only(&aql).await?;
+        self.db.aql_query_batch_with_meta(aql).await
+    }
+
+    /// Get next batch given the cursor id.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_next_batch<R>(&self, cursor_id: &str) -> Result<Cursor<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.db.aql_next_batch(cursor_id).await
+    }
+
+    /// Get next batch given the cursor id with the context of the query, see
+    /// [`Database::aql_next_batch_with`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_next_batch_with<R>(
+        &self,
+        cursor_id: &str,
+        context: &QueryContext,
+    ) -> Result<Cursor<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.db.aql_next_batch_with(cursor_id, context).await
+    }
+
+    /// Execute AQL query and fetch its batches until `budget` is exhausted,
+    /// see [`Database::aql_query_within`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_query_within<R>(
+        &self,
+        aql: AqlQuery<'_>,
+        budget: Duration,
+    ) -> Result<PartialResults<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.check_read_only(&aql).await?;
+        self.db.aql_query_within(aql, budget).await
+    }
+
+    /// Continue fetching the batches of a cursor until `budget` is
+    /// exhausted, see [`Database::aql_next_batches_within`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_next_batches_within<R>(
+        &self,
+        cursor_id: &str,
+        context: &QueryContext,
+        budget: Duration,
+    ) -> Result<PartialResults<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.db
+            .aql_next_batches_within(cursor_id, context, budget)
+            .await
+    }
+
+    /// Execute AQL query fetch all results, see [`Database::aql_query`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_query<R>(&self, aql: AqlQuery<'_>) -> Result<Vec<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.check_read_only(&aql).await?;
+        self.db.aql_query(aql).await
+    }
+
+    /// Similar to `aql_query`, except that this method only accept a string of
+    /// AQL query.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_str<R>(&self, query: &str) -> Result<Vec<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        let aql = AqlQuery::builder().query(query).build();
+        self.aql_query(aql).await
+    }
+
+    /// Similar to `aql_query`, except that this method only accept a string of
+    /// AQL query, with additional bind vars.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_bind_vars<R>(
+        &self,
+        query: &str,
+        bind_vars: impl IntoIterator<Item = (impl Into<String>, impl Serialize)>,
+    ) -> Result<Vec<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        let aql = AqlQuery::builder()
+            .query(query)
+            .bind_vars(bind_values(bind_vars)?)
+            .build();
+        self.aql_query(aql).await
+    }
+
+    /// Run a prepared query with `bind_vars`, see
+    /// [`Database::aql_prepared`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_prepared<R>(
+        &self,
+        prepared: &PreparedQuery<'_>,
+        bind_vars: impl IntoIterator<Item = (impl Into<String>, impl Serialize)>,
+    ) -> Result<Vec<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.aql_query(prepared.bind(bind_values(bind_vars)?)).await
+    }
+
+    /// Fail with `ClientError::InsufficientPermission` when the plan of `aql`
+    /// modifies data.
+    #[maybe_async]
+    async fn check_read_only(&self, aql: &AqlQuery<'_>) -> Result<(), ClientError> {
+        let explained = self.db.explain_query(aql.clone()).await?;
+        let modifies = explained.plan.is_modification_query
+            || explained.plan.nodes.iter().any(|node| {
+                matches!(
+                    node.node_type.as_str(),
+                    "InsertNode" | "UpdateNode" | "ReplaceNode" | "RemoveNode" | "UpsertNode"
+                )
+            });
+        if modifies {
+            return Err(ClientError::InsufficientPermission {
+                permission: Permission::ReadOnly,
+                operation: "run a data-modification AQL query".to_owned(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Search an ArangoSearch view and return the matching documents with
+    /// their relevance score, see [`Database::search`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn search<T>(&self, query: &SearchQuery) -> Result<Vec<Scored<T>>, ClientError>
+    where
+        T: DeserializeOwned,
+    {
+        self.db.search(query).await
+    }
+}
+
+/// Read-only view of a [`Collection`].
+#[derive(Debug, Clone)]
+pub struct ReadOnlyCollection<C: ClientExt> {
+    collection: Collection<C>,
+}
+
+impl<C: ClientExt> ReadOnlyCollection<C> {
+    fn new(collection: Collection<C>) -> Self {
+        ReadOnlyCollection { collection }
+    }
+
+    pub fn collection_type(&self) -> CollectionType {
+        self.collection.collection_type()
+    }
+
+    /// The collection identifier
+    pub fn id(&self) -> &str {
+        self.collection.id()
+    }
+
+    /// The collection name
+    pub fn name(&self) -> &str {
+        self.collection.name()
+    }
+
+    /// The globally unique identifier of the collection
+    pub fn globally_unique_id(&self) -> &str {
+        self.collection.globally_unique_id()
+    }
+
+    /// The status of the collection when the handle was obtained or last
+    /// reloaded
+    pub fn status(&self) -> Status {
+        self.collection.status()
+    }
+
+    /// Fetch the information of the collection and update the handle with
+    /// it.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn reload(&mut self) -> Result<Info, ClientError> {
+        self.collection.reload().await
+    }
+
+    /// Collection url: http://server:port/_db/mydb/_api/collection/{collection-name}
+    pub fn url(&self) -> &Url {
+        self.collection.url()
+    }
+
+    /// Fetch the properties of collection
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn properties(&self) -> Result<Properties, ClientError> {
+        self.collection.properties().await
+    }
+
+    /// Count the documents in this collection
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_count(&self) -> Result<Properties, ClientError> {
+        self.collection.document_count().await
+    }
+
+    /// Fetch the statistics of a collection
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn statistics(&self) -> Result<Statistics, ClientError> {
+        self.collection.statistics().await
+    }
+
+    /// Fetch the statistics of a collection with the detailed figures of the
+    /// storage engine
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn detailed_statistics(&self) -> Result<Statistics, ClientError> {
+        self.collection.detailed_statistics().await
+    }
+
+    /// Retrieve the collection revision id
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn revision_id(&self) -> Result<Revision, ClientError> {
+        self.collection.revision_id().await
+    }
+
+    /// Fetch a checksum for the specified collection
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn checksum(&self) -> Result<Checksum, ClientError> {
+        self.collection.checksum().await
+    }
+
+    /// Fetch a checksum for the specified collection with options
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn checksum_with_options(
+        &self,
+        options: ChecksumOptions,
+    ) -> Result<Checksum, ClientError> {
+        self.collection.checksum_with_options(options).await
+    }
+
+    /// Read a single document with `_key`
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document<T>(&self, _key: &str) -> Result<Document<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        self.collection.document(_key).await
+    }
+
+    /// Read a single document with options
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_with_options<T>(
+        &self,
+        _key: &str,
+        read_options: ReadOptions,
+    ) -> Result<Document<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        self.collection
+            .document_with_options(_key, read_options)
+            .await
+    }
+
+    /// Read a single document with options, along with the metadata of the
+    /// response, see [`Collection::document_with_meta`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_with_meta<T>(
+        &self,
+        _key: &str,
+        read_options: ReadOptions,
+    ) -> Result<(Document<T>, ResponseMeta), ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        self.collection.document_with_meta(_key, read_options).await
+    }
+
+    /// Read the given attributes of a single document, see
+    /// [`Collection::document_with_projection`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_with_projection<T, A>(
+        &self,
+        _key: &str,
+        attributes: &[A],
+    ) -> Result<Document<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+        A: AsRef<str>,
+    {
+        self.collection
+            .document_with_projection(_key, attributes)
+            .await
+    }
+
+    /// Read the documents with the given `_key`s in one request, see
+    /// [`Collection::documents_by_keys`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn documents_by_keys<T, K>(
+        &self,
+        keys: &[K],
+    ) -> Result<DocumentsByKeys<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+        K: AsRef<str>,
+    {
+        self.collection.documents_by_keys(keys).await
+    }
+
+    /// Read the given attributes of the documents with the given `_key`s,
+    /// see [`Collection::documents_by_keys_with_projection`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn documents_by_keys_with_projection<T, K, A>(
+        &self,
+        keys: &[K],
+        attributes: &[A],
+    ) -> Result<DocumentsByKeys<T>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+        K: AsRef<str>,
+        A: AsRef<str>,
+    {
+        self.collection
+            .documents_by_keys_with_projection(keys, attributes)
+            .await
+    }
+
+    /// Read all documents matching `example`, see
+    /// [`Collection::documents_by_example`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn documents_by_example<T, E>(
+        &self,
+        example: E,
+    ) -> Result<Vec<Document<T>>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+        E: Serialize,
+    {
+        self.collection.documents_by_example(example).await
+    }
+
+    /// Read the given attributes of all documents matching `example`, see
+    /// [`Collection::documents_by_example_with_projection`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn documents_by_example_with_projection<T, E, A>(
+        &self,
+        example: E,
+        attributes: &[A],
+    ) -> Result<Vec<Document<T>>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+        E: Serialize,
+        A: AsRef<str>,
+    {
+        self.collection
+            .documents_by_example_with_projection(example, attributes)
+            .await
+    }
+
+    /// Read a random document, or `None` if the collection is empty, see
+    /// [`Collection::random_document`].
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn random_document<T>(&self) -> Result<Option<Document<T>>, ClientError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        self.collection.random_document().await
+    }
+
+    /// Read a single document header
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_header(&self, _key: &str) -> Result<Header, ClientError> {
+        self.collection.document_header(_key).await
+    }
+
+    /// Read a single document header with options
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn document_header_with_options(
+        &self,
+        _key: &str,
+        read_options: ReadOptions,
+    ) -> Result<Header, ClientError> {
+        self.collection
+            .document_header_with_options(_key, read_options)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use serde_json::Value;
+
+    use super::*;
+    use crate::client::mock::RecordingClient;
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn reject_modification_queries() {
+        let session = Arc::new(RecordingClient::default());
+        let arango_url: Url = "http://localhost:8529/".parse().unwrap();
+        let db = ReadOnlyDatabase::new(Database::new("test_db", &arango_url, Arc::clone(&session)));
+
+        session.respond(
+            200,
+            r#"{"plan":{"nodes":[{"type":"SingletonNode","id":1},
+                {"type":"InsertNode","id":2,"collection":"users"}],
+                "isModificationQuery":true},"cacheable":false,"error":false,"code":200}"#,
+        );
+        let result = db
+            .aql_str::<Value>(r#"INSERT { name: "a" } INTO users"#)
+            .await;
+        assert!(matches!(
+            result,
+            Err(ClientError::InsufficientPermission {
+                permission: Permission::ReadOnly,
+                ..
+            })
+        ));
+        let requests = session.take_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].uri().path(), "/_db/test_db/_api/explain");
+
+        session.respond(
+            200,
+            r#"{"plan":{"nodes":[{"type":"SingletonNode","id":1},
+                {"type":"EnumerateCollectionNode","id":2,"collection":"users"}],
+                "isModificationQuery":false},"cacheable":true,"error":false,"code":200}"#,
+        );
+        session.respond(
+            201,
+            r#"{"result":[1],"hasMore":false,"cached":false,"error":false,"code":201}"#,
+        );
+        let results: Vec<u32> = db.aql_str("FOR u IN users RETURN 1").await.unwrap();
+        assert_eq!(results, vec![1]);
+        let paths: Vec<String> = session
+            .take_requests()
+            .iter()
+            .map(|request| request.uri().path().to_owned())
+            .collect();
+        assert_eq!(
+            paths,
+            ["/_db/test_db/_api/explain", "/_db/test_db/_api/cursor"]
+        );
+    }
+}
@@ -6,15 +6,19 @@
 //! status and data of concerns.
 //!
 //! For response storing all information in `result` filed, use
-//! `ArangoResult`.
-use std::ops::Deref;
+//! [`ResponseEnvelope`], which also keeps the `code` of the body, and the
+//! other fields on request.
+use std::{ops::Deref, time::Duration};
 
+use http::header::{HeaderMap, CONTENT_TYPE, ETAG, SERVER};
 use log::trace;
 use serde::{
     de::{self, DeserializeOwned, Deserializer},
     Deserialize,
 };
-use serde_json::value::Value;
+use serde_json::{error::Category, value::Value, Map};
+#[cfg(feature = "path_to_error")]
+use serde_path_to_error::Segment;
 
 use crate::{ArangoError, ClientError};
 
@@ -25,14 +29,263 @@ use crate::{ArangoError, ClientError};
 /// response of success and failure.
 ///
 /// When ArangoDB server response error code, then an error would be cast.
-pub(crate) fn deserialize_response<T>(text: &str) -> Result<T, ClientError>
+///
+/// When the body is not JSON at all, e.g. an HTML error page of a proxy,
+/// `ClientError::UnexpectedResponse` is returned instead of a serde error.
+/// When the JSON does not match `T`, `ClientError::Deserialize` tells where.
+pub(crate) fn deserialize_response<T>(resp: &http::Response<String>) -> Result<T, ClientError>
+where
+    T: DeserializeOwned,
+{
+    let response: Response<T> =
+        serde_json::from_str(resp.body()).map_err(|e| unexpected_response::<T>(resp, e))?;
+    Ok(Into::<Result<T, ArangoError>>::into(response)?)
+}
+
+/// Parse the body of a response as JSON, see [`deserialize_response`].
+pub(crate) fn response_value(resp: &http::Response<String>) -> Result<Value, ClientError> {
+    serde_json::from_str(resp.body()).map_err(|e| unexpected_response::<Value>(resp, e))
+}
+
+/// Method and url of a request, kept in the extensions of its response by the
+/// HTTP clients to tell in errors where a body came from.
+#[derive(Debug, Clone)]
+pub(crate) struct Endpoint(pub(crate) String);
+
+impl Endpoint {
+    pub(crate) fn of<B>(request: &http::Request<B>) -> Self {
+        Endpoint(format!("{} {}", request.method(), request.uri()))
+    }
+}
+
+/// Metadata of a response, read from its headers.
+///
+/// Obtained from the `*_with_meta` variants of reads, to debug data
+/// placement and staleness in clusters and active failover deployments. The
+/// headers starting with `x-arango-` are kept, along with the `ETag` and the
+/// `Server` header.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseMeta {
+    headers: HeaderMap,
+}
+
+impl ResponseMeta {
+    pub(crate) fn of(resp: &http::Response<String>) -> Self {
+        let headers = resp
+            .headers()
+            .iter()
+            .filter(|(name, _)| {
+                name.as_str().starts_with("x-arango-") || *name == ETAG || *name == SERVER
+            })
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        ResponseMeta { headers }
+    }
+
+    /// The kept headers.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The value of a kept header, e.g. `x-arango-queue-time-seconds`.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    /// Whether the response came from a follower and may be outdated, see
+    /// `QueryContext::allow_dirty_read`.
+    pub fn potential_dirty_read(&self) -> bool {
+        self.header("x-arango-potential-dirty-read") == Some("true")
+    }
+
+    /// Time the request spent in the queue of the server before being
+    /// processed.
+    pub fn queue_time(&self) -> Option<Duration> {
+        self.header("x-arango-queue-time-seconds")
+            .and_then(|v| v.parse::<f64>().ok())
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+    }
+
+    /// Leader advertised by a follower of an active failover deployment.
+    pub fn endpoint(&self) -> Option<&str> {
+        self.header("x-arango-endpoint")
+    }
+
+    /// Revision of the document, a hybrid logical clock value of its last
+    /// write.
+    pub fn revision(&self) -> Option<&str> {
+        self.header(ETAG.as_str()).map(|v| v.trim_matches('"'))
+    }
+
+    /// Server software, `ArangoDB` unless a proxy rewrote it.
+    pub fn server(&self) -> Option<&str> {
+        self.header(SERVER.as_str())
+    }
+}
+
+/// Maximum number of characters of the body kept in
+/// `ClientError::UnexpectedResponse`
+const BODY_SNIPPET_LEN: usize = 256;
+
+/// `ClientError::UnexpectedResponse` with the status and the start of the body
+/// of `resp`, for a response missing what the request should have returned.
+pub(crate) fn unexpected(resp: &http::Response<String>) -> ClientError {
+    ClientError::UnexpectedResponse {
+        status: resp.status().as_u16(),
+        body_snippet: resp.body().chars().take(BODY_SNIPPET_LEN).collect(),
+    }
+}
+
+/// Turn a failure to parse the body into `ClientError::UnexpectedResponse`
+/// when the body is not JSON, and into `ClientError::Deserialize` when the
+/// JSON does not match the expected type.
+fn unexpected_response<T>(resp: &http::Response<String>, err: serde_json::Error) -> ClientError
+where
+    T: DeserializeOwned,
+{
+    let not_json = resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| !v.contains("json"));
+    if not_json || matches!(err.classify(), Category::Syntax | Category::Eof) {
+        unexpected(resp)
+    } else {
+        match serde_json::from_str::<Value>(resp.body()) {
+            Ok(value) => deserialize_error::<T>(&value, resp.extensions().get(), err),
+            Err(_) => err.into(),
+        }
+    }
+}
+
+/// Same as [`deserialize_response`], but from an already parsed JSON value.
+pub(crate) fn deserialize_response_value<T>(value: Value) -> Result<T, ClientError>
 where
     T: DeserializeOwned,
 {
-    let response: Response<T> = serde_json::from_str(text)?;
+    let response: Response<T> =
+        Response::deserialize(&value).map_err(|e| deserialize_error::<T>(&value, None, e))?;
     Ok(Into::<Result<T, ArangoError>>::into(response)?)
 }
 
+/// Items of a listing decoded one by one, so that an item not matching the
+/// expected type, e.g. with a field of a newer server version, does not fail
+/// the whole listing.
+#[derive(Debug)]
+pub struct DecodedItems<T> {
+    /// The items decoded, in the order of the listing
+    pub items: Vec<T>,
+    /// The items which could not be decoded
+    pub errors: Vec<ItemDecodeError>,
+}
+
+impl<T> DecodedItems<T> {
+    /// Whether all items were decoded.
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The items, or the error of the first item which could not be
+    /// decoded.
+    pub fn into_result(self) -> Result<Vec<T>, ClientError> {
+        match self.errors.into_iter().next() {
+            Some(err) => Err(err.error),
+            None => Ok(self.items),
+        }
+    }
+}
+
+/// An item of a listing which could not be decoded.
+#[derive(Debug)]
+pub struct ItemDecodeError {
+    /// Position of the item in the listing
+    pub index: usize,
+    /// The `name` field of the item, when it has one
+    pub name: Option<String>,
+    /// `ClientError::Deserialize` telling where the item does not match
+    pub error: ClientError,
+}
+
+/// Decode the items of the `result` array of a response one by one, see
+/// [`DecodedItems`].
+pub(crate) fn deserialize_items<T>(
+    resp: &http::Response<String>,
+) -> Result<DecodedItems<T>, ClientError>
+where
+    T: DeserializeOwned,
+{
+    let values: ResponseEnvelope<Vec<Value>> = deserialize_response(resp)?;
+    let endpoint = resp.extensions().get::<Endpoint>();
+    let mut decoded = DecodedItems {
+        items: Vec::new(),
+        errors: Vec::new(),
+    };
+    for (index, value) in values.into_result().into_iter().enumerate() {
+        match T::deserialize(&value) {
+            Ok(item) => decoded.items.push(item),
+            Err(err) => decoded.errors.push(ItemDecodeError {
+                index,
+                name: value.get("name").and_then(Value::as_str).map(str::to_owned),
+                error: deserialize_error::<T>(&value, endpoint, err),
+            }),
+        }
+    }
+    Ok(decoded)
+}
+
+/// Locate the part of `value` not matching `T` and describe it in
+/// `ClientError::Deserialize`.
+fn deserialize_error<T>(
+    value: &Value,
+    endpoint: Option<&Endpoint>,
+    err: serde_json::Error,
+) -> ClientError
+where
+    T: DeserializeOwned,
+{
+    #[cfg(feature = "path_to_error")]
+    let (path, near) = locate::<T>(value);
+    // without the feature the mismatch is not located
+    #[cfg(not(feature = "path_to_error"))]
+    let (path, near) = ("?".to_owned(), value);
+    ClientError::Deserialize {
+        type_name: std::any::type_name::<T>(),
+        endpoint: endpoint.map_or_else(|| "unknown endpoint".to_owned(), |e| e.0.clone()),
+        path,
+        snippet: near.to_string().chars().take(BODY_SNIPPET_LEN).collect(),
+        source: err,
+    }
+}
+
+/// Path to the part of `value` not matching `T`, like
+/// `result[3].extra.stats.peakMemoryUsage`, and the innermost value found
+/// along it as preview.
+#[cfg(feature = "path_to_error")]
+fn locate<T>(value: &Value) -> (String, &Value)
+where
+    T: DeserializeOwned,
+{
+    let path = match serde_path_to_error::deserialize::<_, T>(value) {
+        Err(e) => e.path().clone(),
+        // the body is fine for `T`, but not an object as `Response` expects
+        Ok(_) => return (".".to_owned(), value),
+    };
+
+    let mut near = value;
+    for segment in path.iter() {
+        let next = match segment {
+            Segment::Seq { index } => near.get(index),
+            Segment::Map { key } => near.get(key),
+            Segment::Enum { .. } | Segment::Unknown => None,
+        };
+        match next {
+            Some(next) => near = next,
+            None => break,
+        }
+    }
+    (path.to_string(), near)
+}
+
 /// An helper enum to divide into successful and failed response
 ///
 /// Request to server can failed at application level, like insufficient
@@ -66,13 +319,14 @@ where
     where
         D: Deserializer<'de>,
     {
-        let map = serde_json::Map::deserialize(deserializer)?;
-        trace!("Deserialize normal Response: {:?}", map);
-        let error = map
+        let rest = Value::deserialize(deserializer)?;
+        trace!("Deserialize normal Response: {:?}", rest);
+        // only objects can be errors, e.g. bulk operations answer with a
+        // bare array
+        let error = rest
             .get("error")
             .map_or_else(|| Ok(false), Deserialize::deserialize)
             .map_err(de::Error::custom)?;
-        let rest = Value::Object(map);
 
         if error {
             ArangoError::deserialize(rest)
@@ -86,21 +340,92 @@ where
     }
 }
 
-/// Helper struct to deserialize json result that store
-/// information in "result" field
-#[derive(Deserialize, Debug)]
-pub(crate) struct ArangoResult<T> {
-    #[serde(rename = "result")]
+/// Body of the responses storing their data in a `result` field, along with
+/// the status fields common to all endpoints.
+///
+/// Most methods return the data only. Use
+/// [`from_response`](ResponseEnvelope::from_response) on the response of a
+/// request sent through `Connection::session()` to read the `code`
+/// uniformly across endpoints, and
+/// [`with_extra`](ResponseEnvelope::with_extra) to read the fields besides
+/// `result` as well, like `hasMore` or `extra`. A body with `error` set to
+/// `true` is returned as `ClientError::Arango` instead.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResponseEnvelope<T> {
+    #[serde(default)]
+    error: bool,
+
+    #[serde(default)]
+    code: Option<u16>,
+
     result: T,
 }
 
-impl<T> ArangoResult<T> {
-    pub fn unwrap(self) -> T {
+impl<T> ResponseEnvelope<T>
+where
+    T: DeserializeOwned,
+{
+    /// Deserialize the body of a response of the server.
+    pub fn from_response(resp: &http::Response<String>) -> Result<Self, ClientError> {
+        deserialize_response(resp)
+    }
+
+    /// Deserialize the body of a response of the server, along with its
+    /// fields besides `error`, `code` and `result`.
+    ///
+    /// The whole body is parsed into a map first, so prefer
+    /// [`from_response`](ResponseEnvelope::from_response) when the other
+    /// fields are not needed.
+    pub fn with_extra(
+        resp: &http::Response<String>,
+    ) -> Result<(Self, Map<String, Value>), ClientError> {
+        let mut extra: Map<String, Value> = deserialize_response(resp)?;
+        let result = extra.remove("result").unwrap_or(Value::Null);
+        let result = T::deserialize(&result).map_err(|e| {
+            // locate the mismatch under `result`, as `from_response` does
+            let mut body = Map::new();
+            body.insert("result".to_owned(), result.clone());
+            deserialize_error::<Self>(&Value::Object(body), resp.extensions().get(), e)
+        })?;
+        let envelope = ResponseEnvelope {
+            error: extra
+                .remove("error")
+                .and_then(|e| e.as_bool())
+                .unwrap_or(false),
+            code: extra
+                .remove("code")
+                .and_then(|code| code.as_u64())
+                .and_then(|code| u16::try_from(code).ok()),
+            result,
+        };
+        Ok((envelope, extra))
+    }
+}
+
+impl<T> ResponseEnvelope<T> {
+    /// The HTTP status code repeated in the body, when the endpoint sends
+    /// it.
+    pub fn code(&self) -> Option<u16> {
+        self.code
+    }
+
+    /// The `error` flag of the body, `false` when it is not sent.
+    pub fn error(&self) -> bool {
+        self.error
+    }
+
+    /// The data of the `result` field.
+    pub fn result(&self) -> &T {
+        &self.result
+    }
+
+    /// Take the data of the `result` field.
+    pub fn into_result(self) -> T {
         self.result
     }
 }
 
-impl<T> Deref for ArangoResult<T> {
+impl<T> Deref for ResponseEnvelope<T> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
         &self.result
@@ -110,6 +435,7 @@ impl<T> Deref for ArangoResult<T> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::error::codes;
 
     #[derive(Debug, Deserialize)]
     pub struct CollectionResponse {
@@ -145,4 +471,212 @@ mod test {
             response
         );
     }
+
+    #[test]
+    fn response_envelope() {
+        let resp = http::Response::builder()
+            .status(201)
+            .header(CONTENT_TYPE, "application/json")
+            .body(
+                "{\"error\":false,\"code\":201,\"result\":[1,2],\"hasMore\":true,\"id\":\"42\"}"
+                    .to_owned(),
+            )
+            .unwrap();
+        let envelope = ResponseEnvelope::<Vec<u8>>::from_response(&resp).unwrap();
+        assert_eq!(envelope.code(), Some(201));
+        assert!(!envelope.error());
+        assert_eq!(envelope.result(), &vec![1, 2]);
+        assert_eq!(envelope.len(), 2);
+        assert_eq!(envelope.into_result(), vec![1, 2]);
+
+        let (envelope, extra) = ResponseEnvelope::<Vec<u8>>::with_extra(&resp).unwrap();
+        assert_eq!(envelope.code(), Some(201));
+        assert_eq!(envelope.result(), &vec![1, 2]);
+        assert_eq!(extra.len(), 2);
+        assert_eq!(extra["hasMore"], Value::Bool(true));
+
+        let mut resp = http::Response::builder()
+            .status(201)
+            .header(CONTENT_TYPE, "application/json")
+            .body("{\"error\":false,\"code\":201,\"result\":[1,\"2\"],\"hasMore\":true}".to_owned())
+            .unwrap();
+        resp.extensions_mut()
+            .insert(Endpoint("PUT http://localhost:8529/_api/cursor".to_owned()));
+        match ResponseEnvelope::<Vec<u8>>::with_extra(&resp) {
+            Err(ClientError::Deserialize { endpoint, path, .. }) => {
+                assert_eq!(endpoint, "PUT http://localhost:8529/_api/cursor");
+                #[cfg(feature = "path_to_error")]
+                assert_eq!(path, "result[1]");
+                #[cfg(not(feature = "path_to_error"))]
+                assert_eq!(path, "?");
+            }
+            other => panic!("should be a deserialize error: {:?}", other),
+        }
+
+        let resp = http::Response::builder()
+            .status(404)
+            .header(CONTENT_TYPE, "application/json")
+            .body(
+                "{\"error\":true,\"code\":404,\"errorNum\":1203,\"errorMessage\":\"not found\"}"
+                    .to_owned(),
+            )
+            .unwrap();
+        let result = ResponseEnvelope::<Vec<u8>>::from_response(&resp);
+        assert!(
+            matches!(result, Err(ClientError::Arango(err)) if err.error_num() == codes::ERROR_ARANGO_DATA_SOURCE_NOT_FOUND)
+        );
+    }
+
+    #[test]
+    fn decoded_items() {
+        let resp = http::Response::builder()
+            .status(200)
+            .header(CONTENT_TYPE, "application/json")
+            .body(
+                "{\"error\":false,\"code\":200,\"result\":[\
+                 {\"id\":\"1\",\"name\":\"a\",\"status\":3,\"type\":2,\"isSystem\":false},\
+                 {\"id\":\"2\",\"name\":\"b\",\"status\":{\"new\":3},\"type\":2,\"isSystem\":false},\
+                 {\"id\":\"3\",\"name\":\"c\",\"status\":3,\"type\":2,\"isSystem\":true}]}"
+                    .to_owned(),
+            )
+            .unwrap();
+        #[derive(Debug, Deserialize)]
+        struct Named {
+            name: String,
+            status: u8,
+        }
+        let decoded = deserialize_items::<Named>(&resp).unwrap();
+        assert!(!decoded.is_complete());
+        let items: Vec<(&str, u8)> = decoded
+            .items
+            .iter()
+            .map(|c| (c.name.as_str(), c.status))
+            .collect();
+        assert_eq!(items, [("a", 3), ("c", 3)]);
+        assert_eq!(decoded.errors.len(), 1);
+        assert_eq!(decoded.errors[0].index, 1);
+        assert_eq!(decoded.errors[0].name.as_deref(), Some("b"));
+        assert!(matches!(
+            decoded.errors[0].error,
+            ClientError::Deserialize { .. }
+        ));
+        assert!(matches!(
+            decoded.into_result(),
+            Err(ClientError::Deserialize { .. })
+        ));
+    }
+
+    #[test]
+    fn unexpected_response() {
+        let resp = http::Response::builder()
+            .status(502)
+            .header(CONTENT_TYPE, "text/html")
+            .body("<html><body>502 Bad Gateway</body></html>".to_owned())
+            .unwrap();
+        let result = deserialize_response::<CollectionResponse>(&resp);
+        match result {
+            Err(ClientError::UnexpectedResponse {
+                status,
+                body_snippet,
+            }) => {
+                assert_eq!(status, 502);
+                assert_eq!(body_snippet, "<html><body>502 Bad Gateway</body></html>");
+            }
+            _ => panic!("should be an unexpected response: {:?}", result),
+        }
+
+        let resp = http::Response::builder()
+            .status(200)
+            .header(CONTENT_TYPE, "application/json")
+            .body("{\"id\":\"9947\"}".to_owned())
+            .unwrap();
+        let result = deserialize_response::<CollectionResponse>(&resp);
+        match result {
+            Err(ClientError::Deserialize {
+                type_name,
+                endpoint,
+                path,
+                snippet,
+                ..
+            }) => {
+                assert!(type_name.ends_with("CollectionResponse"), "{}", type_name);
+                assert_eq!(endpoint, "unknown endpoint");
+                #[cfg(feature = "path_to_error")]
+                assert_eq!(path, ".");
+                #[cfg(not(feature = "path_to_error"))]
+                assert_eq!(path, "?");
+                assert_eq!(snippet, "{\"id\":\"9947\"}");
+            }
+            _ => panic!("should be a deserialize error: {:?}", result),
+        }
+    }
+
+    #[cfg(feature = "path_to_error")]
+    #[test]
+    fn deserialize_error_path() {
+        let mut resp = http::Response::builder()
+            .status(200)
+            .header(CONTENT_TYPE, "application/json")
+            .body(
+                "{\"id\":\"9947\",\"name\":\"relation\",\"status\":\"loaded\",\"type\":3,\
+                 \"isSystem\":false}"
+                    .to_owned(),
+            )
+            .unwrap();
+        resp.extensions_mut().insert(Endpoint(
+            "GET http://localhost:8529/_api/collection/relation".to_owned(),
+        ));
+        let result = deserialize_response::<CollectionResponse>(&resp);
+        match result {
+            Err(ClientError::Deserialize {
+                endpoint,
+                path,
+                snippet,
+                ..
+            }) => {
+                assert_eq!(
+                    endpoint,
+                    "GET http://localhost:8529/_api/collection/relation"
+                );
+                assert_eq!(path, "status");
+                assert_eq!(snippet, "\"loaded\"");
+            }
+            _ => panic!("should be a deserialize error: {:?}", result),
+        }
+
+        let value = serde_json::json!({ "result": [{ "id": "1" }, { "id": 2 }] });
+        let result = deserialize_response_value::<
+            ResponseEnvelope<Vec<std::collections::HashMap<String, String>>>,
+        >(value);
+        match result {
+            Err(ClientError::Deserialize { path, snippet, .. }) => {
+                assert_eq!(path, "result[1].id");
+                assert_eq!(snippet, "2");
+            }
+            _ => panic!("should be a deserialize error: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn response_meta() {
+        let resp = http::Response::builder()
+            .header("x-arango-potential-dirty-read", "true")
+            .header("x-arango-queue-time-seconds", "0.250")
+            .header(ETAG, "\"_hJ7bAyW---\"")
+            .header(SERVER, "ArangoDB")
+            .header(CONTENT_TYPE, "application/json")
+            .body(String::new())
+            .unwrap();
+        let meta = ResponseMeta::of(&resp);
+        assert!(meta.potential_dirty_read());
+        assert_eq!(meta.queue_time(), Some(Duration::from_millis(250)));
+        assert_eq!(meta.revision(), Some("_hJ7bAyW---"));
+        assert_eq!(meta.server(), Some("ArangoDB"));
+        assert_eq!(meta.endpoint(), None);
+        assert_eq!(meta.header("content-type"), None);
+
+        let meta = ResponseMeta::default();
+        assert!(!meta.potential_dirty_read());
+        assert_eq!(meta.queue_time(), None);
+    }
 }
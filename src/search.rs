@@ -0,0 +1,246 @@
+//! Scored searches on ArangoSearch views
+//!
+//! [`SearchQuery`] generates the AQL of a search on a view, ranked by a
+//! relevance [`Scorer`]. `Database::search` runs it and returns each matching
+//! document with its score in a [`Scored`] wrapper.
+//!
+//! # Example
+//! ```rust, ignore
+//! use arangors::search::{Scored, Scorer, SearchQuery};
+//!
+//! let query = SearchQuery::builder()
+//!     .view("articles_view")
+//!     .search(r#"ANALYZER(doc.body IN TOKENS(@terms, "text_en"), "text_en")"#)
+//!     .scorer(Scorer::Tfidf { normalize: true })
+//!     .limit(10)
+//!     .build()
+//!     .bind_var("terms", "graph database");
+//! let hits: Vec<Scored<Article>> = db.search(&query).await?;
+//! for hit in hits {
+//!     println!("{:.3} {}", hit.score, hit.doc.title);
+//! }
+//! ```
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use typed_builder::TypedBuilder;
+
+use crate::{document::projection, ClientError};
+
+/// Relevance scoring function of a search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scorer {
+    /// Okapi BM25, `BM25(doc, k, b)`. The default uses `k = 1.2` and
+    /// `b = 0.75`, like the server. Both must be finite, AQL has no literal
+    /// for `NaN` or infinities.
+    Bm25 { k: f64, b: f64 },
+    /// Term frequency–inverse document frequency, `TFIDF(doc, normalize)`.
+    Tfidf { normalize: bool },
+}
+
+impl Default for Scorer {
+    fn default() -> Self {
+        Scorer::Bm25 { k: 1.2, b: 0.75 }
+    }
+}
+
+impl Scorer {
+    /// Reject parameters that cannot be written as AQL literals.
+    fn check(&self) -> Result<(), ClientError> {
+        match self {
+            Scorer::Bm25 { k, b } if !k.is_finite() || !b.is_finite() => {
+                Err(ClientError::InvalidArgument(format!(
+                    "BM25 parameters must be finite, got k = {} and b = {}",
+                    k, b
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The AQL call scoring the documents bound to `var`.
+    fn aql(&self, var: &str) -> String {
+        match self {
+            Scorer::Bm25 { k, b } => format!("BM25({}, {:?}, {:?})", var, k, b),
+            Scorer::Tfidf { normalize } => format!("TFIDF({}, {})", var, normalize),
+        }
+    }
+}
+
+/// A document found by a search, with its relevance score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scored<T> {
+    pub score: f64,
+    pub doc: T,
+}
+
+/// A search on an ArangoSearch view, sorted by descending score.
+///
+/// The generated query is
+/// ```text
+/// FOR doc IN @@view SEARCH <search> LET score = <scorer> SORT score DESC
+///     LIMIT <offset>, <limit> RETURN { score, doc }
+/// ```
+/// so the search expression refers to the document as `doc`. The bind
+/// parameter `@view` is reserved for the view, and `@attrs` for the
+/// [`projection`](SearchQueryBuilder::projection) which returns
+/// `KEEP(doc, @attrs)` instead of `doc`.
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(doc)]
+pub struct SearchQuery {
+    /// Name of the view to search
+    #[builder(setter(into))]
+    view: String,
+
+    /// AQL search expression on `doc`, e.g.
+    /// `ANALYZER(doc.body IN TOKENS(@terms, "text_en"), "text_en")`
+    #[builder(setter(into))]
+    search: String,
+
+    /// Bind parameters of the search expression
+    #[builder(default, setter(skip))]
+    bind_vars: HashMap<String, Value>,
+
+    #[builder(default)]
+    scorer: Scorer,
+
+    /// Maximum number of documents returned, all matches when not set
+    #[builder(default, setter(strip_option))]
+    limit: Option<u32>,
+
+    /// Number of best matches skipped, for pagination along with `limit`.
+    /// Ignored without `limit`.
+    #[builder(default)]
+    offset: u32,
+
+    /// Attributes of the documents returned, all when not set. The system
+    /// attributes `_id`, `_key` and `_rev` are always kept.
+    #[builder(default, setter(strip_option, into))]
+    projection: Option<Vec<String>>,
+}
+
+impl SearchQuery {
+    /// Bind a parameter of the search expression.
+    pub fn bind_var<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<Value>,
+    {
+        self.bind_vars.insert(key.into(), value.into());
+        self
+    }
+
+    /// The generated AQL query.
+    pub fn aql(&self) -> String {
+        let mut aql = format!(
+            "FOR doc IN @@view SEARCH {} LET score = {} SORT score DESC",
+            self.search,
+            self.scorer.aql("doc")
+        );
+        if let Some(limit) = self.limit {
+            aql.push_str(&format!(" LIMIT {}, {}", self.offset, limit));
+        }
+        if self.projection.is_some() {
+            aql.push_str(" RETURN { score, doc: KEEP(doc, @attrs) }");
+        } else {
+            aql.push_str(" RETURN { score, doc }");
+        }
+        aql
+    }
+
+    /// Fails with `ClientError::InvalidArgument` when the scorer cannot be
+    /// written in AQL.
+    pub(crate) fn check(&self) -> Result<(), ClientError> {
+        self.scorer.check()
+    }
+
+    /// Bind parameters of the generated query, including the view.
+    pub(crate) fn bind_vars(&self) -> Vec<(String, Value)> {
+        let mut bind_vars: Vec<(String, Value)> = self
+            .bind_vars
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        bind_vars.push(("@view".to_owned(), Value::from(self.view.as_str())));
+        if let Some(attributes) = &self.projection {
+            bind_vars.push(("attrs".to_owned(), Value::from(projection(attributes))));
+        }
+        bind_vars
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn search_query_aql() {
+        let query = SearchQuery::builder()
+            .view("articles_view")
+            .search("PHRASE(doc.body, @phrase, \"text_en\")")
+            .limit(10)
+            .offset(20)
+            .build()
+            .bind_var("phrase", "graph database");
+        assert_eq!(
+            query.aql(),
+            "FOR doc IN @@view SEARCH PHRASE(doc.body, @phrase, \"text_en\") LET score = \
+             BM25(doc, 1.2, 0.75) SORT score DESC LIMIT 20, 10 RETURN { score, doc }"
+        );
+        let bind_vars: HashMap<String, Value> = query.bind_vars().into_iter().collect();
+        assert_eq!(bind_vars["@view"], "articles_view");
+        assert_eq!(bind_vars["phrase"], "graph database");
+
+        let query = SearchQuery::builder()
+            .view("articles_view")
+            .search("doc.lang == \"en\"")
+            .scorer(Scorer::Tfidf { normalize: true })
+            .build();
+        assert_eq!(
+            query.aql(),
+            "FOR doc IN @@view SEARCH doc.lang == \"en\" LET score = TFIDF(doc, true) SORT \
+             score DESC RETURN { score, doc }"
+        );
+
+        let query = SearchQuery::builder()
+            .view("articles_view")
+            .search("doc.lang == \"en\"")
+            .projection(vec!["title".to_owned(), "_key".to_owned()])
+            .build();
+        assert_eq!(
+            query.aql(),
+            "FOR doc IN @@view SEARCH doc.lang == \"en\" LET score = BM25(doc, 1.2, 0.75) \
+             SORT score DESC RETURN { score, doc: KEEP(doc, @attrs) }"
+        );
+        let bind_vars: HashMap<String, Value> = query.bind_vars().into_iter().collect();
+        assert_eq!(bind_vars["attrs"], json!(["_id", "_key", "_rev", "title"]));
+
+        let scored: Scored<Value> =
+            serde_json::from_str(r#"{"score":1.5,"doc":{"_key":"1"}}"#).unwrap();
+        assert_eq!(scored.score, 1.5);
+        assert_eq!(scored.doc["_key"], "1");
+    }
+
+    #[test]
+    fn reject_non_finite_bm25() {
+        let query = |k: f64, b: f64| {
+            SearchQuery::builder()
+                .view("articles_view")
+                .search("doc.lang == \"en\"")
+                .scorer(Scorer::Bm25 { k, b })
+                .build()
+        };
+        assert!(query(1.2, 0.75).check().is_ok());
+        assert!(matches!(
+            query(f64::NAN, 0.75).check(),
+            Err(ClientError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            query(1.2, f64::INFINITY).check(),
+            Err(ClientError::InvalidArgument(_))
+        ));
+    }
+}
@@ -0,0 +1,282 @@
+//! Temporary databases and collections for tests
+//!
+//! [`TempDatabase`] creates a database with a unique name and drops it when
+//! the guard is dropped, [`TempCollection`] does the same with a collection,
+//! so that tests running in parallel on one server do not see each other.
+//!
+//! The module is only built with the `testing` feature.
+//!
+//! In blocking mode the database or collection is dropped right away on
+//! drop. In async mode a request cannot be awaited in `Drop`, so calling
+//! [`TempDatabase::drop`] or [`TempCollection::drop`] is mandatory: a guard
+//! dropped without it only queues its database or collection, deleted when
+//! the next temporary one is created on the same server, and the last one
+//! of a test run is left on the server.
+//!
+//! # Example
+//! ```rust, ignore
+//! use arangors::testing::{TempCollection, TempDatabase};
+//!
+//! let conn = Connection::establish_jwt(url, "root", password).await?;
+//! let db = TempDatabase::new(&conn).await?;
+//! let users = TempCollection::new(&db).await?;
+//! users.create_document(doc, Default::default()).await?;
+//! users.drop().await?;
+//! db.drop().await?;
+//! ```
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::SystemTime;
+use std::{
+    ops::Deref,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::UNIX_EPOCH,
+};
+#[cfg(target_arch = "wasm32")]
+use web_time::SystemTime;
+
+use log::trace;
+#[cfg(not(feature = "blocking"))]
+use log::warn;
+use maybe_async::maybe_async;
+use serde_json::Value;
+use url::Url;
+
+use crate::{
+    client::{ClientExt, RequestExt},
+    connection::GenericConnection,
+    response::deserialize_response,
+    ClientError, Collection, Database,
+};
+
+/// Prefix of the names of temporary databases and collections.
+pub const DEFAULT_PREFIX: &str = "arangors_test";
+
+/// Urls of the databases and collections of dropped guards, deleted by
+/// [`delete_pending`] in async mode.
+static PENDING: Mutex<Vec<Url>> = Mutex::new(Vec::new());
+
+/// A name starting with `prefix`, unique in the process and unlikely to be
+/// used by another one.
+pub fn unique_name(prefix: &str) -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    format!(
+        "{}_{}_{}",
+        prefix,
+        nanos,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Delete the databases and collections of the server at `url` queued by
+/// dropped guards, ignoring failures.
+#[maybe_async]
+async fn delete_pending<C: ClientExt>(session: &C, url: &Url) {
+    let urls: Vec<Url> = match PENDING.lock() {
+        Ok(mut pending) => {
+            let (same_server, others) = pending
+                .drain(..)
+                .partition(|pending: &Url| pending.origin() == url.origin());
+            *pending = others;
+            same_server
+        }
+        Err(_) => return,
+    };
+    for url in urls {
+        if let Err(e) = session.delete(url.clone(), "").await {
+            trace!("Failed to delete temporary {}: {}", url, e);
+        }
+    }
+}
+
+/// Delete `url` when a guard is dropped, see the [module](self) docs.
+fn delete_on_drop<C: ClientExt>(_session: &C, url: Url) {
+    trace!("Deleting temporary {}", url);
+    #[cfg(feature = "blocking")]
+    {
+        let _ = _session.delete(url, "");
+    }
+    #[cfg(not(feature = "blocking"))]
+    {
+        warn!(
+            "Temporary {} dropped without `drop().await`, it is deleted with the next one",
+            url
+        );
+        if let Ok(mut pending) = PENDING.lock() {
+            pending.push(url);
+        }
+    }
+}
+
+/// A database with a unique name, dropped with the guard.
+///
+/// `TempDatabase` dereferences to the [`Database`].
+#[derive(Debug)]
+pub struct TempDatabase<C: ClientExt> {
+    database: Database<C>,
+    /// `None` once dropped
+    url: Option<Url>,
+}
+
+impl<C: ClientExt> TempDatabase<C> {
+    /// Create a database named after [`DEFAULT_PREFIX`].
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async]
+    pub async fn new(conn: &GenericConnection<C>) -> Result<Self, ClientError> {
+        Self::with_prefix(conn, DEFAULT_PREFIX).await
+    }
+
+    /// Create a database whose name starts with `prefix`.
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async]
+    pub async fn with_prefix(
+        conn: &GenericConnection<C>,
+        prefix: &str,
+    ) -> Result<Self, ClientError> {
+        delete_pending(conn.session().as_ref(), conn.url()).await;
+
+        let name = unique_name(prefix);
+        let database = conn.create_database(&name).await?;
+        let url = conn
+            .url()
+            .join(&format!("/_api/database/{}", name))
+            .unwrap();
+        Ok(TempDatabase {
+            database,
+            url: Some(url),
+        })
+    }
+
+    /// The database.
+    pub fn database(&self) -> &Database<C> {
+        &self.database
+    }
+
+    /// Drop the database now, and return the error of the server if any.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn drop(mut self) -> Result<(), ClientError> {
+        if let Some(url) = self.url.take() {
+            let resp = self.database.session().delete(url, "").await?;
+            deserialize_response::<Value>(&resp)?;
+        }
+        Ok(())
+    }
+}
+
+impl<C: ClientExt> Deref for TempDatabase<C> {
+    type Target = Database<C>;
+
+    fn deref(&self) -> &Database<C> {
+        &self.database
+    }
+}
+
+impl<C: ClientExt> Drop for TempDatabase<C> {
+    fn drop(&mut self) {
+        if let Some(url) = self.url.take() {
+            delete_on_drop(self.database.session().as_ref(), url);
+        }
+    }
+}
+
+/// A collection with a unique name, dropped with the guard.
+///
+/// `TempCollection` dereferences to the [`Collection`].
+#[derive(Debug)]
+pub struct TempCollection<C: ClientExt> {
+    collection: Collection<C>,
+    /// `None` once dropped
+    url: Option<Url>,
+}
+
+impl<C: ClientExt> TempCollection<C> {
+    /// Create a document collection named after [`DEFAULT_PREFIX`].
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async]
+    pub async fn new(database: &Database<C>) -> Result<Self, ClientError> {
+        Self::with_prefix(database, DEFAULT_PREFIX).await
+    }
+
+    /// Create a document collection whose name starts with `prefix`.
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async]
+    pub async fn with_prefix(database: &Database<C>, prefix: &str) -> Result<Self, ClientError> {
+        delete_pending(database.session().as_ref(), database.url()).await;
+
+        let collection = database.create_collection(&unique_name(prefix)).await?;
+        let url = collection.url().join("").unwrap();
+        Ok(TempCollection {
+            collection,
+            url: Some(url),
+        })
+    }
+
+    /// The collection.
+    pub fn collection(&self) -> &Collection<C> {
+        &self.collection
+    }
+
+    /// Drop the collection now, and return the error of the server if any.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn drop(mut self) -> Result<(), ClientError> {
+        if let Some(url) = self.url.take() {
+            let resp = self.collection.session().delete(url, "").await?;
+            deserialize_response::<Value>(&resp)?;
+        }
+        Ok(())
+    }
+}
+
+impl<C: ClientExt> Deref for TempCollection<C> {
+    type Target = Collection<C>;
+
+    fn deref(&self) -> &Collection<C> {
+        &self.collection
+    }
+}
+
+impl<C: ClientExt> Drop for TempCollection<C> {
+    fn drop(&mut self) {
+        if let Some(url) = self.url.take() {
+            delete_on_drop(self.collection.session().as_ref(), url);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unique_names() {
+        let first = unique_name(DEFAULT_PREFIX);
+        let second = unique_name(DEFAULT_PREFIX);
+        assert_ne!(first, second);
+        assert!(first.starts_with("arangors_test_"));
+        assert!(crate::naming::validate_database_name(
+            &first,
+            crate::naming::NamingConvention::Traditional
+        )
+        .is_ok());
+    }
+}
@@ -1,21 +1,32 @@
+use http::Method;
 use maybe_async::maybe_async;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
-use std::{collections::HashMap, sync::Arc};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::{sync::Arc, time::Duration};
 use typed_builder::TypedBuilder;
 use url::Url;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
 
 use crate::{
-    aql::Cursor,
-    client::ClientExt,
+    aql::{
+        check_warnings, delete_pending_cursors, fetch_within, next_batches_within, Cursor,
+        CursorGuard, PartialResults, QueryContext, WarningPolicy,
+    },
+    client::{ClientExt, RequestExt},
     collection::response::Info,
-    response::{deserialize_response, ArangoResult},
-    AqlQuery, ClientError, Collection,
+    error::codes,
+    graph::GraphHandle,
+    response::{deserialize_response, ResponseEnvelope},
+    util::sleep,
+    AqlQuery, ClientError, Collection, Database,
 };
 
 pub const TRANSACTION_HEADER: &str = "x-arango-trx-id";
 
-#[derive(Debug, Serialize, Deserialize, TypedBuilder)]
+#[derive(Debug, Clone, Serialize, Deserialize, TypedBuilder)]
 #[builder(doc)]
 pub struct TransactionCollections {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -23,9 +34,13 @@ pub struct TransactionCollections {
     read: Option<Vec<String>>,
 
     write: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    exclusive: Option<Vec<String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, TypedBuilder)]
+#[derive(Debug, Clone, Serialize, Deserialize, TypedBuilder)]
 #[serde(rename_all = "camelCase")]
 #[builder(doc)]
 pub struct TransactionSettings {
@@ -47,7 +62,284 @@ pub struct TransactionSettings {
     max_transaction_size: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+/// Options applied to the transactions begun with
+/// [`Database::transaction`](crate::Database::transaction), unless the
+/// builder sets them. Unset options are left to the server.
+#[derive(Debug, Clone, Default, PartialEq, TypedBuilder)]
+#[builder(doc)]
+pub struct TransactionDefaults {
+    #[builder(default, setter(strip_option))]
+    pub wait_for_sync: Option<bool>,
+
+    #[builder(default, setter(strip_option))]
+    pub allow_implicit: Option<bool>,
+
+    #[builder(default, setter(strip_option))]
+    pub lock_timeout: Option<usize>,
+
+    #[builder(default, setter(strip_option))]
+    pub max_transaction_size: Option<usize>,
+}
+
+/// Builder of a stream transaction, returned by
+/// [`Database::transaction`](crate::Database::transaction).
+///
+/// Options not set on the builder are taken from the
+/// [`TransactionDefaults`] of the database.
+///
+/// # Example
+/// ```rust, ignore
+/// let trx = db
+///     .transaction()
+///     .read(["users"])
+///     .write(["accounts"])
+///     .wait_for_sync(true)
+///     .begin()
+///     .await?;
+/// ```
+#[derive(Debug)]
+pub struct TransactionBuilder<'a, C: ClientExt> {
+    database: &'a Database<C>,
+    read: Vec<String>,
+    write: Vec<String>,
+    exclusive: Vec<String>,
+    options: TransactionDefaults,
+    max_attempts: u32,
+}
+
+impl<'a, C> TransactionBuilder<'a, C>
+where
+    C: ClientExt,
+{
+    pub(crate) fn new(database: &'a Database<C>, defaults: TransactionDefaults) -> Self {
+        TransactionBuilder {
+            database,
+            read: Vec::new(),
+            write: Vec::new(),
+            exclusive: Vec::new(),
+            options: defaults,
+            max_attempts: 1,
+        }
+    }
+
+    /// Start from `settings`, with the `defaults` for the options it leaves
+    /// unset.
+    pub(crate) fn from_settings(
+        database: &'a Database<C>,
+        settings: TransactionSettings,
+        defaults: &TransactionDefaults,
+    ) -> Self {
+        let collections = settings.collections;
+        TransactionBuilder {
+            database,
+            read: collections.read.unwrap_or_default(),
+            write: collections.write,
+            exclusive: collections.exclusive.unwrap_or_default(),
+            options: TransactionDefaults {
+                wait_for_sync: settings.wait_for_sync.or(defaults.wait_for_sync),
+                allow_implicit: Some(settings.allow_implicit),
+                lock_timeout: settings.lock_timeout.or(defaults.lock_timeout),
+                max_transaction_size: settings
+                    .max_transaction_size
+                    .or(defaults.max_transaction_size),
+            },
+            max_attempts: 1,
+        }
+    }
+
+    /// Declare collections read in the transaction.
+    pub fn read<I, S>(mut self, collections: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.read.extend(collections.into_iter().map(Into::into));
+        self
+    }
+
+    /// Declare collections written in the transaction.
+    pub fn write<I, S>(mut self, collections: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.write.extend(collections.into_iter().map(Into::into));
+        self
+    }
+
+    /// Declare collections written in the transaction with an exclusive
+    /// lock.
+    pub fn exclusive<I, S>(mut self, collections: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.exclusive
+            .extend(collections.into_iter().map(Into::into));
+        self
+    }
+
+    /// Whether the commit waits until the changes are synced to disk.
+    pub fn wait_for_sync(mut self, wait_for_sync: bool) -> Self {
+        self.options.wait_for_sync = Some(wait_for_sync);
+        self
+    }
+
+    /// Whether collections not declared can be read, true by default.
+    pub fn allow_implicit(mut self, allow_implicit: bool) -> Self {
+        self.options.allow_implicit = Some(allow_implicit);
+        self
+    }
+
+    /// Seconds to wait for the collection locks.
+    pub fn lock_timeout(mut self, lock_timeout: usize) -> Self {
+        self.options.lock_timeout = Some(lock_timeout);
+        self
+    }
+
+    /// Maximum size of the transaction in bytes.
+    pub fn max_transaction_size(mut self, max_transaction_size: usize) -> Self {
+        self.options.max_transaction_size = Some(max_transaction_size);
+        self
+    }
+
+    /// Number of times [`run`](Self::run) may run its operations, each in a
+    /// new transaction, when the previous one fails with a write-write
+    /// conflict, a lock timeout or an abort by the server. 1 by default, at
+    /// least 1.
+    ///
+    /// The transaction of a failed attempt is aborted, and the next attempt
+    /// waits for a random delay under an exponential backoff, from 50ms up
+    /// to 2s. As the operations may run several times, they should not have
+    /// side effects outside of the transaction.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// The settings the transaction would begin with.
+    pub fn settings(&self) -> TransactionSettings {
+        let non_empty = |names: &Vec<String>| Some(names.clone()).filter(|n| !n.is_empty());
+        TransactionSettings {
+            collections: TransactionCollections {
+                read: non_empty(&self.read),
+                write: self.write.clone(),
+                exclusive: non_empty(&self.exclusive),
+            },
+            wait_for_sync: self.options.wait_for_sync,
+            allow_implicit: self.options.allow_implicit.unwrap_or(true),
+            lock_timeout: self.options.lock_timeout,
+            max_transaction_size: self.options.max_transaction_size,
+        }
+    }
+
+    /// Begin the transaction.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn begin(self) -> Result<Transaction<C>, ClientError> {
+        self.database.begin_transaction(self.settings()).await
+    }
+
+    /// Begin the transaction and run `operations` in it, committing it when
+    /// they succeed and aborting it when they or the commit fail. Retryable
+    /// failures run `operations` again up to
+    /// [`max_attempts`](Self::max_attempts) times in total, other errors are
+    /// returned right away.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let balance: u64 = db
+    ///     .transaction()
+    ///     .write(["accounts"])
+    ///     .max_attempts(5)
+    ///     .run(|trx| async move {
+    ///         let accounts = trx.collection("accounts").await?;
+    ///         let account: Document<Account> = accounts.document("alice").await?;
+    ///         Ok(account.document.balance)
+    ///     })
+    ///     .await?;
+    /// ```
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async::async_impl]
+    pub async fn run<T, F, Fut>(self, mut operations: F) -> Result<T, ClientError>
+    where
+        F: FnMut(Transaction<C>) -> Fut,
+        Fut: std::future::Future<Output = Result<T, ClientError>>,
+    {
+        let settings = self.settings();
+        let mut attempt = 1;
+        loop {
+            let transaction = self.database.begin_transaction(settings.clone()).await?;
+            let result = match operations(transaction.share()).await {
+                Ok(value) => transaction.commit().await.map(|_| value),
+                Err(err) => Err(err),
+            };
+            let err = match result {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+            let _ = transaction.abort().await;
+            if attempt >= self.max_attempts || !is_retryable(&err) {
+                return Err(err);
+            }
+            sleep(retry_delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Begin the transaction and run `operations` in it, committing it when
+    /// they succeed and aborting it when they or the commit fail. Retryable
+    /// failures run `operations` again up to
+    /// [`max_attempts`](Self::max_attempts) times in total, other errors are
+    /// returned right away.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let balance: u64 = db
+    ///     .transaction()
+    ///     .write(["accounts"])
+    ///     .max_attempts(5)
+    ///     .run(|trx| {
+    ///         let accounts = trx.collection("accounts")?;
+    ///         let account: Document<Account> = accounts.document("alice")?;
+    ///         Ok(account.document.balance)
+    ///     })?;
+    /// ```
+    ///
+    /// # Note
+    /// this function would make requests to arango server.
+    #[maybe_async::sync_impl]
+    pub fn run<T, F>(self, mut operations: F) -> Result<T, ClientError>
+    where
+        F: FnMut(Transaction<C>) -> Result<T, ClientError>,
+    {
+        let settings = self.settings();
+        let mut attempt = 1;
+        loop {
+            let transaction = self.database.begin_transaction(settings.clone())?;
+            let result = match operations(transaction.share()) {
+                Ok(value) => transaction.commit().map(|_| value),
+                Err(err) => Err(err),
+            };
+            let err = match result {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+            let _ = transaction.abort();
+            if attempt >= self.max_attempts || !is_retryable(&err) {
+                return Err(err);
+            }
+            sleep(retry_delay(attempt));
+            attempt += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Status {
     Running,
@@ -119,24 +411,54 @@ pub struct TransactionList {
 /// # Ok(())
 /// # }
 /// ```
+///
+/// # Transactional operations
+///
+/// Stream transactions only cover data operations. The handles obtained
+/// from a transaction send its id along with every request:
+///
+/// - [`collection`](Transaction::collection): document operations and
+///   truncation. Managing the collection itself, like dropping, renaming or
+///   changing its properties, is not transactional.
+/// - [`aql_query`](Transaction::aql_query) and friends: AQL queries,
+///   including searches on Views.
+/// - [`graph_handle`](Transaction::graph_handle): vertex and edge
+///   operations. Changing the graph definition fails with
+///   [`ClientError::NotTransactional`].
+/// - [`database`](Transaction::database): the same operations through a
+///   [`Database`](crate::Database) handle, for code written against one.
+///
+/// Views and analyzers can not be managed in a transaction, use the
+/// [`Database`](crate::Database) for them.
+///
+/// `Transaction<C>` is `Send + Sync` when `C` is.
 #[derive(Debug)]
 pub struct Transaction<C: ClientExt> {
     id: String,
     status: Status,
-    session: Arc<C>,
-    base_url: Url,
+    database: Database<C>,
 }
 
 impl<C> Transaction<C>
 where
     C: ClientExt,
 {
-    pub(crate) fn new(tx: ArangoTransaction, session: Arc<C>, base_url: Url) -> Self {
+    /// `database` sends its requests with the transaction header.
+    pub(crate) fn new(tx: ArangoTransaction, database: Database<C>) -> Self {
         Transaction {
             id: tx.id,
             status: tx.status,
-            session,
-            base_url,
+            database,
+        }
+    }
+
+    /// Another handle on the same transaction, for the closures of
+    /// `Database::run_transaction_with_retry`.
+    pub(crate) fn share(&self) -> Self {
+        Transaction {
+            id: self.id.clone(),
+            status: self.status,
+            database: self.database.clone(),
         }
     }
 
@@ -151,12 +473,48 @@ where
     }
 
     pub fn url(&self) -> &Url {
-        &self.base_url
+        self.database.url()
     }
 
     /// The transaction session, contains the streaming transaction header value
     pub fn session(&self) -> Arc<C> {
-        Arc::clone(&self.session)
+        self.database.session()
+    }
+
+    /// The database of the transaction, whose requests all carry the
+    /// transaction header.
+    ///
+    /// The operations of the database which support stream transactions,
+    /// like AQL queries and the document operations of its collections, are
+    /// then part of the transaction. The others, like creating a collection,
+    /// are not. The handle should not be used once the transaction is
+    /// committed or aborted.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// let trx = db.transaction().write(["accounts"]).begin().await?;
+    /// let trx_db = trx.database();
+    /// let accounts = trx_db.collection("accounts").await?;
+    /// accounts.create_document(doc, Default::default()).await?;
+    /// let total: Vec<u64> = trx_db
+    ///     .aql_str("RETURN SUM(FOR a IN accounts RETURN a.balance)")
+    ///     .await?;
+    /// trx.commit().await?;
+    /// ```
+    pub fn database(&self) -> Database<C> {
+        self.database.clone()
+    }
+
+    /// What is done with the warnings returned with AQL query results,
+    /// inherited from the database.
+    pub fn warning_policy(&self) -> WarningPolicy {
+        self.database.warning_policy()
+    }
+
+    /// Set what is done with the warnings returned with AQL query results,
+    /// unless a query sets its own policy in its options.
+    pub fn set_warning_policy(&mut self, policy: WarningPolicy) {
+        self.database.set_warning_policy(policy);
     }
 
     /// Tries to commit the transaction, consuming the current object.
@@ -169,15 +527,15 @@ where
     #[maybe_async]
     pub async fn commit_transaction(self) -> Result<Status, ClientError> {
         let url = self
-            .base_url
+            .url()
             .join(&format!("_api/transaction/{}", self.id))
             .unwrap();
 
-        let resp = self.session.put(url, "").await?;
+        let resp = self.session().put(url, "").await?;
 
-        let result: ArangoResult<ArangoTransaction> = deserialize_response(resp.body())?;
+        let result: ResponseEnvelope<ArangoTransaction> = deserialize_response(&resp)?;
 
-        Ok(result.unwrap().status)
+        Ok(result.into_result().status)
     }
 
     /// Tries to commit the transaction.
@@ -191,15 +549,15 @@ where
     #[maybe_async]
     pub async fn commit(&self) -> Result<Status, ClientError> {
         let url = self
-            .base_url
+            .url()
             .join(&format!("_api/transaction/{}", self.id))
             .unwrap();
 
-        let resp = self.session.put(url, "").await?;
+        let resp = self.session().put(url, "").await?;
 
-        let result: ArangoResult<ArangoTransaction> = deserialize_response(resp.body())?;
+        let result: ResponseEnvelope<ArangoTransaction> = deserialize_response(&resp)?;
 
-        Ok(result.unwrap().status)
+        Ok(result.into_result().status)
     }
 
     /// Tries to abort the transaction.
@@ -219,15 +577,15 @@ where
     #[maybe_async]
     pub async fn abort(&self) -> Result<Status, ClientError> {
         let url = self
-            .base_url
+            .url()
             .join(&format!("_api/transaction/{}", self.id))
             .unwrap();
 
-        let resp = self.session.delete(url, "").await?;
+        let resp = self.session().delete(url, "").await?;
 
-        let result: ArangoResult<ArangoTransaction> = deserialize_response(resp.body())?;
+        let result: ResponseEnvelope<ArangoTransaction> = deserialize_response(&resp)?;
 
-        Ok(result.unwrap().status)
+        Ok(result.into_result().status)
     }
 
     /// Get collection object with name.
@@ -242,59 +600,199 @@ where
     #[maybe_async]
     pub async fn collection(&self, name: &str) -> Result<Collection<C>, ClientError> {
         let url = self
-            .base_url
+            .url()
             .join(&format!("_api/collection/{}", name))
             .unwrap();
-        let resp: Info = deserialize_response(self.session.get(url, "").await?.body())?;
+        let resp: Info = deserialize_response(&self.session().get(url, "").await?)?;
         Ok(Collection::from_transaction_response(self, &resp))
     }
 
+    /// Get a handle on a graph for vertex and edge operations within the
+    /// transaction.
+    ///
+    /// The vertex and edge collections must be declared in the transaction.
+    /// Changing the graph definition or dropping the graph through this
+    /// handle fails with [`ClientError::NotTransactional`].
+    pub fn graph_handle(&self, name: &str) -> GraphHandle<C> {
+        GraphHandle::from_transaction(name, self)
+    }
+
+    /// Get a handle on a graph for vertex and edge operations within the
+    /// transaction, checking that the graph exists.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn graph(&self, name: &str) -> Result<GraphHandle<C>, ClientError> {
+        let handle = self.graph_handle(name);
+        handle.info().await?;
+        Ok(handle)
+    }
+
     #[maybe_async]
     pub async fn aql_query_batch<R>(&self, aql: AqlQuery<'_>) -> Result<Cursor<R>, ClientError>
     where
         R: DeserializeOwned,
     {
-        let url = self.base_url.join("_api/cursor").unwrap();
-        let resp = self
-            .session
-            .post(url, &serde_json::to_string(&aql)?)
-            .await?;
-        deserialize_response(resp.body())
+        delete_pending_cursors(
+            self.database.session().as_ref(),
+            self.database.url(),
+            &self.database.pending_cursors,
+        )
+        .await;
+
+        let policy = aql.warning_policy().unwrap_or(self.warning_policy());
+        let url = self.url().join("_api/cursor").unwrap();
+        let req = aql
+            .context()
+            .request(Method::POST, url, serde_json::to_string(&aql)?)?;
+        let resp = self.session().request(req).await?;
+        let cursor: Cursor<R> = deserialize_response(&resp)?;
+        if let Err(e) = check_warnings(policy, cursor.extra.as_ref()) {
+            if let Some(id) = cursor
+                .id
+                .filter(|_| cursor.more && self.database.cursor_cleanup())
+            {
+                self.database.pending_cursors.lock().unwrap().push(id);
+            }
+            return Err(e);
+        }
+        Ok(cursor)
     }
 
     #[maybe_async]
     pub async fn aql_next_batch<R>(&self, cursor_id: &str) -> Result<Cursor<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.next_batch(cursor_id, self.warning_policy(), &QueryContext::default())
+            .await
+    }
+
+    /// Get next batch given the cursor id, sending the request with the
+    /// context of the query, see
+    /// [`Database::aql_next_batch_with`](crate::Database::aql_next_batch_with).
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_next_batch_with<R>(
+        &self,
+        cursor_id: &str,
+        context: &QueryContext,
+    ) -> Result<Cursor<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        self.next_batch(cursor_id, self.warning_policy(), context)
+            .await
+    }
+
+    #[maybe_async]
+    async fn next_batch<R>(
+        &self,
+        cursor_id: &str,
+        policy: WarningPolicy,
+        context: &QueryContext,
+    ) -> Result<Cursor<R>, ClientError>
     where
         R: DeserializeOwned,
     {
         let url = self
-            .base_url
+            .url()
             .join(&format!("_api/cursor/{}", cursor_id))
             .unwrap();
-        let resp = self.session.put(url, "").await?;
+        let req = context.request(Method::PUT, url, String::new())?;
+        let resp = self.session().request(req).await?;
 
-        deserialize_response(resp.body())
+        let cursor: Cursor<R> = deserialize_response(&resp)?;
+        check_warnings(policy, cursor.extra.as_ref())?;
+        Ok(cursor)
     }
 
     #[maybe_async]
-    async fn aql_fetch_all<R>(&self, response: Cursor<R>) -> Result<Vec<R>, ClientError>
+    async fn aql_fetch_all<R>(
+        &self,
+        response: Cursor<R>,
+        policy: WarningPolicy,
+        context: &QueryContext,
+    ) -> Result<Vec<R>, ClientError>
     where
         R: DeserializeOwned,
     {
         let mut response_cursor = response;
         let mut results: Vec<R> = Vec::new();
+        let mut guard = CursorGuard::new(
+            self.session(),
+            self.url().clone(),
+            response_cursor
+                .id
+                .clone()
+                .filter(|_| self.database.cursor_cleanup()),
+            Arc::clone(&self.database.pending_cursors),
+        );
         loop {
+            results.extend(response_cursor.result);
             if response_cursor.more {
                 let id = response_cursor.id.unwrap().clone();
-                results.extend(response_cursor.result.into_iter());
-                response_cursor = self.aql_next_batch(id.as_str()).await?;
+                response_cursor = self.next_batch(id.as_str(), policy, context).await?;
             } else {
                 break;
             }
         }
+        guard.disarm();
         Ok(results)
     }
 
+    /// Execute AQL query and fetch its batches until `budget` is exhausted,
+    /// see [`Database::aql_query_within`](crate::Database::aql_query_within).
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_query_within<R>(
+        &self,
+        aql: AqlQuery<'_>,
+        budget: Duration,
+    ) -> Result<PartialResults<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        let deadline = Instant::now() + budget;
+        let policy = aql.warning_policy().unwrap_or(self.warning_policy());
+        let context = aql.context();
+        let response = self.aql_query_batch(aql).await?;
+        fetch_within(&self.database, response, policy, context, deadline).await
+    }
+
+    /// Continue fetching the batches of a cursor left by
+    /// [`aql_query_within`](Self::aql_query_within) until `budget` is
+    /// exhausted, see
+    /// [`Database::aql_next_batches_within`](crate::Database::aql_next_batches_within).
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn aql_next_batches_within<R>(
+        &self,
+        cursor_id: &str,
+        context: &QueryContext,
+        budget: Duration,
+    ) -> Result<PartialResults<R>, ClientError>
+    where
+        R: DeserializeOwned,
+    {
+        let deadline = Instant::now() + budget;
+        next_batches_within(
+            &self.database,
+            cursor_id,
+            self.warning_policy(),
+            context,
+            deadline,
+        )
+        .await
+    }
+
     /// Execute AQL query fetch all results.
     ///
     /// DO NOT do this when the count of results is too large that network or
@@ -310,9 +808,11 @@ where
     where
         R: DeserializeOwned,
     {
+        let policy = aql.warning_policy().unwrap_or(self.warning_policy());
+        let context = aql.context();
         let response = self.aql_query_batch(aql).await?;
         if response.more {
-            self.aql_fetch_all(response).await
+            self.aql_fetch_all(response, policy, &context).await
         } else {
             Ok(response.result)
         }
@@ -335,17 +835,24 @@ where
     /// Similar to `aql_query`, except that this method only accept a string of
     /// AQL query, with additional bind vars.
     ///
+    /// Bind vars can be any pairs of key and serializable value, e.g. a
+    /// `HashMap<&str, Value>` or a `Vec<(String, T)>`.
+    ///
     /// # Note
     /// this function would make a request to arango server.
     #[maybe_async]
     pub async fn aql_bind_vars<R>(
         &self,
         query: &str,
-        bind_vars: HashMap<&str, Value>,
+        bind_vars: impl IntoIterator<Item = (impl Into<String>, impl Serialize)>,
     ) -> Result<Vec<R>, ClientError>
     where
         R: DeserializeOwned,
     {
+        let bind_vars = bind_vars
+            .into_iter()
+            .map(|(k, v)| Ok((k.into(), serde_json::to_value(v)?)))
+            .collect::<Result<Vec<(String, Value)>, serde_json::Error>>()?;
         let aql = AqlQuery::builder()
             .query(query)
             .bind_vars(bind_vars)
@@ -353,3 +860,135 @@ where
         self.aql_query(aql).await
     }
 }
+
+/// Error numbers after which a transaction is worth running again: a
+/// write-write conflict, a lock timeout, and a transaction aborted or
+/// expired on the server.
+const RETRYABLE_ERRORS: [u16; 4] = [
+    codes::ERROR_ARANGO_CONFLICT,
+    codes::ERROR_LOCK_TIMEOUT,
+    codes::ERROR_TRANSACTION_ABORTED,
+    codes::ERROR_TRANSACTION_NOT_FOUND,
+];
+
+/// Delay before the first retry, doubled for every further attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+/// Longest delay between attempts
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(2);
+
+/// Whether a transaction failing with `err` should be run again.
+fn is_retryable(err: &ClientError) -> bool {
+    matches!(err, ClientError::Arango(err) if RETRYABLE_ERRORS.contains(&err.error_num()))
+}
+
+/// Delay before running a transaction again after `attempt` failed
+/// attempts: a random duration up to an exponential backoff, so that
+/// conflicting clients do not retry in lockstep.
+///
+/// The jitter comes from the SipHash keys of `RandomState`, to avoid a
+/// dependency on a random number generator. The standard library seeds them
+/// from the OS once per thread and changes them for every `RandomState`, so
+/// hashing nothing gives another value on every call. It is not meant for
+/// anything but spreading retries.
+fn retry_delay(attempt: u32) -> Duration {
+    use std::{
+        collections::hash_map::RandomState,
+        hash::{BuildHasher, Hasher},
+    };
+
+    let backoff = RETRY_BASE_DELAY
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(RETRY_MAX_DELAY);
+    let random = RandomState::new().build_hasher().finish();
+    backoff.mul_f64((random % 1_000_001) as f64 / 1_000_000.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ArangoError;
+
+    #[test]
+    fn retry_on_conflicts() {
+        let conflict: ArangoError = serde_json::from_str(
+            r#"{"code":409,"error":true,"errorMessage":"write-write conflict","errorNum":1200}"#,
+        )
+        .unwrap();
+        assert!(is_retryable(&ClientError::Arango(conflict)));
+        let not_found: ArangoError = serde_json::from_str(
+            r#"{"code":404,"error":true,"errorMessage":"document not found","errorNum":1202}"#,
+        )
+        .unwrap();
+        assert!(!is_retryable(&ClientError::Arango(not_found)));
+        assert!(!is_retryable(&ClientError::HttpClient("reset".to_owned())));
+
+        for attempt in 1..40 {
+            let delay = retry_delay(attempt);
+            let max = RETRY_BASE_DELAY * 2u32.pow(attempt.min(16) - 1);
+            assert!(delay <= max.min(RETRY_MAX_DELAY));
+        }
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn run_again_after_a_conflict() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use crate::client::mock::RecordingClient;
+
+        let session = Arc::new(RecordingClient::default());
+        let mut db = Database::new(
+            "test_db",
+            &"http://localhost:8529/".parse().unwrap(),
+            Arc::clone(&session),
+        );
+        db.set_transaction_defaults(TransactionDefaults::builder().lock_timeout(5).build());
+        let transaction = |id: &str, status: &str| {
+            format!(
+                r#"{{"error":false,"code":200,"result":{{"id":"{}","status":"{}"}}}}"#,
+                id, status
+            )
+        };
+        session.respond(201, transaction("1", "running"));
+        session.respond(
+            409,
+            r#"{"code":409,"error":true,"errorMessage":"write-write conflict","errorNum":1200}"#,
+        );
+        session.respond(200, transaction("1", "aborted"));
+        session.respond(201, transaction("2", "running"));
+        session.respond(200, transaction("2", "committed"));
+
+        let runs = AtomicUsize::new(0);
+        #[cfg(not(feature = "blocking"))]
+        let operations = |_: Transaction<RecordingClient>| async {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, ClientError>(7)
+        };
+        #[cfg(feature = "blocking")]
+        let operations = |_: Transaction<RecordingClient>| {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, ClientError>(7)
+        };
+        let settings = TransactionSettings::builder()
+            .collections(
+                TransactionCollections::builder()
+                    .write(vec!["accounts".to_owned()])
+                    .build(),
+            )
+            .allow_implicit(false)
+            .build();
+        let value = db
+            .run_transaction_with_retry(settings, 3, operations)
+            .await
+            .unwrap();
+        assert_eq!(value, 7);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        let requests = session.take_requests();
+        let methods: Vec<&str> = requests.iter().map(|r| r.method().as_str()).collect();
+        assert_eq!(methods, ["POST", "PUT", "DELETE", "POST", "PUT"]);
+        let begin: Value = serde_json::from_str(requests[0].body()).unwrap();
+        assert_eq!(begin["collections"]["write"][0], "accounts");
+        assert_eq!(begin["allowImplicit"], false);
+        assert_eq!(begin["lockTimeout"], 5);
+    }
+}
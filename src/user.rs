@@ -13,6 +13,30 @@ pub struct User {
     pub extra: Option<HashMap<String, Value>>, // change_password: Option<bool>,
 }
 
+/// Attributes of a user to create or change, see
+/// [`GenericConnection::create_user`] and [`GenericConnection::update_user`].
+///
+/// Unset attributes are left unchanged on update.
+///
+/// [`GenericConnection::create_user`]: crate::connection::GenericConnection::create_user
+/// [`GenericConnection::update_user`]: crate::connection::GenericConnection::update_user
+#[derive(Debug, Clone, Default, Serialize, TypedBuilder)]
+#[builder(doc)]
+pub struct UserOptions {
+    /// Password of the user, empty if not set on creation
+    #[serde(rename = "passwd", skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    pub password: Option<String>,
+    /// Whether the user can log in, `true` by default on creation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub active: Option<bool>,
+    /// Custom data stored with the user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub extra: Option<HashMap<String, Value>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserResponse {
     error: bool,
@@ -46,3 +70,24 @@ pub(crate) fn access_level_enum_to_str(level: UserAccessLevel) -> String {
         UserAccessLevel::ReadOnly => "ro".into(),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn user_options() {
+        let options = UserOptions::builder()
+            .password("secret")
+            .active(false)
+            .build();
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({ "passwd": "secret", "active": false })
+        );
+        assert_eq!(
+            serde_json::to_value(UserOptions::default()).unwrap(),
+            serde_json::json!({})
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Helpers shared by the modules of the crate
+use std::time::Duration;
+
+/// Sleep without depending on an async runtime, by waking the task from the
+/// timer thread shared by all the sleeping tasks.
+#[cfg(not(target_arch = "wasm32"))]
+#[maybe_async::async_impl]
+pub(crate) async fn sleep(duration: Duration) {
+    use std::{future::poll_fn, task::Poll, time::Instant};
+
+    let deadline = Instant::now() + duration;
+    poll_fn(|cx| {
+        if Instant::now() >= deadline {
+            Poll::Ready(())
+        } else {
+            timer::wake_at(deadline, cx.waker().clone());
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "blocking")))]
+mod timer {
+    use std::{
+        cmp::{Ordering, Reverse},
+        collections::BinaryHeap,
+        sync::{Condvar, Mutex},
+        task::Waker,
+        thread,
+        time::Instant,
+    };
+
+    struct Timer {
+        deadline: Instant,
+        waker: Waker,
+    }
+
+    impl PartialEq for Timer {
+        fn eq(&self, other: &Self) -> bool {
+            self.deadline == other.deadline
+        }
+    }
+
+    impl Eq for Timer {}
+
+    impl PartialOrd for Timer {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Timer {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.deadline.cmp(&other.deadline)
+        }
+    }
+
+    /// Pending timers, earliest first, and whether the thread waking them is
+    /// started.
+    static TIMERS: Mutex<(BinaryHeap<Reverse<Timer>>, bool)> =
+        Mutex::new((BinaryHeap::new(), false));
+    static CHANGED: Condvar = Condvar::new();
+
+    /// Wake `waker` once `deadline` is reached.
+    pub(super) fn wake_at(deadline: Instant, waker: Waker) {
+        let mut timers = TIMERS.lock().unwrap();
+        timers.0.push(Reverse(Timer { deadline, waker }));
+        if !timers.1 {
+            timers.1 = true;
+            thread::Builder::new()
+                .name("arangors-timer".to_owned())
+                .spawn(run)
+                .expect("failed to spawn the timer thread");
+        }
+        CHANGED.notify_one();
+    }
+
+    fn run() {
+        let mut timers = TIMERS.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            let mut due = Vec::new();
+            while timers.0.peek().is_some_and(|timer| timer.0.deadline <= now) {
+                due.push(timers.0.pop().unwrap().0.waker);
+            }
+            if !due.is_empty() {
+                // wake the tasks without holding the lock they may need
+                drop(timers);
+                due.into_iter().for_each(Waker::wake);
+                timers = TIMERS.lock().unwrap();
+                continue;
+            }
+            timers = match timers.0.peek() {
+                Some(timer) => {
+                    let timeout = timer.0.deadline - now;
+                    CHANGED.wait_timeout(timers, timeout).unwrap().0
+                }
+                None => CHANGED.wait(timers).unwrap(),
+            };
+        }
+    }
+}
+
+// threads are not available in the browser, wait for a timer of the
+// JavaScript runtime instead
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let global = js_sys::global();
+        let set_timeout: js_sys::Function = js_sys::Reflect::get(&global, &"setTimeout".into())
+            .expect("setTimeout is available")
+            .unchecked_into();
+        let _ = set_timeout.call2(
+            &global,
+            &resolve,
+            &JsValue::from_f64(duration.as_millis() as f64),
+        );
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+#[maybe_async::sync_impl]
+pub(crate) fn sleep(duration: Duration) {
+    std::thread::sleep(duration)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Instant;
+
+    use super::*;
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn sleep_for_duration() {
+        let start = Instant::now();
+        sleep(Duration::from_millis(20)).await;
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    #[tokio::test]
+    async fn concurrent_sleeps_wake_in_order() {
+        use futures_lite::future::zip;
+
+        let start = Instant::now();
+        let (short, long) = zip(
+            async {
+                sleep(Duration::from_millis(10)).await;
+                start.elapsed()
+            },
+            async {
+                sleep(Duration::from_millis(40)).await;
+                start.elapsed()
+            },
+        )
+        .await;
+        assert!(short >= Duration::from_millis(10));
+        assert!(long >= Duration::from_millis(40));
+        assert!(short < long);
+    }
+}
@@ -1,6 +1,14 @@
+use maybe_async::maybe_async;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 use typed_builder::TypedBuilder;
+use url::Url;
+
+use crate::{
+    client::{ClientExt, RequestExt},
+    response::{deserialize_response, ResponseEnvelope},
+    ClientError,
+};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub enum ViewType {
@@ -8,14 +16,14 @@ pub enum ViewType {
     ArangoSearchView,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum StoreValues {
     None,
     Id,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum PrimarySortCompression {
     Lz4,
@@ -39,7 +47,7 @@ pub struct ViewDescription {
     pub typ: ViewType,
 }
 
-#[derive(Debug, Serialize, Deserialize, TypedBuilder)]
+#[derive(Debug, Serialize, Deserialize, TypedBuilder, Clone, Default)]
 #[builder(doc)]
 #[serde(rename_all = "camelCase")]
 pub struct ArangoSearchViewLink {
@@ -76,6 +84,99 @@ pub struct ArangoSearchViewLink {
     pub store_values: Option<StoreValues>,
 }
 
+/// Shorthand for [`ArangoSearchViewLink`], for use with
+/// [`ViewLinksBuilder`].
+pub type Link = ArangoSearchViewLink;
+
+impl ArangoSearchViewLink {
+    /// A link processing all document attributes.
+    pub fn all_fields() -> Self {
+        ArangoSearchViewLink {
+            include_all_fields: Some(true),
+            ..Default::default()
+        }
+    }
+
+    /// A link processing only the attributes added with
+    /// [`field`](ArangoSearchViewLink::field).
+    pub fn only_fields() -> Self {
+        ArangoSearchViewLink::default()
+    }
+
+    /// Apply the Analyzer `name` to the processed values, in addition to
+    /// the Analyzers already added.
+    pub fn analyzer<S: Into<String>>(mut self, name: S) -> Self {
+        self.analyzers
+            .get_or_insert_with(Vec::new)
+            .push(name.into());
+        self
+    }
+
+    /// Process the attribute `name` with its own link definition.
+    pub fn field<S: Into<String>>(mut self, name: S, link: ArangoSearchViewLink) -> Self {
+        self.fields
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), link);
+        self
+    }
+
+    pub fn track_list_positions(mut self, track: bool) -> Self {
+        self.track_list_positions = Some(track);
+        self
+    }
+
+    pub fn store_values(mut self, store_values: StoreValues) -> Self {
+        self.store_values = Some(store_values);
+        self
+    }
+}
+
+/// Maps collection names to the definitions of their links to a View.
+///
+/// # Example
+/// ```
+/// use arangors::view::{ArangoSearchViewPropertiesOptions, Link, ViewLinksBuilder};
+///
+/// let properties: ArangoSearchViewPropertiesOptions = ViewLinksBuilder::new()
+///     .link("users", Link::all_fields().analyzer("text_en"))
+///     .link(
+///         "posts",
+///         Link::only_fields().field("body", Link::only_fields().analyzer("text_en")),
+///     )
+///     .into();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ViewLinksBuilder {
+    links: HashMap<String, ArangoSearchViewLink>,
+}
+
+impl ViewLinksBuilder {
+    pub fn new() -> Self {
+        ViewLinksBuilder::default()
+    }
+
+    /// Link the collection `name` to the View, replacing a link already
+    /// added for it.
+    pub fn link<S: Into<String>>(mut self, name: S, link: ArangoSearchViewLink) -> Self {
+        self.links.insert(name.into(), link);
+        self
+    }
+
+    /// The links, for `ArangoSearchViewPropertiesOptions::builder().links()`.
+    pub fn build(self) -> HashMap<String, ArangoSearchViewLink> {
+        self.links
+    }
+}
+
+impl From<ViewLinksBuilder> for ArangoSearchViewPropertiesOptions {
+    /// View properties with only the links set.
+    fn from(links: ViewLinksBuilder) -> Self {
+        ArangoSearchViewPropertiesOptions::builder()
+            .links(links.build())
+            .build()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum SortDirection {
@@ -155,7 +256,7 @@ impl PrimarySort {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct StoredValues {
     pub fields: Vec<String>,
@@ -274,6 +375,13 @@ pub struct ViewOptions {
     properties: Option<ArangoSearchViewPropertiesOptions>,
 }
 
+impl ViewOptions {
+    /// Name of the View to create.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct View {
@@ -283,3 +391,159 @@ pub struct View {
     #[serde(flatten)]
     pub properties: ArangoSearchViewProperties,
 }
+
+/// A handle on a View of a database, to read or drop it.
+///
+/// Obtained with [`Database::view_handle`](crate::Database::view_handle),
+/// like [`GraphHandle`](crate::graph::GraphHandle) for graphs. No request is
+/// made to get it, so the View is not checked to exist.
+#[derive(Debug, Clone)]
+pub struct ViewHandle<C: ClientExt> {
+    name: String,
+    base_url: Url,
+    session: Arc<C>,
+}
+
+impl<C: ClientExt> ViewHandle<C> {
+    /// Base url should be like `http://server:port/_db/mydb/`
+    pub(crate) fn new<T: Into<String>>(name: T, db_url: &Url, session: Arc<C>) -> Self {
+        let name = name.into();
+        let base_url = db_url.join(&format!("_api/view/{}/", name)).unwrap();
+        ViewHandle {
+            name,
+            base_url,
+            session,
+        }
+    }
+
+    /// Name of the View.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Read the description of the View.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn description(&self) -> Result<ViewDescription, ClientError> {
+        let resp = self.session.get(self.base_url.clone(), "").await?;
+        deserialize_response(&resp)
+    }
+
+    /// Read the properties of the View.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn properties(&self) -> Result<ArangoSearchViewProperties, ClientError> {
+        let url = self.base_url.join("properties").unwrap();
+        let resp = self.session.get(url, "").await?;
+        deserialize_response(&resp)
+    }
+
+    /// Drop the View.
+    ///
+    /// # Note
+    /// this function would make a request to arango server.
+    #[maybe_async]
+    pub async fn drop(self) -> Result<bool, ClientError> {
+        let resp = self.session.delete(self.base_url.clone(), "").await?;
+        let result: ResponseEnvelope<bool> = deserialize_response(&resp)?;
+        Ok(result.into_result())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn view_links_builder() {
+        let properties: ArangoSearchViewPropertiesOptions = ViewLinksBuilder::new()
+            .link("users", Link::all_fields().analyzer("text_en"))
+            .link(
+                "posts",
+                Link::only_fields()
+                    .field(
+                        "body",
+                        Link::only_fields().analyzer("text_en").analyzer("identity"),
+                    )
+                    .track_list_positions(true)
+                    .store_values(StoreValues::Id),
+            )
+            .into();
+        assert_eq!(
+            serde_json::to_value(&properties).unwrap(),
+            json!({
+                "links": {
+                    "users": { "analyzers": ["text_en"], "includeAllFields": true },
+                    "posts": {
+                        "fields": { "body": { "analyzers": ["text_en", "identity"] } },
+                        "trackListPositions": true,
+                        "storeValues": "id",
+                    },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn view_serde_round_trip() {
+        let body = json!({
+            "globallyUniqueId": "h1/123",
+            "id": "123",
+            "name": "test_view",
+            "type": "arangosearch",
+            "cleanupIntervalStep": 2,
+            "consolidationIntervalMsec": 1000,
+            "writebufferIdle": 64,
+            "writebufferActive": 0,
+            "writebufferSizeMax": 33554432,
+            "consolidationPolicy": { "type": "bytes_accum", "threshold": 0 },
+            "primarySort": null,
+            "primarySortCompression": "lz4",
+            "storedValues": [],
+            "links": {},
+        });
+        let view: View = serde_json::from_value(body.clone()).unwrap();
+        assert_eq!(view.description.name, "test_view");
+        assert_eq!(serde_json::to_value(&view).unwrap(), body);
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn view_handle_requests() {
+        use http::Method;
+
+        use crate::client::mock::RecordingClient;
+
+        let session = Arc::new(RecordingClient::default());
+        let db_url: Url = "http://localhost:8529/_db/test_db/".parse().unwrap();
+        let view = ViewHandle::new("test_view", &db_url, Arc::clone(&session));
+
+        session.respond(
+            200,
+            r#"{"error":false,"code":200,"globallyUniqueId":"h1/123","id":"123","name":"test_view","type":"arangosearch"}"#,
+        );
+        let description = view.description().await.unwrap();
+        assert_eq!(description.id, "123");
+
+        session.respond(200, r#"{"error":false,"code":200,"result":true}"#);
+        let dropped = view.drop().await.unwrap();
+        assert!(dropped);
+
+        let requests = session.take_requests();
+        assert_eq!(requests[0].method(), Method::GET);
+        assert_eq!(
+            requests[0].uri(),
+            "http://localhost:8529/_db/test_db/_api/view/test_view/"
+        );
+        assert_eq!(requests[1].method(), Method::DELETE);
+        assert_eq!(
+            requests[1].uri(),
+            "http://localhost:8529/_db/test_db/_api/view/test_view/"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(unused_imports)]
 #![allow(unused_parens)]
-use crate::common::{collection, connection};
+use crate::common::{collection, connection, root_connection};
 
 use log::{info, trace};
 use maybe_async::maybe_async;
@@ -9,9 +9,9 @@ use std::collections::HashMap;
 
 use arangors::{
     analyzer::{
-        AnalyzerCase, AnalyzerFeature, AnalyzerInfo, GeoJsonAnalyzerProperties, GeoJsonType,
-        NgramAnalyzerProperties, NgramStreamType, NormAnalyzerProperties,
-        PipelineAnalyzerProperties, PipelineAnalyzers,
+        AnalyzerCase, AnalyzerFeature, AnalyzerInfo, AnalyzerSyncOptions,
+        GeoJsonAnalyzerProperties, GeoJsonType, NgramAnalyzerProperties, NgramStreamType,
+        NormAnalyzerProperties, PipelineAnalyzerProperties, PipelineAnalyzers,
     },
     client::ClientExt,
     collection::{
@@ -267,3 +267,88 @@ async fn test_create_and_exists() {
 
     assert_eq!(result.is_err(), false);
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_exists_analyzer() {
+    test_setup();
+    let analyzer_name = "test_analyzer_exists_check".to_string();
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let analyzer = create_norm_analyzer(&database, analyzer_name.clone()).await;
+    assert_eq!(analyzer.is_err(), false);
+
+    let exists = database.exists_analyzer(&analyzer_name).await.unwrap();
+    assert_eq!(exists, true);
+
+    database.drop_analyzer(&analyzer_name).await.unwrap();
+
+    let exists = database.exists_analyzer(&analyzer_name).await.unwrap();
+    assert_eq!(exists, false);
+}
+
+#[cfg(feature = "testing")]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_sync_analyzers() {
+    use arangors::testing::TempDatabase;
+
+    test_setup();
+    // removing extra analyzers must not race with the other tests of test_db
+    let conn = root_connection().await;
+    let temp = TempDatabase::with_prefix(&conn, "test_sync_analyzers")
+        .await
+        .unwrap();
+    let database = temp.database();
+    let norm = |name: &str, case: AnalyzerCase| AnalyzerInfo::Norm {
+        name: name.to_string(),
+        features: Some(vec![AnalyzerFeature::Frequency, AnalyzerFeature::Norm]),
+        properties: Some(
+            NormAnalyzerProperties::builder()
+                .locale("en.utf-8".to_string())
+                .case(case)
+                .build(),
+        ),
+    };
+    create_norm_analyzer(database, "test_sync_changed".to_string())
+        .await
+        .unwrap();
+    create_norm_analyzer(database, "test_sync_extra".to_string())
+        .await
+        .unwrap();
+
+    let desired = vec![
+        norm("test_sync_kept", AnalyzerCase::Lower),
+        norm("test_sync_changed", AnalyzerCase::Upper),
+    ];
+    let report = database
+        .sync_analyzers(desired.clone(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(report.created, vec!["test_sync_kept".to_string()]);
+    assert_eq!(report.updated, vec!["test_sync_changed".to_string()]);
+    assert_eq!(report.removed.is_empty(), true);
+    let exists = database.exists_analyzer("test_sync_extra").await.unwrap();
+    assert_eq!(exists, true);
+
+    let report = database
+        .sync_analyzers(
+            desired,
+            AnalyzerSyncOptions::builder().remove_extra(true).build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(report.unchanged.len(), 2);
+    assert_eq!(report.removed, vec!["test_sync_extra".to_string()]);
+    let exists = database.exists_analyzer("test_sync_extra").await.unwrap();
+    assert_eq!(exists, false);
+
+    temp.drop().await.unwrap();
+}
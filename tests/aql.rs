@@ -1,10 +1,16 @@
 #![allow(unused_imports)]
 #![allow(unused_parens)]
 
+use std::time::Duration;
+
 use pretty_assertions::assert_eq;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use arangors::{AqlQuery, Connection, Document};
+use arangors::{
+    aql::{PreparedQuery, SlowQueryLog, WarningPolicy},
+    AqlOptions, AqlQuery, ClientError, Connection, Document,
+};
 use common::{connection, test_setup};
 
 use crate::common::{get_arangodb_host, get_root_password, get_root_user};
@@ -70,6 +76,31 @@ async fn test_aql_bind_vars() {
     assert_eq!(result[0].document.password, "test2_pwd");
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_bind_vars_owned_keys() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+    let vars: Vec<(String, &str)> = ["username", "password"]
+        .iter()
+        .map(|field| field.to_string())
+        .zip(["test2", "test2_pwd"])
+        .collect();
+    let result: Vec<Document<User>> = db
+        .aql_bind_vars(
+            r#"FOR i in test_collection FILTER i.username==@username AND i.password==@password return i"#,
+            vars,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].document.password, "test2_pwd");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -93,3 +124,291 @@ async fn test_aql_try_bind() {
     assert_eq!(result.len(), 1);
     assert_eq!(result[0].document.password, "test2_pwd");
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_plan_cache() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+
+    let query = r#"FOR i in @@collection FILTER i.username==@username return i"#;
+    for username in ["test2", "test10"] {
+        let aql = AqlQuery::builder()
+            .query(query)
+            .bind_var("@collection", "test_collection")
+            .bind_var("username", username)
+            .options(AqlOptions::builder().use_plan_cache(true).build())
+            .build();
+        let result: Vec<Document<User>> = db.aql_query(aql).await.unwrap();
+        assert_eq!(result.len(), 1);
+    }
+
+    let entries = db.plan_cache_entries().await.unwrap();
+    assert_eq!(entries.iter().any(|e| e.query == query), true);
+
+    db.clear_plan_cache().await.unwrap();
+    let entries = db.plan_cache_entries().await.unwrap();
+    assert_eq!(entries.iter().any(|e| e.query == query), false);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_analyze_query() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+
+    let aql = AqlQuery::builder()
+        .query("FOR i IN test_collection FILTER i._key == @key RETURN i")
+        .bind_var("key", "test2")
+        .build();
+    let analysis = db.analyze_query(aql).await.unwrap();
+    assert_eq!(analysis.has_full_scan(), false);
+    assert_eq!(
+        analysis
+            .indexes_used
+            .iter()
+            .any(|index| index.index_type == "primary" && index.collection == "test_collection"),
+        true
+    );
+    assert_eq!(analysis.rules.is_empty(), false);
+
+    let aql = AqlQuery::builder()
+        .query("FOR i IN test_collection FILTER i.username == @username RETURN i")
+        .bind_var("username", "test2")
+        .build();
+    let analysis = db.analyze_query(aql).await.unwrap();
+    assert_eq!(analysis.full_scans, vec!["test_collection".to_owned()]);
+    let denied = analysis.deny_full_scans();
+    assert!(matches!(denied, Err(ClientError::FullCollectionScan(_))));
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_explain_query() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+
+    let aql = AqlQuery::builder()
+        .query("FOR i IN test_collection FILTER i.username == @username RETURN i")
+        .bind_var("username", "test2")
+        .build();
+    let explained = db.explain_query(aql).await.unwrap();
+    let plan = &explained.plan;
+    assert_eq!(plan.is_modification_query, false);
+    assert!(plan.estimated_cost > 0.0);
+    assert!(plan
+        .nodes
+        .iter()
+        .any(|node| node.node_type == "EnumerateCollectionNode"
+            && node.collection() == Some("test_collection")));
+    assert!(plan
+        .collections
+        .iter()
+        .any(|c| c.name == "test_collection" && c.access_type == "read"));
+    assert!(plan.variables.iter().any(|v| v.name == "i"));
+    // every node but the first takes its input from another one
+    assert!(plan
+        .nodes
+        .iter()
+        .skip(1)
+        .all(|node| !node.dependencies.is_empty()));
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_warning_policy() {
+    test_setup();
+    let conn = connection().await;
+    let mut db = conn.db("test_db").await.unwrap();
+
+    // division by zero is a warning, the query returns null
+    let query = "RETURN 1 / 0";
+    let result: Vec<Option<f64>> = db.aql_str(query).await.unwrap();
+    assert_eq!(result, vec![None]);
+
+    db.set_warning_policy(WarningPolicy::Fail);
+    let result = db.aql_str::<Option<f64>>(query).await;
+    assert!(matches!(result, Err(ClientError::AqlWarnings(ref w)) if w[0].code == 1562));
+
+    // the policy of a query takes precedence
+    let aql = AqlQuery::builder()
+        .query(query)
+        .options(
+            AqlOptions::builder()
+                .warning_policy(WarningPolicy::Ignore)
+                .build(),
+        )
+        .build();
+    let result: Vec<Option<f64>> = db.aql_query(aql).await.unwrap();
+    assert_eq!(result, vec![None]);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_query_within_budget() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+
+    let aql = AqlQuery::builder()
+        .query("FOR i IN 1..5 RETURN i")
+        .batch_size(1)
+        .build();
+    let partial = db
+        .aql_query_within::<u32>(aql, Duration::ZERO)
+        .await
+        .unwrap();
+    assert_eq!(partial.result, vec![1]);
+    assert_eq!(partial.is_complete(), false);
+
+    let cursor_id = partial.cursor_id.unwrap();
+    let rest = db
+        .aql_next_batches_within::<u32>(&cursor_id, &partial.context, Duration::from_secs(60))
+        .await
+        .unwrap();
+    assert_eq!(rest.result, vec![2, 3, 4, 5]);
+    assert_eq!(rest.is_complete(), true);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_profile_query() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+
+    let aql = AqlQuery::builder()
+        .query("FOR i IN test_collection RETURN i")
+        .options(AqlOptions::builder().profile(2).build())
+        .build();
+    let cursor = db.aql_query_batch::<Document<User>>(aql).await.unwrap();
+    let extra = cursor.extra.unwrap();
+    assert_eq!(extra.profile.is_some(), true);
+    assert_eq!(extra.plan.is_some(), true);
+    let stats = extra.stats.unwrap();
+    assert_eq!(stats.peak_memory_usage.is_some(), true);
+    assert_eq!(stats.nodes.is_some_and(|nodes| !nodes.is_empty()), true);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_slow_query_log() {
+    test_setup();
+    let conn = connection().await;
+    let mut db = conn.db("test_db").await.unwrap();
+
+    let reported = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = reported.clone();
+    db.set_slow_query_log(Some(SlowQueryLog::new(Duration::ZERO).callback(
+        move |slow| {
+            sink.lock()
+                .unwrap()
+                .push((slow.query.to_owned(), slow.bind_vars.is_some()));
+        },
+    )));
+    let aql = AqlQuery::builder()
+        .query("FOR i IN test_collection FILTER i.username == @name RETURN i")
+        .bind_var("name", "test2")
+        .build();
+    let _: Vec<Document<User>> = db.aql_query(aql).await.unwrap();
+    assert_eq!(
+        *reported.lock().unwrap(),
+        vec![(
+            "FOR i IN test_collection FILTER i.username == @name RETURN i".to_owned(),
+            false
+        )]
+    );
+
+    db.set_slow_query_log(Some(SlowQueryLog::new(Duration::from_secs(3600))));
+    let _: Vec<Document<User>> = db
+        .aql_str("FOR i IN test_collection RETURN i")
+        .await
+        .unwrap();
+    assert_eq!(reported.lock().unwrap().len(), 1);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_prepared_query() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+
+    let prepared = PreparedQuery::new(
+        AqlQuery::builder()
+            .query("FOR i IN test_collection FILTER i.username == @name RETURN i")
+            .options(AqlOptions::builder().use_plan_cache(true).build())
+            .build(),
+    );
+    let users: Vec<Document<User>> = db
+        .aql_prepared(&prepared, [("name", "test2")])
+        .await
+        .unwrap();
+    assert_eq!(users.len(), 1);
+
+    let names = ["test2", "nobody", "test2"];
+    let results = db
+        .aql_prepared_many::<Document<User>, _, _, _>(
+            &prepared,
+            names.iter().map(|name| [("name", *name)]),
+            2,
+        )
+        .await
+        .unwrap();
+    let counts: Vec<usize> = results.into_iter().map(|r| r.unwrap().len()).collect();
+    assert_eq!(counts, vec![1, 0, 1]);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_query_stream() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+
+    let aql = AqlQuery::builder()
+        .query("FOR i IN 1..5 RETURN { i, name: CONCAT('row-', i) }")
+        .batch_size(2)
+        .count(true)
+        .build();
+    let mut stream = db.aql_query_stream::<Value>(aql).await.unwrap();
+    assert_eq!(stream.total_count(), Some(5));
+
+    let mut rows = Vec::new();
+    while let Some(row) = stream.next_result().await.unwrap() {
+        rows.push(row["i"].as_u64().unwrap());
+    }
+    assert_eq!(rows, vec![1, 2, 3, 4, 5]);
+    let end = stream.next_result().await.unwrap();
+    assert!(end.is_none());
+}
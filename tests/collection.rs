@@ -1,14 +1,16 @@
 #![allow(unused_imports)]
 #![allow(unused_parens)]
 
+use std::time::Duration;
+
 use log::trace;
-use pretty_assertions::assert_eq;
+use pretty_assertions::{assert_eq, assert_ne};
 use serde_json::{json, Value};
 
 use crate::common::{collection, connection};
 use arangors::{
     collection::{
-        options::{ChecksumOptions, PropertiesOptions},
+        options::{ChecksumOptions, CreateOptions, CreateParameters, PropertiesOptions},
         response::Status,
         CollectionType,
     },
@@ -36,6 +38,89 @@ async fn test_get_collection() {
     assert_eq!(coll.is_err(), true);
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_accessible_collections_decoded() {
+    test_setup();
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+    let all = database.accessible_collections().await.unwrap();
+    let decoded = database.accessible_collections_decoded().await.unwrap();
+    assert_eq!(decoded.is_complete(), true);
+    assert_eq!(decoded.items.len(), all.len());
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_reload_collection() {
+    test_setup();
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+    let mut coll = database.collection("test_collection").await.unwrap();
+    assert_eq!(coll.globally_unique_id().is_empty(), false);
+    assert_eq!(coll.status(), Status::Loaded);
+
+    let info = coll.info().await.unwrap();
+    assert_eq!(info.name, "test_collection");
+    assert_eq!(info.id, coll.id());
+
+    let info = coll.reload().await.unwrap();
+    assert_eq!(info.globally_unique_id, coll.globally_unique_id());
+    assert_eq!(info.status, coll.status());
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_non_system_collections() {
+    test_setup();
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+    let all = database.accessible_collections().await.unwrap();
+    assert_eq!(all.iter().any(|c| c.is_system), true);
+
+    let collections = database.non_system_collections().await.unwrap();
+    assert_eq!(collections.iter().any(|c| c.is_system), false);
+    assert_eq!(
+        collections.iter().any(|c| c.name == "test_collection"),
+        true
+    );
+    assert_eq!(
+        collections.len(),
+        all.iter().filter(|c| !c.is_system).count()
+    );
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_get_collection_cached() {
+    test_setup();
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+    let coll = database.collection_cached("test_collection").await.unwrap();
+    let cached = database.collection_cached("test_collection").await.unwrap();
+    assert_eq!(coll.id(), cached.id());
+    let coll = database
+        .collection_cached("test_collection_non_exists")
+        .await;
+    assert_eq!(coll.is_err(), true);
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -89,6 +174,47 @@ async fn test_create_and_drop_collection() {
 
     let res = coll.unwrap().drop().await;
     assert_eq!(res.is_err(), false, "Fail to drop the collection");
+
+    let coll = database
+        .create_collection_and_fetch(
+            CreateOptions::builder().name(collection_name).build(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(coll.name(), collection_name);
+    assert_eq!(coll.id().is_empty(), false);
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[cfg(feature = "cluster")]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_create_collection_with_parameters() {
+    test_setup();
+    let collection_name = "test_collection_create_with_parameters";
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+    let coll = database
+        .create_collection_with_options(
+            CreateOptions::builder()
+                .name(collection_name)
+                .replication_factor(1)
+                .build(),
+            CreateParameters::builder()
+                .wait_for_sync_replication(false)
+                .enforce_replication_factor(false)
+                .build(),
+        )
+        .await;
+    assert_eq!(coll.is_err(), false, "Fail to create the collection");
+
+    let res = database.drop_collection(collection_name).await;
+    assert_eq!(res.is_err(), false, "Fail to drop the collection");
 }
 
 #[maybe_async::test(
@@ -163,16 +289,15 @@ async fn test_get_properties() {
     assert_eq!(properties.is_err(), false);
 
     let result = properties.unwrap();
+    let features = coll.db().server_features().await.unwrap();
 
     assert_eq!(result.info.name, collection_name);
-    #[cfg(rocksdb)]
-    {
-        assert_eq!(result.detail.cache_enabled, false);
+    if features.rocksdb() {
+        assert_eq!(result.detail.cache_enabled, Some(false));
     }
-    #[cfg(mmfiles)]
-    {
-        assert_eq!(result.detail.is_volatile, false);
-        assert_eq!(result.detail.do_compact, true);
+    if features.mmfiles() {
+        assert_eq!(result.detail.is_volatile, Some(false));
+        assert_eq!(result.detail.do_compact, Some(true));
     }
     assert_eq!(result.info.is_system, false);
     assert_eq!(result.detail.wait_for_sync, false);
@@ -203,10 +328,12 @@ async fn test_get_document_count() {
     let count = coll.document_count().await;
 
     let result = count.unwrap();
+    let features = coll.db().server_features().await.unwrap();
     assert_eq!(result.info.count, Some(0));
     assert_eq!(result.info.name, collection_name);
-    #[cfg(rocksdb)]
-    assert_eq!(result.detail.cache_enabled, false);
+    if features.rocksdb() {
+        assert_eq!(result.detail.cache_enabled, Some(false));
+    }
     assert_eq!(result.info.is_system, false);
     assert_eq!(result.detail.wait_for_sync, false);
     assert_eq!(result.detail.key_options.allow_user_keys, true);
@@ -244,10 +371,12 @@ async fn test_get_statistics() {
     let statistics = coll.statistics().await;
 
     let result = statistics.unwrap();
+    let features = coll.db().server_features().await.unwrap();
     assert_eq!(result.count, Some(0), "count");
     assert_eq!(result.info.name, collection_name);
-    #[cfg(rocksdb)]
-    assert_eq!(result.detail.cache_enabled, false);
+    if features.rocksdb() {
+        assert_eq!(result.detail.cache_enabled, Some(false));
+    }
     assert_eq!(result.info.is_system, false);
     assert_eq!(result.detail.wait_for_sync, false, "wait for sync");
     assert_eq!(
@@ -263,8 +392,40 @@ async fn test_get_statistics() {
     assert_eq!(result.detail.write_concern, 1);
 
     assert_eq!(result.figures.indexes.count, Some(1));
-    #[cfg(not(feature = "mmfiles"))]
-    assert_eq!(result.figures.indexes.size, Some(0), "indexes size");
+    if !features.mmfiles() {
+        assert_eq!(result.figures.indexes.size, Some(0), "indexes size");
+    }
+    assert_eq!(result.figures.engine.is_none(), true);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_get_detailed_statistics() {
+    test_setup();
+    let collection_name = "test_collection_detailed_statistics";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+    coll.create_document(json!({ "name": "figures" }), Default::default())
+        .await
+        .unwrap();
+
+    let result = coll.detailed_statistics().await.unwrap();
+    assert_eq!(result.info.name, collection_name);
+    if !coll.db().server_features().await.unwrap().mmfiles() {
+        let engine = result.figures.engine.expect("engine figures");
+        assert_eq!(engine.documents, Some(1));
+        let primary = engine
+            .indexes
+            .iter()
+            .find(|index| index.index_type == "primary")
+            .expect("primary index figures");
+        assert_eq!(primary.count, Some(1));
+    }
 
     coll.drop().await.expect("Should drop the collection");
 }
@@ -283,9 +444,11 @@ async fn test_get_revision_id() {
     let revision = coll.revision_id().await;
 
     let result = revision.unwrap();
+    let features = coll.db().server_features().await.unwrap();
     assert_eq!(result.info.name, collection_name);
-    #[cfg(rocksdb)]
-    assert_eq!(result.detail.cache_enabled, false);
+    if features.rocksdb() {
+        assert_eq!(result.detail.cache_enabled, Some(false));
+    }
     assert_eq!(result.info.is_system, false);
     assert_eq!(result.detail.wait_for_sync, false);
     assert_eq!(result.detail.key_options.allow_user_keys, true);
@@ -300,6 +463,37 @@ async fn test_get_revision_id() {
     coll.drop().await.expect("Should drop the collection");
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_wait_for_revision_change() {
+    test_setup();
+    let collection_name = "test_collection_wait_for_revision";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+    let interval = Duration::from_millis(50);
+
+    let revision = coll.revision_id().await.unwrap().revision;
+    let unchanged = coll
+        .wait_for_revision_change(&revision, interval, Duration::from_millis(200))
+        .await
+        .unwrap();
+    assert_eq!(unchanged.is_none(), true);
+
+    coll.create_document(json!({ "name": "changed" }), Default::default())
+        .await
+        .unwrap();
+    let changed = coll
+        .wait_for_revision_change(&revision, interval, Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_ne!(changed.unwrap().revision, revision);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -498,9 +692,11 @@ async fn test_put_changes_properties() {
     let updated_properties = coll.change_properties(options).await;
 
     let result = updated_properties.unwrap();
+    let features = coll.db().server_features().await.unwrap();
     assert_eq!(result.info.name, collection_name);
-    #[cfg(rocksdb)]
-    assert_eq!(result.detail.cache_enabled, false);
+    if features.rocksdb() {
+        assert_eq!(result.detail.cache_enabled, Some(false));
+    }
     assert_eq!(result.info.is_system, false);
     assert_eq!(result.detail.wait_for_sync, true);
     assert_eq!(result.detail.key_options.allow_user_keys, true);
@@ -515,6 +711,47 @@ async fn test_put_changes_properties() {
     coll.drop().await.expect("Should drop the collection");
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_put_changes_cache_and_schema() {
+    test_setup();
+    let collection_name = "test_collection_changes_cache_and_schema";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let schema = serde_json::json!({
+        "rule": {
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+        },
+        "level": "moderate",
+        "message": "name is required"
+    });
+    let options = PropertiesOptions::builder()
+        .cache_enabled(true)
+        .schema(schema.clone())
+        .build();
+    let updated_properties = coll.change_properties(options).await;
+
+    let result = updated_properties.unwrap();
+    assert_eq!(result.info.name, collection_name);
+    if coll.db().server_features().await.unwrap().rocksdb() {
+        assert_eq!(result.detail.cache_enabled, Some(true));
+    }
+    let changed = result.detail.schema.expect("the schema should be set");
+    assert_eq!(changed["rule"], schema["rule"]);
+    assert_eq!(changed["level"], "moderate");
+    assert_eq!(changed["message"], "name is required");
+
+    let properties = coll.properties().await.unwrap();
+    assert_eq!(properties.detail.schema, Some(changed));
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -539,7 +776,6 @@ async fn test_put_rename() {
     coll.drop().await.expect("Should drop the collection");
 }
 
-#[cfg(feature = "rocksdb")]
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -553,13 +789,19 @@ async fn test_put_recalculate() {
 
     let recalculate = coll.recalculate_count().await;
 
-    let result = recalculate.unwrap();
-    assert_eq!(result, true);
+    if coll.db().server_features().await.unwrap().rocksdb() {
+        let result = recalculate.unwrap();
+        assert_eq!(result, true);
+    } else {
+        assert!(matches!(
+            recalculate,
+            Err(ClientError::UnsupportedByServer(_))
+        ));
+    }
 
     coll.drop().await.expect("Should drop the collection");
 }
 
-#[cfg(any(feature = "mmfiles"))]
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -571,7 +813,10 @@ async fn test_put_rotate_journal() {
     let conn = connection().await;
     let coll = collection(&conn, collection_name).await;
 
-    let _rotate = coll.rotate_journal().await;
+    let rotate = coll.rotate_journal().await;
+    if !coll.db().server_features().await.unwrap().mmfiles() {
+        assert!(matches!(rotate, Err(ClientError::UnsupportedByServer(_))));
+    }
 
     // TODO got error in arangoDB 3.7.1: expecting one of the actions 'load',
     // 'unload',   'truncate', 'properties', 'compact', 'rename',
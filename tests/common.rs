@@ -1,7 +1,7 @@
 #![allow(unused_imports)]
 #![allow(unused_parens)]
 use arangors::{connection::Connection, Collection, Database};
-use std::{env, future::Future};
+use std::{env, future::Future, time::Duration};
 
 pub const ARANGODB_HOST: &str = "http://localhost:8529/";
 
@@ -120,3 +120,16 @@ where
     test(get_root_user(), get_root_password()).await;
     test(get_normal_user(), get_normal_password()).await;
 }
+
+/// Wait before polling the server again.
+#[maybe_async::sync_impl]
+pub fn pause(duration: Duration) {
+    std::thread::sleep(duration)
+}
+
+/// Wait before polling the server again, with a timer working under any
+/// runtime.
+#[maybe_async::async_impl]
+pub async fn pause(duration: Duration) {
+    async_std::task::sleep(duration).await
+}
@@ -1,12 +1,18 @@
 #![allow(unused_imports)]
 #![allow(unused_parens)]
-use arangors::client::ClientExt;
+use arangors::client::{ClientExt, RequestExt};
 use pretty_assertions::assert_eq;
 
-use arangors::{connection::Permission, Connection};
+use arangors::{
+    connection::{
+        options::{ConnectionOptions, TtlProperties},
+        Auth, Permission,
+    },
+    ClientError, Connection,
+};
 use common::{
-    connection, get_arangodb_host, get_normal_password, get_normal_user, test_root_and_normal,
-    test_setup,
+    connection, get_arangodb_host, get_normal_password, get_normal_user, get_root_password,
+    get_root_user, root_connection, test_root_and_normal, test_setup,
 };
 
 pub mod common;
@@ -31,6 +37,21 @@ async fn test_list_databases() {
     };
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_databases() {
+    test_setup();
+    let conn = connection().await;
+    let dbs = conn.databases(None).await.unwrap();
+    assert_eq!(dbs.iter().any(|db| db.name() == "test_db"), true);
+
+    let dbs = conn.databases(Some(Permission::ReadWrite)).await.unwrap();
+    assert_eq!(dbs.iter().any(|db| db.name() == "test_db"), true);
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -63,6 +84,65 @@ async fn test_get_database() {
     assert_eq!(database.is_err(), true);
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_get_database_unchecked() {
+    test_setup();
+    let conn = connection().await;
+    let info = conn.db_unchecked("test_db").info().await;
+    assert_eq!(info.is_err(), false);
+    let info = conn.db_unchecked("test_db_non_exist").info().await;
+    assert_eq!(info.is_err(), true);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_read_only_connection() {
+    test_setup();
+    let conn = connection().await.into_read_only();
+    let database = conn.db("test_db_non_exist").await;
+    assert_eq!(database.is_err(), true);
+
+    let database = conn.db("test_db").await.unwrap();
+    let collection = database.collection("test_collection").await.unwrap();
+    assert_eq!(collection.name(), "test_collection");
+    let count = collection.document_count().await;
+    assert_eq!(count.is_err(), false);
+
+    let result: Vec<u32> = database.aql_str("RETURN 1").await.unwrap();
+    assert_eq!(result, vec![1]);
+
+    let result = database
+        .aql_str::<serde_json::Value>(r#"INSERT { name: "read_only" } INTO test_collection"#)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ClientError::InsufficientPermission { .. })
+    ));
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_server_time() {
+    test_setup();
+    let conn = connection().await;
+    let time = conn.server_time().await.unwrap();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs_f64();
+    assert!((time - now).abs() < 60.0, "server time {} is off", time);
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -83,6 +163,185 @@ async fn test_basic_auth() {
     assert_eq!(headers.get("Server").unwrap(), "ArangoDB");
 }
 
+#[cfg(any(feature = "reqwest_async", feature = "reqwest_blocking"))]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test)
+)]
+async fn test_establish_with_client() {
+    use arangors::{
+        client::reqwest::{ReqwestClient, ReqwestOptions},
+        connection::Auth,
+    };
+    use std::time::Duration;
+
+    test_setup();
+    let host = get_arangodb_host();
+    let user = get_normal_user();
+    let password = get_normal_password();
+
+    let options = ReqwestOptions::builder()
+        .system_proxy(false)
+        .connect_timeout(Duration::from_secs(5))
+        .timeout(Duration::from_secs(30))
+        .build();
+    let client = ReqwestClient::with_options(None, options).unwrap();
+    let conn = Connection::establish_with_client(client, host, Auth::jwt(&user, &password))
+        .await
+        .unwrap();
+    let database = conn.db("test_db").await;
+    assert_eq!(database.is_err(), false);
+}
+
+#[cfg(all(
+    feature = "toml",
+    any(feature = "reqwest_async", feature = "reqwest_blocking")
+))]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test)
+)]
+async fn test_from_profile() {
+    use arangors::{connection::profile::Profile, ClientError};
+
+    test_setup();
+    let path = std::env::temp_dir().join("arangors_test_profiles.toml");
+    let content = format!(
+        "[test]\nurl = \"{}\"\ndatabase = \"test_db\"\ntimeout = 30\n\n[test.auth]\nkind = \
+         \"jwt\"\nusername = \"{}\"\npassword = \"{}\"\n",
+        get_arangodb_host(),
+        get_normal_user(),
+        get_normal_password()
+    );
+    std::fs::write(&path, content).unwrap();
+
+    let conn = Connection::from_profile(&path, "test").await.unwrap();
+    let profile = Profile::load(&path, "test").unwrap();
+    let database = conn.db(profile.database()).await;
+    assert_eq!(database.is_err(), false);
+
+    let missing = Connection::from_profile(&path, "missing").await;
+    assert!(matches!(missing, Err(ClientError::NotFound(_))));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_set_auth() {
+    use arangors::connection::Auth;
+
+    test_setup();
+    let host = get_arangodb_host();
+    let user = get_normal_user();
+    let password = get_normal_password();
+
+    let mut conn = Connection::establish_jwt(&host, &user, &password)
+        .await
+        .unwrap();
+    let database = conn.db("test_db").await.unwrap();
+
+    let result = conn.set_auth(Auth::jwt(&user, "wrong password")).await;
+    assert_eq!(result.is_err(), true);
+    let info = database.info().await;
+    assert_eq!(info.is_err(), false);
+
+    conn.set_auth(Auth::basic(&user, "wrong password"))
+        .await
+        .unwrap();
+    let info = database.info().await;
+    assert_eq!(info.is_err(), true);
+
+    conn.set_auth(Auth::basic(&user, &password)).await.unwrap();
+    let info = database.info().await;
+    assert_eq!(info.is_err(), false);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_as_user() {
+    use arangors::connection::Auth;
+
+    test_setup();
+    let host = get_arangodb_host();
+    let user = get_normal_user();
+    let password = get_normal_password();
+
+    let conn = Connection::establish_jwt(&host, &user, &password)
+        .await
+        .unwrap();
+    let database = conn.db("test_db").await.unwrap();
+
+    let result = conn.as_user(Auth::None).await;
+    assert_eq!(result.is_err(), true);
+
+    let impostor = conn
+        .as_user(Auth::basic(&user, "wrong password"))
+        .await
+        .unwrap();
+    let info = impostor.db_unchecked("test_db").info().await;
+    assert_eq!(info.is_err(), true);
+    let info = database.info().await;
+    assert_eq!(info.is_err(), false);
+
+    let itself = impostor
+        .as_user(Auth::basic(&user, &password))
+        .await
+        .unwrap();
+    let info = itself.db_unchecked("test_db").info().await;
+    assert_eq!(info.is_err(), false);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_bearer_token() {
+    use arangors::connection::Auth;
+
+    test_setup();
+    let host = get_arangodb_host();
+    let user = get_normal_user();
+    let password = get_normal_password();
+
+    // a token issued beforehand, as by a managed deployment
+    let conn = Connection::establish_jwt(&host, &user, &password)
+        .await
+        .unwrap();
+    let url = conn.url().join("/_open/auth").unwrap();
+    let body = serde_json::json!({ "username": user, "password": password }).to_string();
+    let resp = conn.session().post(url, body).await.unwrap();
+    let jwt: serde_json::Value = serde_json::from_str(resp.body()).unwrap();
+    let token = jwt["jwt"].as_str().unwrap();
+
+    let conn = Connection::establish_bearer(&host, token).await.unwrap();
+    let databases = conn.accessible_databases().await.unwrap();
+    assert_eq!(databases.contains_key("test_db"), true);
+
+    let value = format!("bearer {}", token);
+    let conn = Connection::establish_with_client(
+        ClientExt::new(None).unwrap(),
+        host.as_str(),
+        Auth::header("authorization", &value),
+    )
+    .await
+    .unwrap();
+    let database = conn.db("test_db").await;
+    assert_eq!(database.is_err(), false);
+
+    let result = Connection::establish_bearer(&host, "not a token").await;
+    assert_eq!(result.is_err(), false);
+    let database = result.unwrap().db("test_db").await;
+    assert_eq!(database.is_err(), true);
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -103,3 +362,118 @@ async fn test_jwt() {
     }
     test_root_and_normal(jwt).await;
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_database_and_collection_permission() {
+    test_setup();
+    let conn = connection().await;
+    let user = get_normal_user();
+
+    let permission = conn.database_permission(&user, "test_db").await.unwrap();
+    assert_eq!(permission, Permission::ReadWrite);
+
+    let permission = conn
+        .collection_permission(&user, "test_db", "test_collection")
+        .await;
+    assert!(permission.is_ok(), "{:?}", permission);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_username() {
+    test_setup();
+    let conn = connection().await;
+    assert_eq!(conn.username(), Some(get_normal_user().as_str()));
+
+    let admin = root_connection().await.into_admin().await.unwrap();
+    assert_eq!(admin.username(), Some(get_root_user().as_str()));
+    let databases = admin
+        .accessible_databases_of(&get_normal_user())
+        .await
+        .unwrap();
+    assert_eq!(databases.get("test_db"), Some(&Permission::ReadWrite));
+
+    let normal = admin.into_normal();
+    assert_eq!(normal.username(), Some(get_root_user().as_str()));
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_ttl_statistics_and_properties() {
+    test_setup();
+    let conn = root_connection().await.into_admin().await.unwrap();
+
+    let statistics = conn.ttl_statistics().await;
+    assert_eq!(statistics.is_ok(), true, "{:?}", statistics);
+
+    let properties = conn.ttl_properties().await.unwrap();
+    let updated = conn
+        .set_ttl_properties(&TtlProperties::builder().frequency(60000).build())
+        .await
+        .unwrap();
+    assert_eq!(updated.frequency, Some(60000));
+
+    let restored = conn.set_ttl_properties(&properties).await.unwrap();
+    assert_eq!(restored.frequency, properties.frequency);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_validate_server() {
+    use arangors::connection::{options::ConnectionOptions, Auth};
+
+    test_setup();
+    let host = get_arangodb_host();
+    let url = url::Url::parse(&host).unwrap();
+    let validated = Connection::validate_server(&url).await;
+    assert_eq!(validated.is_ok(), true);
+
+    let conn = Connection::establish_with_options(
+        ClientExt::new(None).unwrap(),
+        host,
+        Auth::jwt(&get_normal_user(), &get_normal_password()),
+        ConnectionOptions::builder().validate_server(false).build(),
+    )
+    .await
+    .unwrap();
+    let db = conn.db("test_db").await;
+    assert_eq!(db.is_ok(), true);
+}
+
+#[cfg(feature = "cluster")]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_current_leader() {
+    test_setup();
+    let conn = root_connection().await;
+    let endpoints = conn.cluster_endpoints().await.unwrap();
+    let leader = conn.current_leader().await.unwrap();
+    assert_eq!(endpoints.first(), Some(&leader));
+
+    let (user, password) = (get_root_user(), get_root_password());
+    let conn = Connection::establish_with_options(
+        ClientExt::new(None).unwrap(),
+        get_arangodb_host(),
+        Auth::jwt(&user, &password),
+        ConnectionOptions::builder().discover_leader(true).build(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(conn.url(), &leader);
+}
@@ -1,9 +1,9 @@
 #![allow(unused_imports)]
 #![allow(unused_parens)]
 use log::trace;
-use pretty_assertions::assert_eq;
+use pretty_assertions::{assert_eq, assert_ne};
 
-use arangors::Connection;
+use arangors::{connection::License, database::DatabaseProperties, ClientError, Connection};
 use common::{
     connection, get_arangodb_host, get_normal_password, get_normal_user, get_root_password,
     get_root_user, test_root_and_normal, test_setup,
@@ -35,6 +35,14 @@ async fn test_create_and_drop_database() {
     let result = conn.db(NEW_DB_NAME).await;
     assert_eq!(result.is_err(), false);
 
+    let result = conn.rename_database(NEW_DB_NAME, "renamed_example").await;
+    assert!(matches!(result, Err(ClientError::UnsupportedByServer(_))));
+
+    let db = conn.db(NEW_DB_NAME).await.unwrap();
+    let properties = DatabaseProperties::builder().write_concern(1).build();
+    let result = db.set_properties(properties).await;
+    assert!(matches!(result, Err(ClientError::UnsupportedByServer(_))));
+
     let result = conn.drop_database(NEW_DB_NAME).await;
     if let Err(e) = result {
         assert!(false, "Fail to drop database: {:?}", e)
@@ -81,8 +89,15 @@ async fn test_get_version() {
     let db = conn.db("test_db").await.unwrap();
     let version = db.arango_version().await.unwrap();
     trace!("{:?}", version);
-    assert_eq!(version.license, "community");
+    assert_eq!(version.license, License::Community);
     assert_eq!(version.server, "arango");
+    assert_eq!(version.details.is_none(), true);
+
+    let version = db.arango_version_details().await.unwrap();
+    let details = version.details.unwrap();
+    assert_eq!(details.mode.as_deref(), Some("server"));
+    assert_eq!(details.architecture.is_some(), true);
+    assert_eq!(details.other.is_empty(), false);
 
     let re = regex::Regex::new(r"3\.\d+\.\d+").unwrap();
     assert_eq!(
@@ -92,3 +107,74 @@ async fn test_get_version() {
         version.version
     );
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_namespace() {
+    test_setup();
+    let conn = connection().await;
+    let db = conn.db("test_db").await.unwrap();
+    let tenant = db.namespace("tenant1");
+    assert_eq!(tenant.collection_name("users"), "tenant1_users");
+
+    let _ = tenant.drop_collection("users").await;
+    let users = tenant.create_collection("users").await.unwrap();
+    assert_eq!(users.name(), "tenant1_users");
+    let users = tenant.collection("users").await.unwrap();
+    assert_eq!(users.name(), "tenant1_users");
+
+    let collections = tenant.accessible_collections().await.unwrap();
+    assert!(collections
+        .iter()
+        .all(|info| info.name.starts_with("tenant1_")));
+    assert!(collections.iter().any(|info| info.name == "tenant1_users"));
+
+    let names: Vec<String> = tenant
+        .aql_bind_vars(
+            "INSERT { name: @name } INTO @@users RETURN NEW.name",
+            [("@users", "users"), ("name", "alice")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(names, vec!["alice".to_owned()]);
+
+    tenant.drop_collection("users").await.unwrap();
+}
+
+#[cfg(feature = "testing")]
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_temp_database_and_collection() {
+    use arangors::testing::{TempCollection, TempDatabase};
+
+    test_setup();
+    let host = get_arangodb_host();
+    let conn = Connection::establish_jwt(&host, &get_root_user(), &get_root_password())
+        .await
+        .unwrap();
+
+    let temp = TempDatabase::new(&conn).await.unwrap();
+    let name = temp.name().to_owned();
+    assert_eq!(name.starts_with("arangors_test_"), true);
+    let other = TempDatabase::new(&conn).await.unwrap();
+    assert_ne!(other.name(), name);
+    other.drop().await.unwrap();
+
+    let collection = TempCollection::new(&temp).await.unwrap();
+    let collection_name = collection.name().to_owned();
+    let result = temp.collection(&collection_name).await;
+    assert_eq!(result.is_ok(), true);
+    collection.drop().await.unwrap();
+    let result = temp.collection(&collection_name).await;
+    assert_eq!(result.is_err(), true);
+
+    temp.drop().await.unwrap();
+    let result = conn.db(&name).await;
+    assert_eq!(result.is_err(), true);
+}
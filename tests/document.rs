@@ -6,9 +6,11 @@ use pretty_assertions::assert_eq;
 use serde_json::{json, Value};
 
 use arangors::{
+    collection::options::{ImportOptions, OnDuplicate},
     document::{
         options::{
-            InsertOptions, OverwriteMode, ReadOptions, RemoveOptions, ReplaceOptions, UpdateOptions,
+            Durability, InsertOptions, KeyStrategy, OverwriteMode, ReadOptions, RemoveOptions,
+            ReplaceOptions, UpdateOptions,
         },
         response::DocumentResponse,
     },
@@ -21,7 +23,6 @@ use std::{convert::TryInto, ptr::null};
 
 pub mod common;
 
-#[cfg(not(feature = "arango3_7"))]
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -136,12 +137,20 @@ async fn test_post_create_document() {
     let result = create.unwrap();
 
     assert_eq!(result.is_silent(), true);
+    assert_eq!(
+        result.etag().is_some(),
+        true,
+        "We should get the revision from the Etag header"
+    );
+    assert_eq!(
+        result.location().is_some(),
+        true,
+        "We should get the path from the Location header"
+    );
 
     coll.drop().await.expect("Should drop the collection");
 }
 
-/// TODO need to use CI to validate this test
-#[cfg(any(feature = "arango3_7"))]
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -151,6 +160,16 @@ async fn test_post_create_document_3_7() {
     test_setup();
     let collection_name = "test_collection_create_document_3_7";
     let conn = connection().await;
+    let features = conn
+        .db("test_db")
+        .await
+        .unwrap()
+        .server_features()
+        .await
+        .unwrap();
+    if !features.overwrite_mode() {
+        return;
+    }
     let coll = collection(&conn, collection_name).await;
 
     let test_doc: Document<Value> = Document::new(json!({ "no":1 ,
@@ -193,7 +212,7 @@ async fn test_post_create_document_3_7() {
     let result = create.unwrap();
 
     assert_eq!(
-        result.return_new().is_some(),
+        result.new_doc().is_some(),
         true,
         "we should get the new document under 'new' property"
     );
@@ -207,7 +226,7 @@ async fn test_post_create_document_3_7() {
     assert_eq!(header._rev.is_empty(), false);
     assert_eq!(header._key.is_empty(), false);
 
-    let key = header._key;
+    let key = header._key.clone();
     // Third test is to update a simple document with option return old
     // Should not return  anything according to doc if overWriteMode is not used for
     // now TODO update this test with overwriteMode later
@@ -265,12 +284,14 @@ async fn test_post_create_document_3_7() {
             test_doc,
             InsertOptions::builder()
                 .return_new(true)
-                .overwrite_mode(OverwriteMode::Ignore),
+                .overwrite_mode(OverwriteMode::Ignore)
+                .build(),
         )
         .await;
 
     let result = update.unwrap();
 
+    assert_eq!(result.is_ignored(), true);
     assert_eq!(result.new_doc().is_none(), true);
     assert_eq!(result.old_doc().is_none(), true);
     assert_eq!(result.header().is_none(), true);
@@ -283,7 +304,10 @@ async fn test_post_create_document_3_7() {
     let update = coll
         .create_document(
             test_doc,
-            InsertOptions::builder().overwrite_mode(OverwriteMode::Replace),
+            InsertOptions::builder()
+                .return_new(true)
+                .overwrite_mode(OverwriteMode::Replace)
+                .build(),
         )
         .await;
 
@@ -307,7 +331,10 @@ async fn test_post_create_document_3_7() {
     let update = coll
         .create_document(
             test_doc,
-            InsertOptions::builder().overwrite_mode(OverwriteMode::Update),
+            InsertOptions::builder()
+                .return_new(true)
+                .overwrite_mode(OverwriteMode::Update)
+                .build(),
         )
         .await;
 
@@ -324,6 +351,314 @@ async fn test_post_create_document_3_7() {
     coll.drop().await.expect("Should drop the collection");
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_post_create_documents() {
+    test_setup();
+    let collection_name = "test_collection_create_documents";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let docs = vec![
+        json!({ "_key": "a", "no": 1, "tags": { "x": 1 } }),
+        json!({ "_key": "b", "no": 2 }),
+    ];
+    let created = coll
+        .create_documents(docs, InsertOptions::builder().return_new(true).build())
+        .await
+        .unwrap();
+    assert_eq!(created.len(), 2);
+    let new_doc = created[0].as_ref().unwrap().new_doc().unwrap();
+    assert_eq!(new_doc["no"], 1);
+
+    // a duplicated key only fails its own item
+    let docs = vec![
+        json!({ "_key": "a", "no": 3 }),
+        json!({ "_key": "c", "no": 4 }),
+    ];
+    let created = coll
+        .create_documents(docs, Default::default())
+        .await
+        .unwrap();
+    assert_eq!(created[0].is_err(), true);
+    assert_eq!(created[0].as_ref().err().unwrap().error_num(), 1210);
+    assert_eq!(created[1].as_ref().unwrap().header().unwrap()._key, "c");
+
+    let features = conn
+        .db("test_db")
+        .await
+        .unwrap()
+        .server_features()
+        .await
+        .unwrap();
+    if features.overwrite_mode() {
+        let docs = vec![
+            json!({ "_key": "a", "tags": { "y": 2 } }),
+            json!({ "_key": "d", "no": 5 }),
+        ];
+        let upserted = coll
+            .create_documents(
+                docs,
+                InsertOptions::builder()
+                    .overwrite_mode(OverwriteMode::Update)
+                    .merge_objects(true)
+                    .return_old(true)
+                    .return_new(true)
+                    .build(),
+            )
+            .await
+            .unwrap();
+        let first = upserted[0].as_ref().unwrap();
+        assert_eq!(first.old_doc().unwrap()["tags"], json!({ "x": 1 }));
+        assert_eq!(first.new_doc().unwrap()["tags"], json!({ "x": 1, "y": 2 }));
+        assert_eq!(first.new_doc().unwrap()["no"], 1);
+        let second = upserted[1].as_ref().unwrap();
+        assert_eq!(second.old_doc().is_none(), true);
+        assert_eq!(second.new_doc().unwrap()["no"], 5);
+    }
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_post_create_documents_raw() {
+    test_setup();
+    let collection_name = "test_collection_create_documents_raw";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let created: DocumentResponse<Value> = coll
+        .create_document_raw(
+            r#"{"_key":"a","no":1}"#,
+            InsertOptions::builder().return_new(true).build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(created.new_doc().unwrap()["no"], 1);
+
+    let docs = vec![r#"{"_key":"b","no":2}"#, r#"{"_key":"a","no":3}"#];
+    let created: Vec<Result<DocumentResponse<Value>, _>> = coll
+        .create_documents_raw(docs, Default::default())
+        .await
+        .unwrap();
+    assert_eq!(created[0].as_ref().unwrap().header().unwrap()._key, "b");
+    assert_eq!(created[1].as_ref().err().unwrap().error_num(), 1210);
+
+    fn raw_key() -> String {
+        "raw-42".to_owned()
+    }
+    let options = InsertOptions::default().with_generated_key(KeyStrategy::Custom(raw_key));
+    let generated = coll
+        .create_document_raw::<Value>(r#"{"no":4}"#, options)
+        .await;
+    assert_eq!(
+        matches!(generated, Err(ClientError::InvalidArgument(_))),
+        true
+    );
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_post_create_document_ignored() {
+    test_setup();
+    let collection_name = "test_collection_create_document_ignored";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let options = InsertOptions::builder()
+        .return_new(true)
+        .overwrite_mode(OverwriteMode::Ignore)
+        .build();
+    let created = coll
+        .create_document(json!({ "_key": "ignored", "no": 1 }), options.clone())
+        .await
+        .unwrap();
+    assert!(created.has_response());
+    assert_eq!(created.new_doc().unwrap()["no"], 1);
+
+    let ignored = coll
+        .create_document(json!({ "_key": "ignored", "no": 2 }), options)
+        .await
+        .unwrap();
+    assert!(
+        ignored.is_ignored(),
+        "the second insertion should be ignored"
+    );
+    assert!(ignored.new_doc().is_none());
+
+    let doc: Document<Value> = coll.document("ignored").await.unwrap();
+    assert_eq!(doc.document["no"], 1);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_post_create_document_generated_key() {
+    test_setup();
+    let collection_name = "test_collection_create_document_generated_key";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    fn order_key() -> String {
+        "order-42".to_owned()
+    }
+    let options = InsertOptions::builder()
+        .build()
+        .with_generated_key(KeyStrategy::Custom(order_key));
+    assert_eq!(options.key(), Some("order-42"));
+
+    let created = coll
+        .create_document(json!({ "total": 10 }), options.clone())
+        .await
+        .unwrap();
+    assert_eq!(created.header().unwrap()._key, "order-42");
+
+    // retrying with the same options does not duplicate the document
+    let retry = coll
+        .create_document(json!({ "total": 10 }), options.clone())
+        .await;
+    match retry {
+        Err(ClientError::Arango(err)) => assert_eq!(err.error_num(), 1210),
+        _ => panic!("expected a unique constraint violation"),
+    }
+
+    // an explicit key takes precedence
+    let created = coll
+        .create_document(json!({ "_key": "explicit", "total": 1 }), options)
+        .await
+        .unwrap();
+    assert_eq!(created.header().unwrap()._key, "explicit");
+
+    #[cfg(feature = "uuid")]
+    {
+        let options = InsertOptions::default().with_generated_key(KeyStrategy::UuidV7);
+        let key = options.key().unwrap().to_owned();
+        let created = coll
+            .create_document(json!({ "total": 2 }), options)
+            .await
+            .unwrap();
+        assert_eq!(created.header().unwrap()._key, key);
+    }
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_synced_durability() {
+    test_setup();
+    let collection_name = "test_collection_synced_durability";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let created = coll
+        .create_document(
+            json!({ "_key": "payment", "amount": 10 }),
+            InsertOptions::builder()
+                .durability(Durability::Synced)
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(created.header().unwrap()._key, "payment");
+
+    let updated = coll
+        .update_document(
+            "payment",
+            json!({ "amount": 20 }),
+            UpdateOptions::builder()
+                .return_new(true)
+                .durability(Durability::Synced)
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.new_doc().unwrap()["amount"], 20);
+
+    let removed: DocumentResponse<Value> = coll
+        .remove_document(
+            "payment",
+            RemoveOptions::builder()
+                .durability(Durability::Synced)
+                .build(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(removed.header().unwrap()._key, "payment");
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_default_wait_for_sync() {
+    test_setup();
+    let collection_name = "test_collection_default_wait_for_sync";
+    let conn = connection().await;
+    collection(&conn, collection_name).await;
+
+    let mut database = conn.db("test_db").await.unwrap();
+    assert_eq!(database.default_wait_for_sync(), None);
+    let cached = database.collection_cached(collection_name).await.unwrap();
+    assert_eq!(cached.default_wait_for_sync(), None);
+
+    database.set_default_wait_for_sync(Some(true));
+    let coll = database.collection(collection_name).await.unwrap();
+    assert_eq!(coll.default_wait_for_sync(), Some(true));
+    let cached = database.collection_cached(collection_name).await.unwrap();
+    assert_eq!(cached.default_wait_for_sync(), Some(true));
+
+    let created = coll
+        .create_document(json!({ "_key": "order", "amount": 10 }), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(created.header().unwrap()._key, "order");
+
+    let updated = coll
+        .update_document(
+            "order",
+            json!({ "amount": 20 }),
+            UpdateOptions::builder()
+                .return_new(true)
+                .wait_for_sync(false)
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.new_doc().unwrap()["amount"], 20);
+
+    let removed: DocumentResponse<Value> = coll
+        .remove_document("order", Default::default(), None)
+        .await
+        .unwrap();
+    assert_eq!(removed.header().unwrap()._key, "order");
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -375,6 +710,32 @@ async fn test_get_read_document() {
     coll.drop().await.expect("Should drop the collection");
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_get_random_document() {
+    test_setup();
+    let collection_name = "test_collection_random_document";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let random: Option<Document<Value>> = coll.random_document().await.unwrap();
+    assert_eq!(random.is_none(), true);
+
+    for no in 0..3 {
+        let test_doc: Document<Value> = Document::new(json!({ "no": no }));
+        let create = coll.create_document(test_doc, Default::default()).await;
+        assert_eq!(create.is_ok(), true, "succeed create a document");
+    }
+    let random: Option<Document<Value>> = coll.random_document().await.unwrap();
+    let no = random.unwrap().document["no"].as_u64().unwrap();
+    assert!(no < 3);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -529,6 +890,318 @@ async fn test_patch_update_document() {
     // todo do more test for merge objects and stuff
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_document_with_meta() {
+    test_setup();
+    let collection_name = "test_collection_document_with_meta";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let created = coll
+        .create_document(json!({ "no": 1 }), Default::default())
+        .await
+        .unwrap();
+    let header = created.header().unwrap();
+
+    let (doc, meta) = coll
+        .document_with_meta::<Value>(&header._key, Default::default())
+        .await
+        .unwrap();
+    assert_eq!(doc["no"], 1);
+    assert_eq!(meta.revision(), Some(header._rev.as_str()));
+    assert_eq!(meta.potential_dirty_read(), false);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_documents_by_keys() {
+    test_setup();
+    let collection_name = "test_collection_documents_by_keys";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    for no in 1..=3 {
+        coll.create_document(
+            json!({ "_key": format!("doc{}", no), "no": no, "wide": "x".repeat(64) }),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let result = coll
+        .documents_by_keys::<Value, _>(&["doc3", "nope", "doc1", "nope", "gone"])
+        .await
+        .unwrap();
+    let mut found: Vec<u64> = result
+        .found
+        .iter()
+        .map(|doc| doc.document["no"].as_u64().unwrap())
+        .collect();
+    found.sort_unstable();
+    assert_eq!(found, vec![1, 3]);
+    assert_eq!(result.missing, vec!["nope", "gone"]);
+
+    let projected = coll
+        .documents_by_keys_with_projection::<Value, _, _>(&["doc2", "nope"], &["no"])
+        .await
+        .unwrap();
+    assert_eq!(projected.found.len(), 1);
+    assert_eq!(projected.found[0].header._key, "doc2");
+    assert_eq!(projected.found[0].document["no"], 2);
+    assert_eq!(projected.found[0].document.get("wide"), None);
+    assert_eq!(projected.missing, vec!["nope"]);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_remove_by_keys_and_example() {
+    test_setup();
+    let collection_name = "test_collection_remove_by_keys";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    for no in 1..=5 {
+        coll.create_document(
+            json!({ "_key": format!("doc{}", no), "odd": no % 2 == 1 }),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let removed = coll
+        .remove_by_keys(&["doc1", "doc2", "nope"], Default::default())
+        .await
+        .unwrap();
+    assert_eq!(removed.removed, 2);
+    assert_eq!(removed.ignored, 1);
+
+    let removed = coll
+        .remove_by_example(json!({ "odd": true }))
+        .await
+        .unwrap();
+    assert_eq!(removed, 2);
+
+    let left = coll
+        .documents_by_keys::<Value, _>(&["doc1", "doc2", "doc3", "doc4", "doc5"])
+        .await
+        .unwrap();
+    assert_eq!(left.found.len(), 1);
+    assert_eq!(left.found[0].header._key, "doc4");
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_import_documents() {
+    test_setup();
+    let collection_name = "test_collection_import";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let docs = (0..25).map(|no| json!({ "_key": format!("doc{}", no), "no": no }));
+    let options = ImportOptions::builder().batch_size(10).build();
+    let mut sent = Vec::new();
+    let summary = coll
+        .import_documents(docs, options, |progress| sent.push(progress.documents))
+        .await
+        .unwrap();
+    assert_eq!(summary.created, 25);
+    assert_eq!(summary.errors, 0);
+    assert_eq!(sent, vec![10, 20, 25]);
+
+    let lines = "{\"_key\":\"doc0\",\"no\":100}\n\n{\"_key\":\"doc30\",\"no\":30}\n";
+    let options = ImportOptions::builder()
+        .on_duplicate(OnDuplicate::Update)
+        .batch_size(1)
+        .build();
+    let mut requests = 0;
+    let summary = coll
+        .import_json_lines(lines.as_bytes(), options, |progress| {
+            requests = progress.requests
+        })
+        .await
+        .unwrap();
+    assert_eq!(summary.created, 1);
+    assert_eq!(summary.updated, 1);
+    assert_eq!(requests, 2);
+
+    let doc: Document<Value> = coll.document("doc0").await.unwrap();
+    assert_eq!(doc.document["no"], 100);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_merge_patch_document() {
+    test_setup();
+    let collection_name = "test_collection_merge_patch_document";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let created = coll
+        .create_document(
+            json!({ "title": "Goodbye!", "author": { "givenName": "John", "familyName": "Doe" },
+            "tags": ["example", "sample"], "content": "This will be unchanged" }),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    let _key = created.header().unwrap()._key.clone();
+
+    let patched = coll
+        .merge_patch_document::<Value, _>(
+            _key.as_str(),
+            json!({ "title": "Hello!", "phoneNumber": "+01-123-456-7890",
+            "author": { "familyName": null }, "tags": ["example"] }),
+        )
+        .await
+        .unwrap();
+    let new_doc = patched.new_doc().unwrap();
+    assert_eq!(new_doc["title"], "Hello!");
+    assert_eq!(new_doc["author"], json!({ "givenName": "John" }));
+    assert_eq!(new_doc["tags"], json!(["example"]));
+    assert_eq!(new_doc["content"], "This will be unchanged");
+    assert_eq!(new_doc["phoneNumber"], "+01-123-456-7890");
+
+    let not_object = coll
+        .merge_patch_document::<Value, _>(_key.as_str(), json!(["example"]))
+        .await;
+    assert!(matches!(not_object, Err(ClientError::InvalidArgument(_))));
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_patch_update_document_revisions() {
+    test_setup();
+    let collection_name = "test_collection_update_document_revisions";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let created = coll
+        .create_document(json!({ "no": 1 }), Default::default())
+        .await
+        .unwrap();
+    let key = created.header().unwrap()._key.clone();
+    let first_rev = created.rev().unwrap().to_owned();
+
+    // revisions are returned without return_old nor return_new
+    let updated = coll
+        .update_document(&key, json!({ "no": 2 }), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(updated.old_rev().unwrap(), &first_rev);
+    let second_rev = updated.rev().unwrap().to_owned();
+    assert_ne!(second_rev, first_rev);
+
+    // and from the Etag header of a silent response
+    let updated = coll
+        .update_document(
+            &key,
+            json!({ "no": 3 }),
+            UpdateOptions::builder().silent(true).build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.is_silent(), true);
+    let third_rev = updated.rev().unwrap();
+    assert_ne!(third_rev, second_rev);
+
+    let header = coll.document_header(&key).await.unwrap();
+    assert_eq!(header._rev, third_rev);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_update_document_as() {
+    #[derive(Debug, serde::Deserialize)]
+    struct UserV1 {
+        name: String,
+    }
+    #[derive(Debug, serde::Deserialize)]
+    struct UserV2 {
+        first_name: String,
+        last_name: String,
+        name: Option<String>,
+    }
+
+    test_setup();
+    let collection_name = "test_collection_update_document_as";
+    let conn = connection().await;
+    let coll = collection(&conn, collection_name).await;
+
+    let created = coll
+        .create_document(json!({ "name": "Ada Lovelace" }), Default::default())
+        .await
+        .unwrap();
+    let key = created.header().unwrap()._key.clone();
+
+    let migrated = coll
+        .update_document_as::<_, UserV2, UserV1>(
+            &key,
+            json!({ "first_name": "Ada", "last_name": "Lovelace", "name": null }),
+            UpdateOptions::builder()
+                .keep_null(false)
+                .return_old(true)
+                .return_new(true)
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(migrated.old_doc().unwrap().name, "Ada Lovelace");
+    let new = migrated.new_doc().unwrap();
+    assert_eq!(new.first_name, "Ada");
+    assert_eq!(new.last_name, "Lovelace");
+    assert_eq!(new.name, None);
+
+    let replaced = coll
+        .replace_document_as::<_, Value, UserV2>(
+            &key,
+            json!({ "name": "Ada King" }),
+            ReplaceOptions::builder().return_old(true).build(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(replaced.old_doc().unwrap().first_name, "Ada");
+    assert_eq!(replaced.new_doc().is_none(), true);
+
+    coll.drop().await.expect("Should drop the collection");
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
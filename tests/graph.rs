@@ -13,7 +13,7 @@ use arangors::{
         CollectionType,
     },
     graph::*,
-    ClientError, Connection, Database, Document,
+    AqlQuery, ClientError, Connection, Database, Document,
 };
 use common::{get_arangodb_host, get_normal_password, get_normal_user, test_setup};
 
@@ -110,6 +110,48 @@ async fn test_complex_graph() {
     // assert_eq!(options.smart_graph_attribute.unwrap(), "region".to_string());
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_complex_graph_builder() {
+    test_setup();
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+    // Cleanup
+    drop_graph(&database, "test_complex_graph_builder").await;
+
+    let graph = Graph::builder()
+        .name("test_complex_graph_builder")
+        .edge_definition(EdgeDefinition::new(
+            "some_edge_builder",
+            ["from_collection_builder"],
+            ["to_collection_builder"],
+        ))
+        .orphan("some_collection_builder")
+        .smart("region")
+        .is_disjoint(Some(false))
+        .number_of_shards(2)
+        .replication_factor(10)
+        .write_concern(8)
+        .build();
+    assert_eq!(graph.is_smart, Some(true));
+    let options = graph.options.clone().unwrap();
+    assert_eq!(options.smart_graph_attribute, Some("region".to_string()));
+    assert_eq!(options.number_of_shards, Some(2));
+    assert_eq!(options.replication_factor, Some(10));
+    assert_eq!(options.write_concern, Some(8));
+
+    let result = database.create_graph(graph, true).await.unwrap();
+    assert_eq!(result.name, "test_complex_graph_builder".to_string());
+    assert_eq!(
+        result.orphan_collections,
+        vec!["some_collection_builder".to_string()]
+    );
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -159,6 +201,42 @@ async fn test_graph_retrieval() {
     assert_eq!(result.name, "test_graph2");
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_exists_graph() {
+    test_setup();
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+    // Cleanup
+    drop_graph(&database, "test_graph_exists").await;
+
+    let graph = Graph::builder()
+        .name("test_graph_exists".to_string())
+        .edge_definitions(vec![EdgeDefinition {
+            collection: "some_edge_exists".to_string(),
+            from: vec!["from_collection_exists".to_string()],
+            to: vec!["to_collection".to_string()],
+        }])
+        .build();
+    database.create_graph(graph, true).await.unwrap();
+
+    let exists = database.exists_graph("test_graph_exists").await.unwrap();
+    assert_eq!(exists, true);
+    let exists = database.exists_graph("test_graph_missing").await.unwrap();
+    assert_eq!(exists, false);
+
+    database
+        .drop_graph("test_graph_exists", true)
+        .await
+        .unwrap();
+    let exists = database.exists_graph("test_graph_exists").await.unwrap();
+    assert_eq!(exists, false);
+}
+
 // This tests the default value of `orphanCollections` which can't be optional
 // but can be empty
 #[test]
@@ -172,3 +250,205 @@ fn minimal_serialization_works() {
     let graph: Graph = serde_json::from_value(json).unwrap();
     assert!(graph.orphan_collections.is_empty());
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_traversal() {
+    test_setup();
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let vertices = "test_traversal_vertices";
+    let edges = "test_traversal_edges";
+    let _ = database.drop_collection(vertices).await;
+    let _ = database.drop_collection(edges).await;
+    database.create_collection(vertices).await.unwrap();
+    database.create_edge_collection(edges).await.unwrap();
+
+    // a chain v0 -> v1 -> ... -> v9
+    let aql = AqlQuery::builder()
+        .query("FOR i IN 0..9 INSERT { _key: TO_STRING(i) } INTO @@vertices")
+        .bind_var("@vertices", vertices)
+        .build();
+    let _: Vec<Value> = database.aql_query(aql).await.unwrap();
+    let aql = AqlQuery::builder()
+        .query(
+            "FOR i IN 0..8 INSERT { _from: CONCAT(@vertices, '/', i), _to: CONCAT(@vertices, '/', \
+             i + 1) } INTO @@edges",
+        )
+        .bind_var("vertices", vertices)
+        .bind_var("@edges", edges)
+        .build();
+    let _: Vec<Value> = database.aql_query(aql).await.unwrap();
+
+    let start = format!("{}/0", vertices);
+    let aql = AqlQuery::builder()
+        .query(
+            "FOR v, e, p IN 1..5 OUTBOUND @start @@edges \
+             RETURN { vertex: v, edge: e, path: p }",
+        )
+        .bind_var("start", start.as_str())
+        .bind_var("@edges", edges)
+        .batch_size(2)
+        .build();
+    let mut traversal = database.aql_traversal::<Value, Value>(aql).await.unwrap();
+    let mut depth = 0;
+    while let Some(step) = traversal.next_step().await.unwrap() {
+        depth += 1;
+        assert_eq!(step.vertex["_key"], depth.to_string());
+        assert_eq!(step.edge.is_some(), true);
+        assert_eq!(step.path.unwrap().edges.len(), depth);
+    }
+    assert_eq!(depth, 5);
+
+    database.drop_collection(vertices).await.unwrap();
+    database.drop_collection(edges).await.unwrap();
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_graph_handle() {
+    test_setup();
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+    // Cleanup
+    drop_graph(&database, "test_graph_handle").await;
+
+    let graph = Graph::builder()
+        .name("test_graph_handle".to_string())
+        .edge_definitions(vec![EdgeDefinition {
+            collection: "handle_edge".to_string(),
+            from: vec!["handle_person".to_string()],
+            to: vec!["handle_person".to_string()],
+        }])
+        .build();
+    database.create_graph(graph, true).await.unwrap();
+
+    let handle = database.graph_handle("test_graph_handle");
+    let info = handle.info().await.unwrap();
+    assert_eq!(info.name, "test_graph_handle");
+
+    let graph = handle.add_vertex_collection("handle_place").await.unwrap();
+    assert_eq!(graph.orphan_collections, vec!["handle_place".to_string()]);
+    let mut vertex_collections = handle.vertex_collections().await.unwrap();
+    vertex_collections.sort();
+    assert_eq!(vertex_collections, vec!["handle_person", "handle_place"]);
+
+    let alice = handle
+        .create_vertex("handle_person", &json!({ "name": "alice" }))
+        .await
+        .unwrap();
+    let bob = handle
+        .create_vertex("handle_person", &json!({ "name": "bob" }))
+        .await
+        .unwrap();
+    let vertex: Document<Value> = handle.vertex("handle_person", &alice._key).await.unwrap();
+    assert_eq!(vertex.document["name"], "alice");
+
+    let edge = handle
+        .create_edge(
+            "handle_edge",
+            &json!({ "_from": alice._id, "_to": bob._id, "since": 2020 }),
+        )
+        .await
+        .unwrap();
+    let read: Document<Value> = handle.edge("handle_edge", &edge._key).await.unwrap();
+    assert_eq!(read.document["since"], 2020);
+
+    // removing a vertex removes its edges
+    let removed = handle
+        .remove_vertex("handle_person", &bob._key)
+        .await
+        .unwrap();
+    assert_eq!(removed, true);
+    let read = handle.edge::<Value>("handle_edge", &edge._key).await;
+    assert_eq!(read.is_err(), true);
+
+    let graph = handle
+        .replace_edge_definition(&EdgeDefinition {
+            collection: "handle_edge".to_string(),
+            from: vec!["handle_person".to_string()],
+            to: vec!["handle_place".to_string()],
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        graph.edge_definitions[0].to,
+        vec!["handle_place".to_string()]
+    );
+    let edge_collections = handle.edge_collections().await.unwrap();
+    assert_eq!(edge_collections, vec!["handle_edge".to_string()]);
+
+    handle.drop(true).await.unwrap();
+    let info = database.graph("test_graph_handle").await;
+    assert_eq!(info.is_err(), true);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_truncate_and_drop_with_collections() {
+    test_setup();
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    drop_all_graphs(&database, vec!["test_graph_cascade", "test_graph_sharing"]).await;
+
+    let graph = Graph::builder()
+        .name("test_graph_cascade".to_string())
+        .edge_definitions(vec![EdgeDefinition {
+            collection: "cascade_edges".to_string(),
+            from: vec!["cascade_vertices".to_string()],
+            to: vec!["cascade_shared".to_string()],
+        }])
+        .build();
+    database.create_graph(graph, true).await.unwrap();
+    let sharing = Graph::builder()
+        .name("test_graph_sharing".to_string())
+        .edge_definitions(vec![])
+        .orphan_collections(vec!["cascade_shared".to_string()])
+        .build();
+    database.create_graph(sharing, true).await.unwrap();
+
+    let handle = database.graph_handle("test_graph_cascade");
+    let from = handle
+        .create_vertex("cascade_vertices", &json!({ "_key": "a" }))
+        .await
+        .unwrap();
+    let to = handle
+        .create_vertex("cascade_shared", &json!({ "_key": "b" }))
+        .await
+        .unwrap();
+    handle
+        .create_edge(
+            "cascade_edges",
+            &json!({ "_from": from._id, "_to": to._id }),
+        )
+        .await
+        .unwrap();
+
+    handle.truncate_all().await.unwrap();
+    let edges = database.collection("cascade_edges").await.unwrap();
+    let count = edges.document_count().await.unwrap().info.count;
+    assert_eq!(count, Some(0));
+
+    let mut dropped = handle.drop_with_collections().await.unwrap();
+    dropped.sort();
+    assert_eq!(dropped, vec!["cascade_edges", "cascade_vertices"]);
+    let exists = database.exists_graph("test_graph_cascade").await.unwrap();
+    assert_eq!(exists, false);
+    let shared = database.collection("cascade_shared").await;
+    assert!(shared.is_ok());
+
+    database
+        .drop_graph("test_graph_sharing", true)
+        .await
+        .unwrap();
+}
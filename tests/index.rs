@@ -1,18 +1,23 @@
 #![allow(unused_imports)]
 #![allow(unused_parens)]
 
+use std::time::Duration;
+
 use log::trace;
 use pretty_assertions::assert_eq;
 use serde_json::{json, Value};
 
-use crate::common::{collection, connection};
+use crate::common::{collection, connection, pause};
 use arangors::{
     collection::{
         options::{ChecksumOptions, PropertiesOptions},
         response::Status,
         CollectionType,
     },
-    index::{Index, IndexSettings},
+    index::{
+        EdgeDirection, Index, IndexProgress, IndexSettings, InvertedIndexField,
+        InvertedIndexSettings,
+    },
     ClientError, Connection, Document,
 };
 use common::{get_arangodb_host, get_normal_password, get_normal_user, test_setup};
@@ -173,7 +178,10 @@ async fn test_geo_index() {
     let index = Index::builder()
         .name(index_name)
         .fields(vec!["password".to_string()])
-        .settings(IndexSettings::Geo { geo_json: false })
+        .settings(IndexSettings::Geo {
+            geo_json: false,
+            legacy_polygons: None,
+        })
         .build();
 
     let index = database
@@ -187,11 +195,58 @@ async fn test_geo_index() {
     assert_eq!(index.name, index_name.to_string());
     assert_eq!(delete_result.id, index.id);
 
-    if let IndexSettings::Geo { geo_json } = index.settings {
+    if let IndexSettings::Geo { geo_json, .. } = index.settings {
         assert_eq!(geo_json, false);
     }
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_geo_json_index() {
+    test_setup();
+    let collection_name = "test_collection";
+    let index_name = "idx_geo_json_test";
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+
+    let index = Index::builder()
+        .name(index_name)
+        .fields(vec!["location".to_string()])
+        .settings(IndexSettings::Geo {
+            geo_json: true,
+            legacy_polygons: None,
+        })
+        .build();
+
+    let index = database
+        .create_index(collection_name, &index)
+        .await
+        .unwrap();
+    let delete_result = database.delete_index(&index.id).await.unwrap();
+    assert_eq!(delete_result.id, index.id);
+
+    match index.settings {
+        IndexSettings::Geo { geo_json, .. } => assert!(geo_json),
+        other => panic!("expected a geo index, got {:?}", other),
+    }
+
+    // geoJson reads a single attribute, the server would accept two
+    let invalid = Index::builder()
+        .name(index_name)
+        .fields(vec!["lat".to_string(), "lng".to_string()])
+        .settings(IndexSettings::Geo {
+            geo_json: true,
+            legacy_polygons: None,
+        })
+        .build();
+    let result = database.create_index(collection_name, &invalid).await;
+    assert!(matches!(result, Err(ClientError::InvalidArgument(_))));
+}
+
 #[maybe_async::test(
     any(feature = "reqwest_blocking"),
     async(any(feature = "reqwest_async"), tokio::test),
@@ -277,3 +332,178 @@ async fn test_list_indexes() {
 
     assert!(list.indexes.len() > 0);
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_inverted_index() {
+    test_setup();
+    let collection_name = "test_collection";
+    let index_name = "idx_inverted_test";
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+
+    let index = Index::builder()
+        .name(index_name)
+        .fields(vec!["username".to_string(), "password".to_string()])
+        .settings(IndexSettings::Inverted(Box::new(
+            InvertedIndexSettings::builder()
+                .analyzer("identity")
+                .fields(vec![InvertedIndexField::builder()
+                    .name("username")
+                    .analyzer("text_en")
+                    .build()])
+                .build(),
+        )))
+        .build();
+
+    let index = database
+        .create_index(collection_name, &index)
+        .await
+        .unwrap();
+    assert_eq!(index.name, index_name.to_string());
+    assert_eq!(
+        index.fields,
+        vec!["username".to_string(), "password".to_string()]
+    );
+    match &index.settings {
+        IndexSettings::Inverted(inverted) => {
+            let username = inverted
+                .fields
+                .iter()
+                .find(|field| field.name == "username")
+                .unwrap();
+            assert_eq!(username.analyzer.as_deref(), Some("text_en"));
+        }
+        other => panic!("expected an inverted index, got {:?}", other),
+    }
+
+    let delete_result = database.delete_index(&index.id).await.unwrap();
+    assert_eq!(delete_result.id, index.id);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_persistent_index_in_background() {
+    test_setup();
+    let collection_name = "test_collection";
+    let index_name = "idx_persistent_background_test";
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+
+    let index = Index::builder()
+        .name(index_name)
+        .fields(vec!["username".to_string()])
+        .build();
+
+    let mut build = database
+        .create_index_in_background(collection_name, &index)
+        .await
+        .unwrap();
+    assert!(!build.job_id().is_empty());
+
+    let mut ready = None;
+    for _ in 0..100 {
+        match build.poll_progress().await.unwrap() {
+            IndexProgress::Ready(index) => {
+                ready = Some(index);
+                break;
+            }
+            IndexProgress::Building(_) => pause(Duration::from_millis(100)).await,
+        }
+    }
+    let index = ready.expect("the index should be built within 10 seconds");
+    assert_eq!(index.name, index_name.to_string());
+
+    let delete_result = database.delete_index(&index.id).await.unwrap();
+    assert_eq!(delete_result.id, index.id);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_vertex_centric_index() {
+    test_setup();
+    let collection_name = "test_edge_collection_vertex_centric";
+    let index_name = "idx_vertex_centric_test";
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+    let _ = database.drop_collection(collection_name).await;
+    database
+        .create_edge_collection(collection_name)
+        .await
+        .unwrap();
+
+    let index = database
+        .create_vertex_centric_index(
+            collection_name,
+            index_name,
+            EdgeDirection::Outbound,
+            vec!["weight"],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        index.fields,
+        vec!["_from".to_string(), "weight".to_string()]
+    );
+
+    let result = database
+        .create_vertex_centric_index(
+            "test_collection",
+            index_name,
+            EdgeDirection::Inbound,
+            vec!["weight"],
+        )
+        .await;
+    assert_eq!(result.is_err(), true);
+
+    database.drop_collection(collection_name).await.unwrap();
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_delete_index_by_name() {
+    test_setup();
+    let collection_name = "test_collection";
+    let index_name = "idx_delete_by_name_test";
+    let conn = connection().await;
+
+    let database = conn.db("test_db").await.unwrap();
+
+    let index = Index::builder()
+        .name(index_name)
+        .fields(vec!["email".to_string()])
+        .build();
+    let index = database
+        .create_index(collection_name, &index)
+        .await
+        .unwrap();
+
+    let delete_result = database
+        .delete_index_by_name(collection_name, index_name)
+        .await
+        .unwrap();
+    assert_eq!(delete_result.id, index.id);
+
+    let missing = database
+        .delete_index_by_name(collection_name, index_name)
+        .await;
+    assert!(matches!(missing, Err(ClientError::NotFound(_))));
+
+    let missing = database.delete_index(&index.id).await;
+    assert!(matches!(missing, Err(ClientError::NotFound(_))));
+}
@@ -15,9 +15,10 @@ use arangors::{
     },
     document::options::RemoveOptions,
     transaction::{
-        Status as TransactionStatus, Transaction, TransactionCollections, TransactionSettings,
+        Status as TransactionStatus, Transaction, TransactionCollections, TransactionDefaults,
+        TransactionSettings,
     },
-    ClientError, Connection, Database, Document,
+    AqlOptions, AqlQuery, ClientError, Connection, Database, Document, QueryContext,
 };
 use common::{get_arangodb_host, get_normal_password, get_normal_user, test_setup};
 
@@ -155,3 +156,311 @@ async fn test_commit_transaction() {
 
     assert_eq!(old_doc.is_ok(), true);
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_graph_handle_in_transaction() {
+    use arangors::graph::{EdgeDefinition, Graph};
+
+    test_setup();
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let _ = database.drop_graph("test_trx_graph", true).await;
+    let graph = Graph::builder()
+        .name("test_trx_graph".to_string())
+        .edge_definitions(vec![EdgeDefinition {
+            collection: "trx_graph_edge".to_string(),
+            from: vec!["trx_graph_vertex".to_string()],
+            to: vec!["trx_graph_vertex".to_string()],
+        }])
+        .build();
+    database.create_graph(graph, true).await.unwrap();
+
+    let tx = create_transaction(&database, "trx_graph_vertex".to_string())
+        .await
+        .unwrap();
+    let handle = tx.graph_handle("test_trx_graph");
+    assert_eq!(handle.is_transactional(), true);
+
+    let vertex = handle
+        .create_vertex("trx_graph_vertex", &json!({ "name": "in_trx" }))
+        .await
+        .unwrap();
+
+    let add = handle.add_vertex_collection("trx_graph_other").await;
+    assert!(matches!(add, Err(ClientError::NotTransactional(_))));
+
+    tx.abort().await.unwrap();
+
+    let outside = database.graph_handle("test_trx_graph");
+    let read = outside
+        .vertex::<Value>("trx_graph_vertex", &vertex._key)
+        .await;
+    assert_eq!(read.is_err(), true);
+
+    let tx = create_transaction(&database, "trx_graph_vertex".to_string())
+        .await
+        .unwrap();
+    let missing = tx.graph("test_trx_missing_graph").await;
+    assert_eq!(missing.is_err(), true);
+    let handle = tx.graph("test_trx_graph").await.unwrap();
+    assert_eq!(handle.is_transactional(), true);
+    let vertex = handle
+        .create_vertex("trx_graph_vertex", &json!({ "name": "committed" }))
+        .await
+        .unwrap();
+
+    let joined = outside.clone_with_transaction(tx.id().clone()).unwrap();
+    assert_eq!(joined.is_transactional(), true);
+    let read = joined
+        .vertex::<Value>("trx_graph_vertex", &vertex._key)
+        .await;
+    assert_eq!(read.is_ok(), true);
+    let read = outside
+        .vertex::<Value>("trx_graph_vertex", &vertex._key)
+        .await;
+    assert_eq!(read.is_err(), true);
+
+    tx.commit().await.unwrap();
+    let read = outside
+        .vertex::<Value>("trx_graph_vertex", &vertex._key)
+        .await;
+    assert_eq!(read.is_ok(), true);
+
+    outside.drop(true).await.unwrap();
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_query_with_transaction_context() {
+    test_setup();
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let tx = create_transaction(&database, "test_collection".to_string())
+        .await
+        .unwrap();
+    let key = create_document(&tx).await.unwrap();
+
+    let query = "FOR d IN test_collection FILTER d._key == @key RETURN d._key";
+    let context = QueryContext::builder().transaction_id(tx.id()).build();
+    let aql = AqlQuery::builder()
+        .query(query)
+        .bind_var("key", key.as_str())
+        .options(AqlOptions::builder().context(context).build())
+        .build();
+    let inside: Vec<String> = database.aql_query(aql).await.unwrap();
+    assert_eq!(inside, vec![key.clone()]);
+
+    let outside: Vec<String> = database
+        .aql_bind_vars(query, [("key", key.as_str())])
+        .await
+        .unwrap();
+    assert_eq!(outside.is_empty(), true);
+
+    tx.abort().await.unwrap();
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_aql_query_paged_in_transaction() {
+    test_setup();
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let tx = create_transaction(&database, "test_collection".to_string())
+        .await
+        .unwrap();
+    let mut keys = Vec::new();
+    for _ in 0..3 {
+        keys.push(create_document(&tx).await.unwrap());
+    }
+    keys.sort();
+
+    let query = "FOR d IN test_collection FILTER d._key IN @keys SORT d._key RETURN d._key";
+    let aql = AqlQuery::builder()
+        .query(query)
+        .bind_var("keys", keys.clone())
+        .batch_size(1)
+        .build();
+    let paged: Vec<String> = tx.aql_query(aql).await.unwrap();
+    assert_eq!(paged, keys);
+
+    // the further batches are fetched within the transaction as well
+    let context = QueryContext::builder().transaction_id(tx.id()).build();
+    let aql = AqlQuery::builder()
+        .query(query)
+        .bind_var("keys", keys.clone())
+        .batch_size(1)
+        .options(AqlOptions::builder().context(context).build())
+        .build();
+    let paged: Vec<String> = database.aql_query(aql).await.unwrap();
+    assert_eq!(paged, keys);
+
+    tx.abort().await.unwrap();
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_transaction_database() {
+    test_setup();
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let tx = create_transaction(&database, "test_collection".to_string())
+        .await
+        .unwrap();
+    let tx_database = tx.database();
+    assert_eq!(tx_database.name(), database.name());
+
+    let collection = tx_database.collection("test_collection").await.unwrap();
+    let document = collection
+        .create_document(
+            Document::new(json!({ "in": "transaction" })),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    let key = document.header().unwrap()._key.clone();
+
+    let query = "FOR d IN test_collection FILTER d._key == @key RETURN d._key";
+    let inside: Vec<String> = tx_database
+        .aql_bind_vars(query, [("key", key.as_str())])
+        .await
+        .unwrap();
+    assert_eq!(inside, vec![key.clone()]);
+
+    let outside: Vec<String> = database
+        .aql_bind_vars(query, [("key", key.as_str())])
+        .await
+        .unwrap();
+    assert_eq!(outside.is_empty(), true);
+
+    tx.abort().await.unwrap();
+    let doc = database
+        .collection("test_collection")
+        .await
+        .unwrap()
+        .document::<Value>(&key)
+        .await;
+    assert_eq!(doc.is_err(), true);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_run_transaction_with_retry() {
+    test_setup();
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let settings = TransactionSettings::builder()
+        .lock_timeout(60)
+        .collections(
+            TransactionCollections::builder()
+                .write(vec!["test_collection".to_owned()])
+                .build(),
+        )
+        .build();
+
+    let mut attempts = 0;
+    let key = database
+        .run_transaction_with_retry(settings.clone(), 3, |tx| {
+            attempts += 1;
+            async move { create_document(&tx).await }
+        })
+        .await
+        .unwrap();
+    assert_eq!(attempts, 1);
+    let collection = database.collection("test_collection").await.unwrap();
+    let doc = collection.document::<Value>(&key).await;
+    assert_eq!(doc.is_ok(), true);
+
+    let mut attempts = 0;
+    let result: Result<(), ClientError> = database
+        .run_transaction_with_retry(settings, 3, |tx| {
+            attempts += 1;
+            async move {
+                create_document(&tx).await?;
+                Err(ClientError::InvalidArgument("rolled back".to_owned()))
+            }
+        })
+        .await;
+    assert!(matches!(result, Err(ClientError::InvalidArgument(_))));
+    assert_eq!(attempts, 1);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_transaction_builder() {
+    test_setup();
+    let conn = connection().await;
+    let mut database = conn.db("test_db").await.unwrap();
+    database.set_transaction_defaults(TransactionDefaults::builder().lock_timeout(60).build());
+
+    let settings = database
+        .transaction()
+        .write(["test_collection"])
+        .wait_for_sync(true)
+        .settings();
+    assert_eq!(
+        serde_json::to_value(&settings).unwrap(),
+        json!({
+            "collections": { "write": ["test_collection"] },
+            "waitForSync": true,
+            "allowImplicit": true,
+            "lockTimeout": 60,
+        })
+    );
+
+    let tx = database
+        .transaction()
+        .exclusive(["test_collection"])
+        .begin()
+        .await
+        .unwrap();
+    assert_eq!(tx.status(), &TransactionStatus::Running);
+    tx.abort().await.unwrap();
+
+    let key = database
+        .transaction()
+        .write(["test_collection"])
+        .run(|tx| async move { create_document(&tx).await })
+        .await
+        .unwrap();
+    let collection = database.collection("test_collection").await.unwrap();
+    let doc = collection.document::<Value>(&key).await;
+    assert_eq!(doc.is_ok(), true);
+
+    let result: Result<String, ClientError> = database
+        .transaction()
+        .write(["test_collection"])
+        .run(|tx| async move {
+            let key = create_document(&tx).await?;
+            Err(ClientError::InvalidArgument(key))
+        })
+        .await;
+    let key = match result {
+        Err(ClientError::InvalidArgument(key)) => key,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    let doc = collection.document::<Value>(&key).await;
+    assert_eq!(doc.is_err(), true);
+}
@@ -10,7 +10,7 @@ use std::collections::HashMap;
 use crate::common::{get_root_user, root_connection};
 use arangors::{
     connection::Permission,
-    user::{User, UserAccessLevel},
+    user::{User, UserAccessLevel, UserOptions},
     ArangoError, Connection,
 };
 use common::{
@@ -250,3 +250,51 @@ async fn test_user_db_collection_access_put() {
     trace!("resp: {:?}", resp);
     assert_eq!(resp.is_ok(), true);
 }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_admin_user_management() {
+    test_setup();
+    let conn = root_connection().await.into_admin().await.unwrap();
+    let username = "admin_managed_test_user";
+    let _ = conn.delete_user(username).await;
+
+    let options = UserOptions::builder().password("secret").build();
+    let user = conn.create_user(username, &options).await.unwrap();
+    assert_eq!(user.username, username);
+    assert_eq!(user.active, true);
+
+    let users = conn.list_users().await.unwrap();
+    assert_eq!(users.iter().any(|u| u.username == username), true);
+
+    let options = UserOptions::builder().active(false).build();
+    let user = conn.update_user(username, &options).await.unwrap();
+    assert_eq!(user.active, false);
+
+    conn.grant_database_access(username, "test_db", Permission::ReadOnly)
+        .await
+        .unwrap();
+    let permission = conn.database_permission(username, "test_db").await.unwrap();
+    assert_eq!(permission, Permission::ReadOnly);
+
+    conn.grant_collection_access(
+        username,
+        "test_db",
+        "test_collection",
+        Permission::ReadWrite,
+    )
+    .await
+    .unwrap();
+    let permission = conn
+        .collection_permission(username, "test_db", "test_collection")
+        .await
+        .unwrap();
+    assert_eq!(permission, Permission::ReadWrite);
+
+    conn.delete_user(username).await.unwrap();
+    let users = conn.list_users().await.unwrap();
+    assert_eq!(users.iter().any(|u| u.username == username), false);
+}
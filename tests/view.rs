@@ -14,7 +14,10 @@ use arangors::{
         response::Status,
         CollectionType,
     },
-    view::{ArangoSearchViewLink, ArangoSearchViewPropertiesOptions, View, ViewOptions},
+    view::{
+        ArangoSearchViewLink, ArangoSearchViewPropertiesOptions, Link, View, ViewLinksBuilder,
+        ViewOptions,
+    },
     ClientError, Connection, Database, Document,
 };
 use common::{get_arangodb_host, get_normal_password, get_normal_user, test_setup};
@@ -27,23 +30,14 @@ async fn create_view<C: ClientExt>(
     view_name: String,
     collection_name: String,
 ) -> Result<View, ClientError> {
-    let mut links: HashMap<String, ArangoSearchViewLink> = HashMap::new();
-
-    links.insert(
-        collection_name,
-        ArangoSearchViewLink::builder()
-            .include_all_fields(true)
-            .build(),
-    );
-
     database
         .create_view(
             ViewOptions::builder()
                 .name(view_name)
                 .properties(
-                    ArangoSearchViewPropertiesOptions::builder()
-                        .links(links)
-                        .build(),
+                    ViewLinksBuilder::new()
+                        .link(collection_name, Link::all_fields())
+                        .into(),
                 )
                 .build(),
         )
@@ -75,6 +69,65 @@ async fn test_create_and_drop_view() {
     assert_eq!(result.is_err(), false);
 }
 
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_exists_view() {
+    test_setup();
+    let collection_name = "test_collection".to_string();
+    let view_name = format!("{}_view_exists", collection_name);
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let view = create_view(&database, view_name.clone(), collection_name).await;
+    assert_eq!(view.is_err(), false);
+
+    let exists = database.exists_view(&view_name).await.unwrap();
+    assert_eq!(exists, true);
+
+    database.drop_view(&view_name).await.unwrap();
+
+    let exists = database.exists_view(&view_name).await.unwrap();
+    assert_eq!(exists, false);
+}
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_view_handle() {
+    test_setup();
+    let collection_name = "test_collection".to_string();
+    let view_name = format!("{}_view_handle", collection_name);
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+
+    let created = create_view(&database, view_name.clone(), collection_name)
+        .await
+        .unwrap();
+    let view = database.view_handle(&view_name);
+    assert_eq!(view.name(), view_name);
+
+    let description = view.description().await.unwrap();
+    assert_eq!(description.name, view_name);
+    assert_eq!(description.id, created.description.id);
+
+    let properties = view.properties().await.unwrap();
+    assert_eq!(
+        properties.cleanup_interval_step,
+        created.properties.cleanup_interval_step
+    );
+
+    let dropped = view.drop().await.unwrap();
+    assert_eq!(dropped, true);
+
+    let dropped = database.drop_view(&view_name).await;
+    assert_eq!(dropped.is_err(), true);
+}
+
 // #[maybe_async::test(
 //     any(feature = "reqwest_blocking"),
 //     async(any(feature = "reqwest_async"), tokio::test),
@@ -151,3 +204,44 @@ async fn test_create_and_drop_view() {
 
 //     assert_eq!(result.is_err(), false);
 // }
+
+#[maybe_async::test(
+    any(feature = "reqwest_blocking"),
+    async(any(feature = "reqwest_async"), tokio::test),
+    async(any(feature = "surf_async"), async_std::test)
+)]
+async fn test_search_scored() {
+    use arangors::search::{Scored, Scorer, SearchQuery};
+    use serde_json::{json, Value};
+
+    test_setup();
+    let collection_name = "test_collection_search".to_string();
+    let view_name = format!("{}_view", collection_name);
+    let conn = connection().await;
+    let database = conn.db("test_db").await.unwrap();
+    let coll = collection(&conn, &collection_name).await;
+    for name in ["search alpha", "search beta", "other"] {
+        coll.create_document(json!({ "name": name }), Default::default())
+            .await
+            .unwrap();
+    }
+    create_view(&database, view_name.clone(), collection_name)
+        .await
+        .unwrap();
+
+    let query = SearchQuery::builder()
+        .view(view_name.clone())
+        .search("STARTS_WITH(doc.name, @prefix) OPTIONS { waitForSync: true }")
+        .scorer(Scorer::Tfidf { normalize: false })
+        .limit(10)
+        .build()
+        .bind_var("prefix", "search");
+    let hits: Vec<Scored<Value>> = database.search(&query).await.unwrap();
+    assert_eq!(hits.len(), 2);
+    assert!(hits
+        .iter()
+        .all(|hit| hit.doc["name"].as_str().unwrap().starts_with("search")));
+
+    database.drop_view(&view_name).await.unwrap();
+    coll.drop().await.unwrap();
+}